@@ -32,10 +32,9 @@ where
     let alpha = rng.gen();
     let beta = rng.gen();
     let gamma = rng.gen();
-    let delta = rng.gen();
     let tau = rng.gen();
 
-    generate_parameters::<E, C>(circuit, g1, g2, alpha, beta, gamma, delta, tau)
+    generate_parameters::<E, C>(circuit, g1, g2, alpha, beta, gamma, tau)
 }
 
 /// This is our assembly structure that we'll use to synthesize the
@@ -215,10 +214,8 @@ pub fn generate_parameters<E, C>(
     alpha: E::Fr,
     beta: E::Fr,
     gamma: E::Fr,
-    // delta: E::Fr,
     tau: E::Fr,
-) -> Result<(), SynthesisError>
-// Result<Parameters<E>, SynthesisError>
+) -> Result<Parameters<E>, SynthesisError>
 where
     E: Engine,
     C: Circuit<E>,
@@ -273,6 +270,15 @@ where
             assembly.num_constraints,
         );
 
+        let mut lc_input_0_c: LinearCombination<E> = LinearCombination::zero();
+        lc_input_0_c = lc_input_0_c.add(input_0.clone());
+        eval_lc(
+            lc_input_0_c,
+            &mut assembly.ct_inputs,
+            &mut assembly.ct_aux,
+            assembly.num_constraints,
+        );
+
         assembly.num_constraints += 1;
     }
 
@@ -376,7 +382,7 @@ where
 
     // G1^{gamma^2 * Z(t) * t^i} for 0 <= i < 2^m - 1 for 2^m domains
     let mut gamma2_z_t_g1 = vec![E::G1::zero(); domain.as_ref().len() - 1];
-    let mut z_at_tau = E::Fr::zero();
+    let z_at_tau;
 
     {
         // Compute powers of tau
@@ -463,29 +469,25 @@ where
     // G1^{2 * gamma^2 * Z(t) * A_i(t)} for 0 <= i <= num_variables
     let mut c_2_g1 = vec![E::G1::zero(); assembly.num_inputs + assembly.num_aux];
 
-    // G1^{gamma * Z(t)}
     let mut gamma_zt = gamma;
     gamma_zt.mul_assign(&z_at_tau);
 
-    let gamma_z = g1.into_affine().mul(gamma.into_repr());
+    // G1^{gamma * Z(t)}
+    let gamma_z = g1.into_affine().mul(gamma_zt.into_repr());
     // G2^{gamma * Z(t)}
-    let gamma_z_g2 = g2.into_affine().mul(gamma.into_repr());
+    let gamma_z_g2 = g2.into_affine().mul(gamma_zt.into_repr());
 
-    let mut ab_gamma = alpha;
-    ab_gamma.add_assign(&beta);
-    ab_gamma.mul_assign(&gamma);
+    let mut ab_gamma_z = alpha;
+    ab_gamma_z.add_assign(&beta);
+    ab_gamma_z.mul_assign(&gamma_zt);
     // G1^{(alpha + beta) * gamma * Z(t)}
-    let ab_gamma_z_g1 = g1.into_affine().mul(ab_gamma.into_repr());
+    let ab_gamma_z_g1 = g1.into_affine().mul(ab_gamma_z.into_repr());
 
-    let mut gamma2_z2 = gamma;
-    gamma2_z2.mul_assign(&z_at_tau);
+    let mut gamma2_z2 = gamma_zt;
     gamma2_z2.square();
     // G1^{gamma^2 * Z(t)^2}
     let gamma2_z2_g1 = g1.into_affine().mul(gamma2_z2.into_repr());
 
-    // G^{gamma^2 * Z(t) * t^i} for 0 <= i < 2^m - 1 for 2^m domains
-    let mut gamma2_z_t = vec![E::G1::zero(); domain.as_ref().len() - 1];
-
     if verbose {
         eprintln!("using inverse FFT to convert to intepolation coefficients...")
     };
@@ -658,7 +660,7 @@ where
         &worker,
     );
 
-    // Evaluate for inputs.
+    // Evaluate for auxillary variables.
     eval_stage_1(
         &g1_wnaf,
         &g2_wnaf,
@@ -676,10 +678,6 @@ where
         &worker,
     );
 
-    // for _ in 0..assembly.num_inputs {
-    //     c_1_g1.remove(0);
-    // }
-
     if verbose {
         eprintln!(
             "evaluating polynomials done in {} s",
@@ -687,39 +685,51 @@ where
         );
     };
 
-    // // Don't allow any elements be unconstrained, so that
-    // // the L query is always fully dense.
-    // for e in l.iter() {
-    //     if e.is_zero() {
-    //         return Err(SynthesisError::UnconstrainedVariable);
-    //     }
-    // }
-
-    // let g1 = g1.into_affine();
-    // let g2 = g2.into_affine();
-
-    // let vk = VerifyingKey::<E> {
-    //     alpha_g1: g1.mul(alpha).into_affine(),
-    //     beta_g1: g1.mul(beta).into_affine(),
-    //     beta_g2: g2.mul(beta).into_affine(),
-    //     gamma_g2: g2.mul(gamma).into_affine(),
-    //     delta_g1: g1.mul(delta).into_affine(),
-    //     delta_g2: g2.mul(delta).into_affine(),
-    //     ic: ic.into_iter().map(|e| e.into_affine()).collect()
-    // };
-
-    println!("Has generated {} points", a_g1.len());
+    // Public inputs are not a part of the C_1 query. Verifier uses
+    // G1^{gamma * C_i(t) + (alpha + beta) * A_i(t)} for them instead,
+    // that is the C_1 query element divided by gamma
+    let gamma_inverse = gamma.inverse().ok_or(SynthesisError::UnexpectedIdentity)?;
+    let ic = c_1_g1
+        .drain(0..assembly.num_inputs)
+        .map(|mut e| {
+            e.mul_assign(gamma_inverse.into_repr());
+
+            e.into_affine()
+        })
+        .collect();
+
+    // Don't allow any elements be unconstrained, so that
+    // the C_1 query is always fully dense.
+    for e in c_1_g1.iter() {
+        if e.is_zero() {
+            return Err(SynthesisError::UnconstrainedVariable);
+        }
+    }
 
-    Ok(())
+    let g1 = g1.into_affine();
+    let g2 = g2.into_affine();
 
-    // Ok(Parameters {
-    //     vk: vk,
-    //     h: Arc::new(h.into_iter().map(|e| e.into_affine()).collect()),
-    //     l: Arc::new(l.into_iter().map(|e| e.into_affine()).collect()),
+    let vk = VerifyingKey::<E> {
+        h_g2: g2,
+        alpha_g1: g1.mul(alpha).into_affine(),
+        beta_g2: g2.mul(beta).into_affine(),
+        gamma_g1: g1.mul(gamma).into_affine(),
+        gamma_g2: g2.mul(gamma).into_affine(),
+        ic: ic,
+    };
 
-    //     // Filter points at infinity away from A/B queries
-    //     a: Arc::new(a.into_iter().filter(|e| !e.is_zero()).map(|e| e.into_affine()).collect()),
-    //     b_g1: Arc::new(b_g1.into_iter().filter(|e| !e.is_zero()).map(|e| e.into_affine()).collect()),
-    //     b_g2: Arc::new(b_g2.into_iter().filter(|e| !e.is_zero()).map(|e| e.into_affine()).collect())
-    // })
+    Ok(Parameters {
+        vk: vk,
+        // Filter points at infinity away from queries over A polynomials,
+        // inputs are never filtered since each input is constrained
+        a_g1: Arc::new(a_g1.into_iter().filter(|e| !e.is_zero()).map(|e| e.into_affine()).collect()),
+        a_g2: Arc::new(a_g2.into_iter().filter(|e| !e.is_zero()).map(|e| e.into_affine()).collect()),
+        c_1_g1: Arc::new(c_1_g1.into_iter().map(|e| e.into_affine()).collect()),
+        c_2_g1: Arc::new(c_2_g1.into_iter().filter(|e| !e.is_zero()).map(|e| e.into_affine()).collect()),
+        gamma_z: gamma_z.into_affine(),
+        gamma_z_g2: gamma_z_g2.into_affine(),
+        ab_gamma_z_g1: ab_gamma_z_g1.into_affine(),
+        gamma2_z2_g1: gamma2_z2_g1.into_affine(),
+        gamma2_z_t: Arc::new(gamma2_z_t_g1.into_iter().map(|e| e.into_affine()).collect()),
+    })
 }
@@ -11,12 +11,12 @@ use std::sync::Arc;
 mod tests;
 
 mod generator;
-// mod prover;
-// mod verifier;
+mod prover;
+mod verifier;
 
 pub use self::generator::*;
-// pub use self::prover::*;
-// pub use self::verifier::*;
+pub use self::prover::*;
+pub use self::verifier::*;
 
 #[derive(Debug, Clone)]
 pub struct Proof<E: Engine> {
@@ -110,7 +110,7 @@ pub struct VerifyingKey<E: Engine> {
     // gamma in g2 for verifying. Never the point at infinity.
     pub gamma_g2: E::G2Affine,
 
-    // Elements of the form G^{gamma * A_i(t) + (alpha + beta) * A_i(t)}
+    // Elements of the form G^{gamma * C_i(t) + (alpha + beta) * A_i(t)}
     // for all public inputs. Because all public inputs have a dummy constraint,
     // this is the same size as the number of inputs, and never contains points
     // at infinity.
@@ -239,329 +239,380 @@ impl<E: Engine> PartialEq for Parameters<E> {
     }
 }
 
-// impl<E: Engine> Parameters<E> {
-//     pub fn write<W: Write>(
-//         &self,
-//         mut writer: W
-//     ) -> io::Result<()>
-//     {
-//         self.vk.write(&mut writer)?;
-
-//         writer.write_u32::<BigEndian>(self.h.len() as u32)?;
-//         for g in &self.h[..] {
-//             writer.write_all(g.into_uncompressed().as_ref())?;
-//         }
-
-//         writer.write_u32::<BigEndian>(self.l.len() as u32)?;
-//         for g in &self.l[..] {
-//             writer.write_all(g.into_uncompressed().as_ref())?;
-//         }
-
-//         writer.write_u32::<BigEndian>(self.a.len() as u32)?;
-//         for g in &self.a[..] {
-//             writer.write_all(g.into_uncompressed().as_ref())?;
-//         }
-
-//         writer.write_u32::<BigEndian>(self.b_g1.len() as u32)?;
-//         for g in &self.b_g1[..] {
-//             writer.write_all(g.into_uncompressed().as_ref())?;
-//         }
-
-//         writer.write_u32::<BigEndian>(self.b_g2.len() as u32)?;
-//         for g in &self.b_g2[..] {
-//             writer.write_all(g.into_uncompressed().as_ref())?;
-//         }
-
-//         Ok(())
-//     }
-
-//     pub fn read<R: Read>(
-//         mut reader: R,
-//         checked: bool
-//     ) -> io::Result<Self>
-//     {
-//         let read_g1 = |reader: &mut R| -> io::Result<E::G1Affine> {
-//             let mut repr = <E::G1Affine as CurveAffine>::Uncompressed::empty();
-//             reader.read_exact(repr.as_mut())?;
-
-//             if checked {
-//                 repr
-//                 .into_affine()
-//             } else {
-//                 repr
-//                 .into_affine_unchecked()
-//             }
-//             .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
-//             .and_then(|e| if e.is_zero() {
-//                 Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
-//             } else {
-//                 Ok(e)
-//             })
-//         };
-
-//         let read_g2 = |reader: &mut R| -> io::Result<E::G2Affine> {
-//             let mut repr = <E::G2Affine as CurveAffine>::Uncompressed::empty();
-//             reader.read_exact(repr.as_mut())?;
-
-//             if checked {
-//                 repr
-//                 .into_affine()
-//             } else {
-//                 repr
-//                 .into_affine_unchecked()
-//             }
-//             .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
-//             .and_then(|e| if e.is_zero() {
-//                 Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
-//             } else {
-//                 Ok(e)
-//             })
-//         };
-
-//         let vk = VerifyingKey::<E>::read(&mut reader)?;
-
-//         let mut h = vec![];
-//         let mut l = vec![];
-//         let mut a = vec![];
-//         let mut b_g1 = vec![];
-//         let mut b_g2 = vec![];
-
-//         {
-//             let len = reader.read_u32::<BigEndian>()? as usize;
-//             for _ in 0..len {
-//                 h.push(read_g1(&mut reader)?);
-//             }
-//         }
-
-//         {
-//             let len = reader.read_u32::<BigEndian>()? as usize;
-//             for _ in 0..len {
-//                 l.push(read_g1(&mut reader)?);
-//             }
-//         }
-
-//         {
-//             let len = reader.read_u32::<BigEndian>()? as usize;
-//             for _ in 0..len {
-//                 a.push(read_g1(&mut reader)?);
-//             }
-//         }
-
-//         {
-//             let len = reader.read_u32::<BigEndian>()? as usize;
-//             for _ in 0..len {
-//                 b_g1.push(read_g1(&mut reader)?);
-//             }
-//         }
-
-//         {
-//             let len = reader.read_u32::<BigEndian>()? as usize;
-//             for _ in 0..len {
-//                 b_g2.push(read_g2(&mut reader)?);
-//             }
-//         }
-
-//         Ok(Parameters {
-//             vk: vk,
-//             h: Arc::new(h),
-//             l: Arc::new(l),
-//             a: Arc::new(a),
-//             b_g1: Arc::new(b_g1),
-//             b_g2: Arc::new(b_g2)
-//         })
-//     }
-// }
-
-// pub struct PreparedVerifyingKey<E: Engine> {
-//     /// Pairing result of alpha*beta
-//     alpha_g1_beta_g2: E::Fqk,
-//     /// -gamma in G2
-//     neg_gamma_g2: <E::G2Affine as CurveAffine>::Prepared,
-//     /// -delta in G2
-//     neg_delta_g2: <E::G2Affine as CurveAffine>::Prepared,
-//     /// Copy of IC from `VerifiyingKey`.
-//     ic: Vec<E::G1Affine>
-// }
-
-// pub trait ParameterSource<E: Engine> {
-//     type G1Builder: SourceBuilder<E::G1Affine>;
-//     type G2Builder: SourceBuilder<E::G2Affine>;
-
-//     fn get_vk(
-//         &mut self,
-//         num_ic: usize
-//     ) -> Result<VerifyingKey<E>, SynthesisError>;
-//     fn get_h(
-//         &mut self,
-//         num_h: usize
-//     ) -> Result<Self::G1Builder, SynthesisError>;
-//     fn get_l(
-//         &mut self,
-//         num_l: usize
-//     ) -> Result<Self::G1Builder, SynthesisError>;
-//     fn get_a(
-//         &mut self,
-//         num_inputs: usize,
-//         num_aux: usize
-//     ) -> Result<(Self::G1Builder, Self::G1Builder), SynthesisError>;
-//     fn get_b_g1(
-//         &mut self,
-//         num_inputs: usize,
-//         num_aux: usize
-//     ) -> Result<(Self::G1Builder, Self::G1Builder), SynthesisError>;
-//     fn get_b_g2(
-//         &mut self,
-//         num_inputs: usize,
-//         num_aux: usize
-//     ) -> Result<(Self::G2Builder, Self::G2Builder), SynthesisError>;
-// }
-
-// impl<'a, E: Engine> ParameterSource<E> for &'a Parameters<E> {
-//     type G1Builder = (Arc<Vec<E::G1Affine>>, usize);
-//     type G2Builder = (Arc<Vec<E::G2Affine>>, usize);
-
-//     fn get_vk(
-//         &mut self,
-//         _: usize
-//     ) -> Result<VerifyingKey<E>, SynthesisError>
-//     {
-//         Ok(self.vk.clone())
-//     }
-
-//     fn get_h(
-//         &mut self,
-//         _: usize
-//     ) -> Result<Self::G1Builder, SynthesisError>
-//     {
-//         Ok((self.h.clone(), 0))
-//     }
-
-//     fn get_l(
-//         &mut self,
-//         _: usize
-//     ) -> Result<Self::G1Builder, SynthesisError>
-//     {
-//         Ok((self.l.clone(), 0))
-//     }
-
-//     fn get_a(
-//         &mut self,
-//         num_inputs: usize,
-//         _: usize
-//     ) -> Result<(Self::G1Builder, Self::G1Builder), SynthesisError>
-//     {
-//         Ok(((self.a.clone(), 0), (self.a.clone(), num_inputs)))
-//     }
-
-//     fn get_b_g1(
-//         &mut self,
-//         num_inputs: usize,
-//         _: usize
-//     ) -> Result<(Self::G1Builder, Self::G1Builder), SynthesisError>
-//     {
-//         Ok(((self.b_g1.clone(), 0), (self.b_g1.clone(), num_inputs)))
-//     }
-
-//     fn get_b_g2(
-//         &mut self,
-//         num_inputs: usize,
-//         _: usize
-//     ) -> Result<(Self::G2Builder, Self::G2Builder), SynthesisError>
-//     {
-//         Ok(((self.b_g2.clone(), 0), (self.b_g2.clone(), num_inputs)))
-//     }
-// }
-
-// #[cfg(test)]
-// mod test_with_bls12_381 {
-//     use super::*;
-//     use {Circuit, SynthesisError, ConstraintSystem};
-
-//     use rand::{Rand, thread_rng};
-//     use crate::pairing::ff::{Field};
-//     use crate::pairing::bls12_381::{Bls12, Fr};
-
-//     #[test]
-//     fn serialization() {
-//         struct MySillyCircuit<E: Engine> {
-//             a: Option<E::Fr>,
-//             b: Option<E::Fr>
-//         }
-
-//         impl<E: Engine> Circuit<E> for MySillyCircuit<E> {
-//             fn synthesize<CS: ConstraintSystem<E>>(
-//                 self,
-//                 cs: &mut CS
-//             ) -> Result<(), SynthesisError>
-//             {
-//                 let a = cs.alloc(|| "a", || self.a.ok_or(SynthesisError::AssignmentMissing))?;
-//                 let b = cs.alloc(|| "b", || self.b.ok_or(SynthesisError::AssignmentMissing))?;
-//                 let c = cs.alloc_input(|| "c", || {
-//                     let mut a = self.a.ok_or(SynthesisError::AssignmentMissing)?;
-//                     let b = self.b.ok_or(SynthesisError::AssignmentMissing)?;
-
-//                     a.mul_assign(&b);
-//                     Ok(a)
-//                 })?;
-
-//                 cs.enforce(
-//                     || "a*b=c",
-//                     |lc| lc + a,
-//                     |lc| lc + b,
-//                     |lc| lc + c
-//                 );
-
-//                 Ok(())
-//             }
-//         }
-
-//         let rng = &mut thread_rng();
-
-//         let params = generate_random_parameters::<Bls12, _, _>(
-//             MySillyCircuit { a: None, b: None },
-//             rng
-//         ).unwrap();
-
-//         {
-//             let mut v = vec![];
-
-//             params.write(&mut v).unwrap();
-//             assert_eq!(v.len(), 2136);
-
-//             let de_params = Parameters::read(&v[..], true).unwrap();
-//             assert!(params == de_params);
-
-//             let de_params = Parameters::read(&v[..], false).unwrap();
-//             assert!(params == de_params);
-//         }
-
-//         let pvk = prepare_verifying_key::<Bls12>(&params.vk);
-
-//         for _ in 0..100 {
-//             let a = Fr::rand(rng);
-//             let b = Fr::rand(rng);
-//             let mut c = a;
-//             c.mul_assign(&b);
-
-//             let proof = create_random_proof(
-//                 MySillyCircuit {
-//                     a: Some(a),
-//                     b: Some(b)
-//                 },
-//                 &params,
-//                 rng
-//             ).unwrap();
-
-//             let mut v = vec![];
-//             proof.write(&mut v).unwrap();
-
-//             assert_eq!(v.len(), 192);
-
-//             let de_proof = Proof::read(&v[..]).unwrap();
-//             assert!(proof == de_proof);
-
-//             assert!(verify_proof(&pvk, &proof, &[c]).unwrap());
-//             assert!(!verify_proof(&pvk, &proof, &[a]).unwrap());
-//         }
-//     }
-// }
+impl<E: Engine> Parameters<E> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        self.vk.write(&mut writer)?;
+
+        writer.write_u32::<BigEndian>(self.a_g1.len() as u32)?;
+        for g in &self.a_g1[..] {
+            writer.write_all(g.into_uncompressed().as_ref())?;
+        }
+
+        writer.write_u32::<BigEndian>(self.a_g2.len() as u32)?;
+        for g in &self.a_g2[..] {
+            writer.write_all(g.into_uncompressed().as_ref())?;
+        }
+
+        writer.write_u32::<BigEndian>(self.c_1_g1.len() as u32)?;
+        for g in &self.c_1_g1[..] {
+            writer.write_all(g.into_uncompressed().as_ref())?;
+        }
+
+        writer.write_u32::<BigEndian>(self.c_2_g1.len() as u32)?;
+        for g in &self.c_2_g1[..] {
+            writer.write_all(g.into_uncompressed().as_ref())?;
+        }
+
+        writer.write_all(self.gamma_z.into_uncompressed().as_ref())?;
+        writer.write_all(self.gamma_z_g2.into_uncompressed().as_ref())?;
+        writer.write_all(self.ab_gamma_z_g1.into_uncompressed().as_ref())?;
+        writer.write_all(self.gamma2_z2_g1.into_uncompressed().as_ref())?;
+
+        writer.write_u32::<BigEndian>(self.gamma2_z_t.len() as u32)?;
+        for g in &self.gamma2_z_t[..] {
+            writer.write_all(g.into_uncompressed().as_ref())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R,
+        checked: bool
+    ) -> io::Result<Self>
+    {
+        let read_g1 = |reader: &mut R| -> io::Result<E::G1Affine> {
+            let mut repr = <E::G1Affine as CurveAffine>::Uncompressed::empty();
+            reader.read_exact(repr.as_mut())?;
+
+            if checked {
+                repr
+                .into_affine()
+            } else {
+                repr
+                .into_affine_unchecked()
+            }
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            .and_then(|e| if e.is_zero() {
+                Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
+            } else {
+                Ok(e)
+            })
+        };
+
+        let read_g2 = |reader: &mut R| -> io::Result<E::G2Affine> {
+            let mut repr = <E::G2Affine as CurveAffine>::Uncompressed::empty();
+            reader.read_exact(repr.as_mut())?;
+
+            if checked {
+                repr
+                .into_affine()
+            } else {
+                repr
+                .into_affine_unchecked()
+            }
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            .and_then(|e| if e.is_zero() {
+                Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
+            } else {
+                Ok(e)
+            })
+        };
+
+        let vk = VerifyingKey::<E>::read(&mut reader)?;
+
+        let mut a_g1 = vec![];
+        let mut a_g2 = vec![];
+        let mut c_1_g1 = vec![];
+        let mut c_2_g1 = vec![];
+        let mut gamma2_z_t = vec![];
+
+        {
+            let len = reader.read_u32::<BigEndian>()? as usize;
+            for _ in 0..len {
+                a_g1.push(read_g1(&mut reader)?);
+            }
+        }
+
+        {
+            let len = reader.read_u32::<BigEndian>()? as usize;
+            for _ in 0..len {
+                a_g2.push(read_g2(&mut reader)?);
+            }
+        }
+
+        {
+            let len = reader.read_u32::<BigEndian>()? as usize;
+            for _ in 0..len {
+                c_1_g1.push(read_g1(&mut reader)?);
+            }
+        }
+
+        {
+            let len = reader.read_u32::<BigEndian>()? as usize;
+            for _ in 0..len {
+                c_2_g1.push(read_g1(&mut reader)?);
+            }
+        }
+
+        let gamma_z = read_g1(&mut reader)?;
+        let gamma_z_g2 = read_g2(&mut reader)?;
+        let ab_gamma_z_g1 = read_g1(&mut reader)?;
+        let gamma2_z2_g1 = read_g1(&mut reader)?;
+
+        {
+            let len = reader.read_u32::<BigEndian>()? as usize;
+            for _ in 0..len {
+                gamma2_z_t.push(read_g1(&mut reader)?);
+            }
+        }
+
+        Ok(Parameters {
+            vk: vk,
+            a_g1: Arc::new(a_g1),
+            a_g2: Arc::new(a_g2),
+            c_1_g1: Arc::new(c_1_g1),
+            c_2_g1: Arc::new(c_2_g1),
+            gamma_z: gamma_z,
+            gamma_z_g2: gamma_z_g2,
+            ab_gamma_z_g1: ab_gamma_z_g1,
+            gamma2_z2_g1: gamma2_z2_g1,
+            gamma2_z_t: Arc::new(gamma2_z_t)
+        })
+    }
+}
+
+pub struct PreparedVerifyingKey<E: Engine> {
+    /// H in G2
+    h_pc: <E::G2Affine as CurveAffine>::Prepared,
+    /// alpha in G1
+    g_alpha: E::G1Affine,
+    /// beta in G2
+    h_beta: E::G2Affine,
+    /// Miller loop result of alpha*beta, the final exponentiation
+    /// is done together with the rest of the first check
+    g_alpha_h_beta_ml: E::Fqk,
+    /// gamma in G1
+    g_gamma_pc: <E::G1Affine as CurveAffine>::Prepared,
+    /// gamma in G2
+    h_gamma_pc: <E::G2Affine as CurveAffine>::Prepared,
+    /// Copy of IC from `VerifiyingKey`.
+    query: Vec<E::G1Affine>
+}
+
+pub trait ParameterSource<E: Engine> {
+    type G1Builder: SourceBuilder<E::G1Affine>;
+    type G2Builder: SourceBuilder<E::G2Affine>;
+
+    fn get_vk(
+        &mut self,
+        num_ic: usize
+    ) -> Result<VerifyingKey<E>, SynthesisError>;
+    fn get_gamma_z(
+        &mut self
+    ) -> Result<(E::G1Affine, E::G2Affine), SynthesisError>;
+    fn get_ab_gamma_z_g1(
+        &mut self
+    ) -> Result<E::G1Affine, SynthesisError>;
+    fn get_gamma2_z2_g1(
+        &mut self
+    ) -> Result<E::G1Affine, SynthesisError>;
+    fn get_gamma2_z_t(
+        &mut self,
+        num_gamma2_z_t: usize
+    ) -> Result<Self::G1Builder, SynthesisError>;
+    fn get_a_g1(
+        &mut self,
+        num_inputs: usize,
+        num_aux: usize
+    ) -> Result<(Self::G1Builder, Self::G1Builder), SynthesisError>;
+    fn get_a_g2(
+        &mut self,
+        num_inputs: usize,
+        num_aux: usize
+    ) -> Result<(Self::G2Builder, Self::G2Builder), SynthesisError>;
+    fn get_c_1_g1(
+        &mut self,
+        num_c_1: usize
+    ) -> Result<Self::G1Builder, SynthesisError>;
+    fn get_c_2_g1(
+        &mut self,
+        num_inputs: usize,
+        num_aux: usize
+    ) -> Result<(Self::G1Builder, Self::G1Builder), SynthesisError>;
+}
+
+impl<'a, E: Engine> ParameterSource<E> for &'a Parameters<E> {
+    type G1Builder = (Arc<Vec<E::G1Affine>>, usize);
+    type G2Builder = (Arc<Vec<E::G2Affine>>, usize);
+
+    fn get_vk(
+        &mut self,
+        _: usize
+    ) -> Result<VerifyingKey<E>, SynthesisError>
+    {
+        Ok(self.vk.clone())
+    }
+
+    fn get_gamma_z(
+        &mut self
+    ) -> Result<(E::G1Affine, E::G2Affine), SynthesisError>
+    {
+        Ok((self.gamma_z, self.gamma_z_g2))
+    }
+
+    fn get_ab_gamma_z_g1(
+        &mut self
+    ) -> Result<E::G1Affine, SynthesisError>
+    {
+        Ok(self.ab_gamma_z_g1)
+    }
+
+    fn get_gamma2_z2_g1(
+        &mut self
+    ) -> Result<E::G1Affine, SynthesisError>
+    {
+        Ok(self.gamma2_z2_g1)
+    }
+
+    fn get_gamma2_z_t(
+        &mut self,
+        _: usize
+    ) -> Result<Self::G1Builder, SynthesisError>
+    {
+        Ok((self.gamma2_z_t.clone(), 0))
+    }
+
+    fn get_a_g1(
+        &mut self,
+        num_inputs: usize,
+        _: usize
+    ) -> Result<(Self::G1Builder, Self::G1Builder), SynthesisError>
+    {
+        Ok(((self.a_g1.clone(), 0), (self.a_g1.clone(), num_inputs)))
+    }
+
+    fn get_a_g2(
+        &mut self,
+        num_inputs: usize,
+        _: usize
+    ) -> Result<(Self::G2Builder, Self::G2Builder), SynthesisError>
+    {
+        Ok(((self.a_g2.clone(), 0), (self.a_g2.clone(), num_inputs)))
+    }
+
+    fn get_c_1_g1(
+        &mut self,
+        _: usize
+    ) -> Result<Self::G1Builder, SynthesisError>
+    {
+        Ok((self.c_1_g1.clone(), 0))
+    }
+
+    fn get_c_2_g1(
+        &mut self,
+        num_inputs: usize,
+        _: usize
+    ) -> Result<(Self::G1Builder, Self::G1Builder), SynthesisError>
+    {
+        Ok(((self.c_2_g1.clone(), 0), (self.c_2_g1.clone(), num_inputs)))
+    }
+}
+
+#[cfg(test)]
+mod test_with_bls12_381 {
+    use super::*;
+    use crate::{Circuit, SynthesisError, ConstraintSystem};
+
+    use rand::{Rand, thread_rng};
+    use crate::pairing::ff::{Field};
+    use crate::pairing::bls12_381::{Bls12, Fr};
+
+    #[test]
+    fn serialization() {
+        struct MySillyCircuit<E: Engine> {
+            a: Option<E::Fr>,
+            b: Option<E::Fr>
+        }
+
+        impl<E: Engine> Circuit<E> for MySillyCircuit<E> {
+            fn synthesize<CS: ConstraintSystem<E>>(
+                self,
+                cs: &mut CS
+            ) -> Result<(), SynthesisError>
+            {
+                let a = cs.alloc(|| "a", || self.a.ok_or(SynthesisError::AssignmentMissing))?;
+                let b = cs.alloc(|| "b", || self.b.ok_or(SynthesisError::AssignmentMissing))?;
+                let c = cs.alloc_input(|| "c", || {
+                    let mut a = self.a.ok_or(SynthesisError::AssignmentMissing)?;
+                    let b = self.b.ok_or(SynthesisError::AssignmentMissing)?;
+
+                    a.mul_assign(&b);
+                    Ok(a)
+                })?;
+
+                cs.enforce(
+                    || "a*b=c",
+                    |lc| lc + a,
+                    |lc| lc + b,
+                    |lc| lc + c
+                );
+
+                Ok(())
+            }
+        }
+
+        let rng = &mut thread_rng();
+
+        let params = generate_random_parameters::<Bls12, _, _>(
+            MySillyCircuit { a: None, b: None },
+            rng
+        ).unwrap();
+
+        {
+            let mut v = vec![];
+
+            params.write(&mut v).unwrap();
+            assert_eq!(v.len(), 4056);
+
+            let de_params = Parameters::read(&v[..], true).unwrap();
+            assert!(params == de_params);
+
+            let de_params = Parameters::read(&v[..], false).unwrap();
+            assert!(params == de_params);
+        }
+
+        let pvk = prepare_verifying_key::<Bls12>(&params.vk);
+
+        for _ in 0..10 {
+            let a = Fr::rand(rng);
+            let b = Fr::rand(rng);
+            let mut c = a;
+            c.mul_assign(&b);
+
+            let proof = create_random_proof(
+                MySillyCircuit {
+                    a: Some(a),
+                    b: Some(b)
+                },
+                &params,
+                rng
+            ).unwrap();
+
+            let mut v = vec![];
+            proof.write(&mut v).unwrap();
+
+            assert_eq!(v.len(), 192);
+
+            let de_proof = Proof::read(&v[..]).unwrap();
+            assert!(proof == de_proof);
+
+            assert!(verify_proof(&pvk, &proof, &[c]).unwrap());
+            assert!(!verify_proof(&pvk, &proof, &[a]).unwrap());
+        }
+    }
+}
@@ -0,0 +1,343 @@
+use crate::log::Stopwatch;
+
+use rand::Rng;
+
+use std::sync::Arc;
+
+use crate::pairing::{
+    Engine,
+    CurveProjective,
+    CurveAffine
+};
+
+use crate::pairing::ff::{
+    PrimeField,
+    Field
+};
+
+use super::{
+    ParameterSource,
+    Proof
+};
+
+use crate::{
+    SynthesisError,
+    Circuit,
+    ConstraintSystem,
+    LinearCombination,
+    Variable,
+    Index
+};
+
+use crate::domain::{
+    EvaluationDomain,
+    Scalar
+};
+
+use crate::source::{
+    DensityTracker,
+    FullDensity
+};
+
+use crate::multiexp::*;
+
+use crate::groth16::{
+    field_elements_into_representations,
+    scalars_into_representations
+};
+
+use crate::worker::{
+    Worker
+};
+
+fn eval<E: Engine>(
+    lc: &LinearCombination<E>,
+    mut aux_density: Option<&mut DensityTracker>,
+    input_assignment: &[E::Fr],
+    aux_assignment: &[E::Fr]
+) -> E::Fr
+{
+    let mut acc = E::Fr::zero();
+
+    for &(index, coeff) in lc.0.iter() {
+        let mut tmp = match index {
+            Variable(Index::Input(i)) => input_assignment[i],
+            Variable(Index::Aux(i)) => {
+                if let Some(ref mut v) = aux_density {
+                    v.inc(i);
+                }
+
+                aux_assignment[i]
+            }
+        };
+
+        if coeff == E::Fr::one() {
+           acc.add_assign(&tmp);
+        } else {
+           tmp.mul_assign(&coeff);
+           acc.add_assign(&tmp);
+        }
+    }
+
+    acc
+}
+
+/// Witness of the SAP made by the same reduction as in the generator.
+/// Every R1CS constraint <a,x>*<b,x> = <c,x> gets an extra variable y and becomes
+/// <a - b,x> * <a - b,x> = y
+/// <a + b,x> * <a + b,x> = 4*<c,x> + y
+struct ProvingAssignment<E: Engine> {
+    // Density of queries over A polynomials
+    a_aux_density: DensityTracker,
+
+    // Evaluations of A and C polynomials
+    a: Vec<Scalar<E>>,
+    c: Vec<Scalar<E>>,
+
+    // Assignments of variables
+    input_assignment: Vec<E::Fr>,
+    aux_assignment: Vec<E::Fr>
+}
+
+impl<E: Engine> ConstraintSystem<E> for ProvingAssignment<E> {
+    type Root = Self;
+
+    fn alloc<F, A, AR>(
+        &mut self,
+        _: A,
+        f: F
+    ) -> Result<Variable, SynthesisError>
+        where F: FnOnce() -> Result<E::Fr, SynthesisError>, A: FnOnce() -> AR, AR: Into<String>
+    {
+        self.aux_assignment.push(f()?);
+        self.a_aux_density.add_element();
+
+        Ok(Variable(Index::Aux(self.aux_assignment.len() - 1)))
+    }
+
+    fn alloc_input<F, A, AR>(
+        &mut self,
+        _: A,
+        f: F
+    ) -> Result<Variable, SynthesisError>
+        where F: FnOnce() -> Result<E::Fr, SynthesisError>, A: FnOnce() -> AR, AR: Into<String>
+    {
+        self.input_assignment.push(f()?);
+
+        Ok(Variable(Index::Input(self.input_assignment.len() - 1)))
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(
+        &mut self,
+        _: A,
+        a: LA,
+        b: LB,
+        c: LC
+    )
+        where A: FnOnce() -> AR, AR: Into<String>,
+              LA: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+              LB: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+              LC: FnOnce(LinearCombination<E>) -> LinearCombination<E>
+    {
+        // Inputs have full density in the A query
+        // because each input is constrained.
+        let a = eval(&a(LinearCombination::zero()), Some(&mut self.a_aux_density), &self.input_assignment, &self.aux_assignment);
+        let b = eval(&b(LinearCombination::zero()), Some(&mut self.a_aux_density), &self.input_assignment, &self.aux_assignment);
+        let c = eval(&c(LinearCombination::zero()), None, &self.input_assignment, &self.aux_assignment);
+
+        // <a - b,x> * <a - b,x> = y
+        let mut a_minus_b = a;
+        a_minus_b.sub_assign(&b);
+
+        let mut y = a_minus_b;
+        y.square();
+
+        // allocated in the same place as in the generator
+        self.aux_assignment.push(y);
+        self.a_aux_density.add_element();
+
+        self.a.push(Scalar(a_minus_b));
+        self.c.push(Scalar(y));
+
+        // <a + b,x> * <a + b,x> = 4*<c,x> + y
+        let mut a_plus_b = a;
+        a_plus_b.add_assign(&b);
+
+        let mut c_quadrupled = c;
+        c_quadrupled.double();
+        c_quadrupled.double();
+        c_quadrupled.add_assign(&y);
+
+        self.a.push(Scalar(a_plus_b));
+        self.c.push(Scalar(c_quadrupled));
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+        where NR: Into<String>, N: FnOnce() -> NR
+    {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    fn pop_namespace(&mut self)
+    {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+}
+
+pub fn create_random_proof<E, C, R, P: ParameterSource<E>>(
+    circuit: C,
+    params: P,
+    rng: &mut R
+) -> Result<Proof<E>, SynthesisError>
+    where E: Engine, C: Circuit<E>, R: Rng
+{
+    let r = rng.gen();
+
+    create_proof::<E, C, P>(circuit, params, r)
+}
+
+pub fn create_proof<E, C, P: ParameterSource<E>>(
+    circuit: C,
+    mut params: P,
+    r: E::Fr
+) -> Result<Proof<E>, SynthesisError>
+    where E: Engine, C: Circuit<E>
+{
+    let mut prover = ProvingAssignment {
+        a_aux_density: DensityTracker::new(),
+        a: vec![],
+        c: vec![],
+        input_assignment: vec![],
+        aux_assignment: vec![]
+    };
+
+    prover.alloc_input(|| "", || Ok(E::Fr::one()))?;
+
+    circuit.synthesize(&mut prover)?;
+
+    // input 0 (identity) is constrained as 1*1=1
+    prover.a.push(Scalar(E::Fr::one()));
+    prover.c.push(Scalar(E::Fr::one()));
+
+    // other inputs are constrained as x_i * 1 = x_i
+    for i in 1..prover.input_assignment.len() {
+        prover.enforce(|| "",
+            |lc| lc + Variable(Index::Input(i)),
+            |lc| lc + Variable(Index::Input(0)),
+            |lc| lc + Variable(Index::Input(i)),
+        );
+    }
+
+    let worker = Worker::new();
+
+    let (gamma_z, gamma_z_g2) = params.get_gamma_z()?;
+
+    if gamma_z.is_zero() || gamma_z_g2.is_zero() {
+        // If this element is zero, someone is trying to perform a
+        // subversion-CRS attack.
+        return Err(SynthesisError::UnexpectedIdentity);
+    }
+
+    let _stopwatch = Stopwatch::new();
+
+    let h = {
+        let mut a = EvaluationDomain::from_coeffs(prover.a)?;
+        let mut c = EvaluationDomain::from_coeffs(prover.c)?;
+        elog_verbose!("H query domain size is {}", a.as_ref().len());
+
+        a.ifft(&worker);
+        a.coset_fft(&worker);
+        c.ifft(&worker);
+        c.coset_fft(&worker);
+
+        // do A*A-C in coset
+        {
+            let a = a.as_mut();
+            worker.scope(a.len(), |scope, chunk| {
+                for a in a.chunks_mut(chunk) {
+                    scope.spawn(move |_| {
+                        for a in a.iter_mut() {
+                            a.0.square();
+                        }
+                    });
+                }
+            });
+        }
+        a.sub_assign(&worker, &c);
+        drop(c);
+        a.divide_by_z_on_coset(&worker);
+        a.icoset_fft(&worker);
+        let mut a = a.into_coeffs();
+        let a_len = a.len() - 1;
+        a.truncate(a_len);
+
+        let a = Arc::new(scalars_into_representations::<E>(&worker, a)?);
+
+        multiexp(&worker, params.get_gamma2_z_t(a.len())?, FullDensity, a)
+    };
+
+    elog_verbose!("{} seconds for prover for H evaluation (mostly FFT)", _stopwatch.elapsed());
+
+    let _stopwatch = Stopwatch::new();
+
+    let input_assignment = Arc::new(field_elements_into_representations::<E>(&worker, prover.input_assignment)?);
+    let aux_assignment = Arc::new(field_elements_into_representations::<E>(&worker, prover.aux_assignment)?);
+
+    let a_aux_density = Arc::new(prover.a_aux_density);
+    let a_aux_density_total = a_aux_density.get_total_density();
+
+    let (a_g1_inputs_source, a_g1_aux_source) = params.get_a_g1(input_assignment.len(), a_aux_density_total)?;
+
+    let a_g1_inputs = multiexp(&worker, a_g1_inputs_source, FullDensity, input_assignment.clone());
+    let a_g1_aux = multiexp(&worker, a_g1_aux_source, a_aux_density.clone(), aux_assignment.clone());
+
+    let (a_g2_inputs_source, a_g2_aux_source) = params.get_a_g2(input_assignment.len(), a_aux_density_total)?;
+
+    let a_g2_inputs = multiexp(&worker, a_g2_inputs_source, FullDensity, input_assignment.clone());
+    let a_g2_aux = multiexp(&worker, a_g2_aux_source, a_aux_density.clone(), aux_assignment.clone());
+
+    let (c_2_g1_inputs_source, c_2_g1_aux_source) = params.get_c_2_g1(input_assignment.len(), a_aux_density_total)?;
+
+    let c_2_g1_inputs = multiexp(&worker, c_2_g1_inputs_source, FullDensity, input_assignment);
+    let c_2_g1_aux = multiexp(&worker, c_2_g1_aux_source, a_aux_density, aux_assignment.clone());
+
+    let c_1_g1 = multiexp(&worker, params.get_c_1_g1(aux_assignment.len())?, FullDensity, aux_assignment);
+
+    // A = G1^{gamma * (r * Z(t) + A(t))}
+    let mut g_a = gamma_z.mul(r);
+    g_a.add_assign(&a_g1_inputs.wait()?);
+    g_a.add_assign(&a_g1_aux.wait()?);
+
+    // B = G2^{gamma * (r * Z(t) + A(t))}
+    let mut g_b = gamma_z_g2.mul(r);
+    g_b.add_assign(&a_g2_inputs.wait()?);
+    g_b.add_assign(&a_g2_aux.wait()?);
+
+    // C = G1^{r^2 * gamma^2 * Z(t)^2 + r * (alpha + beta) * gamma * Z(t) +
+    //         r * 2 * gamma^2 * Z(t) * A(t) + gamma^2 * C_aux(t) +
+    //         (alpha + beta) * gamma * A_aux(t) + gamma^2 * Z(t) * H(t)}
+    let mut r_squared = r;
+    r_squared.square();
+
+    let mut g_c = params.get_gamma2_z2_g1()?.mul(r_squared);
+    g_c.add_assign(&params.get_ab_gamma_z_g1()?.mul(r));
+
+    let mut c_2_answer = c_2_g1_inputs.wait()?;
+    c_2_answer.add_assign(&c_2_g1_aux.wait()?);
+    c_2_answer.mul_assign(r);
+    g_c.add_assign(&c_2_answer);
+    g_c.add_assign(&c_1_g1.wait()?);
+    g_c.add_assign(&h.wait()?);
+
+    elog_verbose!("{} seconds for prover for point multiplication", _stopwatch.elapsed());
+
+    Ok(Proof {
+        a: g_a.into_affine(),
+        b: g_b.into_affine(),
+        c: g_c.into_affine()
+    })
+}
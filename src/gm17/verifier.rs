@@ -24,7 +24,7 @@ pub fn prepare_verifying_key<E: Engine>(
         h_pc: vk.h_g2.prepare(),
         g_alpha: vk.alpha_g1.clone(),
         h_beta: vk.beta_g2.clone(),
-        g_alpha_h_beta_ml: E::pairing(vk.alpha_g1, vk.beta_g2),
+        g_alpha_h_beta_ml: E::miller_loop([(&vk.alpha_g1.prepare(), &vk.beta_g2.prepare())].iter()),
         g_gamma_pc: vk.gamma_g1.prepare(),
         h_gamma_pc: vk.gamma_g2.prepare(),
         query: vk.ic.clone(),
@@ -0,0 +1,349 @@
+//! Polynomial commitments with the inner product argument of Bulletproofs,
+//! as used in Halo. A polynomial of degree below 2^k is committed as a
+//! multiexponentiation of its coefficients with 2^k generators of unknown
+//! discrete logarithm, so no trusted setup is needed.
+//!
+//! Opening proofs reduce the commitment round by round and the verifier is
+//! left with a single commitment G to the polynomial
+//! g(X) = \prod\limits_{j=0}^{k-1} (u_j^{-1} + u_j X^{2^{k-1-j}}),
+//! where u_j are the round challenges. Everything except computing G itself
+//! takes logarithmic time, so G and the challenges are kept as an `Accumulator`
+//! and checked later. A later proof can open G at a fresh point together with
+//! its own polynomials, which leaves only the newest accumulator to be checked.
+
+use crate::pairing::ff::{Field, PrimeField};
+
+use crate::SynthesisError;
+
+use super::curves::{CurveAffine, CurveProjective};
+use super::transcript::Transcript;
+use super::util::*;
+
+/// Generators for commitments to polynomials of degree below 2^k
+#[derive(Clone, Debug)]
+pub struct Params<C: CurveAffine> {
+    pub k: usize,
+    pub n: usize,
+    pub g: Vec<C>,
+    // generator for the inner product value
+    pub u: C
+}
+
+impl<C: CurveAffine> Params<C> {
+    pub fn new(k: usize) -> Self {
+        assert!(k < 32);
+        let n = 1 << k;
+
+        let g = (0..n)
+            .map(|i| C::hash_to_curve(b"Halo-Params-G", &(i as u32).to_be_bytes()))
+            .collect();
+        let u = C::hash_to_curve(b"Halo-Params-U", &[]);
+
+        Params { k, n, g, u }
+    }
+
+    /// Commits to a polynomial given by at most 2^k coefficients
+    pub fn commit(&self, coeffs: &[C::Scalar]) -> C {
+        assert!(coeffs.len() <= self.n);
+
+        multiexp(&self.g[0..coeffs.len()], coeffs).into_affine()
+    }
+
+    /// Coefficients of X^{2^k - degree_bound} p(X). If p(X) had a higher degree the
+    /// shifted polynomial would not fit the generators, so committing to it and
+    /// opening both polynomials at the same point shows the degree bound
+    pub fn shift_to_degree_bound(&self, coeffs: &[C::Scalar], degree_bound: usize) -> Vec<C::Scalar> {
+        assert!(coeffs.len() <= degree_bound && degree_bound <= self.n);
+
+        let mut shifted = vec![C::Scalar::zero(); self.n - degree_bound];
+        shifted.extend_from_slice(coeffs);
+
+        shifted
+    }
+}
+
+/// Proof of an evaluation of a committed polynomial
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InnerProductProof<C: CurveAffine> {
+    pub rounds: Vec<(C, C)>,
+    pub a: C::Scalar,
+    pub g: C
+}
+
+/// Deferred part of the verification of one or more inner product arguments:
+/// `g` must be a commitment to g(X) for the given challenges
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Accumulator<C: CurveAffine> {
+    pub g: C,
+    pub challenges: Vec<C::Scalar>
+}
+
+impl<C: CurveAffine> Accumulator<C> {
+    /// Accumulator to start a chain of proofs with, all challenges are one
+    /// so g(X) = 1 + X + ... + X^{2^k - 1}
+    pub fn trivial(params: &Params<C>) -> Self {
+        let mut g = C::Projective::zero();
+        for base in params.g.iter() {
+            g.add_assign_mixed(base);
+        }
+
+        Accumulator {
+            g: g.into_affine(),
+            challenges: vec![C::Scalar::one(); params.k]
+        }
+    }
+
+    /// Coefficients of g(X)
+    pub fn compute_g(&self) -> Result<Vec<C::Scalar>, SynthesisError> {
+        let mut s = vec![C::Scalar::one()];
+        for u in self.challenges.iter().rev() {
+            let u_inv = u.inverse().ok_or(SynthesisError::DivisionByZero)?;
+
+            let mut hi = s.clone();
+            for s in s.iter_mut() {
+                s.mul_assign(&u_inv);
+            }
+            for s in hi.iter_mut() {
+                s.mul_assign(u);
+            }
+            s.extend(hi);
+        }
+
+        Ok(s)
+    }
+
+    /// Evaluates g(X) at a point with a logarithmic number of operations
+    pub fn evaluate(&self, point: C::Scalar) -> Result<C::Scalar, SynthesisError> {
+        let mut acc = C::Scalar::one();
+        let mut point_power = point;
+        for u in self.challenges.iter().rev() {
+            let mut term = *u;
+            term.mul_assign(&point_power);
+            term.add_assign(&u.inverse().ok_or(SynthesisError::DivisionByZero)?);
+            acc.mul_assign(&term);
+
+            point_power.square();
+        }
+
+        Ok(acc)
+    }
+
+    /// Performs the deferred linear time check
+    pub fn check(&self, params: &Params<C>) -> Result<bool, SynthesisError> {
+        if self.challenges.len() != params.k {
+            return Ok(false);
+        }
+
+        Ok(params.commit(&self.compute_g()?) == self.g)
+    }
+}
+
+fn inner_product<F: Field>(a: &[F], b: &[F]) -> F {
+    let mut acc = F::zero();
+    for (a, b) in a.iter().zip(b.iter()) {
+        let mut tmp = *a;
+        tmp.mul_assign(b);
+        acc.add_assign(&tmp);
+    }
+
+    acc
+}
+
+/// Proves that the polynomial committed with `coeffs` evaluates to
+/// the inner product of the coefficients with powers of `point`
+pub(crate) fn create_opening<C: CurveAffine>(
+    params: &Params<C>,
+    transcript: &mut Transcript,
+    mut a: Vec<C::Scalar>,
+    point: C::Scalar
+) -> Result<(InnerProductProof<C>, Accumulator<C>), SynthesisError>
+{
+    a.resize(params.n, C::Scalar::zero());
+
+    // bind the value to a generator chosen after the commitment
+    let xi: C::Scalar = transcript.get_challenge_scalar();
+    let u = params.u.mul(xi).into_affine();
+
+    let mut b = vec![C::Scalar::one(); params.n];
+    mut_distribute_consequitive_powers(&mut b, C::Scalar::one(), point);
+
+    let mut g = params.g.clone();
+
+    let mut rounds = Vec::with_capacity(params.k);
+    let mut challenges = Vec::with_capacity(params.k);
+    for _ in 0..params.k {
+        let half = a.len() / 2;
+
+        let mut l = multiexp(&g[half..], &a[..half]);
+        l.add_assign(&u.mul(inner_product(&a[..half], &b[half..])));
+        let l = l.into_affine();
+
+        let mut r = multiexp(&g[..half], &a[half..]);
+        r.add_assign(&u.mul(inner_product(&a[half..], &b[..half])));
+        let r = r.into_affine();
+
+        transcript.commit_point(&l);
+        transcript.commit_point(&r);
+        rounds.push((l, r));
+
+        let challenge: C::Scalar = transcript.get_challenge_scalar();
+        let challenge_inv = challenge.inverse().ok_or(SynthesisError::DivisionByZero)?;
+        challenges.push(challenge);
+
+        // a' = a_lo * u + a_hi * u^{-1}
+        // b' = b_lo * u^{-1} + b_hi * u
+        // G' = G_lo * u^{-1} + G_hi * u
+        let (a_lo, a_hi) = a.split_at(half);
+        a = a_lo.iter().zip(a_hi.iter()).map(|(lo, hi)| {
+            let mut lo = *lo;
+            lo.mul_assign(&challenge);
+            let mut hi = *hi;
+            hi.mul_assign(&challenge_inv);
+            lo.add_assign(&hi);
+
+            lo
+        }).collect();
+
+        let (b_lo, b_hi) = b.split_at(half);
+        b = b_lo.iter().zip(b_hi.iter()).map(|(lo, hi)| {
+            let mut lo = *lo;
+            lo.mul_assign(&challenge_inv);
+            let mut hi = *hi;
+            hi.mul_assign(&challenge);
+            lo.add_assign(&hi);
+
+            lo
+        }).collect();
+
+        let (g_lo, g_hi) = g.split_at(half);
+        let mut g_projective: Vec<C::Projective> = g_lo.iter().zip(g_hi.iter()).map(|(lo, hi)| {
+            let mut lo = lo.mul(challenge_inv);
+            lo.add_assign(&hi.mul(challenge));
+
+            lo
+        }).collect();
+        C::Projective::batch_normalization(&mut g_projective);
+        g = g_projective.into_iter().map(|g| g.into_affine()).collect();
+    }
+
+    let proof = InnerProductProof {
+        rounds,
+        a: a[0],
+        g: g[0]
+    };
+
+    let accumulator = Accumulator {
+        g: g[0],
+        challenges
+    };
+
+    Ok((proof, accumulator))
+}
+
+/// Checks an opening up to the linear time part, which is returned
+/// as an accumulator. Returns `None` if the proof is invalid.
+pub(crate) fn verify_opening<C: CurveAffine>(
+    params: &Params<C>,
+    transcript: &mut Transcript,
+    commitment: C::Projective,
+    point: C::Scalar,
+    value: C::Scalar,
+    proof: &InnerProductProof<C>
+) -> Result<Option<Accumulator<C>>, SynthesisError>
+{
+    if proof.rounds.len() != params.k {
+        return Ok(None);
+    }
+
+    let xi: C::Scalar = transcript.get_challenge_scalar();
+    let u = params.u.mul(xi).into_affine();
+
+    // P' = P + [v] U
+    let mut p = commitment;
+    p.add_assign(&u.mul(value));
+
+    let mut challenges = Vec::with_capacity(params.k);
+    for &(l, r) in proof.rounds.iter() {
+        transcript.commit_point(&l);
+        transcript.commit_point(&r);
+
+        let challenge: C::Scalar = transcript.get_challenge_scalar();
+        let challenge_inv = challenge.inverse().ok_or(SynthesisError::DivisionByZero)?;
+        challenges.push(challenge);
+
+        // P' = P + [u^2] L + [u^{-2}] R
+        let mut challenge_sq = challenge;
+        challenge_sq.square();
+        let mut challenge_inv_sq = challenge_inv;
+        challenge_inv_sq.square();
+
+        p.add_assign(&l.mul(challenge_sq));
+        p.add_assign(&r.mul(challenge_inv_sq));
+    }
+
+    let accumulator = Accumulator {
+        g: proof.g,
+        challenges
+    };
+
+    // b is folded the same way as G, so it is g(point)
+    let mut ab = proof.a;
+    ab.mul_assign(&accumulator.evaluate(point)?);
+
+    let mut expected = proof.g.mul(proof.a);
+    expected.add_assign(&u.mul(ab));
+
+    if expected == p {
+        Ok(Some(accumulator))
+    } else {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::halo::curves::VestaAffine;
+    use crate::halo::fields::Fq;
+    use rand::{Rand, SeedableRng, XorShiftRng};
+
+    #[test]
+    fn test_inner_product_argument() {
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        let params = Params::<VestaAffine>::new(4);
+
+        let poly: Vec<Fq> = (0..13).map(|_| Fq::rand(rng)).collect();
+        let commitment = params.commit(&poly);
+        let point = Fq::rand(rng);
+        let value = evaluate_at_consequitive_powers(&poly, Fq::one(), point);
+
+        let mut transcript = Transcript::new(&[]);
+        let (proof, accumulator) = create_opening(&params, &mut transcript, poly, point).unwrap();
+        assert!(accumulator.check(&params).unwrap());
+
+        // g(X) is evaluated both ways
+        let g = accumulator.compute_g().unwrap();
+        assert_eq!(
+            evaluate_at_consequitive_powers(&g, Fq::one(), point),
+            accumulator.evaluate(point).unwrap()
+        );
+
+        let verify = |commitment: VestaAffine, value: Fq| {
+            let mut transcript = Transcript::new(&[]);
+            verify_opening(&params, &mut transcript, commitment.into_projective(), point, value, &proof).unwrap()
+        };
+
+        assert_eq!(Some(accumulator.clone()), verify(commitment, value));
+
+        let mut wrong_value = value;
+        wrong_value.add_assign(&Fq::one());
+        assert_eq!(None, verify(commitment, wrong_value));
+        assert_eq!(None, verify(VestaAffine::one(), value));
+
+        assert!(Accumulator::trivial(&params).check(&params).unwrap());
+        let mut wrong_accumulator = accumulator;
+        wrong_accumulator.challenges[0] = Fq::one();
+        assert!(!wrong_accumulator.check(&params).unwrap());
+    }
+}
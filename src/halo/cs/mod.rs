@@ -0,0 +1,166 @@
+use crate::pairing::ff::{Field};
+
+use crate::{SynthesisError};
+
+mod lc;
+pub use self::lc::{Coeff, Variable, LinearCombination};
+
+pub trait Circuit<F: Field> {
+    fn synthesize<CS: ConstraintSystem<F>>(&self, cs: &mut CS) -> Result<(), SynthesisError>;
+}
+
+pub trait ConstraintSystem<F: Field> {
+    const ONE: Variable;
+
+    fn alloc<V>(&mut self, value: V) -> Result<Variable, SynthesisError>
+    where
+        V: FnOnce() -> Result<F, SynthesisError>;
+
+    fn alloc_input<V>(&mut self, value: V) -> Result<Variable, SynthesisError>
+    where
+        V: FnOnce() -> Result<F, SynthesisError>;
+
+    fn enforce_zero(&mut self, lc: LinearCombination<F>);
+
+    fn multiply<V>(&mut self, values: V) -> Result<(Variable, Variable, Variable), SynthesisError>
+    where
+        V: FnOnce() -> Result<(F, F, F), SynthesisError>;
+}
+
+/// Records the multiplication gates and linear constraints of a circuit
+/// in the form used by Sonic:
+///
+/// a_i * b_i = c_i for every gate i = 1..N
+/// \sum\limits_{i=1}^N u_{q,i} a_i + v_{q,i} b_i + w_{q,i} c_i = k_q for q = 1..Q
+///
+/// where k_q is zero unless the q'th constraint binds a public input.
+/// Wire values are only computed if the assembly is created with a witness,
+/// so the verifier never calls the value closures.
+pub(crate) struct Assembly<F: Field> {
+    with_witness: bool,
+
+    pub(crate) a: Vec<F>,
+    pub(crate) b: Vec<F>,
+    pub(crate) c: Vec<F>,
+
+    // gate that has its left wire allocated and the right one still free
+    half_used_gate: Option<usize>,
+
+    pub(crate) constraints: Vec<Vec<(Variable, Coeff<F>)>>,
+
+    // linear constraint and value for every public input
+    pub(crate) inputs: Vec<(usize, F)>
+}
+
+impl<F: Field> Assembly<F> {
+    pub(crate) fn synthesize<C: Circuit<F>>(circuit: &C, with_witness: bool) -> Result<Self, SynthesisError> {
+        let mut assembly = Assembly {
+            with_witness,
+            a: vec![],
+            b: vec![],
+            c: vec![],
+            half_used_gate: None,
+            constraints: vec![],
+            inputs: vec![]
+        };
+
+        let one = assembly.alloc_input(|| Ok(F::one()))?;
+
+        match (one, <Self as ConstraintSystem<F>>::ONE) {
+            (Variable::A(1), Variable::A(1)) => {},
+            _ => panic!("one variable is incorrect")
+        }
+
+        circuit.synthesize(&mut assembly)?;
+
+        Ok(assembly)
+    }
+
+    /// Number of multiplication gates
+    pub(crate) fn n(&self) -> usize {
+        self.a.len()
+    }
+
+    fn value<V>(&self, value: V) -> Result<F, SynthesisError>
+        where V: FnOnce() -> Result<F, SynthesisError>
+    {
+        if self.with_witness {
+            value()
+        } else {
+            Ok(F::zero())
+        }
+    }
+}
+
+impl<F: Field> ConstraintSystem<F> for Assembly<F> {
+    const ONE: Variable = Variable::A(1);
+
+    fn alloc<V>(&mut self, value: V) -> Result<Variable, SynthesisError>
+    where
+        V: FnOnce() -> Result<F, SynthesisError>
+    {
+        let value = self.value(value)?;
+
+        match self.half_used_gate.take() {
+            Some(index) => {
+                let mut product = self.a[index - 1];
+                product.mul_assign(&value);
+                self.b[index - 1] = value;
+                self.c[index - 1] = product;
+
+                Ok(Variable::B(index))
+            },
+            None => {
+                // b = 0 and c = 0 until the right wire is allocated
+                self.a.push(value);
+                self.b.push(F::zero());
+                self.c.push(F::zero());
+                let index = self.n();
+                self.half_used_gate = Some(index);
+
+                Ok(Variable::A(index))
+            }
+        }
+    }
+
+    fn alloc_input<V>(&mut self, value: V) -> Result<Variable, SynthesisError>
+    where
+        V: FnOnce() -> Result<F, SynthesisError>
+    {
+        let mut input_value = None;
+        let var = self.alloc(|| {
+            let value = value()?;
+            input_value = Some(value);
+
+            Ok(value)
+        })?;
+
+        self.enforce_zero(LinearCombination::from(var));
+        let q = self.constraints.len();
+        self.inputs.push((q, input_value.unwrap_or(F::zero())));
+
+        Ok(var)
+    }
+
+    fn enforce_zero(&mut self, lc: LinearCombination<F>) {
+        self.constraints.push(lc.as_ref().to_vec());
+    }
+
+    fn multiply<V>(&mut self, values: V) -> Result<(Variable, Variable, Variable), SynthesisError>
+    where
+        V: FnOnce() -> Result<(F, F, F), SynthesisError>
+    {
+        let (a, b, c) = if self.with_witness {
+            values()?
+        } else {
+            (F::zero(), F::zero(), F::zero())
+        };
+
+        self.a.push(a);
+        self.b.push(b);
+        self.c.push(c);
+        let index = self.n();
+
+        Ok((Variable::A(index), Variable::B(index), Variable::C(index)))
+    }
+}
@@ -0,0 +1,17 @@
+//! Pallas curve, defined over the native field `Fq` with the scalar field `Fp`.
+
+use crate::pairing::ff::{BitIterator, Field, PrimeField, PrimeFieldRepr, SqrtField};
+use crate::pairing::GroupDecodingError;
+use crate::halo::fields::{Fp, Fq};
+use byteorder::{BigEndian, ByteOrder};
+
+use super::{CurveAffine, CurveProjective, EncodedPoint};
+
+curve_impl!(
+    "Pallas",
+    Pallas,
+    PallasAffine,
+    PallasCompressed,
+    Fq,
+    Fp
+);
@@ -0,0 +1,17 @@
+//! Vesta curve, defined over the field `Fp` with the scalar field `Fq`.
+
+use crate::pairing::ff::{BitIterator, Field, PrimeField, PrimeFieldRepr, SqrtField};
+use crate::pairing::GroupDecodingError;
+use crate::halo::fields::{Fp, Fq};
+use byteorder::{BigEndian, ByteOrder};
+
+use super::{CurveAffine, CurveProjective, EncodedPoint};
+
+curve_impl!(
+    "Vesta",
+    Vesta,
+    VestaAffine,
+    VestaCompressed,
+    Fp,
+    Fq
+);
@@ -1,5 +1,1065 @@
+//! Pallas and Vesta curves, y^2 = x^3 + 5 over `Fq` and `Fp` respectively.
+//! Group order of each curve is the size of the base field of the other one,
+//! so the curves form a cycle: scalars of Pallas are native to Vesta and vice versa.
+//! Both curves have a cofactor of one, so any point on the curve is in the prime order group.
+//!
+//! | Curve  | Base field | Scalar field |
+//! |--------|------------|--------------|
+//! | Pallas | `Fq`, p = 0x40000000000000000000000000000000224698fc094cf91b992d30ed00000001 | `Fp`, q = 0x40000000000000000000000000000000224698fc0994a8dd8c46eb2100000001 |
+//! | Vesta  | `Fp`, q    | `Fq`, p      |
+//!
+//! The curves, moduli and generators (-1, 2) are the ones of the Pasta reference
+//! (https://github.com/zcash/pasta), but the field names are swapped: the reference
+//! calls the base field of Pallas `Fp`, here it is `Fq`.
+
+use crate::pairing::ff::{PrimeField, SqrtField};
+use crate::pairing::GroupDecodingError;
+
+use std::fmt;
+
+/// Projective representation of a point of a curve in the cycle
+pub trait CurveProjective:
+    PartialEq
+    + Eq
+    + Sized
+    + Copy
+    + Clone
+    + Send
+    + Sync
+    + fmt::Debug
+    + fmt::Display
+    + rand::Rand
+    + 'static
+{
+    type Scalar: PrimeField + SqrtField;
+    type Base: PrimeField + SqrtField;
+    type Affine: CurveAffine<Projective = Self, Scalar = Self::Scalar, Base = Self::Base>;
+
+    /// Returns the additive identity.
+    fn zero() -> Self;
+
+    /// Returns a fixed generator of unknown exponent.
+    fn one() -> Self;
+
+    /// Determines if this point is the point at infinity.
+    fn is_zero(&self) -> bool;
+
+    /// Normalizes a slice of projective elements so that
+    /// conversion to affine is cheap.
+    fn batch_normalization(v: &mut [Self]);
+
+    /// Checks if the point is already "normalized" so that
+    /// cheap affine conversion is possible.
+    fn is_normalized(&self) -> bool;
+
+    /// Doubles this element.
+    fn double(&mut self);
+
+    /// Adds another element to this element.
+    fn add_assign(&mut self, other: &Self);
+
+    /// Subtracts another element from this element.
+    fn sub_assign(&mut self, other: &Self) {
+        let mut tmp = *other;
+        tmp.negate();
+        self.add_assign(&tmp);
+    }
+
+    /// Adds an affine element to this element.
+    fn add_assign_mixed(&mut self, other: &Self::Affine);
+
+    /// Negates this element.
+    fn negate(&mut self);
+
+    /// Performs scalar multiplication of this element.
+    fn mul_assign<S: Into<<Self::Scalar as PrimeField>::Repr>>(&mut self, other: S);
+
+    /// Converts this element into its affine representation.
+    fn into_affine(&self) -> Self::Affine;
+}
+
+/// Affine representation of a point of a curve in the cycle
+pub trait CurveAffine:
+    Copy + Clone + Sized + Send + Sync + fmt::Debug + fmt::Display + PartialEq + Eq + 'static
+{
+    type Scalar: PrimeField + SqrtField;
+    type Base: PrimeField + SqrtField;
+    type Projective: CurveProjective<Affine = Self, Scalar = Self::Scalar, Base = Self::Base>;
+    type Compressed: EncodedPoint<Affine = Self>;
+
+    /// Returns the additive identity.
+    fn zero() -> Self;
+
+    /// Returns a fixed generator of unknown exponent.
+    fn one() -> Self;
+
+    /// Determines if this point represents the point at infinity; the
+    /// additive identity.
+    fn is_zero(&self) -> bool;
+
+    /// Negates this element.
+    fn negate(&mut self);
+
+    /// Performs scalar multiplication of this element with mixed addition.
+    fn mul<S: Into<<Self::Scalar as PrimeField>::Repr>>(&self, other: S) -> Self::Projective;
+
+    /// Converts this element into its projective representation.
+    fn into_projective(&self) -> Self::Projective;
+
+    /// Converts this element into its compressed encoding.
+    fn into_compressed(&self) -> Self::Compressed {
+        <Self::Compressed as EncodedPoint>::from_affine(*self)
+    }
+
+    /// Returns references to underlying X and Y coordinates. Users should check for infinity
+    /// outside of this call
+    fn as_xy(&self) -> (&Self::Base, &Self::Base);
+
+    /// Creates a point from raw X and Y coordinates. Point of infinity is encoded as (0,0).
+    /// On-curve check is performed
+    fn from_xy_checked(x: Self::Base, y: Self::Base) -> Result<Self, GroupDecodingError>;
+
+    /// Deterministically maps a message into a point of unknown discrete logarithm.
+    /// Candidate x coordinates are blake2s(len(domain) || domain || message || counter)
+    /// with the highest bit selecting the lexicographically largest y, and the first one
+    /// on the curve is taken. Running time depends on the input, so it is only meant for
+    /// public values such as commitment generators
+    fn hash_to_curve(domain: &[u8], message: &[u8]) -> Self;
+}
+
+/// Compressed point encoding: x coordinate as a 32 byte big endian integer
+/// with the highest bit set if the lexicographically largest y is used.
+/// The point at infinity is encoded as all zeroes, there is no point
+/// with x = 0 since 5 is not a square in either field
+pub trait EncodedPoint:
+    Sized + Send + Sync + AsRef<[u8]> + AsMut<[u8]> + Clone + Copy + 'static
+{
+    type Affine: CurveAffine;
+
+    /// Creates an empty representation.
+    fn empty() -> Self;
+
+    /// Returns the number of bytes consumed by this representation.
+    fn size() -> usize;
+
+    /// Converts an `EncodedPoint` into a `CurveAffine` element,
+    /// if the encoding represents a valid element.
+    fn into_affine(&self) -> Result<Self::Affine, GroupDecodingError>;
+
+    /// Creates an `EncodedPoint` from an affine point.
+    fn from_affine(affine: Self::Affine) -> Self;
+}
+
+macro_rules! curve_impl {
+    (
+        $name:expr,
+        $projective:ident,
+        $affine:ident,
+        $compressed:ident,
+        $basefield:ident,
+        $scalarfield:ident
+    ) => {
+        #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+        pub struct $affine {
+            pub(crate) x: $basefield,
+            pub(crate) y: $basefield,
+            pub(crate) infinity: bool
+        }
+
+        impl ::std::fmt::Display for $affine
+        {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                if self.infinity {
+                    write!(f, "{}(Infinity)", $name)
+                } else {
+                    write!(f, "{}(x={}, y={})", $name, self.x, self.y)
+                }
+            }
+        }
+
+        #[derive(Copy, Clone, Debug, Eq)]
+        pub struct $projective {
+           pub(crate) x: $basefield,
+           pub(crate) y: $basefield,
+           pub(crate) z: $basefield
+        }
+
+        impl ::std::fmt::Display for $projective
+        {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                write!(f, "{}", self.into_affine())
+            }
+        }
+
+        impl PartialEq for $projective {
+            fn eq(&self, other: &$projective) -> bool {
+                if self.is_zero() {
+                    return other.is_zero();
+                }
+
+                if other.is_zero() {
+                    return false;
+                }
+
+                // The points (X, Y, Z) and (X', Y', Z')
+                // are equal when (X * Z^2) = (X' * Z'^2)
+                // and (Y * Z^3) = (Y' * Z'^3).
+
+                let mut z1 = self.z;
+                z1.square();
+                let mut z2 = other.z;
+                z2.square();
+
+                let mut tmp1 = self.x;
+                tmp1.mul_assign(&z2);
+
+                let mut tmp2 = other.x;
+                tmp2.mul_assign(&z1);
+
+                if tmp1 != tmp2 {
+                    return false;
+                }
+
+                z1.mul_assign(&self.z);
+                z2.mul_assign(&other.z);
+                z2.mul_assign(&self.y);
+                z1.mul_assign(&other.y);
+
+                if z1 != z2 {
+                    return false;
+                }
+
+                true
+            }
+        }
+
+        impl $affine {
+            fn mul_bits<S: AsRef<[u64]>>(&self, bits: BitIterator<S>) -> $projective {
+                let mut res = $projective::zero();
+                for i in bits {
+                    res.double();
+                    if i { res.add_assign_mixed(self) }
+                }
+                res
+            }
+
+            fn get_coeff_b() -> $basefield {
+                $basefield::from_str("5").unwrap()
+            }
+
+            fn get_generator() -> $affine {
+                // (-1, 2) is on the curve since (-1)^3 + 5 = 4
+                let mut x = $basefield::one();
+                x.negate();
+
+                $affine {
+                    x,
+                    y: $basefield::from_str("2").unwrap(),
+                    infinity: false
+                }
+            }
+
+            /// Attempts to construct an affine point given an x-coordinate.
+            ///
+            /// If and only if `greatest` is set will the lexicographically
+            /// largest y-coordinate be selected.
+            fn get_point_from_x(x: $basefield, greatest: bool) -> Option<$affine> {
+                // Compute x^3 + b
+                let mut x3b = x;
+                x3b.square();
+                x3b.mul_assign(&x);
+                x3b.add_assign(&$affine::get_coeff_b());
+
+                x3b.sqrt().map(|y| {
+                    let mut negy = y;
+                    negy.negate();
+
+                    $affine {
+                        x,
+                        y: if (y < negy) ^ greatest {
+                            y
+                        } else {
+                            negy
+                        },
+                        infinity: false
+                    }
+                })
+            }
+
+            fn is_on_curve(&self) -> bool {
+                if self.is_zero() {
+                    true
+                } else {
+                    // Check that the point is on the curve
+                    let mut y2 = self.y;
+                    y2.square();
+
+                    let mut x3b = self.x;
+                    x3b.square();
+                    x3b.mul_assign(&self.x);
+                    x3b.add_assign(&Self::get_coeff_b());
+
+                    y2 == x3b
+                }
+            }
+        }
+
+        impl CurveAffine for $affine {
+            type Scalar = $scalarfield;
+            type Base = $basefield;
+            type Projective = $projective;
+            type Compressed = $compressed;
+
+            fn zero() -> Self {
+                $affine {
+                    x: $basefield::zero(),
+                    y: $basefield::zero(),
+                    infinity: true
+                }
+            }
+
+            fn one() -> Self {
+                Self::get_generator()
+            }
+
+            fn is_zero(&self) -> bool {
+                self.infinity
+            }
+
+            fn mul<S: Into<<Self::Scalar as PrimeField>::Repr>>(&self, by: S) -> $projective {
+                let bits = BitIterator::new(by.into());
+                self.mul_bits(bits)
+            }
+
+            fn negate(&mut self) {
+                if !self.is_zero() {
+                    self.y.negate();
+                }
+            }
+
+            fn into_projective(&self) -> $projective {
+                (*self).into()
+            }
+
+            fn as_xy(&self) -> (&Self::Base, &Self::Base) {
+                (&self.x, &self.y)
+            }
+
+            fn from_xy_checked(x: Self::Base, y: Self::Base) -> Result<Self, GroupDecodingError> {
+                let infinity = x.is_zero() && y.is_zero();
+                let affine = $affine {
+                    x,
+                    y,
+                    infinity
+                };
+
+                if !affine.is_on_curve() {
+                    Err(GroupDecodingError::NotOnCurve)
+                } else {
+                    Ok(affine)
+                }
+            }
+
+            fn hash_to_curve(domain: &[u8], message: &[u8]) -> Self {
+                let mut domain_len = [0u8; 4];
+                BigEndian::write_u32(&mut domain_len, domain.len() as u32);
+
+                let mut counter = 0u32;
+                loop {
+                    let mut counter_bytes = [0u8; 4];
+                    BigEndian::write_u32(&mut counter_bytes, counter);
+                    counter += 1;
+
+                    let mut hasher = blake2s_simd::State::new();
+                    hasher.update(&domain_len);
+                    hasher.update(domain);
+                    hasher.update(message);
+                    hasher.update(&counter_bytes);
+                    let mut hash = [0u8; 32];
+                    hash.copy_from_slice(hasher.finalize().as_bytes());
+
+                    let greatest = hash[0] & (1 << 7) != 0;
+                    hash[0] &= 0x7f;
+
+                    let mut repr = <$basefield as PrimeField>::Repr::default();
+                    repr.read_be(&hash[..]).expect("must read 32 bytes");
+
+                    if let Ok(x) = $basefield::from_repr(repr) {
+                        if let Some(point) = $affine::get_point_from_x(x, greatest) {
+                            return point;
+                        }
+                    }
+                }
+            }
+        }
+
+        impl CurveProjective for $projective {
+            type Scalar = $scalarfield;
+            type Base = $basefield;
+            type Affine = $affine;
+
+            // The point at infinity is always represented by
+            // Z = 0.
+            fn zero() -> Self {
+                $projective {
+                    x: $basefield::zero(),
+                    y: $basefield::one(),
+                    z: $basefield::zero()
+                }
+            }
+
+            fn one() -> Self {
+                $affine::one().into()
+            }
+
+            // The point at infinity is always represented by
+            // Z = 0.
+            fn is_zero(&self) -> bool {
+                self.z.is_zero()
+            }
+
+            fn is_normalized(&self) -> bool {
+                self.is_zero() || self.z == $basefield::one()
+            }
+
+            fn batch_normalization(v: &mut [Self])
+            {
+                // Montgomery’s Trick and Fast Implementation of Masked AES
+                // Genelle, Prouff and Quisquater
+                // Section 3.2
+
+                // First pass: compute [a, ab, abc, ...]
+                let mut prod = Vec::with_capacity(v.len());
+                let mut tmp = $basefield::one();
+                for g in v.iter_mut()
+                          // Ignore normalized elements
+                          .filter(|g| !g.is_normalized())
+                {
+                    tmp.mul_assign(&g.z);
+                    prod.push(tmp);
+                }
+
+                // Invert `tmp`.
+                tmp = tmp.inverse().unwrap(); // Guaranteed to be nonzero.
+
+                // Second pass: iterate backwards to compute inverses
+                for (g, s) in v.iter_mut()
+                               // Backwards
+                               .rev()
+                               // Ignore normalized elements
+                               .filter(|g| !g.is_normalized())
+                               // Backwards, skip last element, fill in one for last term.
+                               .zip(prod.into_iter().rev().skip(1).chain(Some($basefield::one())))
+                {
+                    // tmp := tmp * g.z; g.z := tmp * s = 1/z
+                    let mut newtmp = tmp;
+                    newtmp.mul_assign(&g.z);
+                    g.z = tmp;
+                    g.z.mul_assign(&s);
+                    tmp = newtmp;
+                }
+
+                // Perform affine transformations
+                for g in v.iter_mut()
+                          .filter(|g| !g.is_normalized())
+                {
+                    let mut z = g.z; // 1/z
+                    z.square(); // 1/z^2
+                    g.x.mul_assign(&z); // x/z^2
+                    z.mul_assign(&g.z); // 1/z^3
+                    g.y.mul_assign(&z); // y/z^3
+                    g.z = $basefield::one(); // z = 1
+                }
+            }
+
+            fn double(&mut self) {
+                if self.is_zero() {
+                    return;
+                }
+
+                // No points on the curve can double to equal the point at infinity,
+                // as y=0 is never true for points on the curve of prime order.
+
+                // http://www.hyperelliptic.org/EFD/g1p/auto-shortw-jacobian-0.html#doubling-dbl-2009-l
+
+                // A = X1^2
+                let mut a = self.x;
+                a.square();
+
+                // B = Y1^2
+                let mut b = self.y;
+                b.square();
+
+                // C = B^2
+                let mut c = b;
+                c.square();
+
+                // D = 2*((X1+B)2-A-C)
+                let mut d = self.x;
+                d.add_assign(&b);
+                d.square();
+                d.sub_assign(&a);
+                d.sub_assign(&c);
+                d.double();
+
+                // E = 3*A
+                let mut e = a;
+                e.double();
+                e.add_assign(&a);
+
+                // F = E^2
+                let mut f = e;
+                f.square();
+
+                // Z3 = 2*Y1*Z1
+                self.z.mul_assign(&self.y);
+                self.z.double();
+
+                // X3 = F-2*D
+                self.x = f;
+                self.x.sub_assign(&d);
+                self.x.sub_assign(&d);
+
+                // Y3 = E*(D-X3)-8*C
+                self.y = d;
+                self.y.sub_assign(&self.x);
+                self.y.mul_assign(&e);
+                c.double();
+                c.double();
+                c.double();
+                self.y.sub_assign(&c);
+            }
+
+            fn add_assign(&mut self, other: &Self) {
+                if self.is_zero() {
+                    *self = *other;
+                    return;
+                }
+
+                if other.is_zero() {
+                    return;
+                }
+
+                // http://www.hyperelliptic.org/EFD/g1p/auto-shortw-jacobian-0.html#addition-add-2007-bl
+
+                // Z1Z1 = Z1^2
+                let mut z1z1 = self.z;
+                z1z1.square();
+
+                // Z2Z2 = Z2^2
+                let mut z2z2 = other.z;
+                z2z2.square();
+
+                // U1 = X1*Z2Z2
+                let mut u1 = self.x;
+                u1.mul_assign(&z2z2);
+
+                // U2 = X2*Z1Z1
+                let mut u2 = other.x;
+                u2.mul_assign(&z1z1);
+
+                // S1 = Y1*Z2*Z2Z2
+                let mut s1 = self.y;
+                s1.mul_assign(&other.z);
+                s1.mul_assign(&z2z2);
+
+                // S2 = Y2*Z1*Z1Z1
+                let mut s2 = other.y;
+                s2.mul_assign(&self.z);
+                s2.mul_assign(&z1z1);
+
+                if u1 == u2 && s1 == s2 {
+                    // The two points are equal, so we double.
+                    self.double();
+                } else if u1 == u2 {
+                    // The two points are opposite, so the sum is the point at infinity.
+                    (*self) = Self::zero();
+                } else {
+                    // H = U2-U1
+                    let mut h = u2;
+                    h.sub_assign(&u1);
+
+                    // I = (2*H)^2
+                    let mut i = h;
+                    i.double();
+                    i.square();
+
+                    // J = H*I
+                    let mut j = h;
+                    j.mul_assign(&i);
+
+                    // r = 2*(S2-S1)
+                    let mut r = s2;
+                    r.sub_assign(&s1);
+                    r.double();
+
+                    // V = U1*I
+                    let mut v = u1;
+                    v.mul_assign(&i);
+
+                    // X3 = r^2 - J - 2*V
+                    self.x = r;
+                    self.x.square();
+                    self.x.sub_assign(&j);
+                    self.x.sub_assign(&v);
+                    self.x.sub_assign(&v);
+
+                    // Y3 = r*(V - X3) - 2*S1*J
+                    self.y = v;
+                    self.y.sub_assign(&self.x);
+                    self.y.mul_assign(&r);
+                    s1.mul_assign(&j); // S1 = S1 * J * 2
+                    s1.double();
+                    self.y.sub_assign(&s1);
+
+                    // Z3 = ((Z1+Z2)^2 - Z1Z1 - Z2Z2)*H
+                    self.z.add_assign(&other.z);
+                    self.z.square();
+                    self.z.sub_assign(&z1z1);
+                    self.z.sub_assign(&z2z2);
+                    self.z.mul_assign(&h);
+                }
+            }
+
+            fn add_assign_mixed(&mut self, other: &Self::Affine) {
+                if other.is_zero() {
+                    return;
+                }
+
+                if self.is_zero() {
+                    self.x = other.x;
+                    self.y = other.y;
+                    self.z = $basefield::one();
+                    return;
+                }
+
+                // http://www.hyperelliptic.org/EFD/g1p/auto-shortw-jacobian-0.html#addition-madd-2007-bl
+
+                // Z1Z1 = Z1^2
+                let mut z1z1 = self.z;
+                z1z1.square();
+
+                // U2 = X2*Z1Z1
+                let mut u2 = other.x;
+                u2.mul_assign(&z1z1);
+
+                // S2 = Y2*Z1*Z1Z1
+                let mut s2 = other.y;
+                s2.mul_assign(&self.z);
+                s2.mul_assign(&z1z1);
+
+                if self.x == u2 && self.y == s2 {
+                    // The two points are equal, so we double.
+                    self.double();
+                } else if self.x == u2 {
+                    // The two points are opposite, so the sum is the point at infinity.
+                    (*self) = Self::zero();
+                } else {
+                    // H = U2-X1
+                    let mut h = u2;
+                    h.sub_assign(&self.x);
+
+                    // HH = H^2
+                    let mut hh = h;
+                    hh.square();
+
+                    // I = 4*HH
+                    let mut i = hh;
+                    i.double();
+                    i.double();
+
+                    // J = H*I
+                    let mut j = h;
+                    j.mul_assign(&i);
+
+                    // r = 2*(S2-Y1)
+                    let mut r = s2;
+                    r.sub_assign(&self.y);
+                    r.double();
+
+                    // V = X1*I
+                    let mut v = self.x;
+                    v.mul_assign(&i);
+
+                    // X3 = r^2 - J - 2*V
+                    self.x = r;
+                    self.x.square();
+                    self.x.sub_assign(&j);
+                    self.x.sub_assign(&v);
+                    self.x.sub_assign(&v);
+
+                    // Y3 = r*(V-X3)-2*Y1*J
+                    j.mul_assign(&self.y); // J = 2*Y1*J
+                    j.double();
+                    self.y = v;
+                    self.y.sub_assign(&self.x);
+                    self.y.mul_assign(&r);
+                    self.y.sub_assign(&j);
+
+                    // Z3 = (Z1+H)^2-Z1Z1-HH
+                    self.z.add_assign(&h);
+                    self.z.square();
+                    self.z.sub_assign(&z1z1);
+                    self.z.sub_assign(&hh);
+                }
+            }
+
+            fn negate(&mut self) {
+                if !self.is_zero() {
+                    self.y.negate()
+                }
+            }
+
+            fn mul_assign<S: Into<<Self::Scalar as PrimeField>::Repr>>(&mut self, other: S) {
+                let mut res = Self::zero();
+
+                let mut found_one = false;
+
+                for i in BitIterator::new(other.into())
+                {
+                    if found_one {
+                        res.double();
+                    } else {
+                        found_one = i;
+                    }
+
+                    if i {
+                        res.add_assign(self);
+                    }
+                }
+
+                *self = res;
+            }
+
+            fn into_affine(&self) -> $affine {
+                (*self).into()
+            }
+        }
+
+        impl ::rand::Rand for $projective {
+            fn rand<R: ::rand::Rng>(rng: &mut R) -> Self {
+                let mut point = $projective::one();
+                point.mul_assign($scalarfield::rand(rng));
+
+                point
+            }
+        }
+
+        // The affine point X, Y is represented in the jacobian
+        // coordinates with Z = 1.
+        impl From<$affine> for $projective {
+            fn from(p: $affine) -> $projective {
+                if p.is_zero() {
+                    $projective::zero()
+                } else {
+                    $projective {
+                        x: p.x,
+                        y: p.y,
+                        z: $basefield::one()
+                    }
+                }
+            }
+        }
+
+        // The projective point X, Y, Z is represented in the affine
+        // coordinates as X/Z^2, Y/Z^3.
+        impl From<$projective> for $affine {
+            fn from(p: $projective) -> $affine {
+                if p.is_zero() {
+                    $affine::zero()
+                } else if p.z == $basefield::one() {
+                    // If Z is one, the point is already normalized.
+                    $affine {
+                        x: p.x,
+                        y: p.y,
+                        infinity: false
+                    }
+                } else {
+                    // Z is nonzero, so it must have an inverse in a field.
+                    let zinv = p.z.inverse().unwrap();
+                    let mut zinv_powered = zinv;
+                    zinv_powered.square();
+
+                    // X/Z^2
+                    let mut x = p.x;
+                    x.mul_assign(&zinv_powered);
+
+                    // Y/Z^3
+                    let mut y = p.y;
+                    zinv_powered.mul_assign(&zinv);
+                    y.mul_assign(&zinv_powered);
+
+                    $affine {
+                        x,
+                        y,
+                        infinity: false
+                    }
+                }
+            }
+        }
+
+        #[derive(Copy, Clone)]
+        pub struct $compressed([u8; 32]);
+
+        impl AsRef<[u8]> for $compressed {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl AsMut<[u8]> for $compressed {
+            fn as_mut(&mut self) -> &mut [u8] {
+                &mut self.0
+            }
+        }
+
+        impl ::std::fmt::Debug for $compressed {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                self.0[..].fmt(f)
+            }
+        }
+
+        impl EncodedPoint for $compressed {
+            type Affine = $affine;
+
+            fn empty() -> Self {
+                $compressed([0; 32])
+            }
+
+            fn size() -> usize {
+                32
+            }
+
+            fn into_affine(&self) -> Result<$affine, GroupDecodingError> {
+                if self.0.iter().all(|b| *b == 0) {
+                    return Ok($affine::zero());
+                }
+
+                let mut copy = self.0;
+
+                // Determine if the intended y coordinate must be greater
+                // lexicographically.
+                let greatest = copy[0] & (1 << 7) != 0;
+
+                // Unset the flag
+                copy[0] &= 0x7f;
+
+                let mut x = <$basefield as PrimeField>::Repr::default();
+                x.read_be(&copy[..]).expect("must read 32 bytes");
+
+                let x = $basefield::from_repr(x)
+                    .map_err(|e| GroupDecodingError::CoordinateDecodingError("x coordinate", e))?;
+
+                $affine::get_point_from_x(x, greatest).ok_or(GroupDecodingError::NotOnCurve)
+            }
+
+            fn from_affine(affine: $affine) -> Self {
+                let mut res = Self::empty();
+
+                if affine.is_zero() {
+                    return res;
+                }
+
+                affine.x.into_repr().write_be(&mut res.0[..]).expect("must write 32 bytes");
+
+                let mut negy = affine.y;
+                negy.negate();
+
+                // Set the flag if y is lexicographically largest
+                if affine.y > negy {
+                    res.0[0] |= 1 << 7;
+                }
+
+                res
+            }
+        }
+    }
+}
+
 mod ec0;
 mod ec1;
 
-pub use ec0::*;
-pub use ec1::*;
+pub use self::ec0::*;
+pub use self::ec1::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pairing::ff::{Field, PrimeFieldRepr};
+    use crate::halo::fields::{Fp, Fq};
+    use rand::{Rand, SeedableRng, XorShiftRng};
+
+    fn curve_tests<G: CurveProjective>() {
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        // Generator is on the curve and round trips through the encoding
+        let one = G::Affine::one();
+        let (x, y) = one.as_xy();
+        assert_eq!(G::Affine::from_xy_checked(*x, *y).unwrap(), one);
+        assert!(G::Affine::from_xy_checked(*x, *x).is_err());
+
+        for _ in 0..10 {
+            let a = G::rand(rng);
+            let b = G::rand(rng);
+            let c = G::rand(rng);
+
+            // doubling is addition to itself
+            let mut doubled = a;
+            doubled.double();
+            let mut added = a;
+            added.add_assign(&a);
+            assert_eq!(doubled, added);
+
+            // addition is associative and mixed addition agrees with it
+            let mut ab_c = a;
+            ab_c.add_assign(&b);
+            ab_c.add_assign(&c);
+            let mut bc = b;
+            bc.add_assign_mixed(&c.into_affine());
+            let mut a_bc = bc;
+            a_bc.add_assign_mixed(&a.into_affine());
+            assert_eq!(ab_c, a_bc);
+
+            // a - a = 0, also with mixed addition
+            let mut zero = a;
+            zero.sub_assign(&a);
+            assert!(zero.is_zero());
+            let mut neg_a = a.into_affine();
+            neg_a.negate();
+            let mut zero = a;
+            zero.add_assign_mixed(&neg_a);
+            assert!(zero.is_zero());
+
+            // scalar multiplication is distributive
+            let s = G::Scalar::rand(rng);
+            let t = G::Scalar::rand(rng);
+            let mut s_plus_t = s;
+            s_plus_t.add_assign(&t);
+            let mut lhs = a;
+            lhs.mul_assign(s_plus_t);
+            let mut rhs = a.into_affine().mul(s);
+            rhs.add_assign(&a.into_affine().mul(t));
+            assert_eq!(lhs, rhs);
+
+            // batch normalization doesn't change points
+            let mut v = vec![a, b, c, G::zero(), ab_c];
+            let expected = v.clone();
+            G::batch_normalization(&mut v);
+            for (normalized, expected) in v.iter().zip(expected.iter()) {
+                assert!(normalized.is_normalized());
+                assert_eq!(normalized, expected);
+            }
+
+            // compressed encoding round trips
+            for p in [a.into_affine(), neg_a, G::Affine::zero()].iter() {
+                let encoded = p.into_compressed();
+                assert_eq!(encoded.as_ref().len(), <G::Affine as CurveAffine>::Compressed::size());
+                assert_eq!(encoded.into_affine().unwrap(), *p);
+            }
+        }
+
+        // x coordinates past the modulus are rejected
+        let mut encoded = <G::Affine as CurveAffine>::Compressed::empty();
+        for b in encoded.as_mut().iter_mut() {
+            *b = 0xff;
+        }
+        assert!(encoded.into_affine().is_err());
+    }
+
+    fn hash_to_curve_tests<G: CurveAffine>() {
+        let a = G::hash_to_curve(b"domain", b"message");
+        assert!(!a.is_zero());
+        assert_eq!(a, G::hash_to_curve(b"domain", b"message"));
+
+        let (x, y) = a.as_xy();
+        assert!(G::from_xy_checked(*x, *y).is_ok());
+
+        // domain is length prefixed, so it can't be shifted into the message
+        assert!(a != G::hash_to_curve(b"domai", b"nmessage"));
+        assert!(a != G::hash_to_curve(b"domain", b"other message"));
+        assert!(a != G::one());
+    }
+
+    fn check_cycle<C0, C1>()
+        where C0: CurveAffine, C1: CurveAffine<Base = C0::Scalar, Scalar = C0::Base>
+    {
+        // each curve has as many points as the other base field has elements
+        assert!(C0::one().mul(C0::Scalar::char()).is_zero());
+        assert!(C1::one().mul(C1::Scalar::char()).is_zero());
+
+        let mut minus_one = C0::Scalar::one();
+        minus_one.negate();
+        let mut expected = C0::one();
+        expected.negate();
+        assert_eq!(C0::one().mul(minus_one).into_affine(), expected);
+
+        let mut minus_one = C1::Scalar::one();
+        minus_one.negate();
+        let mut expected = C1::one();
+        expected.negate();
+        assert_eq!(C1::one().mul(minus_one).into_affine(), expected);
+    }
+
+    #[test]
+    fn test_pallas() {
+        curve_tests::<Pallas>();
+        hash_to_curve_tests::<PallasAffine>();
+    }
+
+    #[test]
+    fn test_vesta() {
+        curve_tests::<Vesta>();
+        hash_to_curve_tests::<VestaAffine>();
+    }
+
+    // big endian hex into a field representation
+    fn repr_from_hex<F: PrimeField>(hex: &str) -> F::Repr {
+        let bytes: Vec<u8> = (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..(i + 2)], 16).unwrap()).collect();
+        let mut repr = F::Repr::default();
+        repr.read_be(&bytes[..]).unwrap();
+
+        repr
+    }
+
+    fn check_reference_curve<C: CurveAffine>(modulus: &str, generator_x: &str) {
+        assert_eq!(C::Base::char(), repr_from_hex::<C::Base>(modulus));
+
+        let generator = C::one();
+        let (x, y) = generator.as_xy();
+        assert_eq!(x.into_repr(), repr_from_hex::<C::Base>(generator_x));
+        assert_eq!(*y, C::Base::from_str("2").unwrap());
+
+        // y^2 = x^3 + 5
+        let mut lhs = *y;
+        lhs.square();
+        let mut rhs = *x;
+        rhs.square();
+        rhs.mul_assign(x);
+        rhs.add_assign(&C::Base::from_str("5").unwrap());
+        assert_eq!(lhs, rhs);
+    }
+
+    // moduli and generators published in https://github.com/zcash/pasta
+    #[test]
+    fn test_pasta_reference_constants() {
+        let p = "40000000000000000000000000000000224698fc094cf91b992d30ed00000001";
+        let q = "40000000000000000000000000000000224698fc0994a8dd8c46eb2100000001";
+
+        // Pallas is defined over p and has q points, Vesta the other way around
+        check_reference_curve::<PallasAffine>(p, "40000000000000000000000000000000224698fc094cf91b992d30ed00000000");
+        check_reference_curve::<VestaAffine>(q, "40000000000000000000000000000000224698fc0994a8dd8c46eb2100000000");
+        assert_eq!(Fq::char(), repr_from_hex::<Fq>(p));
+        assert_eq!(Fp::char(), repr_from_hex::<Fp>(q));
+        assert!(PallasAffine::one().mul(repr_from_hex::<Fp>(q)).is_zero());
+        assert!(VestaAffine::one().mul(repr_from_hex::<Fq>(p)).is_zero());
+    }
+
+    #[test]
+    fn test_pasta_cycle() {
+        check_cycle::<PallasAffine, VestaAffine>();
+
+        // x = 0 is never on the curves, so it is free to encode the point at infinity
+        assert!(Fq::from_str("5").unwrap().sqrt().is_none());
+        assert!(Fp::from_str("5").unwrap().sqrt().is_none());
+    }
+}
@@ -1,10 +1,11 @@
-/// Scalar field of the curve.
+/// Scalar field of the Pallas curve, base field of the Vesta curve. This is the field
+/// called `Fq` in the Pasta reference, q = 0x40000000000000000000000000000000224698fc0994a8dd8c46eb2100000001.
 
 use pairing::ff::{Field, PrimeField, PrimeFieldRepr, SqrtField};
 
 #[derive(PrimeField)]
-#[PrimeFieldModulus = "28948022309329048855892746252171976963322203655955319056773317069363642105857"]
-#[PrimeFieldGenerator = "7"]
+#[PrimeFieldModulus = "28948022309329048855892746252171976963363056481941647379679742748393362948097"]
+#[PrimeFieldGenerator = "5"]
 pub struct Fp(FpRepr);
 
 #[cfg(test)]
@@ -45,6 +46,6 @@ mod tests {
 
     #[test]
     fn fp_roots_of_unity() {
-        assert_eq!(Fp::S, 33);
+        assert_eq!(Fp::S, 32);
     }
 }
@@ -1,9 +1,10 @@
-/// Native, base field of the curve.
+/// Native, base field of the Pallas curve, scalar field of the Vesta curve. This is the field
+/// called `Fp` in the Pasta reference, p = 0x40000000000000000000000000000000224698fc094cf91b992d30ed00000001.
 
 use pairing::ff::{Field, PrimeField, PrimeFieldRepr, SqrtField};
 
 #[derive(PrimeField)]
-#[PrimeFieldModulus = "28948022309329048855892746252171976963322203655954433126947083963168578338817"]
+#[PrimeFieldModulus = "28948022309329048855892746252171976963363056481941560715954676764349967630337"]
 #[PrimeFieldGenerator = "5"]
 pub struct Fq(FqRepr);
 
@@ -45,6 +46,6 @@ mod tests {
 
     #[test]
     fn fq_roots_of_unity() {
-        assert_eq!(Fq::S, 34);
+        assert_eq!(Fq::S, 32);
     }
 }
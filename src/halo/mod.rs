@@ -1,2 +1,60 @@
-mod curves;
-mod fields;
+//! Accumulation of inner product arguments without a trusted setup on the
+//! Pallas/Vesta cycle, following Halo (https://eprint.iacr.org/2019/1021).
+//!
+//! Circuits are arithmetized as in Sonic and polynomials are committed with the
+//! inner product argument (see `commitment`). Verifying a proof is linear in the
+//! circuit size, except for a commitment which is accumulated: every proof opens
+//! the accumulator of the previous step, so checking the final accumulator covers
+//! the openings of the whole chain. Proofs are not zero knowledge.
+//!
+//! This is only the accumulation scheme. Every proof of a chain still has to be
+//! checked with `verify_proof` natively, there is no circuit that verifies the
+//! previous proof on the other curve, so chains are not recursive proofs.
+
+pub mod curves;
+pub mod fields;
+
+pub mod cs;
+mod commitment;
+mod poly;
+mod prover;
+mod transcript;
+mod util;
+mod verifier;
+
+#[cfg(test)]
+mod tests;
+
+pub use self::commitment::{Accumulator, InnerProductProof, Params};
+pub use self::prover::create_proof;
+pub use self::verifier::{verify_final, verify_proof};
+
+use self::curves::CurveAffine;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Proof<C: CurveAffine> {
+    // commitments to negative and positive powers of r(X, 1), which has no constant term
+    pub r_lo: C,
+    pub r_hi: C,
+    // both parts of r(X, 1) shifted to their degree bounds
+    pub r_lo_shifted: C,
+    pub r_hi_shifted: C,
+    // commitments to negative and positive powers of t(X, y),
+    // and the negative part shifted to its degree bound
+    pub t_lo: C,
+    pub t_hi: C,
+    pub t_lo_shifted: C,
+    // both parts of r(z, 1), r(zy, 1) and t(z, y)
+    pub r_lo_z: C::Scalar,
+    pub r_hi_z: C::Scalar,
+    pub r_lo_zy: C::Scalar,
+    pub r_hi_zy: C::Scalar,
+    pub t_lo_z: C::Scalar,
+    pub t_hi_z: C::Scalar,
+    // commitment to the quotient used to open at both z and zy
+    pub q: C,
+    // batched polynomials for z and zy evaluated at x
+    pub z_batch_x: C::Scalar,
+    pub zy_batch_x: C::Scalar,
+    pub opening: InnerProductProof<C>
+}
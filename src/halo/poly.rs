@@ -0,0 +1,104 @@
+use crate::pairing::ff::{Field};
+
+use crate::SynthesisError;
+
+use super::cs::{Assembly, Variable};
+use super::util::*;
+
+/*
+s(X, Y) =   \sum\limits_{i=1}^N u_i(Y) X^{-i}
+          + \sum\limits_{i=1}^N v_i(Y) X^{i}
+          + \sum\limits_{i=1}^N w_i(Y) X^{i+N}
+
+where
+
+    u_i(Y) =        \sum\limits_{q=1}^Q Y^{q+N} u_{i,q}
+    v_i(Y) =        \sum\limits_{q=1}^Q Y^{q+N} v_{i,q}
+    w_i(Y) = -Y^i + -Y^{-i} + \sum\limits_{q=1}^Q Y^{q+N} w_{i,q}
+
+and
+
+k(Y) = \sum\limits_{q=1}^Q Y^{q+N} k_q
+
+*/
+impl<F: Field> Assembly<F> {
+    /// Coefficients of r(X, 1) for powers X^{-2N}, ..., X^{-1} and X^1, ..., X^N,
+    /// there is no constant term
+    pub(crate) fn r_poly(&self) -> (Vec<F>, Vec<F>) {
+        let mut negative = self.b.clone();
+        negative.extend(self.c.iter().cloned());
+        negative.reverse();
+
+        (negative, self.a.clone())
+    }
+
+    /// Coefficients of s(X, y) for powers X^{-1}, ..., X^{-N} and X^1, ..., X^{2N}
+    pub(crate) fn s_poly(&self, y: F) -> Result<(Vec<F>, Vec<F>), SynthesisError> {
+        let n = self.n();
+        let y_inv = y.inverse().ok_or(SynthesisError::DivisionByZero)?;
+
+        let mut u = vec![F::zero(); n];
+        let mut v = vec![F::zero(); n];
+
+        let mut minus_one = F::one();
+        minus_one.negate();
+
+        let mut w = vec![minus_one; n];
+        let mut w_neg = vec![minus_one; n];
+        mut_distribute_consequitive_powers(&mut w[..], y, y);
+        mut_distribute_consequitive_powers(&mut w_neg[..], y_inv, y_inv);
+        add_polynomials(&mut w[..], &w_neg[..]);
+
+        let mut yqn = y.pow([n as u64]);
+        for constraint in self.constraints.iter() {
+            yqn.mul_assign(&y);
+
+            for &(var, coeff) in constraint.iter() {
+                let acc = match var {
+                    Variable::A(index) => &mut u[index - 1],
+                    Variable::B(index) => &mut v[index - 1],
+                    Variable::C(index) => &mut w[index - 1]
+                };
+
+                let mut term = yqn;
+                coeff.multiply(&mut term);
+                acc.add_assign(&term);
+            }
+        }
+
+        v.extend(w);
+
+        Ok((u, v))
+    }
+
+    /// Evaluates s(x, y)
+    pub(crate) fn s_at(&self, x: F, y: F) -> Result<F, SynthesisError> {
+        let x_inv = x.inverse().ok_or(SynthesisError::DivisionByZero)?;
+        let (negative, positive) = self.s_poly(y)?;
+
+        let mut acc = evaluate_at_consequitive_powers(&negative, x_inv, x_inv);
+        acc.add_assign(&evaluate_at_consequitive_powers(&positive, x, x));
+
+        Ok(acc)
+    }
+
+    /// Evaluates k(y) for values of public inputs, including the leading one
+    pub(crate) fn k_at(&self, inputs: &[F], y: F) -> F {
+        assert_eq!(inputs.len(), self.inputs.len());
+
+        let n = self.n();
+        let mut acc = F::zero();
+        for (&(q, _), input) in self.inputs.iter().zip(inputs.iter()) {
+            let mut tmp = y.pow([(q + n) as u64]);
+            tmp.mul_assign(input);
+            acc.add_assign(&tmp);
+        }
+
+        acc
+    }
+
+    /// Values of public inputs, including the leading one
+    pub(crate) fn input_values(&self) -> Vec<F> {
+        self.inputs.iter().map(|&(_, value)| value).collect()
+    }
+}
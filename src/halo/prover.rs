@@ -0,0 +1,202 @@
+use crate::pairing::ff::{Field};
+
+use crate::SynthesisError;
+
+use super::{Proof, Params, Accumulator};
+use super::commitment::create_opening;
+use super::cs::{Assembly, Circuit};
+use super::curves::CurveAffine;
+use super::transcript::Transcript;
+use super::util::*;
+
+/// Proves a step of the computation. The accumulator of the previous step is folded
+/// into the opening of this proof, so only the returned accumulator has to be checked
+/// once a chain of proofs is done.
+pub fn create_proof<C: CurveAffine, S: Circuit<C::Scalar>>(
+    params: &Params<C>,
+    circuit: &S,
+    old_accumulator: &Accumulator<C>
+) -> Result<(Proof<C>, Accumulator<C>), SynthesisError>
+{
+    let assembly = Assembly::synthesize(circuit, true)?;
+    let n = assembly.n();
+
+    // t(X, y) has 4n negative powers
+    if 4 * n > params.n {
+        return Err(SynthesisError::PolynomialDegreeTooLarge);
+    }
+
+    let (r_lo, r_hi) = assembly.r_poly();
+    let r_shifted = (
+        params.shift_to_degree_bound(&r_lo, 2 * n),
+        params.shift_to_degree_bound(&r_hi, n)
+    );
+
+    create_proof_with_r(params, &assembly, old_accumulator, (r_lo, r_hi), r_shifted)
+}
+
+/// Proves a step for the given negative and positive parts of r(X, 1) and the same parts
+/// shifted to their degree bounds. `create_proof` computes them from the witness, tests
+/// use this to check that a malformed r(X, 1) is rejected
+pub(crate) fn create_proof_with_r<C: CurveAffine>(
+    params: &Params<C>,
+    assembly: &Assembly<C::Scalar>,
+    old_accumulator: &Accumulator<C>,
+    (r_lo_poly, r_hi_poly): (Vec<C::Scalar>, Vec<C::Scalar>),
+    (r_lo_shifted_poly, r_hi_shifted_poly): (Vec<C::Scalar>, Vec<C::Scalar>)
+) -> Result<(Proof<C>, Accumulator<C>), SynthesisError>
+{
+    let n = assembly.n();
+
+    let mut transcript = Transcript::new(b"Halo");
+    for input in assembly.input_values().iter() {
+        transcript.commit_scalar(input);
+    }
+    transcript.commit_point(&old_accumulator.g);
+    for challenge in old_accumulator.challenges.iter() {
+        transcript.commit_scalar(challenge);
+    }
+
+    // r(X, 1) = X^{-2n} r_lo(X) + X r_hi(X), r_lo and r_hi are committed
+    // together with copies shifted to the degree bounds 2n and n
+    let r_lo = params.commit(&r_lo_poly);
+    let r_hi = params.commit(&r_hi_poly);
+    let r_lo_shifted = params.commit(&r_lo_shifted_poly);
+    let r_hi_shifted = params.commit(&r_hi_shifted_poly);
+    transcript.commit_point(&r_lo);
+    transcript.commit_point(&r_hi);
+    transcript.commit_point(&r_lo_shifted);
+    transcript.commit_point(&r_hi_shifted);
+
+    let y: C::Scalar = transcript.get_challenge_scalar();
+    let y_inv = y.inverse().ok_or(SynthesisError::DivisionByZero)?;
+
+    // X^{2n} r(X, 1) for powers X^{-2n}...X^{-1}, X^0, X^1...
+    let mut rx1 = r_lo_poly.clone();
+    rx1.push(C::Scalar::zero());
+    rx1.extend(r_hi_poly.iter().cloned());
+
+    // r(X, y) in the same representation
+    let mut rxy = rx1.clone();
+    mut_distribute_consequitive_powers(&mut rxy, y_inv.pow([(2 * n) as u64]), y);
+
+    // r'(X, y) = r(X, y) + s(X, y) for powers X^{-2n}...X^{2n}
+    let mut rxy_prime = rxy;
+    {
+        let (mut s_poly_negative, s_poly_positive) = assembly.s_poly(y)?;
+        let len = std::cmp::max(rxy_prime.len(), 4 * n + 1);
+        rxy_prime.resize(len, C::Scalar::zero());
+
+        s_poly_negative.reverse();
+        add_polynomials(&mut rxy_prime[n..(2 * n)], &s_poly_negative[..]);
+        add_polynomials(&mut rxy_prime[(2 * n + 1)..(4 * n + 1)], &s_poly_positive[..]);
+    }
+
+    // t(X, y) = r(X, 1) r'(X, y) - k(y) for powers X^{-4n}...X^{3n}, the constant term
+    // is k(y) for a satisfied circuit. Negative and positive powers are committed
+    // separately as X^{4n} t_lo(X) and X^{-1} t_hi(X), so t has no constant term
+    // as long as t_lo has degree below 4n.
+    let mut txy = multiply_polynomials(rx1, rxy_prime);
+    let t_hi_poly = txy.split_off(4 * n + 1);
+    txy.truncate(4 * n);
+    let t_lo_poly = txy;
+    let t_lo_shifted_poly = params.shift_to_degree_bound(&t_lo_poly, 4 * n);
+
+    let t_lo = params.commit(&t_lo_poly);
+    let t_hi = params.commit(&t_hi_poly);
+    let t_lo_shifted = params.commit(&t_lo_shifted_poly);
+    transcript.commit_point(&t_lo);
+    transcript.commit_point(&t_hi);
+    transcript.commit_point(&t_lo_shifted);
+
+    let z: C::Scalar = transcript.get_challenge_scalar();
+    let z_inv = z.inverse().ok_or(SynthesisError::DivisionByZero)?;
+    let mut zy = z;
+    zy.mul_assign(&y);
+    let zy_inv = zy.inverse().ok_or(SynthesisError::DivisionByZero)?;
+
+    let r_lo_z = evaluate_at_consequitive_powers(&r_lo_poly, z_inv.pow([(2 * n) as u64]), z);
+    let r_hi_z = evaluate_at_consequitive_powers(&r_hi_poly, z, z);
+    let r_lo_zy = evaluate_at_consequitive_powers(&r_lo_poly, zy_inv.pow([(2 * n) as u64]), zy);
+    let r_hi_zy = evaluate_at_consequitive_powers(&r_hi_poly, zy, zy);
+    let t_lo_z = evaluate_at_consequitive_powers(&t_lo_poly, z_inv.pow([(4 * n) as u64]), z);
+    let t_hi_z = evaluate_at_consequitive_powers(&t_hi_poly, z, z);
+
+    transcript.commit_scalar(&r_lo_z);
+    transcript.commit_scalar(&r_hi_z);
+    transcript.commit_scalar(&r_lo_zy);
+    transcript.commit_scalar(&r_hi_zy);
+    transcript.commit_scalar(&t_lo_z);
+    transcript.commit_scalar(&t_hi_z);
+
+    let zeta: C::Scalar = transcript.get_challenge_scalar();
+    let nu: C::Scalar = transcript.get_challenge_scalar();
+
+    // polynomials opened at z are batched with powers of zeta,
+    // the old accumulator is opened at z as well
+    let mut z_batch = vec![C::Scalar::zero(); params.n];
+    {
+        let mut zeta_power = C::Scalar::one();
+        for poly in [
+            &r_lo_poly,
+            &r_hi_poly,
+            &r_lo_shifted_poly,
+            &r_hi_shifted_poly,
+            &t_lo_poly,
+            &t_hi_poly,
+            &t_lo_shifted_poly,
+            &old_accumulator.compute_g()?
+        ].iter() {
+            mul_add_polynomials(&mut z_batch[0..poly.len()], poly, zeta_power);
+            zeta_power.mul_assign(&zeta);
+        }
+    }
+    let mut zy_batch = vec![C::Scalar::zero(); params.n];
+    mul_add_polynomials(&mut zy_batch[0..r_lo_poly.len()], &r_lo_poly, C::Scalar::one());
+    mul_add_polynomials(&mut zy_batch[0..r_hi_poly.len()], &r_hi_poly, zeta);
+
+    // q(X) = (p_z(X) - p_z(z)) / (X - z) + nu (p_zy(X) - p_zy(zy)) / (X - zy)
+    let mut q_poly = kate_divison(&z_batch, z);
+    mul_add_polynomials(&mut q_poly, &kate_divison(&zy_batch, zy), nu);
+
+    let q = params.commit(&q_poly);
+    transcript.commit_point(&q);
+
+    let x: C::Scalar = transcript.get_challenge_scalar();
+
+    let z_batch_x = evaluate_at_consequitive_powers(&z_batch, C::Scalar::one(), x);
+    let zy_batch_x = evaluate_at_consequitive_powers(&zy_batch, C::Scalar::one(), x);
+    transcript.commit_scalar(&z_batch_x);
+    transcript.commit_scalar(&zy_batch_x);
+
+    let rho: C::Scalar = transcript.get_challenge_scalar();
+
+    // q(X) + rho p_z(X) + rho^2 p_zy(X) is opened at x
+    let mut rho_squared = rho;
+    rho_squared.square();
+    q_poly.resize(params.n, C::Scalar::zero());
+    mul_add_polynomials(&mut q_poly, &z_batch, rho);
+    mul_add_polynomials(&mut q_poly, &zy_batch, rho_squared);
+
+    let (opening, accumulator) = create_opening(params, &mut transcript, q_poly, x)?;
+
+    Ok((Proof {
+        r_lo,
+        r_hi,
+        r_lo_shifted,
+        r_hi_shifted,
+        t_lo,
+        t_hi,
+        t_lo_shifted,
+        r_lo_z,
+        r_hi_z,
+        r_lo_zy,
+        r_hi_zy,
+        t_lo_z,
+        t_hi_z,
+        q,
+        z_batch_x,
+        zy_batch_x,
+        opening
+    }, accumulator))
+}
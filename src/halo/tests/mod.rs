@@ -0,0 +1,186 @@
+use crate::pairing::ff::{Field, PrimeField};
+
+use crate::SynthesisError;
+
+use super::cs::{Assembly, Circuit, ConstraintSystem, LinearCombination};
+use super::curves::{CurveAffine, PallasAffine, VestaAffine};
+use super::fields::{Fp, Fq};
+use super::prover::create_proof_with_r;
+use super::{create_proof, verify_final, verify_proof, Accumulator, Params};
+
+// Advances a counter and multiplies a running product by the new counter value.
+// Public inputs are the old and the new (counter, product) pairs, so the new pair
+// of one step is the old pair of the next one
+struct CounterStep<F: Field> {
+    old: Option<(F, F)>,
+    new: Option<(F, F)>
+}
+
+impl<F: PrimeField> Circuit<F> for CounterStep<F> {
+    fn synthesize<CS: ConstraintSystem<F>>(&self, cs: &mut CS) -> Result<(), SynthesisError> {
+        let old = || self.old.ok_or(SynthesisError::AssignmentMissing);
+        let new = || self.new.ok_or(SynthesisError::AssignmentMissing);
+
+        let old_counter = cs.alloc_input(|| Ok(old()?.0))?;
+        let old_product = cs.alloc_input(|| Ok(old()?.1))?;
+        let new_counter = cs.alloc_input(|| Ok(new()?.0))?;
+        let new_product = cs.alloc_input(|| Ok(new()?.1))?;
+
+        cs.enforce_zero(LinearCombination::from(new_counter) - old_counter - CS::ONE);
+
+        let (a, b, c) = cs.multiply(|| {
+            let (_, old_product) = old()?;
+            let (new_counter, _) = new()?;
+            let mut product = old_product;
+            product.mul_assign(&new_counter);
+
+            Ok((old_product, new_counter, product))
+        })?;
+
+        cs.enforce_zero(LinearCombination::from(a) - old_product);
+        cs.enforce_zero(LinearCombination::from(b) - new_counter);
+        cs.enforce_zero(LinearCombination::from(c) - new_product);
+
+        Ok(())
+    }
+}
+
+fn empty_step<F: Field>() -> CounterStep<F> {
+    CounterStep {
+        old: None,
+        new: None
+    }
+}
+
+// step from (counter, product) to (counter + 1, product * (counter + 1))
+fn counter_step<F: PrimeField>(old: (F, F)) -> (CounterStep<F>, (F, F)) {
+    let mut counter = old.0;
+    counter.add_assign(&F::one());
+    let mut product = old.1;
+    product.mul_assign(&counter);
+    let new = (counter, product);
+
+    (CounterStep { old: Some(old), new: Some(new) }, new)
+}
+
+fn step_inputs<F: Field>(old: (F, F), new: (F, F)) -> [F; 4] {
+    [old.0, old.1, new.0, new.1]
+}
+
+// Every step continues from the state the previous one ended with. Steps are still
+// verified natively, only the openings are accumulated
+fn accumulate_counter<C: CurveAffine>() {
+    let params = Params::<C>::new(5);
+
+    let mut accumulator = Accumulator::trivial(&params);
+    let mut state = (C::Scalar::one(), C::Scalar::one());
+    let mut last = None;
+
+    for _ in 0..5 {
+        let (circuit, next_state) = counter_step::<C::Scalar>(state);
+        let inputs = step_inputs(state, next_state);
+
+        let (proof, new_accumulator) = create_proof(&params, &circuit, &accumulator).unwrap();
+
+        let verified = verify_proof(&params, &proof, &empty_step(), &inputs, &accumulator).unwrap();
+        assert_eq!(Some(new_accumulator.clone()), verified);
+
+        // the proof doesn't continue from another state, doesn't end in another state
+        // and doesn't open another accumulator
+        let mut other_state = state;
+        other_state.1.add_assign(&C::Scalar::one());
+        assert!(verify_proof(&params, &proof, &empty_step(), &step_inputs(other_state, next_state), &accumulator).unwrap().is_none());
+        let mut other_state = next_state;
+        other_state.1.add_assign(&C::Scalar::one());
+        assert!(verify_proof(&params, &proof, &empty_step(), &step_inputs(state, other_state), &accumulator).unwrap().is_none());
+        assert!(verify_proof(&params, &proof, &empty_step(), &inputs, &new_accumulator).unwrap().is_none());
+
+        last = Some((proof, inputs, accumulator));
+        accumulator = new_accumulator;
+        state = next_state;
+    }
+
+    // five steps from (1, 1) end in (6, 6!)
+    assert_eq!(state, (C::Scalar::from_str("6").unwrap(), C::Scalar::from_str("720").unwrap()));
+
+    let (proof, inputs, previous_accumulator) = last.unwrap();
+    assert!(verify_final(&params, &proof, &empty_step(), &inputs, &previous_accumulator).unwrap());
+    assert!(accumulator.check(&params).unwrap());
+
+    // folding an accumulator which doesn't commit to its challenges fails
+    let mut bad_accumulator = accumulator.clone();
+    bad_accumulator.g = C::one();
+    assert!(!bad_accumulator.check(&params).unwrap());
+
+    let (circuit, next_state) = counter_step::<C::Scalar>(state);
+    let (proof, _) = create_proof(&params, &circuit, &bad_accumulator).unwrap();
+    assert!(verify_proof(&params, &proof, &empty_step(), &step_inputs(state, next_state), &bad_accumulator).unwrap().is_none());
+}
+
+#[test]
+fn test_counter_accumulation_on_pallas() {
+    accumulate_counter::<PallasAffine>();
+}
+
+#[test]
+fn test_counter_accumulation_on_vesta() {
+    accumulate_counter::<VestaAffine>();
+}
+
+#[test]
+fn test_unsatisfied_step() {
+    let params = Params::<PallasAffine>::new(5);
+    let accumulator = Accumulator::trivial(&params);
+
+    // the product is not multiplied by the new counter value
+    let old = (Fp::one(), Fp::one());
+    let (mut circuit, new) = counter_step(old);
+    let wrong_new = (new.0, old.1);
+    circuit.new = Some(wrong_new);
+
+    let (proof, _) = create_proof(&params, &circuit, &accumulator).unwrap();
+    assert!(!verify_final(&params, &proof, &empty_step(), &step_inputs(old, wrong_new), &accumulator).unwrap());
+}
+
+// r(X, 1) gets a term at X^{2n+1}. Neither r(X, 1) nor s(X, y) have the power X^{-2n-1},
+// so the constant term of t(X, y) stays the same and every check except the degree
+// bound of r_hi would pass
+#[test]
+fn test_r_above_degree_bound() {
+    let params = Params::<PallasAffine>::new(5);
+    let accumulator = Accumulator::trivial(&params);
+
+    let old = (Fp::one(), Fp::one());
+    let (circuit, new) = counter_step(old);
+    let inputs = step_inputs(old, new);
+    let assembly = Assembly::synthesize(&circuit, true).unwrap();
+    let n = assembly.n();
+
+    let (r_lo, r_hi) = assembly.r_poly();
+    let r_shifted = (
+        params.shift_to_degree_bound(&r_lo, 2 * n),
+        params.shift_to_degree_bound(&r_hi, n)
+    );
+
+    let (proof, _) = create_proof_with_r(&params, &assembly, &accumulator, (r_lo.clone(), r_hi.clone()), r_shifted.clone()).unwrap();
+    assert!(verify_final(&params, &proof, &empty_step(), &inputs, &accumulator).unwrap());
+
+    let mut r_hi_too_long = r_hi;
+    r_hi_too_long.resize(2 * n + 1, Field::zero());
+    r_hi_too_long[2 * n] = Field::one();
+
+    let (proof, _) = create_proof_with_r(&params, &assembly, &accumulator, (r_lo, r_hi_too_long), r_shifted).unwrap();
+    assert!(verify_proof(&params, &proof, &empty_step(), &inputs, &accumulator).unwrap().is_none());
+}
+
+#[test]
+fn test_circuit_too_large() {
+    let params = Params::<VestaAffine>::new(2);
+    let accumulator = Accumulator::trivial(&params);
+
+    let (circuit, _) = counter_step((Fq::one(), Fq::one()));
+    match create_proof(&params, &circuit, &accumulator) {
+        Err(SynthesisError::PolynomialDegreeTooLarge) => {},
+        _ => panic!("circuit must not fit")
+    }
+}
@@ -0,0 +1,70 @@
+use crate::pairing::ff::{PrimeField, PrimeFieldRepr};
+use byteorder::{BigEndian, ByteOrder};
+
+use super::curves::{CurveAffine, EncodedPoint};
+
+/// Rolling blake2s transcript over points of either curve of the cycle.
+/// Points are committed in compressed form and challenges are squeezed
+/// with a nonce until the hash is a canonical field element
+#[derive(Clone)]
+pub struct Transcript {
+    buffer: [u8; 32],
+    repeated_request_nonce: u32
+}
+
+impl Transcript {
+    pub fn new(personalization: &[u8]) -> Self {
+        let mut buffer = [0u8; 32];
+        buffer.copy_from_slice(blake2s_simd::blake2s(personalization).as_bytes());
+
+        Self {
+            buffer,
+            repeated_request_nonce: 0u32
+        }
+    }
+
+    fn commit_bytes(&mut self, personalization: &[u8], bytes: &[u8]) {
+        let mut h = blake2s_simd::State::new();
+        h.update(&self.buffer);
+        h.update(personalization);
+        h.update(bytes);
+
+        self.buffer.copy_from_slice(h.finalize().as_bytes());
+        self.repeated_request_nonce = 0u32;
+    }
+
+    pub fn commit_point<C: CurveAffine>(&mut self, point: &C) {
+        self.commit_bytes(b"point", point.into_compressed().as_ref());
+    }
+
+    pub fn commit_scalar<F: PrimeField>(&mut self, scalar: &F) {
+        let mut v = vec![];
+        scalar.into_repr().write_be(&mut v).unwrap();
+
+        self.commit_bytes(b"scalar", &v);
+    }
+
+    pub fn get_challenge_scalar<F: PrimeField>(&mut self) -> F {
+        let mut nonce = self.repeated_request_nonce;
+        loop {
+            let mut nonce_bytes = [0u8; 4];
+            BigEndian::write_u32(&mut nonce_bytes, nonce);
+
+            let mut h = blake2s_simd::State::new();
+            h.update(&self.buffer);
+            h.update(&nonce_bytes);
+
+            let mut repr = F::Repr::default();
+            repr.read_be(h.finalize().as_bytes()).unwrap();
+
+            if let Ok(result) = F::from_repr(repr) {
+                self.repeated_request_nonce = nonce + 1u32;
+                return result;
+            }
+            if nonce == 0xffffffff {
+                panic!("can not make challenge scalar");
+            }
+            nonce += 1;
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use crate::pairing::ff::{Field, PrimeField, PrimeFieldRepr};
+
+use super::curves::{CurveAffine, CurveProjective};
+
+/// Evaluates a polynomial with coefficients for powers `first_power`, `first_power * base`, ...
+pub fn evaluate_at_consequitive_powers<F: Field>(coeffs: &[F], first_power: F, base: F) -> F {
+    // Horner's rule, then shift by the first power
+    let mut acc = F::zero();
+    for c in coeffs.iter().rev() {
+        acc.mul_assign(&base);
+        acc.add_assign(c);
+    }
+    acc.mul_assign(&first_power);
+
+    acc
+}
+
+/// Multiplies coefficients by `first_power`, `first_power * base`, ...
+pub fn mut_distribute_consequitive_powers<F: Field>(coeffs: &mut [F], first_power: F, base: F) {
+    let mut power = first_power;
+    for c in coeffs.iter_mut() {
+        c.mul_assign(&power);
+        power.mul_assign(&base);
+    }
+}
+
+pub fn add_polynomials<F: Field>(a: &mut [F], b: &[F]) {
+    assert_eq!(a.len(), b.len());
+
+    for (a, b) in a.iter_mut().zip(b.iter()) {
+        a.add_assign(b);
+    }
+}
+
+pub fn mul_add_polynomials<F: Field>(a: &mut [F], b: &[F], c: F) {
+    assert_eq!(a.len(), b.len());
+
+    for (a, b) in a.iter_mut().zip(b.iter()) {
+        let mut tmp = *b;
+        tmp.mul_assign(&c);
+        a.add_assign(&tmp);
+    }
+}
+
+/// Divides a(X) - a(b) by X - b, the remainder is dropped
+pub fn kate_divison<F: Field>(a: &[F], mut b: F) -> Vec<F> {
+    b.negate();
+
+    let mut q = vec![F::zero(); a.len() - 1];
+
+    let mut tmp = F::zero();
+    for (q, r) in q.iter_mut().rev().zip(a.iter().rev()) {
+        let mut lead_coeff = *r;
+        lead_coeff.sub_assign(&tmp);
+        *q = lead_coeff;
+        tmp = lead_coeff;
+        tmp.mul_assign(&b);
+    }
+
+    q
+}
+
+pub fn multiply_polynomials<F: PrimeField>(mut a: Vec<F>, mut b: Vec<F>) -> Vec<F> {
+    let result_len = a.len() + b.len() - 1;
+
+    // Compute the size of our evaluation domain
+    let mut m = 1;
+    let mut exp = 0;
+    while m < result_len {
+        m *= 2;
+        exp += 1;
+
+        if exp >= F::S {
+            panic!("polynomial too large")
+        }
+    }
+
+    // Compute omega, the 2^exp primitive root of unity
+    let mut omega = F::root_of_unity();
+    for _ in exp..F::S {
+        omega.square();
+    }
+
+    // Extend with zeroes
+    a.resize(m, F::zero());
+    b.resize(m, F::zero());
+
+    serial_fft(&mut a[..], &omega, exp);
+    serial_fft(&mut b[..], &omega, exp);
+
+    for (a, b) in a.iter_mut().zip(b.iter()) {
+        a.mul_assign(b);
+    }
+
+    serial_fft(&mut a[..], &omega.inverse().unwrap(), exp);
+
+    a.truncate(result_len);
+
+    let minv = F::from_str(&format!("{}", m))
+        .unwrap()
+        .inverse()
+        .unwrap();
+
+    for a in a.iter_mut() {
+        a.mul_assign(&minv);
+    }
+
+    a
+}
+
+fn serial_fft<F: PrimeField>(a: &mut [F], omega: &F, log_n: u32) {
+    fn bitreverse(mut n: u32, l: u32) -> u32 {
+        let mut r = 0;
+        for _ in 0..l {
+            r = (r << 1) | (n & 1);
+            n >>= 1;
+        }
+        r
+    }
+
+    let n = a.len() as u32;
+    assert_eq!(n, 1 << log_n);
+
+    for k in 0..n {
+        let rk = bitreverse(k, log_n);
+        if k < rk {
+            a.swap(rk as usize, k as usize);
+        }
+    }
+
+    let mut m = 1;
+    for _ in 0..log_n {
+        let w_m = omega.pow([(n / (2 * m)) as u64]);
+
+        let mut k = 0;
+        while k < n {
+            let mut w = F::one();
+            for j in 0..m {
+                let mut t = a[(k + j + m) as usize];
+                t.mul_assign(&w);
+                let mut tmp = a[(k + j) as usize];
+                tmp.sub_assign(&t);
+                a[(k + j + m) as usize] = tmp;
+                a[(k + j) as usize].add_assign(&t);
+                w.mul_assign(&w_m);
+            }
+
+            k += 2 * m;
+        }
+
+        m *= 2;
+    }
+}
+
+/// Bucket method multiexponentiation, bases and scalars are zipped
+pub fn multiexp<C: CurveAffine>(bases: &[C], scalars: &[C::Scalar]) -> C::Projective {
+    assert_eq!(bases.len(), scalars.len());
+
+    let c = if scalars.len() < 32 {
+        3
+    } else {
+        (f64::from(scalars.len() as u32)).ln().ceil() as u32
+    };
+
+    let scalars: Vec<_> = scalars.iter().map(|s| s.into_repr()).collect();
+    let num_bits = <C::Scalar as PrimeField>::NUM_BITS;
+
+    let mut acc = C::Projective::zero();
+    // process windows from the most significant one
+    let num_windows = (num_bits + c - 1) / c;
+    for window in (0..num_windows).rev() {
+        for _ in 0..c {
+            acc.double();
+        }
+
+        let mut buckets = vec![C::Projective::zero(); (1 << c) - 1];
+        for (base, scalar) in bases.iter().zip(scalars.iter()) {
+            let mut scalar = *scalar;
+            scalar.shr(window * c);
+            let index = (scalar.as_ref()[0] % (1 << c)) as usize;
+            if index != 0 {
+                buckets[index - 1].add_assign_mixed(base);
+            }
+        }
+
+        // sum_{i} i * bucket_i
+        let mut running_sum = C::Projective::zero();
+        for bucket in buckets.into_iter().rev() {
+            running_sum.add_assign(&bucket);
+            acc.add_assign(&running_sum);
+        }
+    }
+
+    acc
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::halo::curves::{PallasAffine, Pallas};
+    use crate::halo::fields::Fp;
+    use rand::{Rand, SeedableRng, XorShiftRng};
+
+    #[test]
+    fn test_multiexp() {
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for &size in [1, 5, 40].iter() {
+            let bases: Vec<PallasAffine> = (0..size).map(|_| Pallas::rand(rng).into_affine()).collect();
+            let scalars: Vec<Fp> = (0..size).map(|_| Fp::rand(rng)).collect();
+
+            let mut naive = Pallas::zero();
+            for (base, scalar) in bases.iter().zip(scalars.iter()) {
+                naive.add_assign(&base.mul(*scalar));
+            }
+
+            assert_eq!(naive, multiexp(&bases, &scalars));
+        }
+    }
+
+    #[test]
+    fn test_mul_and_divide() {
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        let a: Vec<Fp> = (0..7).map(|_| Fp::rand(rng)).collect();
+        let b: Vec<Fp> = (0..12).map(|_| Fp::rand(rng)).collect();
+        let x = Fp::rand(rng);
+
+        let product = multiply_polynomials(a.clone(), b.clone());
+        let mut expected = evaluate_at_consequitive_powers(&a, Fp::one(), x);
+        expected.mul_assign(&evaluate_at_consequitive_powers(&b, Fp::one(), x));
+        assert_eq!(expected, evaluate_at_consequitive_powers(&product, Fp::one(), x));
+
+        // (a(X) - a(x)) / (X - x) evaluated at some other point
+        let point = Fp::rand(rng);
+        let quotient = kate_divison(&a, x);
+        let mut lhs = evaluate_at_consequitive_powers(&quotient, Fp::one(), point);
+        let mut tmp = point;
+        tmp.sub_assign(&x);
+        lhs.mul_assign(&tmp);
+        let mut rhs = evaluate_at_consequitive_powers(&a, Fp::one(), point);
+        rhs.sub_assign(&evaluate_at_consequitive_powers(&a, Fp::one(), x));
+        assert_eq!(lhs, rhs);
+    }
+}
@@ -0,0 +1,208 @@
+use crate::pairing::ff::{Field};
+
+use crate::SynthesisError;
+
+use super::{Proof, Params, Accumulator};
+use super::commitment::verify_opening;
+use super::cs::{Assembly, Circuit};
+use super::curves::{CurveAffine, CurveProjective};
+use super::transcript::Transcript;
+
+/// Checks a proof of a step of the computation, deferring the linear time part
+/// of the opening. On success the accumulator that replaces `old_accumulator`
+/// is returned, `None` means the proof is invalid.
+pub fn verify_proof<C: CurveAffine, S: Circuit<C::Scalar>>(
+    params: &Params<C>,
+    proof: &Proof<C>,
+    circuit: &S,
+    public_inputs: &[C::Scalar],
+    old_accumulator: &Accumulator<C>
+) -> Result<Option<Accumulator<C>>, SynthesisError>
+{
+    let assembly = Assembly::synthesize(circuit, false)?;
+    let n = assembly.n();
+
+    if 4 * n > params.n {
+        return Err(SynthesisError::PolynomialDegreeTooLarge);
+    }
+
+    let mut inputs = vec![C::Scalar::one()];
+    inputs.extend_from_slice(public_inputs);
+
+    if inputs.len() != assembly.inputs.len() {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+
+    if old_accumulator.challenges.len() != params.k {
+        return Ok(None);
+    }
+
+    let mut transcript = Transcript::new(b"Halo");
+    for input in inputs.iter() {
+        transcript.commit_scalar(input);
+    }
+    transcript.commit_point(&old_accumulator.g);
+    for challenge in old_accumulator.challenges.iter() {
+        transcript.commit_scalar(challenge);
+    }
+
+    transcript.commit_point(&proof.r_lo);
+    transcript.commit_point(&proof.r_hi);
+    transcript.commit_point(&proof.r_lo_shifted);
+    transcript.commit_point(&proof.r_hi_shifted);
+    let y: C::Scalar = transcript.get_challenge_scalar();
+
+    transcript.commit_point(&proof.t_lo);
+    transcript.commit_point(&proof.t_hi);
+    transcript.commit_point(&proof.t_lo_shifted);
+    let z: C::Scalar = transcript.get_challenge_scalar();
+    let z_inv = z.inverse().ok_or(SynthesisError::DivisionByZero)?;
+    let mut zy = z;
+    zy.mul_assign(&y);
+    let zy_inv = zy.inverse().ok_or(SynthesisError::DivisionByZero)?;
+
+    transcript.commit_scalar(&proof.r_lo_z);
+    transcript.commit_scalar(&proof.r_hi_z);
+    transcript.commit_scalar(&proof.r_lo_zy);
+    transcript.commit_scalar(&proof.r_hi_zy);
+    transcript.commit_scalar(&proof.t_lo_z);
+    transcript.commit_scalar(&proof.t_hi_z);
+
+    // t(z, y) = r(z, 1) (r(z, y) + s(z, y)) - k(y), where r(z, y) = r(zy, 1)
+    {
+        let mut t = proof.t_lo_z;
+        t.add_assign(&proof.t_hi_z);
+
+        let mut rz = proof.r_lo_z;
+        rz.add_assign(&proof.r_hi_z);
+
+        let mut expected = proof.r_lo_zy;
+        expected.add_assign(&proof.r_hi_zy);
+        expected.add_assign(&assembly.s_at(z, y)?);
+        expected.mul_assign(&rz);
+        expected.sub_assign(&assembly.k_at(&inputs, y));
+
+        if t != expected {
+            return Ok(None);
+        }
+    }
+
+    let zeta: C::Scalar = transcript.get_challenge_scalar();
+    let nu: C::Scalar = transcript.get_challenge_scalar();
+
+    // Values of committed polynomials, which are shifted to have no negative powers.
+    // Copies shifted to the degree bounds must evaluate to the same values
+    // multiplied by the shift, which is what bounds the degrees.
+    let mut r_lo_at_z = proof.r_lo_z;
+    r_lo_at_z.mul_assign(&z.pow([(2 * n) as u64]));
+    let mut r_hi_at_z = proof.r_hi_z;
+    r_hi_at_z.mul_assign(&z_inv);
+    let mut r_lo_shifted_at_z = r_lo_at_z;
+    r_lo_shifted_at_z.mul_assign(&z.pow([(params.n - 2 * n) as u64]));
+    let mut r_hi_shifted_at_z = r_hi_at_z;
+    r_hi_shifted_at_z.mul_assign(&z.pow([(params.n - n) as u64]));
+    let mut t_lo_at_z = proof.t_lo_z;
+    t_lo_at_z.mul_assign(&z.pow([(4 * n) as u64]));
+    let mut t_hi_at_z = proof.t_hi_z;
+    t_hi_at_z.mul_assign(&z_inv);
+    let mut t_lo_shifted_at_z = t_lo_at_z;
+    t_lo_shifted_at_z.mul_assign(&z.pow([(params.n - 4 * n) as u64]));
+
+    let mut r_lo_at_zy = proof.r_lo_zy;
+    r_lo_at_zy.mul_assign(&zy.pow([(2 * n) as u64]));
+    let mut r_hi_at_zy = proof.r_hi_zy;
+    r_hi_at_zy.mul_assign(&zy_inv);
+
+    // batch of both parts of r, t, their shifted copies and the old accumulator at z
+    let mut z_batch = C::Projective::zero();
+    let mut z_batch_value = C::Scalar::zero();
+    {
+        let mut zeta_power = C::Scalar::one();
+        for &(commitment, value) in [
+            (proof.r_lo, r_lo_at_z),
+            (proof.r_hi, r_hi_at_z),
+            (proof.r_lo_shifted, r_lo_shifted_at_z),
+            (proof.r_hi_shifted, r_hi_shifted_at_z),
+            (proof.t_lo, t_lo_at_z),
+            (proof.t_hi, t_hi_at_z),
+            (proof.t_lo_shifted, t_lo_shifted_at_z),
+            (old_accumulator.g, old_accumulator.evaluate(z)?)
+        ].iter() {
+            z_batch.add_assign(&commitment.mul(zeta_power));
+
+            let mut value = value;
+            value.mul_assign(&zeta_power);
+            z_batch_value.add_assign(&value);
+
+            zeta_power.mul_assign(&zeta);
+        }
+    }
+
+    // batch of both parts of r at zy
+    let mut zy_batch = proof.r_lo.into_projective();
+    zy_batch.add_assign(&proof.r_hi.mul(zeta));
+    let mut zy_batch_value = r_hi_at_zy;
+    zy_batch_value.mul_assign(&zeta);
+    zy_batch_value.add_assign(&r_lo_at_zy);
+
+    transcript.commit_point(&proof.q);
+    let x: C::Scalar = transcript.get_challenge_scalar();
+
+    transcript.commit_scalar(&proof.z_batch_x);
+    transcript.commit_scalar(&proof.zy_batch_x);
+    let rho: C::Scalar = transcript.get_challenge_scalar();
+
+    // q(x) = (p_z(x) - p_z(z)) / (x - z) + nu (p_zy(x) - p_zy(zy)) / (x - zy)
+    let mut q_at_x = {
+        let mut num = proof.z_batch_x;
+        num.sub_assign(&z_batch_value);
+        let mut den = x;
+        den.sub_assign(&z);
+        num.mul_assign(&den.inverse().ok_or(SynthesisError::DivisionByZero)?);
+
+        let mut num_zy = proof.zy_batch_x;
+        num_zy.sub_assign(&zy_batch_value);
+        let mut den = x;
+        den.sub_assign(&zy);
+        num_zy.mul_assign(&den.inverse().ok_or(SynthesisError::DivisionByZero)?);
+        num_zy.mul_assign(&nu);
+
+        num.add_assign(&num_zy);
+
+        num
+    };
+
+    let mut rho_squared = rho;
+    rho_squared.square();
+
+    let mut commitment = proof.q.into_projective();
+    z_batch.mul_assign(rho);
+    commitment.add_assign(&z_batch);
+    zy_batch.mul_assign(rho_squared);
+    commitment.add_assign(&zy_batch);
+
+    let mut tmp = proof.z_batch_x;
+    tmp.mul_assign(&rho);
+    q_at_x.add_assign(&tmp);
+    let mut tmp = proof.zy_batch_x;
+    tmp.mul_assign(&rho_squared);
+    q_at_x.add_assign(&tmp);
+
+    verify_opening(params, &mut transcript, commitment, x, q_at_x, &proof.opening)
+}
+
+/// Fully verifies the last proof of a chain, including the linear time check
+/// of the accumulator that all previous openings were folded into
+pub fn verify_final<C: CurveAffine, S: Circuit<C::Scalar>>(
+    params: &Params<C>,
+    proof: &Proof<C>,
+    circuit: &S,
+    public_inputs: &[C::Scalar],
+    old_accumulator: &Accumulator<C>
+) -> Result<bool, SynthesisError>
+{
+    match verify_proof(params, proof, circuit, public_inputs, old_accumulator)? {
+        Some(accumulator) => accumulator.check(params),
+        None => Ok(false)
+    }
+}
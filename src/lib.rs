@@ -39,6 +39,15 @@ cfg_if! {
     }
 }
 
+#[cfg(any(feature = "plonk", feature = "sonic"))]
+pub mod shplonk;
+
+#[cfg(any(feature = "plonk", feature = "sonic"))]
+pub mod transcript;
+
+#[cfg(any(feature = "plonk", feature = "sonic"))]
+mod serialization;
+
 mod group;
 pub mod source;
 mod multiexp;
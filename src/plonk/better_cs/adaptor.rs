@@ -3,7 +3,6 @@ use crate::pairing::{Engine};
 
 use crate::SynthesisError;
 
-use super::test_assembly::Gate;
 use crate::plonk::cs::gates::Variable as PlonkVariable;
 use crate::plonk::cs::gates::Index as PlonkIndex;
 
@@ -1835,7 +1834,7 @@ fn transpile_xor_using_new_adaptor() {
 
     let mut assembly = TestAssembly::<Bn256>::new();
     adapted_curcuit.synthesize(&mut assembly).expect("sythesize of transpiled into CS must succeed");
-    assert!(assembly.is_satisfied());
+    assert!(assembly.is_satisfied(false));
     let num_gates = assembly.num_gates();
     println!("Transpiled into {} gates", num_gates);
     // assembly.finalize();
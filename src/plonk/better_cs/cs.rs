@@ -1,10 +1,13 @@
-use crate::pairing::ff::{Field};
+use crate::pairing::ff::{Field, PrimeField};
 use crate::pairing::{Engine};
 
 use crate::{SynthesisError};
 use std::marker::PhantomData;
+use std::fmt::Debug;
+use std::sync::Arc;
 
 pub use crate::plonk::cs::variable::*;
+pub use super::lookup::{LookupTable, LOOKUP_TABLE_WIDTH};
 
 pub trait Circuit<E: Engine, G: Copy + Clone + PartialEq + Eq> {
     fn synthesize<CS: ConstraintSystem<E, GateCoefficients = G>>(&self, cs: &mut CS) -> Result<(), SynthesisError>;
@@ -32,3 +35,174 @@ pub trait ConstraintSystem<E: Engine> {
 
     fn get_dummy_variable(&self) -> Variable;
 }
+
+pub const STATE_WIDTH: usize = 4;
+
+// main gate is always registered first
+pub const MAIN_GATE_ID: usize = 0;
+
+/// Polynomial relation between state wires of a single row and the wire `d` of the next row,
+/// parametrized by per-row constants that become setup polynomials. Every term must be zero
+/// on the rows where this gate is selected
+pub trait GateEquation<F: PrimeField>: Send + Sync + Debug {
+    fn name(&self) -> &'static str;
+
+    // degree of every term as a polynomial in wires and constants
+    fn degree(&self) -> usize;
+
+    fn num_constants(&self) -> usize;
+
+    fn num_terms(&self) -> usize {
+        1
+    }
+
+    fn uses_next_row(&self) -> bool;
+
+    fn evaluate_term(&self, term: usize, wires: &[F; STATE_WIDTH], d_next: F, constants: &[F]) -> F;
+}
+
+/// q_a * a + q_b * b + q_c * c + q_d * d + q_m * a * b + q_const + q_d_next * d_next = 0
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Width4MainGate;
+
+impl<F: PrimeField> GateEquation<F> for Width4MainGate {
+    fn name(&self) -> &'static str {
+        "main gate of width 4"
+    }
+
+    fn degree(&self) -> usize {
+        3
+    }
+
+    fn num_constants(&self) -> usize {
+        7
+    }
+
+    fn uses_next_row(&self) -> bool {
+        true
+    }
+
+    fn evaluate_term(&self, _term: usize, wires: &[F; STATE_WIDTH], d_next: F, constants: &[F]) -> F {
+        let mut res = constants[5];
+
+        for (wire, constant) in wires.iter().zip(constants[0..4].iter()) {
+            let mut tmp = *wire;
+            tmp.mul_assign(constant);
+            res.add_assign(&tmp);
+        }
+
+        let mut tmp = wires[0];
+        tmp.mul_assign(&wires[1]);
+        tmp.mul_assign(&constants[4]);
+        res.add_assign(&tmp);
+
+        let mut tmp = d_next;
+        tmp.mul_assign(&constants[6]);
+        res.add_assign(&tmp);
+
+        res
+    }
+}
+
+/// Single row of the width 4 assembly
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Gate<F: PrimeField> {
+    pub(crate) gate_id: usize,
+    pub(crate) variables: [Variable; STATE_WIDTH],
+    pub(crate) constants: Vec<F>,
+    pub(crate) lookup_table: Option<usize>,
+}
+
+impl<F: PrimeField> Gate<F> {
+    pub fn new_gate(gate_id: usize, variables: [Variable; STATE_WIDTH], constants: &[F]) -> Self {
+        Self {
+            gate_id,
+            variables,
+            constants: constants.to_vec(),
+            lookup_table: None
+        }
+    }
+
+    // main gate with zero constants, so only the lookup is enforced
+    pub(crate) fn new_lookup_gate(table_id: usize, variables: [Variable; LOOKUP_TABLE_WIDTH], dummy: Variable) -> Self {
+        Self {
+            gate_id: MAIN_GATE_ID,
+            variables: [variables[0], variables[1], variables[2], dummy],
+            constants: vec![F::zero(); 7],
+            lookup_table: Some(table_id)
+        }
+    }
+
+    pub(crate) fn new_input_gate(input: Variable, dummy: Variable) -> Self {
+        let mut constants = vec![F::zero(); 7];
+        constants[0] = F::one();
+
+        Self {
+            gate_id: MAIN_GATE_ID,
+            variables: [input, dummy, dummy, dummy],
+            constants,
+            lookup_table: None
+        }
+    }
+}
+
+pub(crate) fn check_used_gates<F: PrimeField>(gates: &[Arc<dyn GateEquation<F>>]) {
+    assert!(!gates.is_empty(), "at least the main gate must be used");
+    assert_eq!(gates[0].name(), GateEquation::<F>::name(&Width4MainGate), "main gate must be declared first");
+    assert_eq!(gates[0].num_constants(), 7);
+}
+
+pub(crate) fn check_gate_arguments<F: PrimeField>(gates: &[Arc<dyn GateEquation<F>>], gate_id: usize, constants: &[F]) {
+    assert!(gate_id < gates.len(), "gate {} is not declared", gate_id);
+    assert_eq!(constants.len(), gates[gate_id].num_constants(), "invalid number of constants for gate {}", gates[gate_id].name());
+}
+
+pub(crate) fn check_lookup_arguments<F: PrimeField>(tables: &[Arc<LookupTable<F>>], table_id: usize) {
+    assert!(table_id < tables.len(), "table {} is not declared", table_id);
+}
+
+pub trait Width4Circuit<E: Engine> {
+    // gates used by the circuit, indexes in this list are gate ids and
+    // the main gate must be the first one
+    fn declare_used_gates() -> Vec<Arc<dyn GateEquation<E::Fr>>> {
+        vec![Arc::new(Width4MainGate)]
+    }
+
+    // tables used by the circuit for lookups, indexes in this list are table ids
+    fn declare_used_tables() -> Vec<Arc<LookupTable<E::Fr>>> {
+        vec![]
+    }
+
+    fn synthesize<CS: Width4ConstraintSystem<E>>(&self, cs: &mut CS) -> Result<(), SynthesisError>;
+}
+
+pub trait Width4ConstraintSystem<E: Engine> {
+    // allocate a variable
+    fn alloc<F>(&mut self, value: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<E::Fr, SynthesisError>;
+
+    // allocate an input variable
+    fn alloc_input<F>(&mut self, value: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<E::Fr, SynthesisError>;
+
+    // place a new row selecting the gate `gate_id`
+    fn new_gate(&mut self, gate_id: usize, variables: [Variable; STATE_WIDTH],
+        constants: &[E::Fr]) -> Result<(), SynthesisError>;
+
+    // place a new row where values of (a, b, c) must be an entry of the table `table_id`
+    fn new_lookup_gate(&mut self, table_id: usize, variables: [Variable; LOOKUP_TABLE_WIDTH]) -> Result<(), SynthesisError>;
+
+    // [q_a, q_b, q_c, q_d, q_m, q_const, q_d_next]
+    fn new_main_gate(&mut self, variables: [Variable; STATE_WIDTH],
+        coeffs: [E::Fr; 7]) -> Result<(), SynthesisError> {
+        self.new_gate(MAIN_GATE_ID, variables, &coeffs)
+    }
+
+    fn get_value(&self, _variable: Variable) -> Result<E::Fr, SynthesisError> {
+        Err(SynthesisError::AssignmentMissing)
+    }
+
+    fn get_dummy_variable(&self) -> Variable;
+}
@@ -0,0 +1,374 @@
+use crate::pairing::ff::{Field, PrimeField};
+use crate::pairing::{Engine};
+
+use crate::{SynthesisError};
+use std::sync::Arc;
+
+use crate::multicore::*;
+use crate::plonk::polynomials::*;
+use crate::plonk::plonk::prover::ProvingAssembly as PlonkProvingAssembly;
+use crate::plonk::plonk::srs::UniversalSrs;
+
+use super::cs::*;
+use super::lookup::*;
+
+#[derive(Debug)]
+pub struct GeneratorAssembly<E: Engine> {
+    pub(crate) n: usize,
+    pub(crate) input_gates: Vec<Gate<E::Fr>>,
+    pub(crate) aux_gates: Vec<Gate<E::Fr>>,
+
+    pub(crate) num_inputs: usize,
+    pub(crate) num_aux: usize,
+
+    pub(crate) gates: Vec<Arc<dyn GateEquation<E::Fr>>>,
+    pub(crate) tables: Vec<Arc<LookupTable<E::Fr>>>,
+}
+
+impl<E: Engine> Width4ConstraintSystem<E> for GeneratorAssembly<E> {
+    // allocate a variable
+    fn alloc<F>(&mut self, _value: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<E::Fr, SynthesisError>
+    {
+        self.num_aux += 1;
+        let index = self.num_aux;
+
+        Ok(Variable(Index::Aux(index)))
+    }
+
+    // allocate an input variable
+    fn alloc_input<F>(&mut self, _value: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<E::Fr, SynthesisError>
+    {
+        self.num_inputs += 1;
+        let index = self.num_inputs;
+
+        let input_var = Variable(Index::Input(index));
+
+        let gate = Gate::<E::Fr>::new_input_gate(input_var, self.get_dummy_variable());
+        self.input_gates.push(gate);
+
+        Ok(input_var)
+    }
+
+    fn new_gate(&mut self, gate_id: usize, variables: [Variable; STATE_WIDTH],
+        constants: &[E::Fr]) -> Result<(), SynthesisError>
+    {
+        check_gate_arguments(&self.gates, gate_id, constants);
+
+        let gate = Gate::<E::Fr>::new_gate(gate_id, variables, constants);
+        self.aux_gates.push(gate);
+        self.n += 1;
+
+        Ok(())
+    }
+
+    fn new_lookup_gate(&mut self, table_id: usize, variables: [Variable; LOOKUP_TABLE_WIDTH]) -> Result<(), SynthesisError> {
+        check_lookup_arguments(&self.tables, table_id);
+
+        let gate = Gate::<E::Fr>::new_lookup_gate(table_id, variables, self.get_dummy_variable());
+        self.aux_gates.push(gate);
+        self.n += 1;
+
+        Ok(())
+    }
+
+    fn get_dummy_variable(&self) -> Variable {
+        Variable(Index::Aux(0))
+    }
+}
+
+// selector polynomials and polynomials of the constants for every gate
+type SelectorsAndConstants<F> = (Vec<Polynomial<F, Values>>, Vec<Vec<Polynomial<F, Values>>>);
+
+// s_id and sigma polynomials for every wire
+type Permutations<F> = (Polynomial<F, Values>, Vec<Polynomial<F, Values>>);
+
+// quotient is computed for the constraints of degree at most this value
+pub(crate) fn constraints_degree<F: PrimeField>(gates: &[Arc<dyn GateEquation<F>>]) -> usize {
+    // copy-permutation argument has degree 5 for 4 wires, selector adds one to the gate degree
+    let max_gate_degree = gates.iter().map(|g| g.degree()).max().unwrap_or(0);
+
+    std::cmp::max(STATE_WIDTH + 1, max_gate_degree + 1)
+}
+
+// domain always has at least one padding row after the last gate
+// and should fit all the table entries
+pub(crate) fn domain_size_for(num_gates: usize, num_table_entries: usize) -> usize {
+    std::cmp::max(num_gates + 1, num_table_entries).next_power_of_two()
+}
+
+impl<E: Engine> GeneratorAssembly<E> {
+    pub fn new_for_gates(gates: Vec<Arc<dyn GateEquation<E::Fr>>>) -> Self {
+        Self::new_for_gates_and_tables(gates, vec![])
+    }
+
+    pub fn new_for_gates_and_tables(
+        gates: Vec<Arc<dyn GateEquation<E::Fr>>>,
+        tables: Vec<Arc<LookupTable<E::Fr>>>
+    ) -> Self {
+        check_used_gates(&gates);
+
+        Self {
+            n: 0,
+            input_gates: vec![],
+            aux_gates: vec![],
+
+            num_inputs: 0,
+            num_aux: 0,
+
+            gates,
+            tables,
+        }
+    }
+
+    pub fn num_gates(&self) -> usize {
+        self.input_gates.len() + self.aux_gates.len()
+    }
+
+    pub fn domain_size(&self) -> usize {
+        domain_size_for(self.num_gates(), total_tables_size(&self.tables))
+    }
+
+    // every gate has a selector polynomial and polynomials for it's constants
+    fn make_selectors_and_constants(&self) -> Result<SelectorsAndConstants<E::Fr>, SynthesisError> {
+        let size = self.domain_size();
+
+        let mut selectors = vec![vec![E::Fr::zero(); size]; self.gates.len()];
+        let mut constants: Vec<Vec<Vec<E::Fr>>> = self.gates.iter()
+                                            .map(|g| vec![vec![E::Fr::zero(); size]; g.num_constants()])
+                                            .collect();
+
+        for (row, gate) in self.input_gates.iter().chain(self.aux_gates.iter()).enumerate() {
+            selectors[gate.gate_id][row] = E::Fr::one();
+            for (values, constant) in constants[gate.gate_id].iter_mut().zip(gate.constants.iter()) {
+                values[row] = *constant;
+            }
+        }
+
+        let selectors = selectors.into_iter()
+                            .map(Polynomial::from_values)
+                            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut constant_polys = Vec::with_capacity(constants.len());
+        for values in constants.into_iter() {
+            let polys = values.into_iter()
+                            .map(Polynomial::from_values)
+                            .collect::<Result<Vec<_>, _>>()?;
+            constant_polys.push(polys);
+        }
+
+        Ok((selectors, constant_polys))
+    }
+
+    // position of the wire `j` in the row `i` is labeled as (i + 1) + j*N, so labels
+    // of the row are s_id(omega^i) + j*N, and sigma_j(omega^i) is a label of the next
+    // position in the cycle of the same variable
+    fn make_permutations(&self) -> Result<Permutations<E::Fr>, SynthesisError> {
+        let size = self.domain_size();
+
+        let mut positions_for_inputs: Vec<Vec<(usize, usize)>> = vec![vec![]; self.num_inputs + 1];
+        let mut positions_for_aux: Vec<Vec<(usize, usize)>> = vec![vec![]; self.num_aux + 1];
+
+        for (row, gate) in self.input_gates.iter().chain(self.aux_gates.iter()).enumerate() {
+            for (wire, var) in gate.variables.iter().enumerate() {
+                match var {
+                    Variable(Index::Aux(0)) => {
+                        // dummy variable is not constrained
+                    },
+                    Variable(Index::Input(0)) => {
+                        unreachable!("input variables are numbered from 1");
+                    },
+                    Variable(Index::Input(index)) => {
+                        positions_for_inputs[*index].push((wire, row));
+                    },
+                    Variable(Index::Aux(index)) => {
+                        positions_for_aux[*index].push((wire, row));
+                    }
+                }
+            }
+        }
+
+        let label = |wire: usize, row: usize| -> E::Fr {
+            E::Fr::from_str(&(row + 1 + wire * size).to_string()).expect("must fit into the field")
+        };
+
+        let mut sigmas: Vec<Vec<E::Fr>> = (0..STATE_WIDTH)
+                                .map(|wire| (0..size).map(|row| label(wire, row)).collect())
+                                .collect();
+
+        let s_id = sigmas[0].clone();
+
+        for cycle in positions_for_inputs.iter().chain(positions_for_aux.iter()) {
+            if cycle.len() < 2 {
+                continue;
+            }
+
+            for (i, &(wire, row)) in cycle.iter().enumerate() {
+                let (next_wire, next_row) = cycle[(i + 1) % cycle.len()];
+                sigmas[wire][row] = label(next_wire, next_row);
+            }
+        }
+
+        let sigmas = sigmas.into_iter()
+                        .map(Polynomial::from_values)
+                        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok((Polynomial::from_values(s_id)?, sigmas))
+    }
+
+    // lookup selector, id of the table used in the row and columns of the tables
+    fn make_lookup_polynomials(&self, worker: &Worker) -> Result<Option<LookupSetupPrecomputation<E>>, SynthesisError> {
+        if self.tables.is_empty() {
+            return Ok(None);
+        }
+
+        let size = self.domain_size();
+
+        let mut selector = vec![E::Fr::zero(); size];
+        let mut table_type = vec![E::Fr::zero(); size];
+
+        for (row, gate) in self.input_gates.iter().chain(self.aux_gates.iter()).enumerate() {
+            if let Some(table_id) = gate.lookup_table {
+                selector[row] = E::Fr::one();
+                table_type[row] = E::Fr::from_str(&table_id.to_string()).expect("must fit into the field");
+            }
+        }
+
+        let table_polys = make_table_columns(&self.tables, size).into_iter()
+                            .map(|values| Ok(Polynomial::from_values(values)?.ifft(worker)))
+                            .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+        Ok(Some(LookupSetupPrecomputation::<E> {
+            selector_poly: Polynomial::from_values(selector)?.ifft(worker),
+            table_type_poly: Polynomial::from_values(table_type)?.ifft(worker),
+            table_polys,
+        }))
+    }
+
+    pub fn make_setup_polynomials(&self, worker: &Worker) -> Result<Width4SetupPrecomputation<E>, SynthesisError> {
+        let (selectors, constants) = self.make_selectors_and_constants()?;
+        let (s_id, sigmas) = self.make_permutations()?;
+
+        let selector_polys = selectors.into_iter().map(|p| p.ifft(worker)).collect();
+        let constant_polys = constants.into_iter()
+                                .map(|polys| polys.into_iter().map(|p| p.ifft(worker)).collect())
+                                .collect();
+        let sigma_polys = sigmas.into_iter().map(|p| p.ifft(worker)).collect();
+
+        Ok(Width4SetupPrecomputation::<E> {
+            n: self.num_gates(),
+            num_inputs: self.num_inputs,
+            selector_polys,
+            constant_polys,
+            s_id_poly: s_id.ifft(worker),
+            sigma_polys,
+            lookup: self.make_lookup_polynomials(worker)?,
+        })
+    }
+}
+
+/// Commitments to the setup polynomials, enough for a verifier
+#[derive(Clone, Debug)]
+pub struct Width4Setup<E: Engine> {
+    pub n: usize,
+    pub num_inputs: usize,
+    pub selector_commitments: Vec<E::G1Affine>,
+    pub constant_commitments: Vec<Vec<E::G1Affine>>,
+    pub s_id_commitment: E::G1Affine,
+    pub sigma_commitments: Vec<E::G1Affine>,
+    pub lookup: Option<LookupSetup<E>>,
+}
+
+/// Commitments to the lookup selector, table ids of the rows and the table columns
+#[derive(Clone, Debug)]
+pub struct LookupSetup<E: Engine> {
+    pub selector_commitment: E::G1Affine,
+    pub table_type_commitment: E::G1Affine,
+    pub table_commitments: Vec<E::G1Affine>,
+}
+
+/// Setup polynomials in the monomial form that are used by the prover
+#[derive(Clone, Debug)]
+pub struct Width4SetupPrecomputation<E: Engine> {
+    pub n: usize,
+    pub num_inputs: usize,
+    pub selector_polys: Vec<Polynomial<E::Fr, Coefficients>>,
+    pub constant_polys: Vec<Vec<Polynomial<E::Fr, Coefficients>>>,
+    pub s_id_poly: Polynomial<E::Fr, Coefficients>,
+    pub sigma_polys: Vec<Polynomial<E::Fr, Coefficients>>,
+    pub lookup: Option<LookupSetupPrecomputation<E>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct LookupSetupPrecomputation<E: Engine> {
+    pub selector_poly: Polynomial<E::Fr, Coefficients>,
+    pub table_type_poly: Polynomial<E::Fr, Coefficients>,
+    pub table_polys: Vec<Polynomial<E::Fr, Coefficients>>,
+}
+
+impl<E: Engine> Width4SetupPrecomputation<E> {
+    pub fn commit(&self, srs: &UniversalSrs<E>, worker: &Worker) -> Result<Width4Setup<E>, SynthesisError> {
+        let commit = |poly: &Polynomial<E::Fr, Coefficients>| {
+            PlonkProvingAssembly::<E>::commit_single_poly(poly, srs, worker)
+        };
+
+        let selector_commitments = self.selector_polys.iter()
+                                        .map(commit)
+                                        .collect::<Result<Vec<_>, _>>()?;
+
+        let mut constant_commitments = Vec::with_capacity(self.constant_polys.len());
+        for polys in self.constant_polys.iter() {
+            let commitments = polys.iter()
+                                .map(commit)
+                                .collect::<Result<Vec<_>, _>>()?;
+            constant_commitments.push(commitments);
+        }
+
+        let sigma_commitments = self.sigma_polys.iter()
+                                    .map(commit)
+                                    .collect::<Result<Vec<_>, _>>()?;
+
+        let lookup = match self.lookup.as_ref() {
+            Some(lookup) => {
+                let table_commitments = lookup.table_polys.iter()
+                                            .map(commit)
+                                            .collect::<Result<Vec<_>, _>>()?;
+
+                Some(LookupSetup::<E> {
+                    selector_commitment: commit(&lookup.selector_poly)?,
+                    table_type_commitment: commit(&lookup.table_type_poly)?,
+                    table_commitments,
+                })
+            },
+            None => None
+        };
+
+        Ok(Width4Setup::<E> {
+            n: self.n,
+            num_inputs: self.num_inputs,
+            selector_commitments,
+            constant_commitments,
+            s_id_commitment: commit(&self.s_id_poly)?,
+            sigma_commitments,
+            lookup,
+        })
+    }
+}
+
+pub fn setup<E: Engine, C: Width4Circuit<E>>(
+    circuit: &C,
+    srs: &UniversalSrs<E>,
+    worker: &Worker
+) -> Result<(Width4Setup<E>, Width4SetupPrecomputation<E>), SynthesisError> {
+    let mut assembly = GeneratorAssembly::<E>::new_for_gates_and_tables(C::declare_used_gates(), C::declare_used_tables());
+
+    circuit.synthesize(&mut assembly)?;
+
+    let precomputation = assembly.make_setup_polynomials(worker)?;
+    let setup = precomputation.commit(srs, worker)?;
+
+    Ok((setup, precomputation))
+}
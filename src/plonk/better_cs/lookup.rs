@@ -0,0 +1,159 @@
+use crate::pairing::ff::{Field, PrimeField};
+
+use crate::{SynthesisError};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+// lookups constrain wires a, b and c of a row
+pub const LOOKUP_TABLE_WIDTH: usize = 3;
+
+/// Fixed table of tuples that can be used in lookups, e.g. XOR of two values
+/// of some bit width, or a range of values with zero padding columns
+#[derive(Clone, Debug)]
+pub struct LookupTable<F: PrimeField> {
+    name: &'static str,
+    entries: Vec<[F; LOOKUP_TABLE_WIDTH]>,
+    entries_set: HashSet<[F::Repr; LOOKUP_TABLE_WIDTH]>,
+}
+
+fn field_element<F: PrimeField>(value: u64) -> F {
+    F::from_repr(F::Repr::from(value)).expect("small value is always a field element")
+}
+
+fn to_reprs<F: PrimeField>(entry: &[F; LOOKUP_TABLE_WIDTH]) -> [F::Repr; LOOKUP_TABLE_WIDTH] {
+    [entry[0].into_repr(), entry[1].into_repr(), entry[2].into_repr()]
+}
+
+impl<F: PrimeField> LookupTable<F> {
+    pub fn new(name: &'static str, entries: Vec<[F; LOOKUP_TABLE_WIDTH]>) -> Self {
+        assert!(!entries.is_empty(), "table must not be empty");
+
+        let entries_set = entries.iter().map(to_reprs::<F>).collect();
+
+        Self {
+            name,
+            entries,
+            entries_set
+        }
+    }
+
+    fn new_for_binary_operation<OP: Fn(u64, u64) -> u64>(name: &'static str, bits: usize, op: OP) -> Self {
+        assert!(bits > 0 && bits <= 16, "table of this width is too large");
+
+        let mut entries = Vec::with_capacity(1 << (2 * bits));
+        for a in 0..(1u64 << bits) {
+            for b in 0..(1u64 << bits) {
+                entries.push([field_element(a), field_element(b), field_element(op(a, b))]);
+            }
+        }
+
+        Self::new(name, entries)
+    }
+
+    /// (a, b, a ^ b) for all a and b of `bits` bits
+    pub fn xor(bits: usize) -> Self {
+        Self::new_for_binary_operation("xor", bits, |a, b| a ^ b)
+    }
+
+    /// (a, b, a & b) for all a and b of `bits` bits
+    pub fn and(bits: usize) -> Self {
+        Self::new_for_binary_operation("and", bits, |a, b| a & b)
+    }
+
+    /// (a, 0, 0) for all a of `bits` bits
+    pub fn range(bits: usize) -> Self {
+        assert!(bits > 0 && bits <= 32, "table of this width is too large");
+
+        let entries = (0..(1u64 << bits)).map(|a| [field_element(a), F::zero(), F::zero()]).collect();
+
+        Self::new("range", entries)
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn size(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn entries(&self) -> &[[F; LOOKUP_TABLE_WIDTH]] {
+        &self.entries
+    }
+
+    pub fn contains(&self, entry: &[F; LOOKUP_TABLE_WIDTH]) -> bool {
+        self.entries_set.contains(&to_reprs(entry))
+    }
+}
+
+impl<F: PrimeField> PartialEq for LookupTable<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.entries == other.entries
+    }
+}
+
+impl<F: PrimeField> Eq for LookupTable<F> {}
+
+pub(crate) fn total_tables_size<F: PrimeField>(tables: &[Arc<LookupTable<F>>]) -> usize {
+    tables.iter().map(|t| t.size()).sum()
+}
+
+// table is combined from the columns and the table id as
+// column_0 + eta * column_1 + eta^2 * column_2 + eta^3 * table_id
+pub(crate) fn compress<F: PrimeField>(values: &[F], eta: F) -> F {
+    let mut res = F::zero();
+    for value in values.iter().rev() {
+        res.mul_assign(&eta);
+        res.add_assign(value);
+    }
+
+    res
+}
+
+// columns of all the tables one after another, with the last column being the table id.
+// Columns are padded to the `size` by repeating the last entry
+pub(crate) fn make_table_columns<F: PrimeField>(tables: &[Arc<LookupTable<F>>], size: usize) -> Vec<Vec<F>> {
+    assert!(total_tables_size(tables) <= size);
+
+    let mut columns: Vec<Vec<F>> = (0..=LOOKUP_TABLE_WIDTH).map(|_| Vec::with_capacity(size)).collect();
+
+    for (table_id, table) in tables.iter().enumerate() {
+        let id = field_element::<F>(table_id as u64);
+        for entry in table.entries().iter() {
+            for (column, value) in columns.iter_mut().zip(entry.iter()) {
+                column.push(*value);
+            }
+            columns[LOOKUP_TABLE_WIDTH].push(id);
+        }
+    }
+
+    for column in columns.iter_mut() {
+        let last = *column.last().expect("tables are not empty");
+        column.resize(size, last);
+    }
+
+    columns
+}
+
+// plookup's `s` that is a union of the witness `f` and the table `t`, sorted by `t`
+pub(crate) fn make_sorted_witness<F: PrimeField>(witness: &[F], table: &[F]) -> Result<Vec<F>, SynthesisError> {
+    let mut first_occurences = HashMap::with_capacity(table.len());
+    for (i, value) in table.iter().enumerate() {
+        first_occurences.entry(value.into_repr()).or_insert(i);
+    }
+
+    let mut counts = vec![0usize; table.len()];
+    for value in witness.iter() {
+        let index = first_occurences.get(&value.into_repr()).ok_or(SynthesisError::Unsatisfiable)?;
+        counts[*index] += 1;
+    }
+
+    let mut sorted = Vec::with_capacity(witness.len() + table.len());
+    for (value, count) in table.iter().zip(counts) {
+        for _ in 0..=count {
+            sorted.push(*value);
+        }
+    }
+
+    Ok(sorted)
+}
@@ -1,3 +1,7 @@
 pub mod cs;
 pub mod test_assembly;
-pub mod adaptor;
\ No newline at end of file
+pub mod adaptor;
+pub mod generator;
+pub mod prover;
+pub mod verifier;
+pub mod lookup;
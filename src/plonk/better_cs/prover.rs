@@ -0,0 +1,952 @@
+use crate::pairing::ff::{Field, PrimeField};
+use crate::pairing::{Engine, CurveAffine};
+
+use crate::{SynthesisError};
+use std::sync::Arc;
+
+use crate::multicore::*;
+use crate::plonk::domains::*;
+use crate::plonk::polynomials::*;
+use crate::plonk::commitments::transcript::*;
+use crate::plonk::plonk::prover::{ProvingAssembly as PlonkProvingAssembly, OpeningRequest};
+use crate::plonk::plonk::srs::UniversalSrs;
+
+use super::cs::*;
+use super::generator::*;
+use super::lookup::*;
+
+#[derive(Debug)]
+pub struct ProvingAssembly<E: Engine> {
+    n: usize,
+    input_gates: Vec<Gate<E::Fr>>,
+    aux_gates: Vec<Gate<E::Fr>>,
+
+    num_inputs: usize,
+    num_aux: usize,
+
+    input_assingments: Vec<E::Fr>,
+    aux_assingments: Vec<E::Fr>,
+
+    gates: Vec<Arc<dyn GateEquation<E::Fr>>>,
+    tables: Vec<Arc<LookupTable<E::Fr>>>,
+}
+
+impl<E: Engine> Width4ConstraintSystem<E> for ProvingAssembly<E> {
+    // allocate a variable
+    fn alloc<F>(&mut self, value: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<E::Fr, SynthesisError>
+    {
+        let value = value()?;
+
+        self.num_aux += 1;
+        let index = self.num_aux;
+        self.aux_assingments.push(value);
+
+        Ok(Variable(Index::Aux(index)))
+    }
+
+    // allocate an input variable
+    fn alloc_input<F>(&mut self, value: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<E::Fr, SynthesisError>
+    {
+        let value = value()?;
+
+        self.num_inputs += 1;
+        let index = self.num_inputs;
+        self.input_assingments.push(value);
+
+        let input_var = Variable(Index::Input(index));
+
+        let gate = Gate::<E::Fr>::new_input_gate(input_var, self.get_dummy_variable());
+        self.input_gates.push(gate);
+
+        Ok(input_var)
+    }
+
+    fn new_gate(&mut self, gate_id: usize, variables: [Variable; STATE_WIDTH],
+        constants: &[E::Fr]) -> Result<(), SynthesisError>
+    {
+        check_gate_arguments(&self.gates, gate_id, constants);
+
+        let gate = Gate::<E::Fr>::new_gate(gate_id, variables, constants);
+        self.aux_gates.push(gate);
+        self.n += 1;
+
+        Ok(())
+    }
+
+    fn new_lookup_gate(&mut self, table_id: usize, variables: [Variable; LOOKUP_TABLE_WIDTH]) -> Result<(), SynthesisError> {
+        check_lookup_arguments(&self.tables, table_id);
+
+        let gate = Gate::<E::Fr>::new_lookup_gate(table_id, variables, self.get_dummy_variable());
+        self.aux_gates.push(gate);
+        self.n += 1;
+
+        Ok(())
+    }
+
+    fn get_value(&self, var: Variable) -> Result<E::Fr, SynthesisError> {
+        let value = match var {
+            Variable(Index::Aux(0)) => {
+                E::Fr::zero()
+            }
+            Variable(Index::Input(0)) => {
+                return Err(SynthesisError::AssignmentMissing);
+            }
+            Variable(Index::Input(input)) => {
+                self.input_assingments[input - 1]
+            },
+            Variable(Index::Aux(aux)) => {
+                self.aux_assingments[aux - 1]
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn get_dummy_variable(&self) -> Variable {
+        Variable(Index::Aux(0))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Width4Proof<E: Engine> {
+    pub wire_commitments: Vec<E::G1Affine>,
+    pub grand_product_commitment: E::G1Affine,
+    pub quotient_poly_parts_commitments: Vec<E::G1Affine>,
+
+    pub wire_values_at_z: Vec<E::Fr>,
+    pub wire_d_value_at_z_omega: E::Fr,
+    pub selector_values_at_z: Vec<E::Fr>,
+    pub constant_values_at_z: Vec<Vec<E::Fr>>,
+    pub s_id_value_at_z: E::Fr,
+    pub sigma_values_at_z: Vec<E::Fr>,
+    pub grand_product_value_at_z: E::Fr,
+    pub grand_product_value_at_z_omega: E::Fr,
+    pub quotient_poly_parts_values_at_z: Vec<E::Fr>,
+
+    pub lookup: Option<LookupProof<E>>,
+
+    pub opening_proof_at_z: E::G1Affine,
+    pub opening_proof_at_z_omega: E::G1Affine,
+}
+
+#[derive(Clone, Debug)]
+pub struct LookupProof<E: Engine> {
+    pub witness_commitment: E::G1Affine,
+    pub h_1_commitment: E::G1Affine,
+    pub h_2_commitment: E::G1Affine,
+    pub grand_product_commitment: E::G1Affine,
+
+    pub witness_value_at_z: E::Fr,
+    pub h_1_value_at_z: E::Fr,
+    pub h_2_value_at_z: E::Fr,
+    pub grand_product_value_at_z: E::Fr,
+    pub selector_value_at_z: E::Fr,
+    pub table_type_value_at_z: E::Fr,
+    pub table_values_at_z: Vec<E::Fr>,
+
+    pub h_1_value_at_z_omega: E::Fr,
+    pub h_2_value_at_z_omega: E::Fr,
+    pub grand_product_value_at_z_omega: E::Fr,
+    pub table_values_at_z_omega: Vec<E::Fr>,
+}
+
+impl<E: Engine> LookupProof<E> {
+    // in the order of opening, that is the same for the prover and the verifier
+    pub(crate) fn values_at_z(&self) -> Vec<E::Fr> {
+        let mut values = vec![
+            self.witness_value_at_z,
+            self.h_1_value_at_z,
+            self.h_2_value_at_z,
+            self.grand_product_value_at_z,
+            self.selector_value_at_z,
+            self.table_type_value_at_z,
+        ];
+        values.extend(self.table_values_at_z.iter().cloned());
+
+        values
+    }
+
+    pub(crate) fn values_at_z_omega(&self) -> Vec<E::Fr> {
+        let mut values = vec![
+            self.h_1_value_at_z_omega,
+            self.h_2_value_at_z_omega,
+            self.grand_product_value_at_z_omega,
+        ];
+        values.extend(self.table_values_at_z_omega.iter().cloned());
+
+        values
+    }
+}
+
+// plookup's witness f, that has values of (a, b, c, table_type) compressed by eta in
+// rows with lookups, and the sorted union of f and t split into overlapping halves h_1 and h_2
+struct LookupWitness<F: PrimeField> {
+    eta: F,
+    f_values: Polynomial<F, Values>,
+    t_values: Polynomial<F, Values>,
+    h_1_values: Polynomial<F, Values>,
+    h_2_values: Polynomial<F, Values>,
+    f_poly: Polynomial<F, Coefficients>,
+    h_1_poly: Polynomial<F, Coefficients>,
+    h_2_poly: Polynomial<F, Coefficients>,
+}
+
+// p_0 + eta * p_1 + eta^2 * p_2 + ...
+fn compress_values<F: PrimeField>(
+    polys: &[&Polynomial<F, Values>],
+    eta: F,
+    worker: &Worker
+) -> Polynomial<F, Values> {
+    let mut result = polys[polys.len() - 1].clone();
+    for poly in polys.iter().rev().skip(1) {
+        result.scale(worker, eta);
+        result.add_assign(worker, poly);
+    }
+
+    result
+}
+
+fn coset_lde<F: PrimeField>(
+    poly: &Polynomial<F, Coefficients>,
+    factor: usize,
+    worker: &Worker
+) -> Result<Polynomial<F, Values>, SynthesisError> {
+    let mut poly = poly.clone();
+    poly.pad_by_factor(factor)?;
+
+    Ok(poly.coset_fft(worker))
+}
+
+// values of p(X*omega) on the same coset, where omega generates the original domain
+fn shift_lde<F: PrimeField>(
+    lde: &Polynomial<F, Values>,
+    factor: usize
+) -> Result<Polynomial<F, Values>, SynthesisError> {
+    let mut values = lde.as_ref().to_vec();
+    values.rotate_left(factor);
+
+    Polynomial::from_values(values)
+}
+
+impl<E: Engine> ProvingAssembly<E> {
+    pub fn new_for_gates(gates: Vec<Arc<dyn GateEquation<E::Fr>>>) -> Self {
+        Self::new_for_gates_and_tables(gates, vec![])
+    }
+
+    pub fn new_for_gates_and_tables(
+        gates: Vec<Arc<dyn GateEquation<E::Fr>>>,
+        tables: Vec<Arc<LookupTable<E::Fr>>>
+    ) -> Self {
+        check_used_gates(&gates);
+
+        Self {
+            n: 0,
+            input_gates: vec![],
+            aux_gates: vec![],
+
+            num_inputs: 0,
+            num_aux: 0,
+
+            input_assingments: vec![],
+            aux_assingments: vec![],
+
+            gates,
+            tables,
+        }
+    }
+
+    pub fn num_gates(&self) -> usize {
+        self.input_gates.len() + self.aux_gates.len()
+    }
+
+    fn make_wire_values(&self, size: usize) -> Result<Vec<Polynomial<E::Fr, Values>>, SynthesisError> {
+        let mut wires = vec![vec![E::Fr::zero(); size]; STATE_WIDTH];
+
+        for (row, gate) in self.input_gates.iter().chain(self.aux_gates.iter()).enumerate() {
+            for (wire, var) in wires.iter_mut().zip(gate.variables.iter()) {
+                wire[row] = self.get_value(*var)?;
+            }
+        }
+
+        wires.into_iter().map(Polynomial::from_values).collect()
+    }
+
+    fn make_lookup_witness(
+        lookup_setup: &LookupSetupPrecomputation<E>,
+        wire_values: &[Polynomial<E::Fr, Values>],
+        eta: E::Fr,
+        worker: &Worker
+    ) -> Result<LookupWitness<E::Fr>, SynthesisError> {
+        let size = wire_values[0].size();
+
+        let table_values: Vec<_> = lookup_setup.table_polys.iter().map(|p| p.clone().fft(worker)).collect();
+        let table_values: Vec<_> = table_values.iter().collect();
+        let t_values = compress_values(&table_values, eta, worker);
+
+        let selector = lookup_setup.selector_poly.clone().fft(worker);
+        let table_type = lookup_setup.table_type_poly.clone().fft(worker);
+
+        let compressed_wires = compress_values(
+            &[&wire_values[0], &wire_values[1], &wire_values[2], &table_type],
+            eta,
+            worker
+        );
+
+        // rows without lookups use some entry of the table
+        let default_value = t_values.as_ref()[size - 1];
+
+        let f_values: Vec<_> = selector.as_ref().iter()
+                                .zip(compressed_wires.as_ref().iter())
+                                .map(|(s, value)| if s.is_zero() { default_value } else { *value })
+                                .collect();
+
+        // f(omega^{N-1}) is not a part of the argument
+        let sorted = make_sorted_witness(&f_values[..(size - 1)], t_values.as_ref())?;
+
+        let h_1_values = Polynomial::from_values(sorted[..size].to_vec())?;
+        let h_2_values = Polynomial::from_values(sorted[(size - 1)..].to_vec())?;
+        let f_values = Polynomial::from_values(f_values)?;
+
+        Ok(LookupWitness {
+            eta,
+            f_poly: f_values.clone().ifft(worker),
+            h_1_poly: h_1_values.clone().ifft(worker),
+            h_2_poly: h_2_values.clone().ifft(worker),
+            f_values,
+            t_values,
+            h_1_values,
+            h_2_values,
+        })
+    }
+
+    // Z(omega^0) = 1, Z(omega^{i+1}) = Z(omega^i) * (1+beta) * (gamma + f_i) * (gamma*(1+beta) + t_i + beta*t_{i+1}) /
+    // ((gamma*(1+beta) + h_1_i + beta*h_1_{i+1}) * (gamma*(1+beta) + h_2_i + beta*h_2_{i+1}))
+    fn make_lookup_grand_product(
+        witness: &LookupWitness<E::Fr>,
+        beta: E::Fr,
+        gamma: E::Fr,
+        worker: &Worker
+    ) -> Result<Polynomial<E::Fr, Coefficients>, SynthesisError> {
+        let size = witness.f_values.size();
+
+        let mut one_plus_beta = beta;
+        one_plus_beta.add_assign(&E::Fr::one());
+
+        let mut gamma_beta = gamma;
+        gamma_beta.mul_assign(&one_plus_beta);
+
+        let f = witness.f_values.as_ref();
+        let t = witness.t_values.as_ref();
+        let h_1 = witness.h_1_values.as_ref();
+        let h_2 = witness.h_2_values.as_ref();
+
+        let pair = |values: &[E::Fr], i: usize| {
+            let mut tmp = values[(i + 1) % size];
+            tmp.mul_assign(&beta);
+            tmp.add_assign(&values[i]);
+            tmp.add_assign(&gamma_beta);
+
+            tmp
+        };
+
+        let mut numerator = Vec::with_capacity(size);
+        let mut denominator = Vec::with_capacity(size);
+
+        for (i, f) in f.iter().enumerate() {
+            let mut tmp = *f;
+            tmp.add_assign(&gamma);
+            tmp.mul_assign(&one_plus_beta);
+            tmp.mul_assign(&pair(t, i));
+            numerator.push(tmp);
+
+            let mut tmp = pair(h_1, i);
+            tmp.mul_assign(&pair(h_2, i));
+            denominator.push(tmp);
+        }
+
+        let mut denominator = Polynomial::from_values(denominator)?;
+        denominator.batch_inversion(worker)?;
+
+        let mut z_values = Vec::with_capacity(size);
+        let mut accumulator = E::Fr::one();
+        for (num, den) in numerator.iter().zip(denominator.as_ref().iter()).take(size - 1) {
+            z_values.push(accumulator);
+            accumulator.mul_assign(num);
+            accumulator.mul_assign(den);
+        }
+        z_values.push(accumulator);
+
+        if accumulator != E::Fr::one() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        Ok(Polynomial::from_values(z_values)?.ifft(worker))
+    }
+
+    pub fn prove<T: Transcript<E::Fr>>(
+        self,
+        setup: &Width4SetupPrecomputation<E>,
+        srs: &UniversalSrs<E>,
+        worker: &Worker
+    ) -> Result<Width4Proof<E>, SynthesisError> {
+        let n = self.num_gates();
+        if n != setup.n || self.num_inputs != setup.num_inputs || setup.selector_polys.len() != self.gates.len() {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+
+        if setup.lookup.is_some() == self.tables.is_empty() {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+
+        let size = domain_size_for(n, total_tables_size(&self.tables));
+        let domain = Domain::<E::Fr>::new_for_size(size as u64)?;
+
+        let mut size_fe = E::Fr::zero();
+        let mut repr = size_fe.into_repr();
+        repr.as_mut()[0] = size as u64;
+        size_fe = E::Fr::from_repr(repr).expect("is a valid representation");
+
+        let mut transcript = T::new();
+
+        let commit = |poly: &Polynomial<E::Fr, Coefficients>| {
+            PlonkProvingAssembly::<E>::commit_single_poly(poly, srs, worker)
+        };
+
+        let wire_values = self.make_wire_values(size)?;
+        let wire_polys: Vec<_> = wire_values.iter().map(|p| p.clone().ifft(worker)).collect();
+
+        let wire_commitments = wire_polys.iter().map(commit).collect::<Result<Vec<_>, _>>()?;
+
+        for commitment in wire_commitments.iter() {
+            transcript.commit_point(commitment);
+        }
+
+        for input in self.input_assingments.iter() {
+            transcript.commit_field_element(input);
+        }
+
+        // lookup witness is committed before the challenges of the grand products are drawn
+        let lookup_witness = match setup.lookup.as_ref() {
+            Some(lookup_setup) => {
+                let eta = transcript.get_challenge();
+
+                let witness = Self::make_lookup_witness(lookup_setup, &wire_values, eta, worker)?;
+
+                let commitments = [
+                    commit(&witness.f_poly)?,
+                    commit(&witness.h_1_poly)?,
+                    commit(&witness.h_2_poly)?,
+                ];
+
+                for commitment in commitments.iter() {
+                    transcript.commit_point(commitment);
+                }
+
+                Some((witness, commitments))
+            },
+            None => None
+        };
+
+        let beta = transcript.get_challenge();
+        let gamma = transcript.get_challenge();
+
+        // copy-permutation grand product
+
+        let s_id_values = setup.s_id_poly.clone().fft(worker);
+        let sigma_values: Vec<_> = setup.sigma_polys.iter().map(|p| p.clone().fft(worker)).collect();
+
+        let mut numerator = Polynomial::from_values(vec![E::Fr::one(); size])?;
+        let mut denominator = Polynomial::from_values(vec![E::Fr::one(); size])?;
+
+        let mut shift = E::Fr::zero();
+        for (wire, sigma) in wire_values.iter().zip(sigma_values.iter()) {
+            let mut tmp = s_id_values.clone();
+            tmp.add_constant(worker, &shift);
+            tmp.scale(worker, beta);
+            tmp.add_assign(worker, wire);
+            tmp.add_constant(worker, &gamma);
+            numerator.mul_assign(worker, &tmp);
+
+            let mut tmp = sigma.clone();
+            tmp.scale(worker, beta);
+            tmp.add_assign(worker, wire);
+            tmp.add_constant(worker, &gamma);
+            denominator.mul_assign(worker, &tmp);
+
+            shift.add_assign(&size_fe);
+        }
+
+        denominator.batch_inversion(worker)?;
+        numerator.mul_assign(worker, &denominator);
+
+        // z(omega^0) = 1, z(omega^{i+1}) = z(omega^i) * numerator(omega^i) / denominator(omega^i)
+        let mut z_values = Vec::with_capacity(size);
+        let mut accumulator = E::Fr::one();
+        for ratio in numerator.as_ref().iter() {
+            z_values.push(accumulator);
+            accumulator.mul_assign(ratio);
+        }
+
+        if accumulator != E::Fr::one() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        let z_poly = Polynomial::from_values(z_values)?.ifft(worker);
+        let z_commitment = commit(&z_poly)?;
+
+        transcript.commit_point(&z_commitment);
+
+        let lookup_grand_product = match lookup_witness.as_ref() {
+            Some((witness, _)) => {
+                let poly = Self::make_lookup_grand_product(witness, beta, gamma, worker)?;
+                let commitment = commit(&poly)?;
+
+                transcript.commit_point(&commitment);
+
+                Some((poly, commitment))
+            },
+            None => None
+        };
+
+        let alpha = transcript.get_challenge();
+
+        // quotient is evaluated on the coset large enough for the constraints of the highest degree
+
+        let constraints_degree = constraints_degree(&self.gates);
+        let lde_factor = constraints_degree.next_power_of_two();
+        let lde_size = size * lde_factor;
+
+        let wire_ldes = wire_polys.iter().map(|p| coset_lde(p, lde_factor, worker)).collect::<Result<Vec<_>, _>>()?;
+        let d_next_lde = shift_lde(&wire_ldes[STATE_WIDTH - 1], lde_factor)?;
+
+        let mut public_inputs_values = vec![E::Fr::zero(); size];
+        for (value, input) in public_inputs_values.iter_mut().zip(self.input_assingments.iter()) {
+            *value = *input;
+            value.negate();
+        }
+
+        // PI(X) = - sum input_i * L_i(X) is a part of the main gate
+        let public_inputs_poly = Polynomial::from_values(public_inputs_values)?.ifft(worker);
+        let mut quotient = coset_lde(&public_inputs_poly, lde_factor, worker)?;
+
+        let mut current_alpha = E::Fr::one();
+
+        for (gate_id, gate) in self.gates.iter().enumerate() {
+            let selector_lde = coset_lde(&setup.selector_polys[gate_id], lde_factor, worker)?;
+            let constant_ldes = setup.constant_polys[gate_id].iter()
+                                    .map(|p| coset_lde(p, lde_factor, worker))
+                                    .collect::<Result<Vec<_>, _>>()?;
+
+            for term in 0..gate.num_terms() {
+                let challenge = current_alpha;
+
+                let wire_ldes = &wire_ldes;
+                let d_next_lde = &d_next_lde;
+                let selector_lde = &selector_lde;
+                let constant_ldes = &constant_ldes;
+
+                worker.scope(lde_size, |scope, chunk| {
+                    for (chunk_idx, quotient) in quotient.as_mut().chunks_mut(chunk).enumerate() {
+                        scope.spawn(move |_| {
+                            let start = chunk_idx * chunk;
+                            let mut wires = [E::Fr::zero(); STATE_WIDTH];
+                            let mut constants = vec![E::Fr::zero(); constant_ldes.len()];
+
+                            for (i, q) in quotient.iter_mut().enumerate() {
+                                let idx = start + i;
+
+                                for (w, lde) in wires.iter_mut().zip(wire_ldes.iter()) {
+                                    *w = lde.as_ref()[idx];
+                                }
+
+                                for (c, lde) in constants.iter_mut().zip(constant_ldes.iter()) {
+                                    *c = lde.as_ref()[idx];
+                                }
+
+                                let mut tmp = gate.evaluate_term(term, &wires, d_next_lde.as_ref()[idx], &constants);
+                                tmp.mul_assign(&selector_lde.as_ref()[idx]);
+                                tmp.mul_assign(&challenge);
+
+                                q.add_assign(&tmp);
+                            }
+                        });
+                    }
+                });
+
+                current_alpha.mul_assign(&alpha);
+            }
+        }
+
+        let z_lde = coset_lde(&z_poly, lde_factor, worker)?;
+
+        {
+            let s_id_lde = coset_lde(&setup.s_id_poly, lde_factor, worker)?;
+
+            let mut lhs = z_lde.clone();
+            let mut rhs = shift_lde(&z_lde, lde_factor)?;
+
+            let mut shift = E::Fr::zero();
+            for (wire, sigma) in wire_ldes.iter().zip(setup.sigma_polys.iter()) {
+                let mut tmp = s_id_lde.clone();
+                tmp.add_constant(worker, &shift);
+                tmp.scale(worker, beta);
+                tmp.add_assign(worker, wire);
+                tmp.add_constant(worker, &gamma);
+                lhs.mul_assign(worker, &tmp);
+
+                let mut tmp = coset_lde(sigma, lde_factor, worker)?;
+                tmp.scale(worker, beta);
+                tmp.add_assign(worker, wire);
+                tmp.add_constant(worker, &gamma);
+                rhs.mul_assign(worker, &tmp);
+
+                shift.add_assign(&size_fe);
+            }
+
+            lhs.sub_assign(worker, &rhs);
+            lhs.scale(worker, current_alpha);
+
+            quotient.add_assign(worker, &lhs);
+
+            current_alpha.mul_assign(&alpha);
+        }
+
+        let mut minus_one = E::Fr::one();
+        minus_one.negate();
+
+        let lagrange_lde = |number: usize| -> Result<Polynomial<E::Fr, Values>, SynthesisError> {
+            let mut values = vec![E::Fr::zero(); size];
+            values[number] = E::Fr::one();
+            let poly = Polynomial::from_values(values)?.ifft(worker);
+
+            coset_lde(&poly, lde_factor, worker)
+        };
+
+        let l_0_lde = lagrange_lde(0)?;
+
+        {
+            // (z(X) - 1) * L_0(X)
+            let mut tmp = z_lde.clone();
+            tmp.add_constant(worker, &minus_one);
+            tmp.mul_assign(worker, &l_0_lde);
+            tmp.scale(worker, current_alpha);
+
+            quotient.add_assign(worker, &tmp);
+
+            current_alpha.mul_assign(&alpha);
+        }
+
+        if let (Some((witness, _)), Some((grand_product, _)), Some(lookup_setup)) =
+            (lookup_witness.as_ref(), lookup_grand_product.as_ref(), setup.lookup.as_ref())
+        {
+            let eta = witness.eta;
+
+            let mut one_plus_beta = beta;
+            one_plus_beta.add_assign(&E::Fr::one());
+
+            let mut gamma_beta = gamma;
+            gamma_beta.mul_assign(&one_plus_beta);
+
+            let selector_lde = coset_lde(&lookup_setup.selector_poly, lde_factor, worker)?;
+            let table_type_lde = coset_lde(&lookup_setup.table_type_poly, lde_factor, worker)?;
+            let table_ldes = lookup_setup.table_polys.iter()
+                                .map(|p| coset_lde(p, lde_factor, worker))
+                                .collect::<Result<Vec<_>, _>>()?;
+
+            let f_lde = coset_lde(&witness.f_poly, lde_factor, worker)?;
+            let h_1_lde = coset_lde(&witness.h_1_poly, lde_factor, worker)?;
+            let h_2_lde = coset_lde(&witness.h_2_poly, lde_factor, worker)?;
+            let grand_product_lde = coset_lde(grand_product, lde_factor, worker)?;
+
+            let l_last_lde = lagrange_lde(size - 1)?;
+
+            {
+                // q_lookup(X) * (a(X) + eta*b(X) + eta^2*c(X) + eta^3*table_type(X) - f(X))
+                let mut tmp = compress_values(
+                    &[&wire_ldes[0], &wire_ldes[1], &wire_ldes[2], &table_type_lde],
+                    eta,
+                    worker
+                );
+                tmp.sub_assign(worker, &f_lde);
+                tmp.mul_assign(worker, &selector_lde);
+                tmp.scale(worker, current_alpha);
+
+                quotient.add_assign(worker, &tmp);
+
+                current_alpha.mul_assign(&alpha);
+            }
+
+            {
+                // (X - omega^{N-1}) * (Z(X)*(1+beta)*(gamma + f(X))*(gamma*(1+beta) + t(X) + beta*t(X*omega)) -
+                // Z(X*omega)*(gamma*(1+beta) + h_1(X) + beta*h_1(X*omega))*(gamma*(1+beta) + h_2(X) + beta*h_2(X*omega)))
+                let table_ldes: Vec<_> = table_ldes.iter().collect();
+                let t_lde = compress_values(&table_ldes, eta, worker);
+
+                let mut lhs = grand_product_lde.clone();
+                lhs.scale(worker, one_plus_beta);
+
+                let mut tmp = f_lde;
+                tmp.add_constant(worker, &gamma);
+                lhs.mul_assign(worker, &tmp);
+
+                let mut tmp = shift_lde(&t_lde, lde_factor)?;
+                tmp.scale(worker, beta);
+                tmp.add_assign(worker, &t_lde);
+                tmp.add_constant(worker, &gamma_beta);
+                lhs.mul_assign(worker, &tmp);
+
+                let mut rhs = shift_lde(&grand_product_lde, lde_factor)?;
+
+                for h_lde in [&h_1_lde, &h_2_lde].iter() {
+                    let mut tmp = shift_lde(h_lde, lde_factor)?;
+                    tmp.scale(worker, beta);
+                    tmp.add_assign(worker, h_lde);
+                    tmp.add_constant(worker, &gamma_beta);
+                    rhs.mul_assign(worker, &tmp);
+                }
+
+                lhs.sub_assign(worker, &rhs);
+
+                let mut x_coeffs = vec![E::Fr::zero(); size];
+                x_coeffs[1] = E::Fr::one();
+                let mut last_omega = domain.generator.pow([(size - 1) as u64]);
+                last_omega.negate();
+
+                let mut x_minus_last_omega = coset_lde(&Polynomial::from_coeffs(x_coeffs)?, lde_factor, worker)?;
+                x_minus_last_omega.add_constant(worker, &last_omega);
+
+                lhs.mul_assign(worker, &x_minus_last_omega);
+                lhs.scale(worker, current_alpha);
+
+                quotient.add_assign(worker, &lhs);
+
+                current_alpha.mul_assign(&alpha);
+            }
+
+            {
+                // (Z(X) - 1) * L_0(X)
+                let mut tmp = grand_product_lde.clone();
+                tmp.add_constant(worker, &minus_one);
+                tmp.mul_assign(worker, &l_0_lde);
+                tmp.scale(worker, current_alpha);
+
+                quotient.add_assign(worker, &tmp);
+
+                current_alpha.mul_assign(&alpha);
+            }
+
+            {
+                // (h_1(X) - h_2(X*omega)) * L_{N-1}(X)
+                let mut tmp = h_1_lde;
+                tmp.sub_assign(worker, &shift_lde(&h_2_lde, lde_factor)?);
+                tmp.mul_assign(worker, &l_last_lde);
+                tmp.scale(worker, current_alpha);
+
+                quotient.add_assign(worker, &tmp);
+
+                current_alpha.mul_assign(&alpha);
+            }
+
+            {
+                // (Z(X) - 1) * L_{N-1}(X)
+                let mut tmp = grand_product_lde;
+                tmp.add_constant(worker, &minus_one);
+                tmp.mul_assign(worker, &l_last_lde);
+                tmp.scale(worker, current_alpha);
+
+                quotient.add_assign(worker, &tmp);
+            }
+        }
+
+        {
+            // Z_H(X) = X^N - 1 takes only lde_factor different values on the coset
+            let lde_domain = Domain::<E::Fr>::new_for_size(lde_size as u64)?;
+            let omega_in_domain_size = lde_domain.generator.pow([size as u64]);
+
+            let mut vanishing_values = Vec::with_capacity(lde_factor);
+            let mut current = E::Fr::multiplicative_generator().pow([size as u64]);
+            for _ in 0..lde_factor {
+                let mut tmp = current;
+                tmp.sub_assign(&E::Fr::one());
+                vanishing_values.push(tmp.inverse().ok_or(SynthesisError::DivisionByZero)?);
+
+                current.mul_assign(&omega_in_domain_size);
+            }
+
+            worker.scope(lde_size, |scope, chunk| {
+                for (chunk_idx, quotient) in quotient.as_mut().chunks_mut(chunk).enumerate() {
+                    let vanishing_values = &vanishing_values;
+                    scope.spawn(move |_| {
+                        let start = chunk_idx * chunk;
+                        for (i, q) in quotient.iter_mut().enumerate() {
+                            q.mul_assign(&vanishing_values[(start + i) % lde_factor]);
+                        }
+                    });
+                }
+            });
+        }
+
+        let quotient_coeffs = quotient.icoset_fft(worker).into_coeffs();
+
+        let num_parts = constraints_degree - 1;
+
+        if quotient_coeffs[(num_parts * size)..].iter().any(|c| !c.is_zero()) {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        let quotient_parts = quotient_coeffs.chunks(size)
+                                .take(num_parts)
+                                .map(|c| Polynomial::from_coeffs(c.to_vec()))
+                                .collect::<Result<Vec<_>, _>>()?;
+
+        let quotient_poly_parts_commitments = quotient_parts.iter().map(commit).collect::<Result<Vec<_>, _>>()?;
+
+        for commitment in quotient_poly_parts_commitments.iter() {
+            transcript.commit_point(commitment);
+        }
+
+        let z = transcript.get_challenge();
+
+        let mut z_by_omega = z;
+        z_by_omega.mul_assign(&domain.generator);
+
+        let wire_values_at_z: Vec<_> = wire_polys.iter().map(|p| p.evaluate_at(worker, z)).collect();
+        let wire_d_value_at_z_omega = wire_polys[STATE_WIDTH - 1].evaluate_at(worker, z_by_omega);
+        let selector_values_at_z: Vec<_> = setup.selector_polys.iter().map(|p| p.evaluate_at(worker, z)).collect();
+        let constant_values_at_z: Vec<Vec<_>> = setup.constant_polys.iter()
+                                                    .map(|polys| polys.iter().map(|p| p.evaluate_at(worker, z)).collect())
+                                                    .collect();
+        let s_id_value_at_z = setup.s_id_poly.evaluate_at(worker, z);
+        let sigma_values_at_z: Vec<_> = setup.sigma_polys.iter().map(|p| p.evaluate_at(worker, z)).collect();
+        let grand_product_value_at_z = z_poly.evaluate_at(worker, z);
+        let grand_product_value_at_z_omega = z_poly.evaluate_at(worker, z_by_omega);
+        let quotient_poly_parts_values_at_z: Vec<_> = quotient_parts.iter().map(|p| p.evaluate_at(worker, z)).collect();
+
+        let mut polys_at_z = vec![];
+        let mut values_at_z = vec![];
+
+        polys_at_z.extend(wire_polys.iter());
+        values_at_z.extend(wire_values_at_z.iter().cloned());
+
+        polys_at_z.extend(setup.selector_polys.iter());
+        values_at_z.extend(selector_values_at_z.iter().cloned());
+
+        polys_at_z.extend(setup.constant_polys.iter().flatten());
+        values_at_z.extend(constant_values_at_z.iter().flatten().cloned());
+
+        polys_at_z.push(&setup.s_id_poly);
+        values_at_z.push(s_id_value_at_z);
+
+        polys_at_z.extend(setup.sigma_polys.iter());
+        values_at_z.extend(sigma_values_at_z.iter().cloned());
+
+        polys_at_z.push(&z_poly);
+        values_at_z.push(grand_product_value_at_z);
+
+        polys_at_z.extend(quotient_parts.iter());
+        values_at_z.extend(quotient_poly_parts_values_at_z.iter().cloned());
+
+        let mut polys_at_z_omega = vec![&wire_polys[STATE_WIDTH - 1], &z_poly];
+        let mut values_at_z_omega = vec![wire_d_value_at_z_omega, grand_product_value_at_z_omega];
+
+        let lookup_proof = match (lookup_witness.as_ref(), lookup_grand_product.as_ref(), setup.lookup.as_ref()) {
+            (Some((witness, commitments)), Some((grand_product, grand_product_commitment)), Some(lookup_setup)) => {
+                let proof = LookupProof::<E> {
+                    witness_commitment: commitments[0],
+                    h_1_commitment: commitments[1],
+                    h_2_commitment: commitments[2],
+                    grand_product_commitment: *grand_product_commitment,
+
+                    witness_value_at_z: witness.f_poly.evaluate_at(worker, z),
+                    h_1_value_at_z: witness.h_1_poly.evaluate_at(worker, z),
+                    h_2_value_at_z: witness.h_2_poly.evaluate_at(worker, z),
+                    grand_product_value_at_z: grand_product.evaluate_at(worker, z),
+                    selector_value_at_z: lookup_setup.selector_poly.evaluate_at(worker, z),
+                    table_type_value_at_z: lookup_setup.table_type_poly.evaluate_at(worker, z),
+                    table_values_at_z: lookup_setup.table_polys.iter().map(|p| p.evaluate_at(worker, z)).collect(),
+
+                    h_1_value_at_z_omega: witness.h_1_poly.evaluate_at(worker, z_by_omega),
+                    h_2_value_at_z_omega: witness.h_2_poly.evaluate_at(worker, z_by_omega),
+                    grand_product_value_at_z_omega: grand_product.evaluate_at(worker, z_by_omega),
+                    table_values_at_z_omega: lookup_setup.table_polys.iter().map(|p| p.evaluate_at(worker, z_by_omega)).collect(),
+                };
+
+                polys_at_z.extend(vec![
+                    &witness.f_poly,
+                    &witness.h_1_poly,
+                    &witness.h_2_poly,
+                    grand_product,
+                    &lookup_setup.selector_poly,
+                    &lookup_setup.table_type_poly
+                ]);
+                polys_at_z.extend(lookup_setup.table_polys.iter());
+                values_at_z.extend(proof.values_at_z());
+
+                polys_at_z_omega.extend(vec![&witness.h_1_poly, &witness.h_2_poly, grand_product]);
+                polys_at_z_omega.extend(lookup_setup.table_polys.iter());
+                values_at_z_omega.extend(proof.values_at_z_omega());
+
+                Some(proof)
+            },
+            _ => None
+        };
+
+        for value in values_at_z.iter().chain(values_at_z_omega.iter()) {
+            transcript.commit_field_element(value);
+        }
+
+        let request_at_z = OpeningRequest {
+            polynomials: polys_at_z,
+            opening_point: z,
+            opening_values: values_at_z
+        };
+
+        let opening_proof_at_z = PlonkProvingAssembly::<E>::multiopening(request_at_z, srs, worker, &mut transcript)?;
+
+        let request_at_z_omega = OpeningRequest {
+            polynomials: polys_at_z_omega,
+            opening_point: z_by_omega,
+            opening_values: values_at_z_omega
+        };
+
+        let opening_proof_at_z_omega = PlonkProvingAssembly::<E>::multiopening(request_at_z_omega, srs, worker, &mut transcript)?;
+
+        Ok(Width4Proof::<E> {
+            wire_commitments,
+            grand_product_commitment: z_commitment,
+            quotient_poly_parts_commitments,
+
+            wire_values_at_z,
+            wire_d_value_at_z_omega,
+            selector_values_at_z,
+            constant_values_at_z,
+            s_id_value_at_z,
+            sigma_values_at_z,
+            grand_product_value_at_z,
+            grand_product_value_at_z_omega,
+            quotient_poly_parts_values_at_z,
+
+            lookup: lookup_proof,
+
+            opening_proof_at_z,
+            opening_proof_at_z_omega,
+        })
+    }
+}
+
+pub fn prove<E: Engine, C: Width4Circuit<E>, T: Transcript<E::Fr>>(
+    circuit: &C,
+    setup: &Width4SetupPrecomputation<E>,
+    srs: &UniversalSrs<E>,
+    worker: &Worker
+) -> Result<Width4Proof<E>, SynthesisError> {
+    let mut assembly = ProvingAssembly::<E>::new_for_gates_and_tables(C::declare_used_gates(), C::declare_used_tables());
+
+    circuit.synthesize(&mut assembly)?;
+
+    assembly.prove::<T>(setup, srs, worker)
+}
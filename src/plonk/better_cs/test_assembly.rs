@@ -3,24 +3,10 @@ use crate::pairing::{Engine};
 
 use crate::{SynthesisError};
 use std::marker::PhantomData;
+use std::sync::Arc;
 
 use super::cs::*;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub struct Gate<F: PrimeField> {
-    variables: [Variable; 3],
-    coefficients: [F; 6],
-}
-
-impl<F: PrimeField> Gate<F> {
-    pub fn new_gate(variables:(Variable, Variable, Variable), coeffs: [F; 6]) -> Self {
-        Self {
-            variables: [variables.0, variables.1, variables.2],
-            coefficients: coeffs
-        }
-    }
-}
-
 #[derive(Debug, Clone)]
 pub struct TestAssembly<E: Engine> {
     m: usize,
@@ -36,12 +22,13 @@ pub struct TestAssembly<E: Engine> {
 
     inputs_map: Vec<usize>,
 
+    gates: Vec<Arc<dyn GateEquation<E::Fr>>>,
+    tables: Vec<Arc<LookupTable<E::Fr>>>,
+
     is_finalized: bool
 }
 
-impl<E: Engine> ConstraintSystem<E> for TestAssembly<E> {
-    type GateCoefficients = [E::Fr; 6];
-
+impl<E: Engine> Width4ConstraintSystem<E> for TestAssembly<E> {
     // allocate a variable
     fn alloc<F>(&mut self, value: F) -> Result<Variable, SynthesisError>
     where
@@ -71,13 +58,9 @@ impl<E: Engine> ConstraintSystem<E> for TestAssembly<E> {
 
         let input_var = Variable(Index::Input(index));
 
-        let zero = E::Fr::zero();
+        let dummy = self.dummy_variable();
 
-        let gate_coeffs = [E::Fr::one(), zero, zero, zero, zero, zero];
-
-        let dummy = self.get_dummy_variable();
-
-        let gate = Gate::<E::Fr>::new_gate((input_var, dummy, dummy), gate_coeffs);
+        let gate = Gate::<E::Fr>::new_input_gate(input_var, dummy);
 
         self.input_gates.push(gate);
 
@@ -87,19 +70,26 @@ impl<E: Engine> ConstraintSystem<E> for TestAssembly<E> {
 
     }
 
-    // allocate an abstract gate
-    fn new_gate(&mut self, variables: (Variable, Variable, Variable), 
-        coeffs: Self::GateCoefficients) -> Result<(), SynthesisError>
+    // allocate a row of the gate `gate_id`
+    fn new_gate(&mut self, gate_id: usize, variables: [Variable; STATE_WIDTH],
+        constants: &[E::Fr]) -> Result<(), SynthesisError>
     {
-        let gate = Gate::<E::Fr>::new_gate(variables, coeffs);
+        check_gate_arguments(&self.gates, gate_id, constants);
+
+        let gate = Gate::<E::Fr>::new_gate(gate_id, variables, constants);
         // println!("Enforced new gate number {}: {:?}", self.n, gate);
         self.aux_gates.push(gate);
         self.n += 1;
 
-        // let satisfied = self.is_satisfied(true);
-        // if !satisfied {
-        //     return Err(SynthesisError::Unsatisfiable);
-        // }
+        Ok(())
+    }
+
+    fn new_lookup_gate(&mut self, table_id: usize, variables: [Variable; LOOKUP_TABLE_WIDTH]) -> Result<(), SynthesisError> {
+        check_lookup_arguments(&self.tables, table_id);
+
+        let gate = Gate::<E::Fr>::new_lookup_gate(table_id, variables, self.dummy_variable());
+        self.aux_gates.push(gate);
+        self.n += 1;
 
         Ok(())
     }
@@ -129,8 +119,61 @@ impl<E: Engine> ConstraintSystem<E> for TestAssembly<E> {
     }
 }
 
+// three wire gate (a, b, c) with [q_l, q_r, q_o, q_m, q_c, q_c_next] is placed
+// as a main gate row (a, b, dummy, c), so c of the next gate is d_next
+impl<E: Engine> ConstraintSystem<E> for TestAssembly<E> {
+    type GateCoefficients = [E::Fr; 6];
+
+    fn alloc<F>(&mut self, value: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<E::Fr, SynthesisError> 
+    {
+        Width4ConstraintSystem::<E>::alloc(self, value)
+    }
+
+    fn alloc_input<F>(&mut self, value: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<E::Fr, SynthesisError> 
+    {
+        Width4ConstraintSystem::<E>::alloc_input(self, value)
+    }
+
+    fn new_gate(&mut self, variables: (Variable, Variable, Variable), 
+        coeffs: Self::GateCoefficients) -> Result<(), SynthesisError>
+    {
+        let [q_l, q_r, q_o, q_m, q_c, q_c_next] = coeffs;
+        let dummy = self.dummy_variable();
+
+        self.new_main_gate(
+            [variables.0, variables.1, dummy, variables.2],
+            [q_l, q_r, E::Fr::zero(), q_o, q_m, q_c, q_c_next]
+        )
+    }
+
+    fn get_value(&self, var: Variable) -> Result<E::Fr, SynthesisError> {
+        Width4ConstraintSystem::<E>::get_value(self, var)
+    }
+
+    fn get_dummy_variable(&self) -> Variable {
+        self.dummy_variable()
+    }
+}
+
 impl<E: Engine> TestAssembly<E> {
     pub fn new() -> Self {
+        Self::new_for_gates(vec![Arc::new(Width4MainGate)])
+    }
+
+    pub fn new_for_gates(gates: Vec<Arc<dyn GateEquation<E::Fr>>>) -> Self {
+        Self::new_for_gates_and_tables(gates, vec![])
+    }
+
+    pub fn new_for_gates_and_tables(
+        gates: Vec<Arc<dyn GateEquation<E::Fr>>>,
+        tables: Vec<Arc<LookupTable<E::Fr>>>
+    ) -> Self {
+        check_used_gates(&gates);
+
         let tmp = Self {
             n: 0,
             m: 0,
@@ -145,6 +188,9 @@ impl<E: Engine> TestAssembly<E> {
 
             inputs_map: vec![],
 
+            gates,
+            tables,
+
             is_finalized: false,
         };
 
@@ -166,6 +212,9 @@ impl<E: Engine> TestAssembly<E> {
 
             inputs_map: Vec::with_capacity(num_inputs),
 
+            gates: vec![Arc::new(Width4MainGate)],
+            tables: vec![],
+
             is_finalized: false,
         };
 
@@ -177,145 +226,386 @@ impl<E: Engine> TestAssembly<E> {
         Variable(Index::Aux(0))
     }
 
+    fn wire_values(&self, gate: &Gate<E::Fr>) -> [E::Fr; STATE_WIDTH] {
+        let mut values = [E::Fr::zero(); STATE_WIDTH];
+        for (value, var) in values.iter_mut().zip(gate.variables.iter()) {
+            *value = Width4ConstraintSystem::<E>::get_value(self, *var).expect("must get a variable value");
+        }
+
+        values
+    }
+
     pub fn is_satisfied(&self, in_a_middle: bool) -> bool {
         // expect a small number of inputs
         for (i, gate) in self.input_gates.iter().enumerate()
         {
-            let Gate::<E::Fr> {
-                variables: [a_var, b_var, c_var],
-                coefficients: [q_l, q_r, q_o, q_m, q_c, q_c_next]
-            } = *gate;
+            assert_eq!(gate.gate_id, MAIN_GATE_ID, "input gates should use the main gate");
+            assert!(gate.constants[5].is_zero(), "should not hardcode a constant into the input gate");
+            assert!(gate.constants[6].is_zero(), "input gates should not link to the next gate");
 
-            assert!(q_c.is_zero(), "should not hardcode a constant into the input gate");
-            assert!(q_c_next.is_zero(), "input gates should not link to the next gate");
+            let wires = self.wire_values(gate);
 
-            let a_value = self.get_value(a_var).expect("must get a variable value");
-            let b_value = self.get_value(b_var).expect("must get a variable value");
-            let c_value = self.get_value(c_var).expect("must get a variable value");
+            let mut res = self.gates[MAIN_GATE_ID].evaluate_term(0, &wires, E::Fr::zero(), &gate.constants);
+            res.sub_assign(&self.input_assingments[i]);
 
-            let input_value = self.input_assingments[i];
-            let mut res = input_value;
-            res.negate();
+            if !res.is_zero() {
+                println!("Unsatisfied at input gate {}: {:?}", i+1, gate);
+                println!("Wire values = {:?}", wires);
+                return false;
+            }
+        }
 
-            let mut tmp = q_l;
-            tmp.mul_assign(&a_value);
-            res.add_assign(&tmp);
+        for (i, gate) in self.aux_gates.iter().enumerate()
+        {
+            let equation = &self.gates[gate.gate_id];
+
+            // there is always a padding row with zero values after the last gate
+            let d_next = match self.aux_gates.get(i+1) {
+                Some(next_gate) => {
+                    Width4ConstraintSystem::<E>::get_value(self, next_gate.variables[STATE_WIDTH - 1]).expect("must get a variable value")
+                },
+                None => {
+                    if in_a_middle && equation.uses_next_row() {
+                        break;
+                    }
+
+                    E::Fr::zero()
+                }
+            };
+
+            let wires = self.wire_values(gate);
+
+            if let Some(table_id) = gate.lookup_table {
+                let mut entry = [E::Fr::zero(); LOOKUP_TABLE_WIDTH];
+                entry.copy_from_slice(&wires[0..LOOKUP_TABLE_WIDTH]);
+
+                if !self.tables[table_id].contains(&entry) {
+                    println!("Missing lookup entry at aux gate {} in the table {}", i+1, self.tables[table_id].name());
+                    println!("Gate {:?}", gate);
+                    println!("Entry = {:?}", entry);
+                    return false;
+                }
+            }
 
-            let mut tmp = q_r;
-            tmp.mul_assign(&b_value);
-            res.add_assign(&tmp);
+            for term in 0..equation.num_terms() {
+                let res = equation.evaluate_term(term, &wires, d_next, &gate.constants);
 
-            let mut tmp = q_o;
-            tmp.mul_assign(&c_value);
-            res.add_assign(&tmp);
+                if !res.is_zero() {
+                    println!("Unsatisfied at aux gate {} ({}), term {}", i+1, equation.name(), term);
+                    println!("Gate {:?}", gate);
+                    println!("Wire values = {:?}, d_next = {}", wires, d_next);
+                    return false;
+                }
+            }
+        }
 
-            let mut tmp = q_m;
-            tmp.mul_assign(&a_value);
-            tmp.mul_assign(&b_value);
-            res.add_assign(&tmp);
+        true
+    }
 
-            if !res.is_zero() {
-                println!("Unsatisfied at input gate {}: {:?}", i+1, gate);
-                println!("A value = {}, B value = {}, C value = {}", a_value, b_value, c_value);
-                return false;
+    /// Table ids of all lookup gates whose wires are not an entry of the table, in order of gates
+    pub fn missing_lookup_entries(&self) -> Vec<usize> {
+        let mut missing = vec![];
+        for gate in self.aux_gates.iter() {
+            if let Some(table_id) = gate.lookup_table {
+                let wires = self.wire_values(gate);
+                let mut entry = [E::Fr::zero(); LOOKUP_TABLE_WIDTH];
+                entry.copy_from_slice(&wires[0..LOOKUP_TABLE_WIDTH]);
+
+                if !self.tables[table_id].contains(&entry) {
+                    missing.push(table_id);
+                }
             }
         }
 
-        for (i, gate_pair) in self.aux_gates.windows(2).enumerate()
-        {
-            let this_gate = gate_pair[0];
-            let next_gate = &gate_pair[1];
+        missing
+    }
+
+    pub fn num_gates(&self) -> usize {
+        self.input_gates.len() + self.aux_gates.len()
+    }
+}
 
-            let Gate::<E::Fr> {
-                variables: [a_var, b_var, c_var],
-                coefficients: [q_l, q_r, q_o, q_m, q_c, q_c_next]
-            } = this_gate;
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
 
-            let a_value = self.get_value(a_var).expect("must get a variable value");
-            let b_value = self.get_value(b_var).expect("must get a variable value");
-            let c_value = self.get_value(c_var).expect("must get a variable value");
+    use crate::pairing::Engine;
+    use crate::pairing::ff::{Field, PrimeField};
 
-            let next_gate_c_var = next_gate.variables[2];
+    // a, b and c are 2-bit values and d_next = 64 * d + 16 * a + 4 * b + c
+    #[derive(Debug)]
+    pub(crate) struct RangeCheckGate;
 
-            let c_next_value = self.get_value(next_gate_c_var).expect("must get a variable value");
+    impl<F: PrimeField> GateEquation<F> for RangeCheckGate {
+        fn name(&self) -> &'static str {
+            "2-bit chunks accumulation"
+        }
 
-            let mut res = q_c;
+        fn degree(&self) -> usize {
+            4
+        }
 
-            let mut tmp = q_l;
-            tmp.mul_assign(&a_value);
-            res.add_assign(&tmp);
+        fn num_constants(&self) -> usize {
+            0
+        }
 
-            let mut tmp = q_r;
-            tmp.mul_assign(&b_value);
-            res.add_assign(&tmp);
+        fn num_terms(&self) -> usize {
+            4
+        }
 
-            let mut tmp = q_o;
-            tmp.mul_assign(&c_value);
-            res.add_assign(&tmp);
+        fn uses_next_row(&self) -> bool {
+            true
+        }
 
-            let mut tmp = q_m;
-            tmp.mul_assign(&a_value);
-            tmp.mul_assign(&b_value);
-            res.add_assign(&tmp);
+        fn evaluate_term(&self, term: usize, wires: &[F; STATE_WIDTH], d_next: F, _constants: &[F]) -> F {
+            if term < 3 {
+                let mut res = F::one();
+                let mut tmp = wires[term];
+                for _ in 0..4 {
+                    res.mul_assign(&tmp);
+                    tmp.sub_assign(&F::one());
+                }
 
-            let mut tmp = q_c_next;
-            tmp.mul_assign(&c_next_value);
-            res.add_assign(&tmp);
+                return res;
+            }
 
-            if !res.is_zero() {
-                println!("Unsatisfied at aux gate {}", i+1);
-                println!("Gate {:?}", this_gate);
-                println!("A = {}, B = {}, C = {}", a_value, b_value, c_value);
-                return false;
+            let mut res = wires[3];
+            for wire in wires[0..3].iter() {
+                res.double();
+                res.double();
+                res.add_assign(wire);
             }
+            res.sub_assign(&d_next);
+
+            res
         }
+    }
 
-        if !in_a_middle {
-            let i = self.aux_gates.len();
-            let last_gate = *self.aux_gates.last().unwrap();
+    // d = a^5
+    #[derive(Debug)]
+    pub(crate) struct Power5Gate;
 
-            let Gate::<E::Fr> {
-                variables: [a_var, b_var, c_var],
-                coefficients: [q_l, q_r, q_o, q_m, q_c, q_c_next]
-            } = last_gate;
+    impl<F: PrimeField> GateEquation<F> for Power5Gate {
+        fn name(&self) -> &'static str {
+            "fifth power"
+        }
 
-            let a_value = self.get_value(a_var).expect("must get a variable value");
-            let b_value = self.get_value(b_var).expect("must get a variable value");
-            let c_value = self.get_value(c_var).expect("must get a variable value");
+        fn degree(&self) -> usize {
+            5
+        }
 
-            assert!(q_c_next.is_zero(), "last gate should not be linked to the next one");
+        fn num_constants(&self) -> usize {
+            0
+        }
 
-            let mut res = q_c;
+        fn uses_next_row(&self) -> bool {
+            false
+        }
 
-            let mut tmp = q_l;
-            tmp.mul_assign(&a_value);
-            res.add_assign(&tmp);
+        fn evaluate_term(&self, _term: usize, wires: &[F; STATE_WIDTH], _d_next: F, _constants: &[F]) -> F {
+            let mut res = wires[0].pow([5u64]);
+            res.sub_assign(&wires[3]);
 
-            let mut tmp = q_r;
-            tmp.mul_assign(&b_value);
-            res.add_assign(&tmp);
+            res
+        }
+    }
 
-            let mut tmp = q_o;
-            tmp.mul_assign(&c_value);
-            res.add_assign(&tmp);
+    pub(crate) const RANGE_CHECK_GATE_ID: usize = 1;
+    pub(crate) const POWER_5_GATE_ID: usize = 2;
 
-            let mut tmp = q_m;
-            tmp.mul_assign(&a_value);
-            tmp.mul_assign(&b_value);
-            res.add_assign(&tmp);
+    /// Checks that a public input fits into 12 bits using two rows of 2-bit chunks
+    /// and exposes it's fifth power as another public input
+    #[derive(Clone, Debug)]
+    pub(crate) struct TestCircuit {
+        pub(crate) value: u64,
+    }
 
-            if !res.is_zero() {
-                println!("Unsatisfied at aux gate {}", i+1);
-                println!("Gate {:?}", last_gate);
-                println!("A = {}, B = {}, C = {}", a_value, b_value, c_value);
-                return false;
+    impl<E: Engine> Width4Circuit<E> for TestCircuit {
+        fn declare_used_gates() -> Vec<Arc<dyn GateEquation<E::Fr>>> {
+            vec![Arc::new(Width4MainGate), Arc::new(RangeCheckGate), Arc::new(Power5Gate)]
+        }
+
+        fn synthesize<CS: Width4ConstraintSystem<E>>(&self, cs: &mut CS) -> Result<(), SynthesisError> {
+            let zero = E::Fr::zero();
+            let one = E::Fr::one();
+            let mut minus_one = one;
+            minus_one.negate();
+
+            let dummy = cs.get_dummy_variable();
+
+            let value = E::Fr::from_str(&self.value.to_string()).unwrap();
+            let fifth_power = value.pow([5u64]);
+
+            let x = cs.alloc_input(|| Ok(value))?;
+            let y = cs.alloc_input(|| Ok(fifth_power))?;
+
+            // most significant chunks first
+            let chunks: Vec<u64> = (0..6).rev().map(|i| (self.value >> (2 * i)) & 3).collect();
+
+            let mut accumulator = dummy;
+            let mut accumulated_value = 0u64;
+
+            for row in chunks.chunks(3) {
+                let mut vars = [dummy; STATE_WIDTH];
+                for (var, chunk) in vars.iter_mut().zip(row.iter()) {
+                    *var = cs.alloc(|| Ok(E::Fr::from_str(&chunk.to_string()).unwrap()))?;
+                    accumulated_value = accumulated_value * 4 + chunk;
+                }
+                vars[3] = accumulator;
+
+                cs.new_gate(RANGE_CHECK_GATE_ID, vars, &[])?;
+
+                accumulator = cs.alloc(|| Ok(E::Fr::from_str(&accumulated_value.to_string()).unwrap()))?;
             }
+
+            // x - accumulator = 0, accumulator is also d_next of the last range check row
+            cs.new_main_gate([x, dummy, dummy, accumulator], [one, zero, zero, minus_one, zero, zero, zero])?;
+
+            let power = cs.alloc(|| Ok(fifth_power))?;
+            cs.new_gate(POWER_5_GATE_ID, [x, dummy, dummy, power], &[])?;
+
+            cs.new_main_gate([power, y, dummy, dummy], [one, minus_one, zero, zero, zero, zero, zero])?;
+
+            Ok(())
         }
+    }
 
-        true
+    pub(crate) const XOR_TABLE_ID: usize = 0;
+    pub(crate) const RANGE_TABLE_ID: usize = 1;
+
+    /// XORs two public 4-bit values using lookups of 2-bit chunks
+    #[derive(Clone, Debug)]
+    pub(crate) struct LookupTestCircuit {
+        pub(crate) a: u64,
+        pub(crate) b: u64,
     }
 
-    pub fn num_gates(&self) -> usize {
-        self.input_gates.len() + self.aux_gates.len()
+    impl<E: Engine> Width4Circuit<E> for LookupTestCircuit {
+        fn declare_used_tables() -> Vec<Arc<LookupTable<E::Fr>>> {
+            vec![Arc::new(LookupTable::xor(2)), Arc::new(LookupTable::range(4))]
+        }
+
+        fn synthesize<CS: Width4ConstraintSystem<E>>(&self, cs: &mut CS) -> Result<(), SynthesisError> {
+            let zero = E::Fr::zero();
+            let one = E::Fr::one();
+            let mut minus_one = one;
+            minus_one.negate();
+            let four = E::Fr::from_str("4").unwrap();
+
+            let dummy = cs.get_dummy_variable();
+
+            let mut vars = vec![];
+            for value in [self.a, self.b, self.a ^ self.b].iter() {
+                let var = cs.alloc_input(|| Ok(E::Fr::from_str(&value.to_string()).unwrap()))?;
+                let low = cs.alloc(|| Ok(E::Fr::from_str(&(value & 3).to_string()).unwrap()))?;
+                let high = cs.alloc(|| Ok(E::Fr::from_str(&(value >> 2).to_string()).unwrap()))?;
+
+                // var = low + 4 * high
+                cs.new_main_gate([low, high, dummy, var], [one, four, zero, minus_one, zero, zero, zero])?;
+
+                vars.push((var, low, high));
+            }
+
+            cs.new_lookup_gate(XOR_TABLE_ID, [vars[0].1, vars[1].1, vars[2].1])?;
+            cs.new_lookup_gate(XOR_TABLE_ID, [vars[0].2, vars[1].2, vars[2].2])?;
+            cs.new_lookup_gate(RANGE_TABLE_ID, [vars[0].0, dummy, dummy])?;
+
+            Ok(())
+        }
+    }
+
+    pub(crate) fn lookup_test_circuit_inputs<E: Engine>(a: u64, b: u64) -> Vec<E::Fr> {
+        [a, b, a ^ b].iter().map(|v| E::Fr::from_str(&v.to_string()).unwrap()).collect()
+    }
+
+    pub(crate) fn test_circuit_inputs<E: Engine>(value: u64) -> Vec<E::Fr> {
+        let value = E::Fr::from_str(&value.to_string()).unwrap();
+
+        vec![value, value.pow([5u64])]
+    }
+
+    #[test]
+    fn test_custom_gates_are_satisfied() {
+        use crate::pairing::bn256::Bn256;
+
+        let circuit = TestCircuit { value: 2925 };
+
+        let mut assembly = TestAssembly::<Bn256>::new_for_gates(<TestCircuit as Width4Circuit<Bn256>>::declare_used_gates());
+        circuit.synthesize(&mut assembly).unwrap();
+
+        assert!(assembly.is_satisfied(false));
+        assert_eq!(assembly.num_gates(), 7);
+    }
+
+    #[test]
+    fn test_custom_gates_are_unsatisfied() {
+        use crate::pairing::bn256::{Bn256, Fr};
+
+        // value does not fit into 12 bits, so the accumulator differs from the input
+        let circuit = TestCircuit { value: 4096 + 2925 };
+
+        let mut assembly = TestAssembly::<Bn256>::new_for_gates(<TestCircuit as Width4Circuit<Bn256>>::declare_used_gates());
+        circuit.synthesize(&mut assembly).unwrap();
+
+        assert!(!assembly.is_satisfied(false));
+
+        // chunk is not a 2-bit value
+        let mut assembly = TestAssembly::<Bn256>::new_for_gates(<TestCircuit as Width4Circuit<Bn256>>::declare_used_gates());
+        let dummy = Width4ConstraintSystem::<Bn256>::get_dummy_variable(&assembly);
+        let a = Width4ConstraintSystem::<Bn256>::alloc(&mut assembly, || Ok(Fr::from_str("4").unwrap())).unwrap();
+        Width4ConstraintSystem::<Bn256>::new_gate(&mut assembly, RANGE_CHECK_GATE_ID, [a, dummy, dummy, dummy], &[]).unwrap();
+        let next = Width4ConstraintSystem::<Bn256>::alloc(&mut assembly, || Ok(Fr::from_str("64").unwrap())).unwrap();
+        assembly.new_main_gate([dummy, dummy, dummy, next], [Fr::zero(); 7]).unwrap();
+
+        assert!(!assembly.is_satisfied(true));
+    }
+
+    #[test]
+    fn test_lookups_are_satisfied() {
+        use crate::pairing::bn256::Bn256;
+
+        let circuit = LookupTestCircuit { a: 9, b: 5 };
+
+        let mut assembly = TestAssembly::<Bn256>::new_for_gates_and_tables(
+            <LookupTestCircuit as Width4Circuit<Bn256>>::declare_used_gates(),
+            <LookupTestCircuit as Width4Circuit<Bn256>>::declare_used_tables()
+        );
+        circuit.synthesize(&mut assembly).unwrap();
+
+        assert!(assembly.is_satisfied(false));
+    }
+
+    #[test]
+    fn test_missing_lookup_entry_is_flagged() {
+        use crate::pairing::bn256::{Bn256, Fr};
+
+        // high chunk of 17 is not a 2-bit value and 17 does not fit into 4 bits,
+        // so both the XOR lookup of high chunks and the range lookup are missing
+        let circuit = LookupTestCircuit { a: 17, b: 5 };
+
+        let mut assembly = TestAssembly::<Bn256>::new_for_gates_and_tables(
+            <LookupTestCircuit as Width4Circuit<Bn256>>::declare_used_gates(),
+            <LookupTestCircuit as Width4Circuit<Bn256>>::declare_used_tables()
+        );
+        circuit.synthesize(&mut assembly).unwrap();
+
+        assert!(!assembly.is_satisfied(false));
+        assert_eq!(assembly.missing_lookup_entries(), vec![XOR_TABLE_ID, RANGE_TABLE_ID]);
+
+        // 9 is in range, but 1 xor 2 is not 0, so only the XOR lookup is missing
+        let mut assembly = TestAssembly::<Bn256>::new_for_gates_and_tables(
+            <LookupTestCircuit as Width4Circuit<Bn256>>::declare_used_gates(),
+            <LookupTestCircuit as Width4Circuit<Bn256>>::declare_used_tables()
+        );
+        let dummy = Width4ConstraintSystem::<Bn256>::get_dummy_variable(&assembly);
+        let mut vars = vec![];
+        for value in ["1", "2", "0", "9"].iter() {
+            vars.push(Width4ConstraintSystem::<Bn256>::alloc(&mut assembly, || Ok(Fr::from_str(value).unwrap())).unwrap());
+        }
+        assembly.new_lookup_gate(XOR_TABLE_ID, [vars[0], vars[1], vars[2]]).unwrap();
+        assembly.new_lookup_gate(RANGE_TABLE_ID, [vars[3], dummy, dummy]).unwrap();
+
+        assert!(!assembly.is_satisfied(false));
+        assert_eq!(assembly.missing_lookup_entries(), vec![XOR_TABLE_ID]);
     }
 }
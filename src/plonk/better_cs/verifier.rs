@@ -0,0 +1,575 @@
+use crate::pairing::ff::{Field, PrimeField};
+use crate::pairing::{Engine, CurveAffine, CurveProjective};
+
+use crate::{SynthesisError};
+
+use crate::plonk::domains::*;
+use crate::plonk::commitments::transcript::*;
+use crate::plonk::plonk::srs::UniversalSrs;
+use crate::plonk::plonk::verifier::aggregate_for_opening;
+
+use super::cs::*;
+use super::generator::*;
+use super::lookup::*;
+use super::prover::Width4Proof;
+
+// L_i(X) = omega^i * (X^N - 1) / (N * (X - omega^i))
+fn evaluate_lagrange_poly<F: PrimeField>(domain: &Domain<F>, poly_number: usize, at: F) -> Result<F, SynthesisError> {
+    let omega_power = domain.generator.pow([poly_number as u64]);
+
+    let mut numerator = at.pow([domain.size]);
+    numerator.sub_assign(&F::one());
+    numerator.mul_assign(&omega_power);
+
+    let mut denominator = at;
+    denominator.sub_assign(&omega_power);
+    denominator.mul_assign(&F::from_str(&domain.size.to_string()).expect("must fit into the field"));
+
+    let denominator = denominator.inverse().ok_or(SynthesisError::DivisionByZero)?;
+    numerator.mul_assign(&denominator);
+
+    Ok(numerator)
+}
+
+/// Verifies a proof produced by `prove` for a circuit of type `C`, that also
+/// declares the gates to be used. Only G2 elements of `srs` are used
+pub fn verify<E: Engine, C: Width4Circuit<E>, T: Transcript<E::Fr>>(
+    proof: &Width4Proof<E>,
+    public_inputs: &[E::Fr],
+    setup: &Width4Setup<E>,
+    srs: &UniversalSrs<E>
+) -> Result<bool, SynthesisError> {
+    let gates = C::declare_used_gates();
+    check_used_gates(&gates);
+
+    let tables = C::declare_used_tables();
+
+    if public_inputs.len() != setup.num_inputs {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+
+    if setup.selector_commitments.len() != gates.len()
+        || setup.constant_commitments.len() != gates.len()
+        || setup.sigma_commitments.len() != STATE_WIDTH {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+
+    for (commitments, gate) in setup.constant_commitments.iter().zip(gates.iter()) {
+        if commitments.len() != gate.num_constants() {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+    }
+
+    let num_parts = constraints_degree(&gates) - 1;
+
+    if proof.wire_commitments.len() != STATE_WIDTH
+        || proof.wire_values_at_z.len() != STATE_WIDTH
+        || proof.sigma_values_at_z.len() != STATE_WIDTH
+        || proof.selector_values_at_z.len() != gates.len()
+        || proof.constant_values_at_z.len() != gates.len()
+        || proof.quotient_poly_parts_commitments.len() != num_parts
+        || proof.quotient_poly_parts_values_at_z.len() != num_parts {
+        return Ok(false);
+    }
+
+    for (values, gate) in proof.constant_values_at_z.iter().zip(gates.iter()) {
+        if values.len() != gate.num_constants() {
+            return Ok(false);
+        }
+    }
+
+    if setup.lookup.is_some() == tables.is_empty() {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+
+    let lookup = match (setup.lookup.as_ref(), proof.lookup.as_ref()) {
+        (None, None) => None,
+        (Some(lookup_setup), Some(lookup_proof)) => {
+            if lookup_setup.table_commitments.len() != LOOKUP_TABLE_WIDTH + 1 {
+                return Err(SynthesisError::MalformedVerifyingKey);
+            }
+
+            if lookup_proof.table_values_at_z.len() != LOOKUP_TABLE_WIDTH + 1
+                || lookup_proof.table_values_at_z_omega.len() != LOOKUP_TABLE_WIDTH + 1 {
+                return Ok(false);
+            }
+
+            Some((lookup_setup, lookup_proof))
+        },
+        _ => {
+            return Ok(false);
+        }
+    };
+
+    let size = domain_size_for(setup.n, total_tables_size(&tables));
+    let domain = Domain::<E::Fr>::new_for_size(size as u64)?;
+    let size_fe = E::Fr::from_str(&size.to_string()).expect("must fit into the field");
+
+    let mut transcript = T::new();
+
+    for commitment in proof.wire_commitments.iter() {
+        transcript.commit_point(commitment);
+    }
+
+    for input in public_inputs.iter() {
+        transcript.commit_field_element(input);
+    }
+
+    let eta = match lookup {
+        Some((_, lookup_proof)) => {
+            let eta = transcript.get_challenge();
+
+            transcript.commit_point(&lookup_proof.witness_commitment);
+            transcript.commit_point(&lookup_proof.h_1_commitment);
+            transcript.commit_point(&lookup_proof.h_2_commitment);
+
+            eta
+        },
+        None => E::Fr::zero()
+    };
+
+    let beta = transcript.get_challenge();
+    let gamma = transcript.get_challenge();
+
+    transcript.commit_point(&proof.grand_product_commitment);
+
+    if let Some((_, lookup_proof)) = lookup {
+        transcript.commit_point(&lookup_proof.grand_product_commitment);
+    }
+
+    let alpha = transcript.get_challenge();
+
+    for commitment in proof.quotient_poly_parts_commitments.iter() {
+        transcript.commit_point(commitment);
+    }
+
+    let z = transcript.get_challenge();
+
+    let mut z_by_omega = z;
+    z_by_omega.mul_assign(&domain.generator);
+
+    let mut commitments_at_z = vec![];
+    let mut values_at_z = vec![];
+
+    commitments_at_z.extend(proof.wire_commitments.iter().cloned());
+    values_at_z.extend(proof.wire_values_at_z.iter().cloned());
+
+    commitments_at_z.extend(setup.selector_commitments.iter().cloned());
+    values_at_z.extend(proof.selector_values_at_z.iter().cloned());
+
+    commitments_at_z.extend(setup.constant_commitments.iter().flatten().cloned());
+    values_at_z.extend(proof.constant_values_at_z.iter().flatten().cloned());
+
+    commitments_at_z.push(setup.s_id_commitment);
+    values_at_z.push(proof.s_id_value_at_z);
+
+    commitments_at_z.extend(setup.sigma_commitments.iter().cloned());
+    values_at_z.extend(proof.sigma_values_at_z.iter().cloned());
+
+    commitments_at_z.push(proof.grand_product_commitment);
+    values_at_z.push(proof.grand_product_value_at_z);
+
+    commitments_at_z.extend(proof.quotient_poly_parts_commitments.iter().cloned());
+    values_at_z.extend(proof.quotient_poly_parts_values_at_z.iter().cloned());
+
+    let mut commitments_at_z_omega = vec![
+        proof.wire_commitments[STATE_WIDTH - 1],
+        proof.grand_product_commitment,
+    ];
+    let mut values_at_z_omega = vec![
+        proof.wire_d_value_at_z_omega,
+        proof.grand_product_value_at_z_omega,
+    ];
+
+    if let Some((lookup_setup, lookup_proof)) = lookup {
+        commitments_at_z.extend(vec![
+            lookup_proof.witness_commitment,
+            lookup_proof.h_1_commitment,
+            lookup_proof.h_2_commitment,
+            lookup_proof.grand_product_commitment,
+            lookup_setup.selector_commitment,
+            lookup_setup.table_type_commitment,
+        ]);
+        commitments_at_z.extend(lookup_setup.table_commitments.iter().cloned());
+        values_at_z.extend(lookup_proof.values_at_z());
+
+        commitments_at_z_omega.extend(vec![
+            lookup_proof.h_1_commitment,
+            lookup_proof.h_2_commitment,
+            lookup_proof.grand_product_commitment,
+        ]);
+        commitments_at_z_omega.extend(lookup_setup.table_commitments.iter().cloned());
+        values_at_z_omega.extend(lookup_proof.values_at_z_omega());
+    }
+
+    for value in values_at_z.iter().chain(values_at_z_omega.iter()) {
+        transcript.commit_field_element(value);
+    }
+
+    let mut wires_at_z = [E::Fr::zero(); STATE_WIDTH];
+    wires_at_z.copy_from_slice(&proof.wire_values_at_z);
+
+    // PI(X) = - sum input_i * L_i(X) is a part of the main gate
+    let mut constraints_at_z = E::Fr::zero();
+    for (i, input) in public_inputs.iter().enumerate() {
+        let mut tmp = evaluate_lagrange_poly(&domain, i, z)?;
+        tmp.mul_assign(input);
+        constraints_at_z.sub_assign(&tmp);
+    }
+
+    let mut current_alpha = E::Fr::one();
+
+    for ((gate, selector_at_z), constants_at_z) in gates.iter()
+                                                .zip(proof.selector_values_at_z.iter())
+                                                .zip(proof.constant_values_at_z.iter())
+    {
+        for term in 0..gate.num_terms() {
+            let mut tmp = gate.evaluate_term(term, &wires_at_z, proof.wire_d_value_at_z_omega, constants_at_z);
+            tmp.mul_assign(selector_at_z);
+            tmp.mul_assign(&current_alpha);
+            constraints_at_z.add_assign(&tmp);
+
+            current_alpha.mul_assign(&alpha);
+        }
+    }
+
+    {
+        let mut lhs = proof.grand_product_value_at_z;
+        let mut rhs = proof.grand_product_value_at_z_omega;
+
+        let mut shift = E::Fr::zero();
+        for (wire_at_z, sigma_at_z) in wires_at_z.iter().zip(proof.sigma_values_at_z.iter()) {
+            let mut tmp = proof.s_id_value_at_z;
+            tmp.add_assign(&shift);
+            tmp.mul_assign(&beta);
+            tmp.add_assign(wire_at_z);
+            tmp.add_assign(&gamma);
+            lhs.mul_assign(&tmp);
+
+            let mut tmp = *sigma_at_z;
+            tmp.mul_assign(&beta);
+            tmp.add_assign(wire_at_z);
+            tmp.add_assign(&gamma);
+            rhs.mul_assign(&tmp);
+
+            shift.add_assign(&size_fe);
+        }
+
+        lhs.sub_assign(&rhs);
+        lhs.mul_assign(&current_alpha);
+        constraints_at_z.add_assign(&lhs);
+
+        current_alpha.mul_assign(&alpha);
+    }
+
+    let l_0_at_z = evaluate_lagrange_poly(&domain, 0, z)?;
+
+    {
+        let mut tmp = proof.grand_product_value_at_z;
+        tmp.sub_assign(&E::Fr::one());
+        tmp.mul_assign(&l_0_at_z);
+        tmp.mul_assign(&current_alpha);
+        constraints_at_z.add_assign(&tmp);
+
+        current_alpha.mul_assign(&alpha);
+    }
+
+    if let Some((_, lookup_proof)) = lookup {
+        let l_last_at_z = evaluate_lagrange_poly(&domain, size - 1, z)?;
+
+        let mut one_plus_beta = beta;
+        one_plus_beta.add_assign(&E::Fr::one());
+
+        let mut gamma_beta = gamma;
+        gamma_beta.mul_assign(&one_plus_beta);
+
+        let mut terms = vec![];
+
+        // q_lookup * (a + eta*b + eta^2*c + eta^3*table_type - f)
+        {
+            let mut tmp = compress(&[wires_at_z[0], wires_at_z[1], wires_at_z[2], lookup_proof.table_type_value_at_z], eta);
+            tmp.sub_assign(&lookup_proof.witness_value_at_z);
+            tmp.mul_assign(&lookup_proof.selector_value_at_z);
+            terms.push(tmp);
+        }
+
+        // (X - omega^{N-1}) * (Z*(1+beta)*(gamma + f)*(gamma*(1+beta) + t + beta*t_omega) -
+        // Z_omega*(gamma*(1+beta) + h_1 + beta*h_1_omega)*(gamma*(1+beta) + h_2 + beta*h_2_omega))
+        {
+            let t_at_z = compress(&lookup_proof.table_values_at_z, eta);
+            let t_at_z_omega = compress(&lookup_proof.table_values_at_z_omega, eta);
+
+            let pair = |value: E::Fr, value_shifted: E::Fr| {
+                let mut tmp = value_shifted;
+                tmp.mul_assign(&beta);
+                tmp.add_assign(&value);
+                tmp.add_assign(&gamma_beta);
+
+                tmp
+            };
+
+            let mut lhs = lookup_proof.grand_product_value_at_z;
+            lhs.mul_assign(&one_plus_beta);
+
+            let mut tmp = lookup_proof.witness_value_at_z;
+            tmp.add_assign(&gamma);
+            lhs.mul_assign(&tmp);
+            lhs.mul_assign(&pair(t_at_z, t_at_z_omega));
+
+            let mut rhs = lookup_proof.grand_product_value_at_z_omega;
+            rhs.mul_assign(&pair(lookup_proof.h_1_value_at_z, lookup_proof.h_1_value_at_z_omega));
+            rhs.mul_assign(&pair(lookup_proof.h_2_value_at_z, lookup_proof.h_2_value_at_z_omega));
+
+            lhs.sub_assign(&rhs);
+
+            let mut tmp = z;
+            tmp.sub_assign(&domain.generator.pow([(size - 1) as u64]));
+            lhs.mul_assign(&tmp);
+
+            terms.push(lhs);
+        }
+
+        // (Z - 1) * L_0
+        {
+            let mut tmp = lookup_proof.grand_product_value_at_z;
+            tmp.sub_assign(&E::Fr::one());
+            tmp.mul_assign(&l_0_at_z);
+            terms.push(tmp);
+        }
+
+        // (h_1 - h_2_omega) * L_{N-1}
+        {
+            let mut tmp = lookup_proof.h_1_value_at_z;
+            tmp.sub_assign(&lookup_proof.h_2_value_at_z_omega);
+            tmp.mul_assign(&l_last_at_z);
+            terms.push(tmp);
+        }
+
+        // (Z - 1) * L_{N-1}
+        {
+            let mut tmp = lookup_proof.grand_product_value_at_z;
+            tmp.sub_assign(&E::Fr::one());
+            tmp.mul_assign(&l_last_at_z);
+            terms.push(tmp);
+        }
+
+        for mut term in terms.into_iter() {
+            term.mul_assign(&current_alpha);
+            constraints_at_z.add_assign(&term);
+
+            current_alpha.mul_assign(&alpha);
+        }
+    }
+
+    // t(z) * Z_H(z) = sum of the constraints at z, where t(X) = sum t_i(X) * X^{i*N}
+    let z_in_domain_size = z.pow([size as u64]);
+
+    let mut t_at_z = E::Fr::zero();
+    let mut power = E::Fr::one();
+    for part in proof.quotient_poly_parts_values_at_z.iter() {
+        let mut tmp = *part;
+        tmp.mul_assign(&power);
+        t_at_z.add_assign(&tmp);
+
+        power.mul_assign(&z_in_domain_size);
+    }
+
+    let mut vanishing_at_z = z_in_domain_size;
+    vanishing_at_z.sub_assign(&E::Fr::one());
+    t_at_z.mul_assign(&vanishing_at_z);
+
+    if t_at_z != constraints_at_z {
+        return Ok(false);
+    }
+
+    // now check the openings, challenges are drawn in the same order as in the prover
+
+    let aggregation_challenge_at_z = transcript.get_challenge();
+    let aggregation_challenge_at_z_omega = transcript.get_challenge();
+
+    let aggregate_at_z = aggregate_for_opening::<E>(
+        &commitments_at_z,
+        &values_at_z,
+        aggregation_challenge_at_z
+    );
+
+    let aggregate_at_z_omega = aggregate_for_opening::<E>(
+        &commitments_at_z_omega,
+        &values_at_z_omega,
+        aggregation_challenge_at_z_omega
+    );
+
+    // both openings are checked at once with a random linear combination
+
+    transcript.commit_point(&proof.opening_proof_at_z);
+    transcript.commit_point(&proof.opening_proof_at_z_omega);
+
+    let u = transcript.get_challenge();
+
+    // e(W_z + u*W_zw, [x]) = e(z*W_z + u*z*omega*W_zw + F_z + u*F_zw, [1])
+
+    let mut pair_with_x = proof.opening_proof_at_z_omega.mul(u.into_repr());
+    pair_with_x.add_assign_mixed(&proof.opening_proof_at_z);
+
+    let mut pair_with_generator = aggregate_at_z;
+    pair_with_generator.add_assign(&proof.opening_proof_at_z.mul(z.into_repr()));
+
+    let mut tmp = aggregate_at_z_omega;
+    tmp.add_assign(&proof.opening_proof_at_z_omega.mul(z_by_omega.into_repr()));
+    tmp.mul_assign(u.into_repr());
+
+    pair_with_generator.add_assign(&tmp);
+    pair_with_generator.negate();
+
+    let valid = E::final_exponentiation(
+        &E::miller_loop([
+            (&pair_with_x.into_affine().prepare(), &srs.g2_powers[1].prepare()),
+            (&pair_with_generator.into_affine().prepare(), &srs.g2_powers[0].prepare())
+        ].iter())
+    ).ok_or(SynthesisError::Unsatisfiable)? == E::Fqk::one();
+
+    Ok(valid)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::prover::*;
+    use super::super::test_assembly::test::*;
+
+    use crate::pairing::Engine;
+    use crate::pairing::ff::{Field, PrimeField};
+    use crate::multicore::Worker;
+
+    fn prove_test_circuit<E: Engine>(value: u64) -> (Width4Setup<E>, Width4Proof<E>, UniversalSrs<E>) {
+        let circuit = TestCircuit { value };
+
+        let worker = Worker::new();
+        let srs = UniversalSrs::<E>::new(16, E::Fr::from_str("42").unwrap());
+
+        let (setup, setup_precomp) = setup::<E, _>(&circuit, &srs, &worker).unwrap();
+
+        let proof = prove::<E, _, Blake2sTranscript<E::Fr>>(&circuit, &setup_precomp, &srs, &worker).unwrap();
+
+        (setup, proof, srs)
+    }
+
+    #[test]
+    fn test_prove_and_verify_with_custom_gates() {
+        use crate::pairing::bn256::{Bn256, Fr};
+
+        let (setup, proof, srs) = prove_test_circuit::<Bn256>(2925);
+
+        // 7 gates and a padding row
+        assert_eq!(setup.n, 7);
+        assert_eq!(proof.quotient_poly_parts_commitments.len(), 5);
+
+        let inputs = test_circuit_inputs::<Bn256>(2925);
+
+        let valid = verify::<Bn256, TestCircuit, Blake2sTranscript<Fr>>(&proof, &inputs, &setup, &srs).unwrap();
+        assert!(valid);
+
+        let valid = verify::<Bn256, TestCircuit, Blake2sTranscript<Fr>>(&proof, &inputs, &setup, &srs.trim(1)).unwrap();
+        assert!(valid);
+    }
+
+    #[test]
+    fn test_custom_gates_proof_is_bound_to_public_inputs() {
+        use crate::pairing::bn256::{Bn256, Fr};
+
+        let (setup, proof, srs) = prove_test_circuit::<Bn256>(1000);
+
+        let mut wrong_inputs = test_circuit_inputs::<Bn256>(1000);
+        wrong_inputs[1].add_assign(&Fr::one());
+        let valid = verify::<Bn256, TestCircuit, Blake2sTranscript<Fr>>(&proof, &wrong_inputs, &setup, &srs).unwrap();
+        assert!(!valid);
+
+        assert!(verify::<Bn256, TestCircuit, Blake2sTranscript<Fr>>(&proof, &wrong_inputs[..1], &setup, &srs).is_err());
+    }
+
+    #[test]
+    fn test_tampered_custom_gates_proof_is_rejected() {
+        use crate::pairing::bn256::{Bn256, Fr};
+
+        let (setup, proof, srs) = prove_test_circuit::<Bn256>(2925);
+        let inputs = test_circuit_inputs::<Bn256>(2925);
+
+        let mut bad_proof = proof.clone();
+        bad_proof.wire_d_value_at_z_omega.add_assign(&Fr::one());
+        let valid = verify::<Bn256, TestCircuit, Blake2sTranscript<Fr>>(&bad_proof, &inputs, &setup, &srs).unwrap();
+        assert!(!valid);
+
+        let mut bad_proof = proof.clone();
+        bad_proof.opening_proof_at_z_omega = bad_proof.opening_proof_at_z;
+        let valid = verify::<Bn256, TestCircuit, Blake2sTranscript<Fr>>(&bad_proof, &inputs, &setup, &srs).unwrap();
+        assert!(!valid);
+
+        let mut bad_proof = proof;
+        bad_proof.quotient_poly_parts_values_at_z.pop();
+        let valid = verify::<Bn256, TestCircuit, Blake2sTranscript<Fr>>(&bad_proof, &inputs, &setup, &srs).unwrap();
+        assert!(!valid);
+    }
+
+    #[test]
+    fn test_unsatisfied_custom_gates_can_not_be_proven() {
+        use crate::pairing::bn256::{Bn256, Fr};
+
+        let worker = Worker::new();
+        let srs = UniversalSrs::<Bn256>::new(16, Fr::from_str("42").unwrap());
+
+        let (_, setup_precomp) = setup::<Bn256, _>(&TestCircuit { value: 2925 }, &srs, &worker).unwrap();
+
+        // same shape of the circuit, but the value does not fit into 12 bits
+        let circuit = TestCircuit { value: 4096 + 2925 };
+        let proof = prove::<Bn256, _, Blake2sTranscript<Fr>>(&circuit, &setup_precomp, &srs, &worker);
+
+        assert!(proof.is_err());
+    }
+
+    fn prove_lookup_test_circuit<E: Engine>(a: u64, b: u64) -> Result<(Width4Setup<E>, Width4Proof<E>, UniversalSrs<E>), SynthesisError> {
+        let worker = Worker::new();
+        let srs = UniversalSrs::<E>::new(64, E::Fr::from_str("42").unwrap());
+
+        // setup does not depend on the witness
+        let (setup, setup_precomp) = setup::<E, _>(&LookupTestCircuit { a: 0, b: 0 }, &srs, &worker)?;
+
+        let proof = prove::<E, _, Blake2sTranscript<E::Fr>>(&LookupTestCircuit { a, b }, &setup_precomp, &srs, &worker)?;
+
+        Ok((setup, proof, srs))
+    }
+
+    #[test]
+    fn test_prove_and_verify_with_lookups() {
+        use crate::pairing::bn256::{Bn256, Fr};
+
+        let (setup, proof, srs) = prove_lookup_test_circuit::<Bn256>(9, 5).unwrap();
+        assert!(setup.lookup.is_some());
+
+        let inputs = lookup_test_circuit_inputs::<Bn256>(9, 5);
+
+        let valid = verify::<Bn256, LookupTestCircuit, Blake2sTranscript<Fr>>(&proof, &inputs, &setup, &srs).unwrap();
+        assert!(valid);
+
+        let wrong_inputs = lookup_test_circuit_inputs::<Bn256>(9, 6);
+        let valid = verify::<Bn256, LookupTestCircuit, Blake2sTranscript<Fr>>(&proof, &wrong_inputs, &setup, &srs).unwrap();
+        assert!(!valid);
+
+        let mut bad_proof = proof.clone();
+        bad_proof.lookup.as_mut().unwrap().h_1_value_at_z_omega.add_assign(&Fr::one());
+        let valid = verify::<Bn256, LookupTestCircuit, Blake2sTranscript<Fr>>(&bad_proof, &inputs, &setup, &srs).unwrap();
+        assert!(!valid);
+
+        let mut bad_proof = proof;
+        bad_proof.lookup = None;
+        let valid = verify::<Bn256, LookupTestCircuit, Blake2sTranscript<Fr>>(&bad_proof, &inputs, &setup, &srs).unwrap();
+        assert!(!valid);
+    }
+
+    #[test]
+    fn test_missing_lookup_entry_can_not_be_proven() {
+        use crate::pairing::bn256::Bn256;
+
+        assert!(prove_lookup_test_circuit::<Bn256>(17, 5).is_err());
+    }
+}
+
@@ -0,0 +1,490 @@
+use crate::pairing::ff::{Field, PrimeField};
+use crate::pairing::{CurveAffine, CurveProjective, EncodedPoint};
+
+use std::marker::PhantomData;
+
+use crate::multicore::Worker;
+use crate::multiexp::dense_multiexp;
+use crate::plonk::polynomials::*;
+use crate::plonk::commitments::transcript::*;
+
+use super::{CommitmentScheme, aggregate_polynomials, group_by_points};
+
+const GENERATORS_DOMAIN: &[u8] = b"bellman_ipa_generators";
+
+/// Proof that <a, (1, z, z^2, ...)> = v for the vector `a` committed as <a, G>,
+/// made of log(n) pairs of cross terms and the final folded scalar
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IpaOpeningProof<G: CurveAffine> {
+    pub l: Vec<G>,
+    pub r: Vec<G>,
+    pub a: G::Scalar,
+}
+
+/// Bulletproofs style inner product argument over any curve. Generators are derived by
+/// hashing into the curve, so no trusted setup is required. Commitments are not hiding.
+/// Openings at the same point are aggregated with powers of the aggregation coefficient,
+/// and a proof is made for every distinct point
+pub struct IpaCommitter<G: CurveAffine, T: Transcript<G::Scalar>> {
+    max_degree_plus_one: usize,
+    generators: Vec<G>,
+    u: G,
+    worker: Worker,
+    _marker: PhantomData<T>
+}
+
+// generators are produced by a seeded rng with x coordinates sampled until one is on the curve,
+// so their discrete logarithms are unknown
+fn make_generators<G: CurveAffine>(num: usize) -> (Vec<G>, G) {
+    use rand::{ChaChaRng, SeedableRng, Rand};
+    use byteorder::{BigEndian, ByteOrder};
+
+    let hash = blake2s_simd::Params::new().hash_length(32).hash(GENERATORS_DOMAIN);
+    let mut seed = [0u32; 8];
+    BigEndian::read_u32_into(hash.as_bytes(), &mut seed);
+
+    let mut rng = ChaChaRng::from_seed(&seed[..]);
+    let u = G::Projective::rand(&mut rng).into_affine();
+    let generators = (0..num).map(|_| G::Projective::rand(&mut rng).into_affine()).collect();
+
+    (generators, u)
+}
+
+fn inner_product<F: PrimeField>(a: &[F], b: &[F]) -> F {
+    let mut result = F::zero();
+    for (a, b) in a.iter().zip(b.iter()) {
+        let mut tmp = *a;
+        tmp.mul_assign(b);
+        result.add_assign(&tmp);
+    }
+
+    result
+}
+
+fn powers<F: PrimeField>(base: F, num: usize) -> Vec<F> {
+    let mut result = Vec::with_capacity(num);
+    let mut current = F::one();
+    for _ in 0..num {
+        result.push(current);
+        current.mul_assign(&base);
+    }
+
+    result
+}
+
+impl<G: CurveAffine, T: Transcript<G::Scalar>> IpaCommitter<G, T> {
+    fn multiexp(&self, bases: &[G], scalars: &[G::Scalar]) -> G::Projective {
+        let reprs: Vec<_> = scalars.iter().map(|s| s.into_repr()).collect();
+
+        dense_multiexp(&self.worker, bases, &reprs).expect("must make a multiexp")
+    }
+
+    // coefficients padded to the number of generators
+    fn padded_coefficients(&self, coeffs: &[G::Scalar]) -> Vec<G::Scalar> {
+        let mut len = coeffs.len();
+        while len > 0 && coeffs[len - 1].is_zero() {
+            len -= 1;
+        }
+
+        assert!(len <= self.generators.len(), "polynomial degree is too large for the generators");
+
+        let mut result = coeffs[..len].to_vec();
+        result.resize(self.generators.len(), G::Scalar::zero());
+
+        result
+    }
+
+    fn commit_coefficients(&self, coeffs: &[G::Scalar]) -> G {
+        let coeffs = self.padded_coefficients(coeffs);
+
+        self.multiexp(&self.generators, &coeffs).into_affine()
+    }
+
+    // binds the claim to the transcript and returns the generator for the inner products
+    fn start_opening(&self, commitment: &G, at_point: G::Scalar, value: G::Scalar, prng: &mut T) -> G {
+        prng.commit_point(commitment);
+        prng.commit_field_element(&at_point);
+        prng.commit_field_element(&value);
+
+        let xi = prng.get_challenge();
+
+        self.u.mul(xi.into_repr()).into_affine()
+    }
+
+    fn open_coefficients(&self, coeffs: &[G::Scalar], at_point: G::Scalar, prng: &mut T) -> IpaOpeningProof<G> {
+        let mut a = self.padded_coefficients(coeffs);
+        let mut b = powers(at_point, a.len());
+        let mut g = self.generators.clone();
+
+        let commitment = self.multiexp(&g, &a).into_affine();
+        let value = inner_product(&a, &b);
+        let u = self.start_opening(&commitment, at_point, value, prng);
+
+        let mut l_vec = vec![];
+        let mut r_vec = vec![];
+
+        while a.len() > 1 {
+            let half = a.len() / 2;
+            let (a_lo, a_hi) = a.split_at(half);
+            let (b_lo, b_hi) = b.split_at(half);
+            let (g_lo, g_hi) = g.split_at(half);
+
+            let mut l = self.multiexp(g_hi, a_lo);
+            l.add_assign(&u.mul(inner_product(a_lo, b_hi).into_repr()));
+            let l = l.into_affine();
+
+            let mut r = self.multiexp(g_lo, a_hi);
+            r.add_assign(&u.mul(inner_product(a_hi, b_lo).into_repr()));
+            let r = r.into_affine();
+
+            prng.commit_point(&l);
+            prng.commit_point(&r);
+
+            let x = prng.get_challenge();
+            let x_inv = x.inverse().expect("challenge is not zero");
+
+            let fold = |lo: &[G::Scalar], hi: &[G::Scalar], lo_by: G::Scalar, hi_by: G::Scalar| -> Vec<G::Scalar> {
+                lo.iter().zip(hi.iter()).map(|(lo, hi)| {
+                    let mut lo = *lo;
+                    lo.mul_assign(&lo_by);
+                    let mut hi = *hi;
+                    hi.mul_assign(&hi_by);
+                    lo.add_assign(&hi);
+
+                    lo
+                }).collect()
+            };
+
+            let new_a = fold(a_lo, a_hi, x, x_inv);
+            let new_b = fold(b_lo, b_hi, x_inv, x);
+            let new_g = g_lo.iter().zip(g_hi.iter()).map(|(lo, hi)| {
+                let mut tmp = lo.mul(x_inv.into_repr());
+                tmp.add_assign(&hi.mul(x.into_repr()));
+
+                tmp.into_affine()
+            }).collect();
+
+            a = new_a;
+            b = new_b;
+            g = new_g;
+
+            l_vec.push(l);
+            r_vec.push(r);
+        }
+
+        IpaOpeningProof {
+            l: l_vec,
+            r: r_vec,
+            a: a[0]
+        }
+    }
+
+    // a * <s, G> + (a * b - v) * U' - C - sum x_j^2 * L_j - sum x_j^-2 * R_j = 0
+    // checked with a single multiexp, where s are the coefficients of the folded generator
+    fn verify_opening(&self, commitment: &G, at_point: G::Scalar, value: G::Scalar, proof: &IpaOpeningProof<G>, prng: &mut T) -> bool {
+        let n = self.generators.len();
+        if proof.l.len() != proof.r.len() || (1 << proof.l.len()) != n {
+            return false;
+        }
+
+        let u = self.start_opening(commitment, at_point, value, prng);
+
+        let mut challenges = Vec::with_capacity(proof.l.len());
+        for (l, r) in proof.l.iter().zip(proof.r.iter()) {
+            prng.commit_point(l);
+            prng.commit_point(r);
+
+            let x = prng.get_challenge();
+            let x_inv = match x.inverse() {
+                Some(x_inv) => x_inv,
+                None => return false
+            };
+
+            challenges.push((x, x_inv));
+        }
+
+        // challenge of the first round multiplies the upper half of the generators,
+        // and the folded b is a product of (x_j^-1 + x_j * z^(2^(k - 1 - j)))
+        let mut s = vec![G::Scalar::one()];
+        let mut b = G::Scalar::one();
+        for (j, (x, x_inv)) in challenges.iter().enumerate() {
+            s = s.iter().flat_map(|s| {
+                let mut lo = *s;
+                lo.mul_assign(x_inv);
+                let mut hi = *s;
+                hi.mul_assign(x);
+
+                vec![lo, hi]
+            }).collect();
+
+            let mut tmp = at_point.pow([(n >> (j + 1)) as u64]);
+            tmp.mul_assign(x);
+            tmp.add_assign(x_inv);
+            b.mul_assign(&tmp);
+        }
+
+        let mut bases = self.generators.clone();
+        let mut scalars: Vec<G::Scalar> = s.into_iter().map(|mut s| {
+            s.mul_assign(&proof.a);
+
+            s
+        }).collect();
+
+        let mut u_scalar = proof.a;
+        u_scalar.mul_assign(&b);
+        u_scalar.sub_assign(&value);
+        bases.push(u);
+        scalars.push(u_scalar);
+
+        let mut minus_one = G::Scalar::one();
+        minus_one.negate();
+        bases.push(*commitment);
+        scalars.push(minus_one);
+
+        for ((l, r), (x, x_inv)) in proof.l.iter().zip(proof.r.iter()).zip(challenges.iter()) {
+            let mut x_squared = *x;
+            x_squared.square();
+            x_squared.negate();
+            bases.push(*l);
+            scalars.push(x_squared);
+
+            let mut x_inv_squared = *x_inv;
+            x_inv_squared.square();
+            x_inv_squared.negate();
+            bases.push(*r);
+            scalars.push(x_inv_squared);
+        }
+
+        self.multiexp(&bases, &scalars).is_zero()
+    }
+}
+
+impl<G: CurveAffine, T: Transcript<G::Scalar>> CommitmentScheme<G::Scalar> for IpaCommitter<G, T> {
+    type Commitment = G;
+    type OpeningProof = Vec<IpaOpeningProof<G>>;
+    type IntermediateData = ();
+    type Meta = ();
+    type Prng = T;
+
+    const REQUIRES_PRECOMPUTATION: bool = false;
+    const IS_HOMOMORPHIC: bool = true;
+
+    fn new_for_size(max_degree_plus_one: usize, _meta: Self::Meta) -> Self {
+        let (generators, u) = make_generators(max_degree_plus_one.next_power_of_two());
+
+        Self {
+            max_degree_plus_one,
+            generators,
+            u,
+            worker: Worker::new(),
+            _marker: PhantomData
+        }
+    }
+
+    fn precompute(&self, _poly: &Polynomial<G::Scalar, Coefficients>) -> Option<Self::IntermediateData> {
+        None
+    }
+
+    fn commit_single(&self, poly: &Polynomial<G::Scalar, Coefficients>) -> (Self::Commitment, Option<Self::IntermediateData>) {
+        (self.commit_coefficients(poly.as_ref()), None)
+    }
+
+    fn commit_multiple(
+        &self,
+        polynomials: Vec<&Polynomial<G::Scalar, Coefficients>>,
+        degrees: Vec<usize>,
+        aggregation_coefficient: G::Scalar
+    ) -> (Self::Commitment, Option<Vec<Self::IntermediateData>>) {
+        assert_eq!(polynomials.len(), degrees.len());
+        assert!(degrees.iter().all(|&d| d < self.max_degree_plus_one));
+
+        let indexes: Vec<usize> = (0..polynomials.len()).collect();
+        let aggregated = aggregate_polynomials(&polynomials, &indexes, aggregation_coefficient);
+
+        (self.commit_coefficients(&aggregated), None)
+    }
+
+    fn open_single(
+        &self,
+        poly: &Polynomial<G::Scalar, Coefficients>,
+        at_point: G::Scalar,
+        _opening_value: G::Scalar,
+        _data: &Option<&Self::IntermediateData>,
+        prng: &mut Self::Prng
+    ) -> Self::OpeningProof {
+        vec![self.open_coefficients(poly.as_ref(), at_point, prng)]
+    }
+
+    fn open_multiple(
+        &self,
+        polynomials: Vec<&Polynomial<G::Scalar, Coefficients>>,
+        degrees: Vec<usize>,
+        aggregation_coefficient: G::Scalar,
+        at_points: Vec<G::Scalar>,
+        opening_values: Vec<G::Scalar>,
+        _data: &Option<Vec<&Self::IntermediateData>>,
+        prng: &mut Self::Prng
+    ) -> Self::OpeningProof {
+        assert_eq!(polynomials.len(), degrees.len());
+        assert_eq!(polynomials.len(), at_points.len());
+        assert_eq!(polynomials.len(), opening_values.len());
+
+        group_by_points(&at_points).into_iter().map(|(point, indexes)| {
+            let aggregated = aggregate_polynomials(&polynomials, &indexes, aggregation_coefficient);
+
+            self.open_coefficients(&aggregated, point, prng)
+        }).collect()
+    }
+
+    fn verify_single(
+        &self,
+        commitment: &Self::Commitment,
+        at_point: G::Scalar,
+        claimed_value: G::Scalar,
+        proof: &Self::OpeningProof,
+        prng: &mut Self::Prng
+    ) -> bool {
+        if proof.len() != 1 {
+            return false;
+        }
+
+        self.verify_opening(commitment, at_point, claimed_value, &proof[0], prng)
+    }
+
+    fn verify_multiple_openings(
+        &self,
+        commitments: Vec<&Self::Commitment>,
+        at_points: Vec<G::Scalar>,
+        claimed_values: &Vec<G::Scalar>,
+        aggregation_coefficient: G::Scalar,
+        proof: &Self::OpeningProof,
+        prng: &mut Self::Prng
+    ) -> bool {
+        if commitments.len() != at_points.len() || commitments.len() != claimed_values.len() {
+            return false;
+        }
+
+        let groups = group_by_points(&at_points);
+        if groups.len() != proof.len() {
+            return false;
+        }
+
+        for ((point, indexes), proof) in groups.into_iter().zip(proof.iter()) {
+            let mut aggregated_commitment = G::Projective::zero();
+            let mut aggregated_value = G::Scalar::zero();
+            for &i in indexes.iter() {
+                let alpha = aggregation_coefficient.pow([i as u64]);
+                aggregated_commitment.add_assign(&commitments[i].mul(alpha.into_repr()));
+
+                let mut tmp = claimed_values[i];
+                tmp.mul_assign(&alpha);
+                aggregated_value.add_assign(&tmp);
+            }
+
+            if !self.verify_opening(&aggregated_commitment.into_affine(), point, aggregated_value, proof, prng) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pairing::bn256::{Fr, G1Affine};
+    use crate::plonk::commitments::transcript::Blake2sTranscript;
+
+    type Committer = IpaCommitter<G1Affine, Blake2sTranscript<Fr>>;
+
+    fn make_polys(num: usize, size: usize) -> Vec<Polynomial<Fr, Coefficients>> {
+        use rand::{XorShiftRng, SeedableRng, Rand};
+        let rng = &mut XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        (0..num).map(|_| {
+            let coeffs = (0..size).map(|_| Fr::rand(rng)).collect();
+            Polynomial::from_coeffs(coeffs).unwrap()
+        }).collect()
+    }
+
+    #[test]
+    fn test_ipa_generators_are_deterministic() {
+        let (small, u_small) = make_generators::<G1Affine>(4);
+        let (large, u_large) = make_generators::<G1Affine>(8);
+
+        assert_eq!(u_small, u_large);
+        assert_eq!(&small[..], &large[..4]);
+    }
+
+    #[test]
+    fn test_ipa_single_opening() {
+        let committer = Committer::new_for_size(16, ());
+        let poly = make_polys(1, 16).pop().unwrap();
+        let z = Fr::from_str("123").unwrap();
+        let value = poly.evaluate_at(&Worker::new(), z);
+
+        let (commitment, _) = committer.commit_single(&poly);
+
+        let mut prover_transcript = Blake2sTranscript::<Fr>::new();
+        let proof = committer.open_single(&poly, z, value, &None, &mut prover_transcript);
+        assert_eq!(proof[0].l.len(), 4);
+
+        let mut verifier_transcript = Blake2sTranscript::<Fr>::new();
+        assert!(committer.verify_single(&commitment, z, value, &proof, &mut verifier_transcript));
+        assert_eq!(prover_transcript.get_challenge(), verifier_transcript.get_challenge());
+
+        let mut wrong_value = value;
+        wrong_value.add_assign(&Fr::one());
+        assert!(!committer.verify_single(&commitment, z, wrong_value, &proof, &mut Blake2sTranscript::<Fr>::new()));
+
+        let mut wrong_proof = proof.clone();
+        wrong_proof[0].a.add_assign(&Fr::one());
+        assert!(!committer.verify_single(&commitment, z, value, &wrong_proof, &mut Blake2sTranscript::<Fr>::new()));
+    }
+
+    #[test]
+    fn test_ipa_multiple_openings() {
+        let committer = Committer::new_for_size(16, ());
+        let polys = make_polys(3, 8);
+        let worker = Worker::new();
+
+        let z = Fr::from_str("123").unwrap();
+        let z_other = Fr::from_str("456").unwrap();
+        let points = vec![z, z_other, z];
+        let values: Vec<Fr> = polys.iter().zip(points.iter()).map(|(p, at)| p.evaluate_at(&worker, *at)).collect();
+        let commitments: Vec<_> = polys.iter().map(|p| committer.commit_single(p).0).collect();
+        let alpha = Fr::from_str("11").unwrap();
+
+        let proof = committer.open_multiple(
+            polys.iter().collect(),
+            vec![7; 3],
+            alpha,
+            points.clone(),
+            values.clone(),
+            &None,
+            &mut Blake2sTranscript::<Fr>::new()
+        );
+        assert_eq!(proof.len(), 2);
+
+        assert!(committer.verify_multiple_openings(
+            commitments.iter().collect(),
+            points.clone(),
+            &values,
+            alpha,
+            &proof,
+            &mut Blake2sTranscript::<Fr>::new()
+        ));
+
+        let mut wrong_values = values.clone();
+        wrong_values[2].add_assign(&Fr::one());
+        assert!(!committer.verify_multiple_openings(
+            commitments.iter().collect(),
+            points,
+            &wrong_values,
+            alpha,
+            &proof,
+            &mut Blake2sTranscript::<Fr>::new()
+        ));
+    }
+}
@@ -0,0 +1,360 @@
+use crate::pairing::ff::{Field, PrimeField};
+use crate::pairing::{Engine, CurveAffine, CurveProjective};
+
+use std::marker::PhantomData;
+
+use crate::multicore::Worker;
+use crate::multiexp::dense_multiexp;
+use crate::plonk::polynomials::*;
+use crate::plonk::commitments::transcript::*;
+use crate::plonk::commitments::transparent::kate_divison_with_same_return_size;
+use crate::plonk::plonk::prover::field_elements_into_representations;
+use crate::plonk::plonk::srs::UniversalSrs;
+
+use super::{CommitmentScheme, aggregate_polynomials, group_by_points};
+
+/// KZG commitments over G1 of the engine. Openings of many polynomials are aggregated
+/// with powers of the aggregation coefficient and result in one witness per distinct point
+pub struct KateCommitter<E: Engine, T: Transcript<E::Fr>> {
+    max_degree_plus_one: usize,
+    srs: UniversalSrs<E>,
+    worker: Worker,
+    _marker: PhantomData<T>
+}
+
+impl<E: Engine, T: Transcript<E::Fr>> KateCommitter<E, T> {
+    fn commit_coefficients(&self, coeffs: &[E::Fr]) -> E::G1Affine {
+        let mut len = coeffs.len();
+        while len > 0 && coeffs[len - 1].is_zero() {
+            len -= 1;
+        }
+
+        assert!(len <= self.srs.size(), "polynomial degree is too large for the SRS");
+
+        if len == 0 {
+            return E::G1Affine::zero();
+        }
+
+        let reprs = field_elements_into_representations::<E>(&self.worker, coeffs[..len].to_vec())
+            .expect("must convert scalars");
+
+        dense_multiexp(&self.worker, &self.srs.g1_powers[..len], &reprs)
+            .expect("must make a multiexp")
+            .into_affine()
+    }
+
+    // e(sum r^j * W_j, [x]) = e(sum r^j * (z_j * W_j + F_j), [1])
+    // where F_j is an aggregated commitment minus an aggregated value at the point z_j
+    fn check_openings(&self, aggregates: Vec<(E::Fr, E::G1)>, witnesses: &[E::G1Affine], challenge: E::Fr) -> bool {
+        if aggregates.len() != witnesses.len() {
+            return false;
+        }
+
+        let mut pair_with_x = E::G1::zero();
+        let mut pair_with_generator = E::G1::zero();
+        let mut current = E::Fr::one();
+
+        for ((point, aggregate), witness) in aggregates.into_iter().zip(witnesses.iter()) {
+            let mut tmp = witness.mul(current.into_repr());
+            pair_with_x.add_assign(&tmp);
+
+            tmp.mul_assign(point.into_repr());
+            let mut aggregate = aggregate;
+            aggregate.mul_assign(current.into_repr());
+            tmp.add_assign(&aggregate);
+            pair_with_generator.add_assign(&tmp);
+
+            current.mul_assign(&challenge);
+        }
+
+        pair_with_generator.negate();
+
+        let result = E::final_exponentiation(
+            &E::miller_loop([
+                (&pair_with_x.into_affine().prepare(), &self.srs.g2_powers[1].prepare()),
+                (&pair_with_generator.into_affine().prepare(), &self.srs.g2_powers[0].prepare())
+            ].iter())
+        );
+
+        match result {
+            Some(value) => value == E::Fqk::one(),
+            None => false
+        }
+    }
+
+    fn commit_witnesses(witnesses: &[E::G1Affine], prng: &mut T) {
+        for w in witnesses.iter() {
+            prng.commit_point(w);
+        }
+    }
+}
+
+impl<E: Engine, T: Transcript<E::Fr>> CommitmentScheme<E::Fr> for KateCommitter<E, T> {
+    type Commitment = E::G1Affine;
+    type OpeningProof = Vec<E::G1Affine>;
+    type IntermediateData = ();
+    type Meta = UniversalSrs<E>;
+    type Prng = T;
+
+    const REQUIRES_PRECOMPUTATION: bool = false;
+    const IS_HOMOMORPHIC: bool = true;
+
+    fn new_for_size(max_degree_plus_one: usize, meta: Self::Meta) -> Self {
+        assert!(meta.size() >= max_degree_plus_one, "SRS is too small for the degree");
+
+        Self {
+            max_degree_plus_one,
+            srs: meta,
+            worker: Worker::new(),
+            _marker: PhantomData
+        }
+    }
+
+    fn precompute(&self, _poly: &Polynomial<E::Fr, Coefficients>) -> Option<Self::IntermediateData> {
+        None
+    }
+
+    fn commit_single(&self, poly: &Polynomial<E::Fr, Coefficients>) -> (Self::Commitment, Option<Self::IntermediateData>) {
+        (self.commit_coefficients(poly.as_ref()), None)
+    }
+
+    fn commit_multiple(
+        &self,
+        polynomials: Vec<&Polynomial<E::Fr, Coefficients>>,
+        degrees: Vec<usize>,
+        aggregation_coefficient: E::Fr
+    ) -> (Self::Commitment, Option<Vec<Self::IntermediateData>>) {
+        assert_eq!(polynomials.len(), degrees.len());
+        assert!(degrees.iter().all(|&d| d < self.max_degree_plus_one));
+
+        let indexes: Vec<usize> = (0..polynomials.len()).collect();
+        let aggregated = aggregate_polynomials(&polynomials, &indexes, aggregation_coefficient);
+
+        (self.commit_coefficients(&aggregated), None)
+    }
+
+    fn open_single(
+        &self,
+        poly: &Polynomial<E::Fr, Coefficients>,
+        at_point: E::Fr,
+        _opening_value: E::Fr,
+        _data: &Option<&Self::IntermediateData>,
+        prng: &mut Self::Prng
+    ) -> Self::OpeningProof {
+        // the remainder of the division is the opening value, so it's dropped
+        let quotient = kate_divison_with_same_return_size(poly.as_ref(), at_point);
+        let witnesses = vec![self.commit_coefficients(&quotient)];
+
+        Self::commit_witnesses(&witnesses, prng);
+
+        witnesses
+    }
+
+    fn open_multiple(
+        &self,
+        polynomials: Vec<&Polynomial<E::Fr, Coefficients>>,
+        degrees: Vec<usize>,
+        aggregation_coefficient: E::Fr,
+        at_points: Vec<E::Fr>,
+        opening_values: Vec<E::Fr>,
+        _data: &Option<Vec<&Self::IntermediateData>>,
+        prng: &mut Self::Prng
+    ) -> Self::OpeningProof {
+        assert_eq!(polynomials.len(), degrees.len());
+        assert_eq!(polynomials.len(), at_points.len());
+        assert_eq!(polynomials.len(), opening_values.len());
+
+        let mut witnesses = vec![];
+        for (point, indexes) in group_by_points(&at_points).into_iter() {
+            let aggregated = aggregate_polynomials(&polynomials, &indexes, aggregation_coefficient);
+            let quotient = kate_divison_with_same_return_size(&aggregated, point);
+            witnesses.push(self.commit_coefficients(&quotient));
+        }
+
+        Self::commit_witnesses(&witnesses, prng);
+
+        // the verifier draws a challenge to combine the openings, so do the same
+        // to keep the transcripts in sync
+        let _ = prng.get_challenge();
+
+        witnesses
+    }
+
+    fn verify_single(
+        &self,
+        commitment: &Self::Commitment,
+        at_point: E::Fr,
+        claimed_value: E::Fr,
+        proof: &Self::OpeningProof,
+        prng: &mut Self::Prng
+    ) -> bool {
+        Self::commit_witnesses(proof, prng);
+
+        let mut aggregate = self.srs.g1_powers[0].mul(claimed_value.into_repr());
+        aggregate.negate();
+        aggregate.add_assign_mixed(commitment);
+
+        self.check_openings(vec![(at_point, aggregate)], proof, E::Fr::one())
+    }
+
+    fn verify_multiple_openings(
+        &self,
+        commitments: Vec<&Self::Commitment>,
+        at_points: Vec<E::Fr>,
+        claimed_values: &Vec<E::Fr>,
+        aggregation_coefficient: E::Fr,
+        proof: &Self::OpeningProof,
+        prng: &mut Self::Prng
+    ) -> bool {
+        if commitments.len() != at_points.len() || commitments.len() != claimed_values.len() {
+            return false;
+        }
+
+        Self::commit_witnesses(proof, prng);
+        let challenge = prng.get_challenge();
+
+        let mut aggregates = vec![];
+        for (point, indexes) in group_by_points(&at_points).into_iter() {
+            let mut aggregated_commitment = E::G1::zero();
+            let mut aggregated_value = E::Fr::zero();
+            for &i in indexes.iter() {
+                let alpha = aggregation_coefficient.pow([i as u64]);
+                aggregated_commitment.add_assign(&commitments[i].mul(alpha.into_repr()));
+
+                let mut tmp = claimed_values[i];
+                tmp.mul_assign(&alpha);
+                aggregated_value.add_assign(&tmp);
+            }
+
+            let mut value = self.srs.g1_powers[0].mul(aggregated_value.into_repr());
+            value.negate();
+            aggregated_commitment.add_assign(&value);
+
+            aggregates.push((point, aggregated_commitment));
+        }
+
+        self.check_openings(aggregates, proof, challenge)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pairing::bn256::{Bn256, Fr};
+    use crate::plonk::commitments::transcript::Blake2sTranscript;
+
+    type Committer = KateCommitter<Bn256, Blake2sTranscript<Fr>>;
+
+    fn make_committer() -> Committer {
+        let srs = UniversalSrs::<Bn256>::new(16, Fr::from_str("42").unwrap());
+
+        Committer::new_for_size(16, srs)
+    }
+
+    fn make_polys(num: usize, size: usize) -> Vec<Polynomial<Fr, Coefficients>> {
+        use rand::{XorShiftRng, SeedableRng, Rand};
+        let rng = &mut XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        (0..num).map(|_| {
+            let coeffs = (0..size).map(|_| Fr::rand(rng)).collect();
+            Polynomial::from_coeffs(coeffs).unwrap()
+        }).collect()
+    }
+
+    #[test]
+    fn test_kate_single_opening() {
+        let committer = make_committer();
+        let poly = make_polys(1, 16).pop().unwrap();
+        let z = Fr::from_str("123").unwrap();
+        let value = poly.evaluate_at(&Worker::new(), z);
+
+        let (commitment, _) = committer.commit_single(&poly);
+
+        let mut prover_transcript = Blake2sTranscript::<Fr>::new();
+        let proof = committer.open_single(&poly, z, value, &None, &mut prover_transcript);
+
+        let mut verifier_transcript = Blake2sTranscript::<Fr>::new();
+        assert!(committer.verify_single(&commitment, z, value, &proof, &mut verifier_transcript));
+        assert_eq!(prover_transcript.get_challenge(), verifier_transcript.get_challenge());
+
+        let mut wrong_value = value;
+        wrong_value.add_assign(&Fr::one());
+        assert!(!committer.verify_single(&commitment, z, wrong_value, &proof, &mut Blake2sTranscript::<Fr>::new()));
+    }
+
+    #[test]
+    fn test_kate_commitments_are_homomorphic() {
+        let committer = make_committer();
+        let polys = make_polys(3, 8);
+        let alpha = Fr::from_str("7").unwrap();
+
+        let (aggregated, _) = committer.commit_multiple(polys.iter().collect(), vec![7; 3], alpha);
+
+        let mut expected = <Bn256 as Engine>::G1::zero();
+        for (i, poly) in polys.iter().enumerate() {
+            let (commitment, _) = committer.commit_single(poly);
+            expected.add_assign(&commitment.mul(alpha.pow(&[i as u64]).into_repr()));
+        }
+
+        assert_eq!(aggregated, expected.into_affine());
+    }
+
+    #[test]
+    fn test_kate_multiple_openings() {
+        let committer = make_committer();
+        let polys = make_polys(3, 16);
+        let worker = Worker::new();
+
+        let z = Fr::from_str("123").unwrap();
+        let z_other = Fr::from_str("456").unwrap();
+        let points = vec![z, z_other, z];
+        let values: Vec<Fr> = polys.iter().zip(points.iter()).map(|(p, at)| p.evaluate_at(&worker, *at)).collect();
+        let commitments: Vec<_> = polys.iter().map(|p| committer.commit_single(p).0).collect();
+        let alpha = Fr::from_str("11").unwrap();
+
+        let mut prover_transcript = Blake2sTranscript::<Fr>::new();
+        let proof = committer.open_multiple(
+            polys.iter().collect(),
+            vec![15; 3],
+            alpha,
+            points.clone(),
+            values.clone(),
+            &None,
+            &mut prover_transcript
+        );
+        assert_eq!(proof.len(), 2);
+
+        let mut verifier_transcript = Blake2sTranscript::<Fr>::new();
+        assert!(committer.verify_multiple_openings(
+            commitments.iter().collect(),
+            points.clone(),
+            &values,
+            alpha,
+            &proof,
+            &mut verifier_transcript
+        ));
+        assert_eq!(prover_transcript.get_challenge(), verifier_transcript.get_challenge());
+
+        let mut wrong_values = values.clone();
+        wrong_values[1].add_assign(&Fr::one());
+        assert!(!committer.verify_multiple_openings(
+            commitments.iter().collect(),
+            points.clone(),
+            &wrong_values,
+            alpha,
+            &proof,
+            &mut Blake2sTranscript::<Fr>::new()
+        ));
+
+        let mut wrong_points = points;
+        wrong_points[2] = z_other;
+        assert!(!committer.verify_multiple_openings(
+            commitments.iter().collect(),
+            wrong_points,
+            &values,
+            alpha,
+            &proof,
+            &mut Blake2sTranscript::<Fr>::new()
+        ));
+    }
+}
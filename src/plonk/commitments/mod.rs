@@ -5,6 +5,12 @@ use crate::plonk::commitments::transcript::*;
 
 pub mod transparent;
 
+pub mod kate;
+
+pub mod ipa;
+
+pub mod poseidon;
+
 pub mod transcript;
 
 pub trait CommitmentScheme<F: PrimeField> {
@@ -25,4 +31,40 @@ pub trait CommitmentScheme<F: PrimeField> {
     fn open_multiple(&self, polynomials: Vec<&Polynomial<F, Coefficients>>, degrees: Vec<usize>, aggregation_coefficient: F, at_points: Vec<F>, opening_values: Vec<F>, data: &Option<Vec<&Self::IntermediateData>>, prng: &mut Self::Prng) -> Self::OpeningProof;
     fn verify_single(&self, commitment: &Self::Commitment, at_point: F, claimed_value: F, proof: &Self::OpeningProof, prng: &mut Self::Prng) -> bool;
     fn verify_multiple_openings(&self, commitments: Vec<&Self::Commitment>, at_points: Vec<F>, claimed_values: &Vec<F>, aggregation_coefficient: F, proof: &Self::OpeningProof, prng: &mut Self::Prng) -> bool;
-}
\ No newline at end of file
+}
+
+// sum of alpha^i * p_i for the polynomials at `indexes`, used by homomorphic schemes
+pub(crate) fn aggregate_polynomials<F: PrimeField>(
+    polynomials: &[&Polynomial<F, Coefficients>],
+    indexes: &[usize],
+    aggregation_coefficient: F
+) -> Vec<F> {
+    use crate::pairing::ff::Field;
+
+    let size = indexes.iter().map(|&i| polynomials[i].size()).max().unwrap_or(0);
+    let mut result = vec![F::zero(); size];
+
+    for &i in indexes.iter() {
+        let challenge = aggregation_coefficient.pow([i as u64]);
+        for (r, c) in result.iter_mut().zip(polynomials[i].as_ref().iter()) {
+            let mut tmp = *c;
+            tmp.mul_assign(&challenge);
+            r.add_assign(&tmp);
+        }
+    }
+
+    result
+}
+
+// indexes of the polynomials grouped by the opening point, in order of the first appearance
+pub(crate) fn group_by_points<F: PrimeField>(at_points: &[F]) -> Vec<(F, Vec<usize>)> {
+    let mut groups: Vec<(F, Vec<usize>)> = vec![];
+    for (i, point) in at_points.iter().enumerate() {
+        match groups.iter_mut().find(|(p, _)| p == point) {
+            Some((_, indexes)) => indexes.push(i),
+            None => groups.push((*point, vec![i]))
+        }
+    }
+
+    groups
+}
@@ -0,0 +1,210 @@
+use crate::pairing::ff::{Field, PrimeField, PrimeFieldRepr};
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+const CONSTANTS_DOMAIN: &[u8] = b"bellman_poseidon_constants";
+
+pub const POSEIDON_WIDTH: usize = 3;
+pub const POSEIDON_RATE: usize = 2;
+
+/// Parameters of the Poseidon permutation of width 3 over the field `F`, so
+/// that hashing can be checked in a circuit over the same field.
+/// State element 0 is the capacity, elements 1 and 2 are the rate
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoseidonParams<F: PrimeField> {
+    pub alpha: u64,
+    pub full_rounds: usize,
+    pub partial_rounds: usize,
+    pub round_constants: Vec<[F; POSEIDON_WIDTH]>,
+    pub mds: [[F; POSEIDON_WIDTH]; POSEIDON_WIDTH],
+}
+
+lazy_static! {
+    // parameters for every field they were requested for, keyed by the field type
+    static ref SHARED_PARAMS: Mutex<HashMap<TypeId, Arc<dyn Any + Send + Sync>>> = Mutex::new(HashMap::new());
+}
+
+// smallest prime alpha such that x^alpha is a permutation of the field,
+// that is gcd(alpha, p - 1) = 1
+fn select_alpha<F: PrimeField>() -> u64 {
+    let modulus = F::char();
+    for alpha in [3u64, 5, 7, 11, 13, 17].iter() {
+        let mut remainder = 0u128;
+        for limb in modulus.as_ref().iter().rev() {
+            remainder = ((remainder << 64) | (*limb as u128)) % (*alpha as u128);
+        }
+        if remainder != 1 {
+            return *alpha;
+        }
+    }
+
+    unreachable!("field modulus is not supported");
+}
+
+impl<F: PrimeField> PoseidonParams<F> {
+    /// Parameters for 128 bit security. Round numbers are taken from the Poseidon paper
+    /// for width 3 and a 256 bit field. Round constants are sampled from a ChaCha rng
+    /// seeded with a hash of a fixed domain, and the MDS matrix is the Cauchy matrix 1/(i + j + 3)
+    pub fn new() -> Self {
+        use rand::{ChaChaRng, SeedableRng, Rand};
+        use byteorder::{BigEndian, ByteOrder};
+
+        assert!(F::NUM_BITS <= 256);
+
+        let alpha = select_alpha::<F>();
+        let full_rounds = 8;
+        let partial_rounds = if alpha == 3 { 84 } else { 57 };
+
+        let hash = blake2s_simd::Params::new().hash_length(32).hash(CONSTANTS_DOMAIN);
+        let mut seed = [0u32; 8];
+        BigEndian::read_u32_into(hash.as_bytes(), &mut seed);
+        let mut rng = ChaChaRng::from_seed(&seed[..]);
+
+        let round_constants = (0..(full_rounds + partial_rounds)).map(|_| {
+            let mut constants = [F::zero(); POSEIDON_WIDTH];
+            for c in constants.iter_mut() {
+                *c = F::rand(&mut rng);
+            }
+
+            constants
+        }).collect();
+
+        let mut mds = [[F::zero(); POSEIDON_WIDTH]; POSEIDON_WIDTH];
+        for (i, row) in mds.iter_mut().enumerate() {
+            for (j, el) in row.iter_mut().enumerate() {
+                let denominator = F::from_str(&(i + j + POSEIDON_WIDTH).to_string()).expect("is a field element");
+                *el = denominator.inverse().expect("is not zero");
+            }
+        }
+
+        Self {
+            alpha,
+            full_rounds,
+            partial_rounds,
+            round_constants,
+            mds,
+        }
+    }
+
+    /// Same parameters as `new`, but built only once per field and shared afterwards,
+    /// since sampling the round constants and inverting the MDS matrix is not cheap
+    pub fn shared() -> Arc<Self> {
+        let mut cache = SHARED_PARAMS.lock().expect("is not poisoned");
+        let params = cache.entry(TypeId::of::<F>())
+            .or_insert_with(|| Arc::new(Self::new()))
+            .clone();
+
+        params.downcast::<Self>().expect("is keyed by the field type")
+    }
+
+    fn sbox(&self, el: &mut F) {
+        *el = el.pow([self.alpha]);
+    }
+
+    pub fn permute(&self, state: &mut [F; POSEIDON_WIDTH]) {
+        let half_full_rounds = self.full_rounds / 2;
+        for (round, constants) in self.round_constants.iter().enumerate() {
+            for (s, c) in state.iter_mut().zip(constants.iter()) {
+                s.add_assign(c);
+            }
+
+            let is_full_round = round < half_full_rounds || round >= half_full_rounds + self.partial_rounds;
+            if is_full_round {
+                for s in state.iter_mut() {
+                    self.sbox(s);
+                }
+            } else {
+                self.sbox(&mut state[0]);
+            }
+
+            let mut new_state = [F::zero(); POSEIDON_WIDTH];
+            for (new, row) in new_state.iter_mut().zip(self.mds.iter()) {
+                for (m, s) in row.iter().zip(state.iter()) {
+                    let mut tmp = *m;
+                    tmp.mul_assign(s);
+                    new.add_assign(&tmp);
+                }
+            }
+
+            *state = new_state;
+        }
+    }
+
+    /// Hashes a fixed length input. The capacity element is initialized with the length,
+    /// and inputs are added to the rate two at a time
+    pub fn hash(&self, input: &[F]) -> F {
+        let mut length_repr = F::Repr::default();
+        length_repr.as_mut()[0] = input.len() as u64;
+
+        let mut state = [F::zero(); POSEIDON_WIDTH];
+        state[0] = F::from_repr(length_repr).expect("is a field element");
+
+        if input.is_empty() {
+            self.permute(&mut state);
+        }
+
+        for chunk in input.chunks(POSEIDON_RATE) {
+            for (s, el) in state[1..].iter_mut().zip(chunk.iter()) {
+                s.add_assign(el);
+            }
+            self.permute(&mut state);
+        }
+
+        state[1]
+    }
+
+    /// Two to one compression for Merkle tree nodes. Capacity is zero, so it can not
+    /// collide with `hash` of non-empty inputs
+    pub fn compress(&self, left: &F, right: &F) -> F {
+        let mut state = [F::zero(), *left, *right];
+        self.permute(&mut state);
+
+        state[1]
+    }
+}
+
+impl<F: PrimeField> Default for PoseidonParams<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::plonk::transparent_engine::Fr;
+
+    #[test]
+    fn test_poseidon_params_are_deterministic() {
+        let params = PoseidonParams::<Fr>::new();
+        assert!(params == PoseidonParams::<Fr>::new());
+        assert_eq!(params.alpha, 3);
+        assert_eq!(params.round_constants.len(), params.full_rounds + params.partial_rounds);
+
+        let alpha = select_alpha::<crate::pairing::bn256::Fr>();
+        assert_eq!(alpha, 5);
+
+        // shared parameters are built once per field
+        let shared = PoseidonParams::<Fr>::shared();
+        assert!(*shared == params);
+        assert!(Arc::ptr_eq(&shared, &PoseidonParams::<Fr>::shared()));
+        assert_eq!(PoseidonParams::<crate::pairing::bn256::Fr>::shared().alpha, 5);
+    }
+
+    #[test]
+    fn test_poseidon_hash_separates_inputs() {
+        let params = PoseidonParams::<Fr>::new();
+        let one = Fr::one();
+        let mut two = one;
+        two.double();
+
+        let h = params.hash(&[one, two]);
+        assert_eq!(h, params.hash(&[one, two]));
+        assert!(h != params.hash(&[two, one]));
+        assert!(h != params.hash(&[one, two, Fr::zero()]));
+        assert!(h != params.compress(&one, &two));
+        assert!(params.hash(&[]) != params.hash(&[Fr::zero()]));
+    }
+}
@@ -0,0 +1,126 @@
+use tiny_keccak::keccak256;
+use crate::pairing::ff::{PrimeField, PrimeFieldRepr};
+use super::{Prng, Transcript};
+
+pub const DEFAULT_KECCAK_TRANSCRIPT_LABEL: &[u8] = b"bellman_keccak256_transcript";
+
+/// Transcript that is cheap to replay in the EVM. The state starts as keccak256(label),
+/// committed bytes are buffered, and every challenge is
+/// state = keccak256(state || buffer), after which the buffer is cleared.
+/// Field elements are committed as 32 byte big endian integers, same as `uint256`
+/// in `abi.encodePacked`. Points use the uncompressed encoding from `commit_point`.
+/// Challenges are the state with the highest bits cleared to fit into the field
+#[derive(Clone)]
+pub struct Keccak256Transcript<F: PrimeField> {
+    state: [u8; 32],
+    buffer: Vec<u8>,
+    _marker: std::marker::PhantomData<F>
+}
+
+impl<F: PrimeField> Keccak256Transcript<F> {
+    const SHAVE_BITS: u32 = 256 - F::CAPACITY;
+
+    pub fn new_with_label(label: &[u8]) -> Self {
+        assert!(F::NUM_BITS <= 256);
+
+        Self {
+            state: keccak256(label),
+            buffer: vec![],
+            _marker: std::marker::PhantomData
+        }
+    }
+
+    fn update_state(&mut self) {
+        let mut input = Vec::with_capacity(32 + self.buffer.len());
+        input.extend_from_slice(&self.state);
+        input.extend_from_slice(&self.buffer);
+
+        self.state = keccak256(&input);
+        self.buffer.truncate(0);
+    }
+}
+
+impl<F: PrimeField> Prng<F> for Keccak256Transcript<F> {
+    type Input = [u8; 32];
+
+    fn new() -> Self {
+        Self::new_with_label(DEFAULT_KECCAK_TRANSCRIPT_LABEL)
+    }
+
+    fn commit_input(&mut self, input: &Self::Input) {
+        self.commit_bytes(input)
+    }
+
+    fn get_challenge(&mut self) -> F {
+        self.update_state();
+
+        let mut repr = F::Repr::default();
+        let shaving_mask: u64 = 0xffffffffffffffff >> (Self::SHAVE_BITS % 64);
+        repr.read_be(&self.state[..]).expect("will read");
+        let last_limb_idx = repr.as_ref().len() - 1;
+        repr.as_mut()[last_limb_idx] &= shaving_mask;
+
+        F::from_repr(repr).expect("in a field")
+    }
+}
+
+impl<F: PrimeField> Transcript<F> for Keccak256Transcript<F> {
+    fn commit_bytes(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    fn commit_field_element(&mut self, element: &F) {
+        let mut bytes = [0u8; 32];
+        element.into_repr().write_be(&mut bytes[..]).expect("should write");
+
+        self.buffer.extend_from_slice(&bytes);
+    }
+
+    fn get_challenge_bytes(&mut self) -> Vec<u8> {
+        self.update_state();
+
+        Vec::from(&self.state[..])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pairing::ff::Field;
+    use crate::pairing::bn256::{Fr, G1Affine};
+    use crate::pairing::CurveAffine;
+
+    #[test]
+    fn test_keccak_transcript_matches_evm_encoding() {
+        let mut transcript = Keccak256Transcript::<Fr>::new_with_label(b"label");
+        let mut two = Fr::one();
+        two.double();
+        transcript.commit_field_element(&two);
+        transcript.commit_point(&G1Affine::one());
+
+        // keccak256(abi.encodePacked(keccak256("label"), uint256(2), uint256(1), uint256(2)))
+        let mut expected_input = keccak256(b"label").to_vec();
+        for value in [2u8, 1, 2].iter() {
+            let mut word = [0u8; 32];
+            word[31] = *value;
+            expected_input.extend_from_slice(&word);
+        }
+
+        assert_eq!(transcript.get_challenge_bytes(), keccak256(&expected_input).to_vec());
+    }
+
+    #[test]
+    fn test_keccak_transcript_labels_separate_domains() {
+        let mut a = Keccak256Transcript::<Fr>::new_with_label(b"a");
+        let mut b = Keccak256Transcript::<Fr>::new_with_label(b"b");
+        let mut default = Keccak256Transcript::<Fr>::new();
+        a.commit_field_element(&Fr::one());
+        b.commit_field_element(&Fr::one());
+        default.commit_field_element(&Fr::one());
+
+        let challenge = a.get_challenge();
+        assert!(challenge != b.get_challenge());
+        assert!(challenge != default.get_challenge());
+        assert!(challenge != a.get_challenge());
+    }
+}
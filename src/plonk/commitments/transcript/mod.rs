@@ -1,7 +1,10 @@
 use blake2s_simd::{Params, State};
 use crate::pairing::ff::{PrimeField, PrimeFieldRepr};
+use crate::pairing::{CurveAffine, EncodedPoint};
 
 pub mod prng;
+pub mod poseidon;
+pub mod keccak;
 
 lazy_static! {
     static ref TRANSCRIPT_BLAKE2S_PARAMS: State = {
@@ -24,6 +27,13 @@ pub trait Transcript<F: PrimeField>: Prng<F> + Sized + Clone + 'static {
     fn commit_bytes(&mut self, bytes: &[u8]);
     fn commit_field_element(&mut self, element: &F);
     fn get_challenge_bytes(&mut self) -> Vec<u8>;
+
+    /// Points are committed in the uncompressed encoding, that is x and y coordinates
+    /// as big endian integers with flags in the highest bits of x, so verifiers do not
+    /// need to decompress them
+    fn commit_point<G: CurveAffine>(&mut self, point: &G) {
+        self.commit_bytes(point.into_uncompressed().as_ref());
+    }
 }
 
 #[derive(Clone)]
@@ -127,3 +137,49 @@ impl<F: PrimeField> Transcript<F> for Blake2sTranscript<F> {
         Vec::from(&value[..])
     }
 }
+
+/// Wraps a transcript so that its input is a curve point, which allows commitment
+/// schemes with points as commitments (e.g. Kate or IPA) in the PLONK provers.
+/// Points are committed with `commit_point` of the inner transcript
+#[derive(Clone)]
+pub struct PointInputTranscript<G: CurveAffine, T: Transcript<G::Scalar>> {
+    transcript: T,
+    _marker: std::marker::PhantomData<G>
+}
+
+impl<G: CurveAffine, T: Transcript<G::Scalar>> Prng<G::Scalar> for PointInputTranscript<G, T> {
+    type Input = G;
+
+    fn new() -> Self {
+        Self {
+            transcript: T::new(),
+            _marker: std::marker::PhantomData
+        }
+    }
+
+    fn commit_input(&mut self, input: &Self::Input) {
+        self.transcript.commit_point(input)
+    }
+
+    fn get_challenge(&mut self) -> G::Scalar {
+        self.transcript.get_challenge()
+    }
+}
+
+impl<G: CurveAffine, T: Transcript<G::Scalar>> Transcript<G::Scalar> for PointInputTranscript<G, T> {
+    fn commit_bytes(&mut self, bytes: &[u8]) {
+        self.transcript.commit_bytes(bytes)
+    }
+
+    fn commit_field_element(&mut self, element: &G::Scalar) {
+        self.transcript.commit_field_element(element)
+    }
+
+    fn get_challenge_bytes(&mut self) -> Vec<u8> {
+        self.transcript.get_challenge_bytes()
+    }
+
+    fn commit_point<P: CurveAffine>(&mut self, point: &P) {
+        self.transcript.commit_point(point)
+    }
+}
@@ -0,0 +1,168 @@
+use crate::pairing::ff::{Field, PrimeField, PrimeFieldRepr};
+use crate::plonk::commitments::poseidon::{PoseidonParams, POSEIDON_WIDTH, POSEIDON_RATE};
+use super::{Prng, Transcript};
+
+use std::sync::Arc;
+
+pub const DEFAULT_POSEIDON_TRANSCRIPT_LABEL: &[u8] = b"bellman_poseidon";
+
+/// Duplex sponge over the Poseidon permutation. Field elements are absorbed into the rate
+/// as they are, and a squeeze pads the pending block with a single one, so challenges
+/// can be replayed by a verifier circuit over the same field. Takes field elements as
+/// `Prng` input to be used together with `FriSpecificPoseidonTree`.
+/// The capacity element starts as the big endian integer 0x01 || label.
+/// Bytes are absorbed as their length followed by big endian chunks of CAPACITY / 8 bytes,
+/// and points are absorbed as their uncompressed encoding from `commit_point`.
+/// Challenge bytes are the big endian representation of the squeezed element
+#[derive(Clone)]
+pub struct PoseidonTranscript<F: PrimeField> {
+    params: Arc<PoseidonParams<F>>,
+    state: [F; POSEIDON_WIDTH],
+    absorbed: usize,
+}
+
+impl<F: PrimeField> PoseidonTranscript<F> {
+    const REPR_SIZE: usize = (((F::NUM_BITS as usize)/ 64) + 1) * 8;
+
+    /// Label must be shorter than CAPACITY / 8 bytes to fit into the capacity element
+    pub fn new_with_label(label: &[u8]) -> Self {
+        let max_label_len = (F::CAPACITY / 8) as usize - 1;
+        assert!(label.len() <= max_label_len, "label is too long");
+
+        let mut bytes = vec![0u8; Self::REPR_SIZE];
+        let start = bytes.len() - label.len();
+        bytes[start..].copy_from_slice(label);
+        bytes[start - 1] = 1u8;
+
+        let mut repr = F::Repr::default();
+        repr.read_be(&bytes[..]).expect("will read");
+
+        let mut state = [F::zero(); POSEIDON_WIDTH];
+        state[0] = F::from_repr(repr).expect("is a field element");
+
+        Self {
+            params: PoseidonParams::shared(),
+            state,
+            absorbed: 0
+        }
+    }
+
+    fn absorb(&mut self, element: &F) {
+        if self.absorbed == POSEIDON_RATE {
+            self.params.permute(&mut self.state);
+            self.absorbed = 0;
+        }
+
+        self.state[1 + self.absorbed].add_assign(element);
+        self.absorbed += 1;
+    }
+
+    fn squeeze(&mut self) -> F {
+        if self.absorbed == POSEIDON_RATE {
+            self.params.permute(&mut self.state);
+            self.absorbed = 0;
+        }
+
+        self.state[1 + self.absorbed].add_assign(&F::one());
+        self.params.permute(&mut self.state);
+        self.absorbed = 0;
+
+        self.state[1]
+    }
+}
+
+impl<F: PrimeField> Prng<F> for PoseidonTranscript<F> {
+    type Input = F;
+
+    fn new() -> Self {
+        Self::new_with_label(DEFAULT_POSEIDON_TRANSCRIPT_LABEL)
+    }
+
+    fn commit_input(&mut self, input: &Self::Input) {
+        self.absorb(input)
+    }
+
+    fn get_challenge(&mut self) -> F {
+        self.squeeze()
+    }
+}
+
+impl<F: PrimeField> Transcript<F> for PoseidonTranscript<F> {
+    fn commit_bytes(&mut self, bytes: &[u8]) {
+        let chunk_size = (F::CAPACITY / 8) as usize;
+
+        let mut repr = F::Repr::default();
+        repr.as_mut()[0] = bytes.len() as u64;
+        self.absorb(&F::from_repr(repr).expect("is a field element"));
+
+        for chunk in bytes.chunks(chunk_size) {
+            let mut padded = vec![0u8; Self::REPR_SIZE];
+            let start = padded.len() - chunk.len();
+            padded[start..].copy_from_slice(chunk);
+            repr.read_be(&padded[..]).expect("will read");
+            self.absorb(&F::from_repr(repr).expect("is a field element"));
+        }
+    }
+
+    fn commit_field_element(&mut self, element: &F) {
+        self.absorb(element)
+    }
+
+    fn get_challenge_bytes(&mut self) -> Vec<u8> {
+        let value = self.squeeze();
+        let mut bytes = vec![0u8; Self::REPR_SIZE];
+        value.into_repr().write_be(&mut bytes[..]).expect("should write");
+
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::plonk::transparent_engine::Fr;
+
+    #[test]
+    fn test_poseidon_transcript_is_deterministic() {
+        let mut a = PoseidonTranscript::<Fr>::new();
+        let mut b = PoseidonTranscript::<Fr>::new();
+        a.commit_input(&Fr::one());
+        b.commit_input(&Fr::one());
+        a.commit_bytes(b"bytes");
+        b.commit_bytes(b"bytes");
+
+        let challenge = a.get_challenge();
+        assert_eq!(challenge, b.get_challenge());
+        assert_eq!(a.get_challenge_bytes(), b.get_challenge_bytes());
+        assert!(a.get_challenge() != challenge);
+    }
+
+    #[test]
+    fn test_poseidon_transcript_pads_inputs() {
+        let mut a = PoseidonTranscript::<Fr>::new();
+        let mut b = PoseidonTranscript::<Fr>::new();
+        a.commit_input(&Fr::one());
+        b.commit_input(&Fr::one());
+        b.commit_input(&Fr::zero());
+
+        assert!(a.get_challenge() != b.get_challenge());
+
+        let mut a = PoseidonTranscript::<Fr>::new();
+        let mut b = PoseidonTranscript::<Fr>::new();
+        a.commit_bytes(&[1u8]);
+        b.commit_bytes(&[0u8, 1u8]);
+
+        assert!(a.get_challenge() != b.get_challenge());
+    }
+
+    #[test]
+    fn test_poseidon_transcript_labels_separate_domains() {
+        let mut a = PoseidonTranscript::<Fr>::new_with_label(b"a");
+        let mut b = PoseidonTranscript::<Fr>::new_with_label(b"\x00a");
+        let mut empty = PoseidonTranscript::<Fr>::new_with_label(b"");
+
+        let challenge = a.get_challenge();
+        assert!(challenge != b.get_challenge());
+        assert!(challenge != empty.get_challenge());
+    }
+}
@@ -7,11 +7,15 @@ use crate::SynthesisError;
 use crate::plonk::commitments::transparent::utils::log2_floor;
 use crate::plonk::commitments::transcript::Prng;
 use crate::plonk::commitments::transparent::precomputations::*;
+use crate::plonk::fft::cooley_tukey_ntt::bitreverse;
 use super::*;
 
-pub struct CosetCombiningFriIop<F: PrimeField> {
+/// FRI that folds a whole coset committed in a single leaf of the oracle `I`.
+/// The tree hash is picked by `I`, e.g. `FriSpecificKeccakTree` for cheap EVM verification
+pub struct CosetCombiningFriIop<F: PrimeField, I: CosetCombiningIopInstance<F> = FriSpecificBlake2sTree<F>> {
     cosets_schedule: Vec<usize>,
     _marker_f: std::marker::PhantomData<F>,
+    _marker_i: std::marker::PhantomData<I>,
 }
 
 #[derive(Clone, Debug)]
@@ -20,10 +24,10 @@ pub struct CosetParams<F: PrimeField> {
     pub coset_factor: F
 }
 
-impl<F: PrimeField> FriIop<F> for CosetCombiningFriIop<F> {
+impl<F: PrimeField, I: CosetCombiningIopInstance<F>> FriIop<F> for CosetCombiningFriIop<F, I> {
     const DEGREE: usize = 2;
 
-    type IopType = FriSpecificBlake2sTree<F>;
+    type IopType = I;
     type ProofPrototype = FRIProofPrototype<F, Self::IopType>;
     type Proof = FRIProof<F, Self::IopType>;
     type Params = CosetParams<F>;
@@ -52,28 +56,33 @@ impl<F: PrimeField> FriIop<F> for CosetCombiningFriIop<F> {
 
     fn prototype_into_proof(
         prototype: Self::ProofPrototype,
-        iop_values: &Polynomial<F, Values>,
         natural_first_element_indexes: Vec<usize>,
         params: &Self::Params
     ) -> Result<Self::Proof, SynthesisError> {
-        unimplemented!()
-        // prototype.produce_proof(iop_values, natural_first_element_indexes)
+        prototype.produce_proof(natural_first_element_indexes, params)
     }
 
     fn get_fri_challenges<P: Prng<F, Input = <Self::IopType as IopInstance<F>>::Commitment>>(
         proof: &Self::Proof,
         prng: &mut P,
         params: &Self::Params
-    ) -> Vec<F> {
+    ) -> Vec<Vec<F>> {
         let mut fri_challenges = vec![];
 
-        for root in proof.roots.iter() {
-            let iop_challenge = {
-                prng.commit_input(&root);
-                prng.get_challenge()
-            };
+        // first step is done over the initial LDE that is not committed by FRI
+        let mut challenges = vec![];
+        for _ in 0..params.cosets_schedule[0] {
+            challenges.push(prng.get_challenge());
+        }
+        fri_challenges.push(challenges);
 
-            fri_challenges.push(iop_challenge);
+        for (root, coset_factor) in proof.roots.iter().zip(params.cosets_schedule.iter().skip(1)) {
+            prng.commit_input(&root);
+            let mut challenges = vec![];
+            for _ in 0..*coset_factor {
+                challenges.push(prng.get_challenge());
+            }
+            fri_challenges.push(challenges);
         }
 
         fri_challenges
@@ -82,12 +91,11 @@ impl<F: PrimeField> FriIop<F> for CosetCombiningFriIop<F> {
     fn verify_proof_with_challenges(
         proof: &Self::Proof,
         natural_element_indexes: Vec<usize>,
-        expected_value: &[F],
-        fri_challenges: &[F],
+        expected_values: &[Vec<F>],
+        fri_challenges: &[Vec<F>],
         params: &Self::Params
     ) -> Result<bool, SynthesisError> {
-        unimplemented!()
-        // Self::verify_proof_queries(proof, natural_element_indexes, Self::DEGREE, expected_value, fri_challenges)
+        Self::verify_proof_queries(proof, natural_element_indexes, expected_values, fri_challenges, params)
     }
 }
 
@@ -146,7 +154,26 @@ impl<F: PrimeField, I: IopInstance<F>> FriProof<F, I> for FRIProof<F, I> {
     }
 }
 
-impl<F: PrimeField> CosetCombiningFriIop<F> {
+impl<F: PrimeField, I: CosetCombiningIopInstance<F>> CosetCombiningFriIop<F, I> {
+    // values are committed in the bitreversed enumeration, so a single leaf of
+    // `values_per_leaf` elements is a coset of the subgroup of this size
+    pub fn get_coset_for_natural_index(natural_index: usize, domain_size: usize, values_per_leaf: usize) -> Vec<usize> {
+        assert!(domain_size.is_power_of_two());
+        assert!(values_per_leaf.is_power_of_two());
+        assert!(natural_index < domain_size);
+
+        let log_domain_size = log2_floor(domain_size) as usize;
+        let position = if log_domain_size == 0 {
+            0
+        } else {
+            bitreverse(natural_index, log_domain_size)
+        };
+
+        let start = (position / values_per_leaf) * values_per_leaf;
+
+        (start..(start + values_per_leaf)).collect()
+    }
+
     pub fn proof_from_lde_by_values<P: Prng<F, Input = <<Self as FriIop<F>>::IopType as IopInstance<F>>::Commitment>,
         C: FriPrecomputations<F>
     >(
@@ -314,10 +341,8 @@ impl<F: PrimeField> CosetCombiningFriIop<F> {
                 coset_schedule_index += 1;
                 this_domain_size = next_domain_size;
                 let coset_factor = params.cosets_schedule[coset_schedule_index];
-                let tree_params = FriSpecificBlake2sTreeParams {
-                    values_per_leaf: (1 << coset_factor)
-                };
-                let intermediate_iop = FriSpecificBlake2sTree::create(next_values.as_ref(), &tree_params);
+                let tree_params = I::params_for_values_per_leaf(1 << coset_factor);
+                let intermediate_iop = I::create(next_values.as_ref(), &tree_params);
                 let root = intermediate_iop.get_commitment();
                 roots.push(root);
                 let num_challenges = coset_factor;
@@ -350,10 +375,12 @@ impl<F: PrimeField> CosetCombiningFriIop<F> {
 
         let mut final_poly_values = Polynomial::from_values(values_slice.to_vec())?;
         final_poly_values.bitreverse_enumeration(&worker);
-        let final_poly_coeffs = if params.coset_factor == F::one() {
-            final_poly_values.icoset_fft(&worker)
+        // every folding by 2 squares the domain, including the coset factor
+        let final_coset_factor = params.coset_factor.pow([total_wrap_factor as u64]);
+        let final_poly_coeffs = if final_coset_factor == F::one() {
+            final_poly_values.ifft(worker)
         } else {
-            final_poly_values.icoset_fft_for_generator(&worker, &params.coset_factor)
+            final_poly_values.icoset_fft_for_generator(worker, &final_coset_factor)
         };
 
         let mut final_poly_coeffs = final_poly_coeffs.into_coeffs();
@@ -491,4 +518,84 @@ mod test {
             &params
         ).expect("FRI must succeed");
     }
+    #[test]
+    fn test_verify_fri_with_coset_combining() {
+        use crate::ff::Field;
+        use crate::ff::PrimeField;
+        use rand::{XorShiftRng, SeedableRng, Rand, Rng};
+        use crate::plonk::transparent_engine::proth_engine::Fr;
+        use crate::plonk::polynomials::*;
+        use crate::multicore::*;
+        use crate::plonk::fft::cooley_tukey_ntt::{CTPrecomputations, BitReversedOmegas};
+        use crate::plonk::commitments::transparent::fri::coset_combining_fri::*;
+        use crate::plonk::commitments::transparent::fri::coset_combining_fri::fri::*;
+        use crate::plonk::commitments::transparent::fri::coset_combining_fri::precomputation::CosetOmegasInvBitreversed;
+        use crate::plonk::commitments::transcript::*;
+
+        const SIZE: usize = 1024;
+        const LDE_FACTOR: usize = 16;
+
+        let worker = Worker::new();
+
+        let rng = &mut XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let coeffs = (0..SIZE).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+
+        let poly = Polynomial::<Fr, _>::from_coeffs(coeffs).unwrap();
+        let precomp = BitReversedOmegas::<Fr>::new_for_domain_size(poly.size());
+        let coset_factor = Fr::multiplicative_generator();
+        let eval_result = poly.bitreversed_lde_using_bitreversed_ntt(&worker, LDE_FACTOR, &precomp, &coset_factor).unwrap();
+
+        let fri_precomp = <CosetOmegasInvBitreversed::<Fr> as FriPrecomputations<Fr>>::new_for_domain_size(eval_result.size());
+
+        let params = CosetParams::<Fr> {
+            cosets_schedule: vec![3, 3, 2, 1],
+            coset_factor: coset_factor
+        };
+
+        let mut transcript = Blake2sTranscript::<Fr>::new();
+
+        let fri_proto = CosetCombiningFriIop::<Fr>::proof_from_lde(
+            &eval_result, 
+            LDE_FACTOR, 
+            2, 
+            &fri_precomp, 
+            &worker, 
+            &mut transcript,
+            &params
+        ).expect("FRI must succeed");
+
+        let natural_indexes: Vec<usize> = (0..8).map(|_| rng.gen_range(0, eval_result.size())).collect();
+
+        let proof = CosetCombiningFriIop::<Fr>::prototype_into_proof(fri_proto, natural_indexes.clone(), &params).unwrap();
+
+        let mut expected_values: Vec<Vec<Fr>> = natural_indexes.iter().map(|idx| {
+            CosetCombiningFriIop::<Fr>::get_coset_for_natural_index(*idx, eval_result.size(), 1 << params.cosets_schedule[0])
+                .into_iter()
+                .map(|position| eval_result.as_ref()[position])
+                .collect()
+        }).collect();
+
+        let mut transcript = Blake2sTranscript::<Fr>::new();
+        let fri_challenges = CosetCombiningFriIop::<Fr>::get_fri_challenges(&proof, &mut transcript, &params);
+
+        let valid = CosetCombiningFriIop::<Fr>::verify_proof_with_challenges(
+            &proof, 
+            natural_indexes.clone(), 
+            &expected_values, 
+            &fri_challenges, 
+            &params
+        ).unwrap();
+        assert!(valid);
+
+        expected_values[3][1].add_assign(&Fr::one());
+
+        let valid = CosetCombiningFriIop::<Fr>::verify_proof_with_challenges(
+            &proof, 
+            natural_indexes, 
+            &expected_values, 
+            &fri_challenges, 
+            &params
+        ).unwrap();
+        assert!(!valid);
+    }
 }
\ No newline at end of file
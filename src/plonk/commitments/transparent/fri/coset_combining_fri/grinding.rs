@@ -0,0 +1,69 @@
+use crate::ff::PrimeField;
+use crate::plonk::commitments::transcript::Transcript;
+
+use byteorder::{BigEndian, ByteOrder};
+
+/// Absorbs a proof of work nonce into the transcript and draws a challenge whose lowest `bits`
+/// bits must be zero. Lowest bits are checked since challenges of algebraic transcripts are
+/// field elements, so their highest bits are always zero. Query indexes should be drawn after it
+pub fn absorb_proof_of_work<F: PrimeField, T: Transcript<F>>(transcript: &mut T, nonce: u64, bits: u32) -> bool {
+    assert!(bits <= 64);
+
+    let mut nonce_bytes = [0u8; 8];
+    BigEndian::write_u64(&mut nonce_bytes, nonce);
+    transcript.commit_bytes(&nonce_bytes);
+
+    if bits == 0 {
+        return true;
+    }
+
+    let challenge = transcript.get_challenge_bytes();
+    let value = BigEndian::read_u64(&challenge[(challenge.len() - 8)..]);
+
+    value.trailing_zeros() >= bits
+}
+
+/// Finds the smallest nonce that passes `absorb_proof_of_work` for the current transcript state
+pub fn grind<F: PrimeField, T: Transcript<F>>(transcript: &T, bits: u32) -> u64 {
+    let mut nonce = 0u64;
+    loop {
+        let mut transcript = transcript.clone();
+        if absorb_proof_of_work(&mut transcript, nonce, bits) {
+            return nonce;
+        }
+        nonce += 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::plonk::transparent_engine::Fr;
+    use crate::plonk::commitments::transcript::{Prng, Blake2sTranscript};
+    use crate::plonk::commitments::transcript::poseidon::PoseidonTranscript;
+
+    fn check_grinding<T: Transcript<Fr>>() {
+        const BITS: u32 = 8;
+
+        let mut transcript = T::new();
+        transcript.commit_bytes(b"grinding");
+
+        let nonce = grind(&transcript, BITS);
+        assert!(absorb_proof_of_work(&mut transcript.clone(), nonce, BITS));
+
+        // grinding returns the smallest nonce, so all smaller ones must fail
+        if nonce > 0 {
+            assert!(!absorb_proof_of_work(&mut transcript.clone(), nonce - 1, BITS));
+        }
+    }
+
+    #[test]
+    fn test_grinding_with_blake2s_transcript() {
+        check_grinding::<Blake2sTranscript<Fr>>();
+    }
+
+    #[test]
+    fn test_grinding_with_poseidon_transcript() {
+        check_grinding::<PoseidonTranscript<Fr>>();
+    }
+}
@@ -1,7 +1,8 @@
 pub mod fri;
-// pub mod query_producer;
-// pub mod verifier;
+pub mod query_producer;
+pub mod verifier;
 pub mod precomputation;
+pub mod grinding;
 
 use crate::SynthesisError;
 use crate::multicore::Worker;
@@ -48,24 +49,28 @@ pub trait FriIop<F: PrimeField> {
         params: &Self::Params
     ) -> Result<Self::ProofPrototype, SynthesisError>;
 
+    // initial LDE is not committed by FRI itself, so proof only contains queries
+    // to the intermediate oracles
     fn prototype_into_proof(
         prototype: Self::ProofPrototype,
-        iop_values: &Polynomial<F, Values>,
         natural_first_element_indexes: Vec<usize>,
         params: &Self::Params
     ) -> Result<Self::Proof, SynthesisError>;
 
+    // challenges are grouped per FRI step, one challenge per folding by 2
     fn get_fri_challenges<P: Prng<F, Input = <Self::IopType as IopInstance<F>>::Commitment>>(
         proof: &Self::Proof,
         prng: &mut P,
         params: &Self::Params
-    ) -> Vec<F>;
+    ) -> Vec<Vec<F>>;
 
+    // `expected_values` are values of the initial LDE on the cosets that contain
+    // natural element indexes, in the same order as they are placed in the oracle leaf
     fn verify_proof_with_challenges(
         proof: &Self::Proof,
         natural_element_indexes: Vec<usize>,
-        expected_value: &[F],
-        fri_challenges: &[F],
+        expected_values: &[Vec<F>],
+        fri_challenges: &[Vec<F>],
         params: &Self::Params
     ) -> Result<bool, SynthesisError>;
 }
\ No newline at end of file
@@ -1,53 +1,47 @@
 use crate::pairing::ff::PrimeField;
-use crate::plonk::commitments::transparent::iop::*;
-use crate::plonk::polynomials::*;
-use crate::plonk::domains::*;
-use crate::multicore::*;
+use crate::plonk::commitments::transparent::iop_compiler::*;
 use crate::SynthesisError;
-use crate::plonk::commitments::transparent::iop::*;
-use crate::plonk::commitments::transparent::utils::log2_floor;
 use super::fri::*;
-use super::super::*;
+use super::*;
 
-impl<F: PrimeField, I: IOP<F>> FRIProofPrototype<F, I> {
+impl<F: PrimeField, I: CosetCombiningIopInstance<F>> FRIProofPrototype<F, I> {
     pub fn produce_proof(
         self,
-        iop_values: &Polynomial<F, Values>,
         natural_first_element_indexes: Vec<usize>,
+        params: &CosetParams<F>
     ) -> Result<FRIProof<F, I>, SynthesisError> {
-        let domain_size = self.initial_degree_plus_one * self.lde_factor;
+        let initial_domain_size = self.initial_degree_plus_one * self.lde_factor;
 
-        let mut roots = vec![];
-        let l0_commitment = Some(self.l0_commitment);
-
-        for iop in l0_commitment.iter().chain(&self.intermediate_commitments) {
-            roots.push(iop.get_root());
+        if self.intermediate_commitments.len() + 1 != params.cosets_schedule.len() {
+            return Err(SynthesisError::PolynomialDegreeTooLarge);
         }
 
         let mut rounds = vec![];
 
         for natural_first_element_index in natural_first_element_indexes.into_iter() {
             let mut queries = vec![];
-            let mut domain_idx = natural_first_element_index;
-            let mut domain_size = domain_size;
 
-            for (iop, leaf_values) in l0_commitment.iter().chain(&self.intermediate_commitments)
-                                        .zip(Some(iop_values).into_iter().chain(&self.intermediate_values)) {
-                
-                let coset_values = <I::Combiner as CosetCombiner<F>>::get_coset_for_natural_index(domain_idx, domain_size);
-                if coset_values.len() != <I::Combiner as CosetCombiner<F>>::COSET_SIZE {
-                    return Err(SynthesisError::PolynomialDegreeTooLarge);
-                }
+            // position of the element in the bitreversed enumeration of the current domain
+            let first_coset = CosetCombiningFriIop::<F, I>::get_coset_for_natural_index(
+                natural_first_element_index,
+                initial_domain_size,
+                1 << params.cosets_schedule[0]
+            );
+
+            // folding of the leaf gives a single element, so leaf index is a position in the next domain
+            let mut position = first_coset[0] >> params.cosets_schedule[0];
 
-                for idx in coset_values.into_iter() {
-                    let query = iop.query(idx, leaf_values.as_ref());
-                    queries.push(query);
-                }
+            for ((iop, leaf_values), coset_factor) in self.intermediate_commitments.iter()
+                                        .zip(self.intermediate_values.iter())
+                                        .zip(params.cosets_schedule.iter().skip(1)) {
+                let values_per_leaf = 1 << *coset_factor;
+                let start = (position / values_per_leaf) * values_per_leaf;
+                let indexes: Vec<usize> = (start..(start + values_per_leaf)).collect();
 
-                let (next_idx, next_size) = Domain::<F>::index_and_size_for_next_domain(domain_idx, domain_size);
+                let query = iop.produce_query(indexes, leaf_values.as_ref());
+                queries.push(query);
 
-                domain_idx = next_idx;
-                domain_size = next_size;
+                position >>= *coset_factor;
             }
 
             rounds.push(queries);
@@ -55,7 +49,7 @@ impl<F: PrimeField, I: IOP<F>> FRIProofPrototype<F, I> {
 
         let proof = FRIProof::<F, I> {
             queries: rounds,
-            roots,
+            roots: self.get_roots(),
             final_coefficients: self.final_coefficients,
             initial_degree_plus_one: self.initial_degree_plus_one,
             output_coeffs_at_degree_plus_one: self.output_coeffs_at_degree_plus_one,
@@ -64,4 +58,4 @@ impl<F: PrimeField, I: IOP<F>> FRIProofPrototype<F, I> {
 
         Ok(proof)
     }
-}
\ No newline at end of file
+}
@@ -1,148 +1,101 @@
-use crate::pairing::ff::PrimeField;
-use crate::plonk::commitments::transparent::iop::*;
-use crate::plonk::polynomials::*;
+use crate::pairing::ff::{Field, PrimeField};
+use crate::plonk::commitments::transparent::iop_compiler::*;
 use crate::plonk::domains::*;
-use crate::multicore::*;
+use crate::plonk::fft::cooley_tukey_ntt::{bitreverse, log2_floor};
 use crate::SynthesisError;
-use crate::plonk::commitments::transparent::iop::*;
-use crate::plonk::commitments::transparent::utils::log2_floor;
 use super::fri::*;
-use super::super::*;
 
-impl<F: PrimeField> CosetCombiningFriIop<F> {
-    pub fn verify_prototype(
-        proof: & FRIProofPrototype<F, <Self as FriIop<F>>>,
-        leaf_values: & Polynomial<F, Values>, 
-        natural_element_index: usize
-    ) -> Result<bool, SynthesisError> {
-        let mut two = F::one();
-        two.double();
-
-        let two_inv = two.inverse().ok_or(
-            SynthesisError::DivisionByZero
-        )?;
+impl<F: PrimeField, I: CosetCombiningIopInstance<F>> CosetCombiningFriIop<F, I> {
+    // does exactly the same as the prover for a single batch: values of the leaf are
+    // f(omega), f(-omega), ... in the bitreversed enumeration and are folded in a single value
+    // that has an index `leaf_index` in the bitreversed enumeration of the next domain.
+    // Prover uses the same precomputed inverses of the initial coset for all the FRI steps
+    fn fold_leaf(
+        values: &[F],
+        leaf_index: usize,
+        challenges: &[F],
+        initial_omega_inv: &F,
+        coset_factor_inv: &F,
+        log_precomputation_size: usize,
+        two_inv: &F
+    ) -> F {
+        let wrapping_factor = values.len();
+        debug_assert_eq!(wrapping_factor, 1 << challenges.len());
+
+        let mut this_level_values = values.to_vec();
+
+        for (wrapping_step, challenge) in challenges.iter().enumerate() {
+            let base_omega_idx = (leaf_index * wrapping_factor) >> (1 + wrapping_step);
+            let mut next_level_values = Vec::with_capacity(this_level_values.len() / 2);
+
+            for (pair_idx, pair) in this_level_values.chunks(2).enumerate() {
+                let omega_idx = base_omega_idx + pair_idx;
+                let power = if log_precomputation_size == 0 {
+                    0
+                } else {
+                    bitreverse(omega_idx, log_precomputation_size)
+                };
+                let mut omega_inv = initial_omega_inv.pow([power as u64]);
+                omega_inv.mul_assign(coset_factor_inv);
+
+                let f_at_omega = pair[0];
+                let f_at_minus_omega = pair[1];
+                let mut v_even_coeffs = f_at_omega;
+                v_even_coeffs.add_assign(&f_at_minus_omega);
 
-        // start from the bottom: we need to get a "pair" and calculate FRI step
+                let mut v_odd_coeffs = f_at_omega;
+                v_odd_coeffs.sub_assign(&f_at_minus_omega);
+                v_odd_coeffs.mul_assign(&omega_inv);
 
-        let domain = Domain::<F>::new_for_size((proof.initial_degree_plus_one * proof.lde_factor) as u64)?;
+                let mut tmp = v_odd_coeffs;
+                tmp.mul_assign(challenge);
+                tmp.add_assign(&v_even_coeffs);
+                tmp.mul_assign(two_inv);
 
-        let domain_element = domain.generator.pow([natural_element_index as u64]);
+                next_level_values.push(tmp);
+            }
 
-        let el = domain_element.pow([domain.size]);
-        if el != F::one() {
-            return Err(SynthesisError::UnexpectedIdentity);
+            this_level_values = next_level_values;
         }
 
-        let mut omega = domain.generator;
-        let mut omega_inv = omega.inverse().ok_or(
-            SynthesisError::DivisionByZero
-        )?;
-
-        debug_assert_eq!(F::one(), omega_inv.pow([domain.size]));
-
-        let mut expected_value: Option<F> = None;
-        let mut domain_size = domain.size as usize;
-        let mut domain_idx = natural_element_index;
-
-        for (iop_values, iop_challenge) in Some(leaf_values).into_iter().chain(&proof.intermediate_values)
-                                        .zip(proof.challenges.iter()) {
-
-            let coset_values = <I::Combiner as CosetCombiner<F>>::get_coset_for_natural_index(domain_idx, domain_size);
-
-            assert!(coset_values.len() == 2);
-            assert!(coset_values[0] < coset_values[1]);
+        this_level_values[0]
+    }
 
-            let f_at_omega = I::get_for_natural_index(iop_values.as_ref(), coset_values[0]);
+    // FRI precomputations used by the prover are expected to be the inverses of
+    // the initial LDE coset with the generator `params.coset_factor`,
+    // e.g. `CosetOmegasInvBitreversed` for the multiplicative generator
+    pub fn verify_proof_queries(
+        proof: &FRIProof<F, I>,
+        natural_element_indexes: Vec<usize>,
+        expected_values: &[Vec<F>],
+        fri_challenges: &[Vec<F>],
+        params: &CosetParams<F>
+    ) -> Result<bool, SynthesisError> {
+        let num_steps = params.cosets_schedule.len();
 
-            if let Some(value) = expected_value {
-                if !coset_values.contains(&domain_idx) {
-                    return Ok(false);
-                }
+        if proof.roots.len() + 1 != num_steps || fri_challenges.len() != num_steps {
+            return Ok(false);
+        }
 
-                let supplied_value = *I::get_for_natural_index(iop_values.as_ref(), domain_idx);
-                // check consistency
-                if supplied_value != value {
-                    return Ok(false);
-                }
+        for (challenges, coset_factor) in fri_challenges.iter().zip(params.cosets_schedule.iter()) {
+            if challenges.len() != *coset_factor {
+                return Ok(false);
             }
-
-            let f_at_minus_omega = I::get_for_natural_index(iop_values.as_ref(), coset_values[1]);
-            let divisor = omega_inv.pow([coset_values[0] as u64]);
-
-            let mut v_even_coeffs = *f_at_omega;
-            v_even_coeffs.add_assign(&f_at_minus_omega);
-
-            let mut v_odd_coeffs = *f_at_omega;
-            v_odd_coeffs.sub_assign(&f_at_minus_omega);
-            v_odd_coeffs.mul_assign(&divisor);
-
-            // those can be treated as (doubled) evaluations of polynomials that
-            // are themselves made only from even or odd coefficients of original poly 
-            // (with reduction of degree by 2) on a domain of the size twice smaller
-            // with an extra factor of "omega" in odd coefficients
-
-            // to do assemble FRI step we just need to add them with a random challenge
-
-            let mut tmp = v_odd_coeffs;
-            tmp.mul_assign(&iop_challenge);
-            tmp.add_assign(&v_even_coeffs);
-            tmp.mul_assign(&two_inv);
-
-            expected_value = Some(tmp);
-
-            // we have jumped in a coset and can keep it ordered using the smaller index out of two
-            // domain_idx = coset_values[0];
-
-            // debug_assert!(domain_idx < domain_size / 2);
-
-            let (next_idx, next_size) = Domain::<F>::index_and_size_for_next_domain(domain_idx, domain_size);
-
-            domain_idx = next_idx;
-            domain_size = next_size;
-
-            omega.square();
-            omega_inv.square();
         }
 
-
-        // finally we need to get expected value from coefficients
-
-        let mut expected_value_from_coefficients = F::zero();
-        let mut power = F::one();
-        let evaluation_point = omega.pow([domain_idx as u64]);
-
-        for c in proof.final_coefficients.iter() {
-            let mut tmp = power;
-            tmp.mul_assign(c);
-
-            expected_value_from_coefficients.add_assign(&tmp);
-            power.mul_assign(&evaluation_point);
+        if natural_element_indexes.len() != proof.queries.len() || expected_values.len() != proof.queries.len() {
+            return Ok(false);
         }
-        
-        let expected_value = expected_value.expect("is some");
-
-        let valid = expected_value_from_coefficients == expected_value;
-
-        Ok(valid)
-    }
 
-    // pub fn verify_proof_queries<P: Prng<F, Input = < < I::Tree as IopTree<F> >::TreeHasher as IopTreeHasher<F> >::HashOutput> >(
-    //     proof: &FRIProof<F, I>,
-    //     natural_element_indexes: Vec<usize>,
-    //     degree: usize, 
-    //     expected_value_from_oracle: F,
-    //     prng: &mut P
-    // ) -> Result<bool, SynthesisError> {
+        let total_wrap_factor = 1usize << params.cosets_schedule.iter().sum::<usize>();
 
-    // }
+        if !proof.output_coeffs_at_degree_plus_one.is_power_of_two()
+            || !proof.lde_factor.is_power_of_two()
+            || proof.initial_degree_plus_one / total_wrap_factor != proof.output_coeffs_at_degree_plus_one
+            || proof.final_coefficients.len() != proof.output_coeffs_at_degree_plus_one {
+            return Ok(false);
+        }
 
-    pub fn verify_proof_queries(
-        proof: &FRIProof<F, I>,
-        natural_element_indexes: Vec<usize>,
-        degree: usize, 
-        expected_values_from_oracle: &[F],
-        fri_challenges: &[F]
-    ) -> Result<bool, SynthesisError> {
         let mut two = F::one();
         two.double();
 
@@ -150,149 +103,106 @@ impl<F: PrimeField> CosetCombiningFriIop<F> {
             SynthesisError::DivisionByZero
         )?;
 
-        let domain = Domain::<F>::new_for_size((proof.initial_degree_plus_one * proof.lde_factor) as u64)?;
+        let initial_domain_size = proof.initial_degree_plus_one * proof.lde_factor;
 
-        let omega = domain.generator;
-        let omega_inv = omega.inverse().ok_or(
+        let domain = Domain::<F>::new_for_size(initial_domain_size as u64)?;
+
+        let omega_inv = domain.generator.inverse().ok_or(
             SynthesisError::DivisionByZero
         )?;
 
-        assert!(fri_challenges.len() == proof.roots.len());
-
-        assert!(natural_element_indexes.len() == proof.queries.len());
+        let coset_factor_inv = params.coset_factor.inverse().ok_or(
+            SynthesisError::DivisionByZero
+        )?;
 
-        for ((query, natural_element_index), expected_value_from_oracle) in proof.queries.iter()
-                                                                            .zip(natural_element_indexes.into_iter())
-                                                                            .zip(expected_values_from_oracle.iter()) 
-        {
+        let log_precomputation_size = log2_floor(initial_domain_size / 2) as usize;
 
-            let domain_element = domain.generator.pow([natural_element_index as u64]);
+        // final polynomial is defined over the initial domain with every element
+        // taken into the power of the total wrapping factor
+        let final_domain_size = initial_domain_size / total_wrap_factor;
+        let final_omega = domain.generator.pow([total_wrap_factor as u64]);
+        let final_coset_factor = params.coset_factor.pow([total_wrap_factor as u64]);
 
-            let el = domain_element.pow([domain.size]);
-            if el != F::one() {
+        for ((queries, natural_element_index), leaf_values) in proof.queries.iter()
+                                                            .zip(natural_element_indexes)
+                                                            .zip(expected_values.iter())
+        {
+            if natural_element_index >= initial_domain_size {
                 return Err(SynthesisError::UnexpectedIdentity);
             }
-            // let next_domain_size = domain.size / 2;
-            // let el = domain_element.pow([next_domain_size]);
-            // if el == F::one() {
-            //     return Err(SynthesisError::UnexpectedIdentity);
-            // }
-
-            let mut expected_value: Option<F> = None;
-            let mut domain_size = domain.size as usize;
-            let mut domain_idx = natural_element_index;
-            let mut omega = omega;
-            let mut omega_inv = omega_inv;
-
-            if query.len() % degree != 0 {
-                return Err(SynthesisError::PolynomialDegreeTooLarge);
+
+            if queries.len() != proof.roots.len() {
+                return Ok(false);
             }
 
-            for (round, ((root, queries), iop_challenge)) in proof.roots.iter()
-                                    .zip(query.chunks_exact(degree)) 
-                                    .zip(fri_challenges.iter())
-                                    .enumerate()
-            {
-                let coset_values = <I::Combiner as CosetCombiner<F>>::get_coset_for_natural_index(domain_idx, domain_size);
+            let values_per_leaf = 1 << params.cosets_schedule[0];
+            if leaf_values.len() != values_per_leaf {
+                return Ok(false);
+            }
 
-                if coset_values.len() != <I::Combiner as CosetCombiner<F>>::COSET_SIZE {
-                    return Err(SynthesisError::PolynomialDegreeTooLarge);
-                }
+            let coset = Self::get_coset_for_natural_index(natural_element_index, initial_domain_size, values_per_leaf);
+            let mut position = coset[0] / values_per_leaf;
+
+            let mut expected_value = Self::fold_leaf(
+                leaf_values,
+                position,
+                &fri_challenges[0],
+                &omega_inv,
+                &coset_factor_inv,
+                log_precomputation_size,
+                &two_inv
+            );
+
+            for (((query, root), challenges), coset_factor) in queries.iter()
+                                                .zip(proof.roots.iter())
+                                                .zip(fri_challenges.iter().skip(1))
+                                                .zip(params.cosets_schedule.iter().skip(1))
+            {
+                let values_per_leaf = 1 << *coset_factor;
+                let start = (position / values_per_leaf) * values_per_leaf;
+                let expected_indexes: Vec<usize> = (start..(start + values_per_leaf)).collect();
 
-                for q in queries.iter() {
-                    if !coset_values.contains(&q.natural_index()) {
-                        println!("Coset values do not contain query index {}", q.natural_index());
-                        return Ok(false);
-                    }
+                if query.indexes() != expected_indexes {
+                    return Ok(false);
                 }
 
-                if round == 0 {
-                    for q in queries.iter() {
-                        if q.natural_index() == natural_element_index && q.value() != *expected_value_from_oracle {
-                            println!("Expected {}, got {} from query", expected_value_from_oracle, q.value());
-                            return Ok(false);
-                        }
-                    }
-                }
+                let tree_params = I::params_for_values_per_leaf(values_per_leaf);
 
-                for (c, q) in coset_values.iter().zip(queries.iter()) {
-                    let tree_index = <I::Combiner as CosetCombiner<F>>::natural_index_into_tree_index(*c);
-                    if q.tree_index() != tree_index {
-                        return Err(SynthesisError::PolynomialDegreeTooLarge);
-                    }
-                    assert!(q.natural_index() == *c, "coset values and produced queries are expected to be sorted!");
+                if !I::verify_query(root, query, &tree_params) {
+                    return Ok(false);
                 }
 
-                for q in queries.iter() {
-                    if !I::verify_query(&q, &root) {
-                        println!("Query is not in the root");
-                        return Ok(false);
-                    }
-                }
-                
-                let f_at_omega = (&queries[0]).value();
-                if let Some(value) = expected_value {
-                    if !coset_values.contains(&domain_idx) {
-                        println!("Coset values {:?} do not containt required index {}", coset_values, domain_idx);
-                        return Ok(false);
-                    }
-
-                    let q: Vec<_> = queries.iter().filter(|el| el.natural_index() == domain_idx).collect();
-                    if q.len() != 1 {
-                        println!("Queries containt duplicate opening for required index {}", domain_idx);
-                        return Ok(false)
-                    }
-
-                    let supplied_value = q[0].value();
-                    // check in the next domain
-                    if supplied_value != value {
-                        println!("Query value {} is not equal to the expected value {} for round {}", supplied_value, value, round);
-                        return Ok(false);
-                    }
+                if query.values()[position - start] != expected_value {
+                    return Ok(false);
                 }
 
-                let f_at_minus_omega = (&queries[1]).value();
-                let divisor = omega_inv.pow([coset_values[0] as u64]);
-
-                let mut v_even_coeffs = f_at_omega;
-                v_even_coeffs.add_assign(&f_at_minus_omega);
-
-                let mut v_odd_coeffs = f_at_omega;
-                v_odd_coeffs.sub_assign(&f_at_minus_omega);
-                v_odd_coeffs.mul_assign(&divisor);
-
-                // those can be treated as (doubled) evaluations of polynomials that
-                // are themselves made only from even or odd coefficients of original poly 
-                // (with reduction of degree by 2) on a domain of the size twice smaller
-                // with an extra factor of "omega" in odd coefficients
-
-                // to do assemble FRI step we just need to add them with a random challenge
-
-                let mut tmp = v_odd_coeffs;
-                tmp.mul_assign(&iop_challenge);
-                tmp.add_assign(&v_even_coeffs);
-                tmp.mul_assign(&two_inv);
-
-                expected_value = Some(tmp);
-
-                // we have jumped in a coset and can keep it ordered using the smaller index out of two
-                // domain_idx = coset_values[0];
-
-                let (next_idx, next_size) = Domain::<F>::index_and_size_for_next_domain(domain_idx, domain_size);
-
-                domain_idx = next_idx;
-                domain_size = next_size;
-
-                omega.square();
-                omega_inv.square();
+                position /= values_per_leaf;
+
+                expected_value = Self::fold_leaf(
+                    query.values(),
+                    position,
+                    challenges,
+                    &omega_inv,
+                    &coset_factor_inv,
+                    log_precomputation_size,
+                    &two_inv
+                );
             }
 
-
             // finally we need to get expected value from coefficients
 
+            let log_final_domain_size = log2_floor(final_domain_size) as usize;
+            let final_natural_index = if log_final_domain_size == 0 {
+                0
+            } else {
+                bitreverse(position, log_final_domain_size)
+            };
+
+            let mut evaluation_point = final_omega.pow([final_natural_index as u64]);
+            evaluation_point.mul_assign(&final_coset_factor);
+
             let mut expected_value_from_coefficients = F::zero();
             let mut power = F::one();
-            let evaluation_point = omega.pow([domain_idx as u64]);
 
             for c in proof.final_coefficients.iter() {
                 let mut tmp = power;
@@ -301,14 +211,8 @@ impl<F: PrimeField> CosetCombiningFriIop<F> {
                 expected_value_from_coefficients.add_assign(&tmp);
                 power.mul_assign(&evaluation_point);
             }
-            
-            let expected_value = expected_value.expect("is some");
-
-            let valid = expected_value_from_coefficients == expected_value;
 
-            if !valid {
-                println!("Value from supplied coefficients {} is not equal to the value from queries {} for natural index {}", expected_value_from_coefficients, expected_value, natural_element_index);
-                println!("Final coefficients = {:?}", proof.final_coefficients);
+            if expected_value_from_coefficients != expected_value {
                 return Ok(false);
             }
         }
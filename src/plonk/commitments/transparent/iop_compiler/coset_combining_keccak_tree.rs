@@ -0,0 +1,153 @@
+use crate::pairing::ff::{PrimeField, PrimeFieldRepr};
+use tiny_keccak::*;
+use super::coset_combining_tree::{CosetCombiningTree, CosetCombiningTreeHasher};
+use super::*;
+
+/// Same tree as `FriSpecificBlake2sTree`, but with Keccak256 and values encoded as
+/// 32 byte big endian words, so leafs and paths are cheap to check in the EVM
+pub type FriSpecificKeccakTree<F> = CosetCombiningTree<F, KeccakTreeHasher>;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FriSpecificKeccakTreeParams {
+    pub values_per_leaf: usize
+}
+
+#[derive(Debug)]
+pub struct KeccakTreeHasher;
+
+impl KeccakTreeHasher {
+    const VALUE_BYTE_SIZE: usize = 32;
+}
+
+impl<F: PrimeField> CosetCombiningTreeHasher<F> for KeccakTreeHasher {
+    type Node = [u8; 32];
+    type Params = FriSpecificKeccakTreeParams;
+
+    fn params_for_values_per_leaf(values_per_leaf: usize) -> Self::Params {
+        FriSpecificKeccakTreeParams {
+            values_per_leaf
+        }
+    }
+
+    fn values_per_leaf(params: &Self::Params) -> usize {
+        params.values_per_leaf
+    }
+
+    fn empty_node() -> Self::Node {
+        [0u8; 32]
+    }
+
+    fn hash_leaf(_params: &Self::Params, values: &[F], scratch_space: &mut Vec<u8>) -> Self::Node {
+        assert!(F::NUM_BITS <= 256);
+        scratch_space.resize(Self::VALUE_BYTE_SIZE * values.len(), 0u8);
+        for (value, chunk) in values.iter().zip(scratch_space.chunks_mut(Self::VALUE_BYTE_SIZE)) {
+            value.into_repr().write_be(chunk).expect("will write");
+        }
+
+        keccak256(&scratch_space[..])
+    }
+
+    fn hash_node(_params: &Self::Params, left: &Self::Node, right: &Self::Node) -> Self::Node {
+        let mut state = Keccak::new_keccak256();
+        state.update(left);
+        state.update(right);
+
+        let mut output = [0u8; 32];
+        state.finalize(&mut output);
+
+        output
+    }
+
+    fn write_node<W: Write>(node: &Self::Node, mut writer: W) -> io::Result<()> {
+        writer.write_all(&node[..])
+    }
+
+    fn read_node<R: Read>(mut reader: R) -> io::Result<Self::Node> {
+        let mut node = [0u8; 32];
+        reader.read_exact(&mut node)?;
+
+        Ok(node)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ff::Field;
+    use crate::plonk::transparent_engine::Fr;
+
+    const SIZE: usize = 16;
+    const VALUES_PER_LEAF: usize = 4;
+
+    fn make_inputs() -> Vec<Fr> {
+        let mut inputs = vec![];
+        let mut f = Fr::one();
+        for _ in 0..SIZE {
+            inputs.push(f);
+            f.double();
+        }
+
+        inputs
+    }
+
+    #[test]
+    fn make_small_keccak_iop() {
+        let params = FriSpecificKeccakTreeParams {
+            values_per_leaf: VALUES_PER_LEAF
+        };
+
+        let inputs = make_inputs();
+        let iop = FriSpecificKeccakTree::create(&inputs, &params);
+        let commitment = iop.get_commitment();
+        assert_eq!(iop.size(), SIZE);
+
+        for i in 0..(SIZE / VALUES_PER_LEAF) {
+            let indexes: Vec<_> = ((i*VALUES_PER_LEAF)..(VALUES_PER_LEAF + i*VALUES_PER_LEAF)).collect();
+            let query = iop.produce_query(indexes, &inputs);
+            assert!(FriSpecificKeccakTree::verify_query(&commitment, &query, &params), "invalid query for leaf index {}", i);
+
+            let mut wrong_query = query.clone();
+            wrong_query.values[0].add_assign(&Fr::one());
+            assert!(!FriSpecificKeccakTree::verify_query(&commitment, &wrong_query, &params));
+        }
+    }
+
+    #[test]
+    fn test_keccak_tree_root_is_keccak_of_children() {
+        let params = FriSpecificKeccakTreeParams {
+            values_per_leaf: SIZE / 2
+        };
+
+        let inputs = make_inputs();
+        let iop = FriSpecificKeccakTree::create(&inputs, &params);
+
+        let mut buffer = vec![];
+        for value in inputs.iter() {
+            value.into_repr().write_be(&mut buffer).unwrap();
+        }
+
+        let left = keccak256(&buffer[..(buffer.len() / 2)]);
+        let right = keccak256(&buffer[(buffer.len() / 2)..]);
+        let mut concatenated = left.to_vec();
+        concatenated.extend_from_slice(&right);
+
+        assert_eq!(iop.get_commitment(), keccak256(&concatenated));
+    }
+
+    #[test]
+    fn test_keccak_tree_serialization() {
+        let params = FriSpecificKeccakTreeParams {
+            values_per_leaf: VALUES_PER_LEAF
+        };
+
+        let iop = FriSpecificKeccakTree::create(&make_inputs(), &params);
+
+        let mut buffer = vec![];
+        iop.write(&mut buffer).unwrap();
+        let deserialized = FriSpecificKeccakTree::<Fr>::read(&buffer[..]).unwrap();
+
+        assert!(iop == deserialized);
+        assert_eq!(iop.nodes, deserialized.nodes);
+        assert!(FriSpecificKeccakTree::<Fr>::read(&buffer[..(buffer.len() - 1)]).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+use crate::pairing::ff::{PrimeField, PrimeFieldRepr};
+use crate::plonk::commitments::poseidon::PoseidonParams;
+use super::coset_combining_tree::{CosetCombiningTree, CosetCombiningTreeHasher, CosetCombinedQuery};
+use super::*;
+
+use std::sync::Arc;
+
+/// Same tree as `FriSpecificBlake2sTree`, but leafs and nodes are field elements hashed
+/// with Poseidon over the same field, so queries are cheap to check inside a circuit.
+/// Use it with a transcript that takes field elements as input, such as `PoseidonTranscript`
+pub type FriSpecificPoseidonTree<F> = CosetCombiningTree<F, PoseidonTreeHasher>;
+
+pub type PoseidonCosetCombinedQuery<F> = CosetCombinedQuery<F, F>;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FriSpecificPoseidonTreeParams<F: PrimeField> {
+    pub values_per_leaf: usize,
+    pub hash_params: Arc<PoseidonParams<F>>
+}
+
+#[derive(Debug)]
+pub struct PoseidonTreeHasher;
+
+impl<F: PrimeField> CosetCombiningTreeHasher<F> for PoseidonTreeHasher {
+    type Node = F;
+    type Params = FriSpecificPoseidonTreeParams<F>;
+
+    fn params_for_values_per_leaf(values_per_leaf: usize) -> Self::Params {
+        FriSpecificPoseidonTreeParams {
+            values_per_leaf,
+            hash_params: PoseidonParams::shared()
+        }
+    }
+
+    fn values_per_leaf(params: &Self::Params) -> usize {
+        params.values_per_leaf
+    }
+
+    fn empty_node() -> Self::Node {
+        F::zero()
+    }
+
+    fn hash_leaf(params: &Self::Params, values: &[F], _scratch_space: &mut Vec<u8>) -> Self::Node {
+        params.hash_params.hash(values)
+    }
+
+    fn hash_node(params: &Self::Params, left: &Self::Node, right: &Self::Node) -> Self::Node {
+        params.hash_params.compress(left, right)
+    }
+
+    fn write_node<W: Write>(node: &Self::Node, writer: W) -> io::Result<()> {
+        node.into_repr().write_be(writer)
+    }
+
+    fn read_node<R: Read>(reader: R) -> io::Result<Self::Node> {
+        let mut repr = F::Repr::default();
+        repr.read_be(reader)?;
+
+        F::from_repr(repr).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ff::Field;
+    use crate::plonk::transparent_engine::Fr;
+
+    const SIZE: usize = 16;
+    const VALUES_PER_LEAF: usize = 4;
+
+    fn make_inputs() -> Vec<Fr> {
+        let mut inputs = vec![];
+        let mut f = Fr::one();
+        for _ in 0..SIZE {
+            inputs.push(f);
+            f.double();
+        }
+
+        inputs
+    }
+
+    #[test]
+    fn make_small_poseidon_iop() {
+        let params = FriSpecificPoseidonTree::<Fr>::params_for_values_per_leaf(VALUES_PER_LEAF);
+
+        let inputs = make_inputs();
+        let iop = FriSpecificPoseidonTree::create(&inputs, &params);
+        let commitment = iop.get_commitment();
+        assert_eq!(iop.size(), SIZE);
+
+        for i in 0..(SIZE / VALUES_PER_LEAF) {
+            let indexes: Vec<_> = ((i*VALUES_PER_LEAF)..(VALUES_PER_LEAF + i*VALUES_PER_LEAF)).collect();
+            let query = iop.produce_query(indexes, &inputs);
+            assert!(FriSpecificPoseidonTree::verify_query(&commitment, &query, &params), "invalid query for leaf index {}", i);
+
+            let mut wrong_query = query.clone();
+            wrong_query.values[0].add_assign(&Fr::one());
+            assert!(!FriSpecificPoseidonTree::verify_query(&commitment, &wrong_query, &params));
+
+            let mut wrong_path = query.clone();
+            wrong_path.path.swap(0, 1);
+            assert!(!FriSpecificPoseidonTree::verify_query(&commitment, &wrong_path, &params));
+        }
+    }
+
+    #[test]
+    fn test_poseidon_tree_root_is_compression_of_children() {
+        let params = FriSpecificPoseidonTree::<Fr>::params_for_values_per_leaf(SIZE / 2);
+
+        let inputs = make_inputs();
+        let iop = FriSpecificPoseidonTree::create(&inputs, &params);
+
+        let left = params.hash_params.hash(&inputs[..(SIZE / 2)]);
+        let right = params.hash_params.hash(&inputs[(SIZE / 2)..]);
+
+        assert_eq!(iop.get_commitment(), params.hash_params.compress(&left, &right));
+    }
+
+    #[test]
+    fn test_poseidon_tree_serialization() {
+        let params = FriSpecificPoseidonTree::<Fr>::params_for_values_per_leaf(VALUES_PER_LEAF);
+
+        let iop = FriSpecificPoseidonTree::create(&make_inputs(), &params);
+
+        let mut buffer = vec![];
+        iop.write(&mut buffer).unwrap();
+        let deserialized = FriSpecificPoseidonTree::<Fr>::read(&buffer[..]).unwrap();
+
+        assert!(iop == deserialized);
+        assert_eq!(iop.nodes, deserialized.nodes);
+        assert!(FriSpecificPoseidonTree::<Fr>::read(&buffer[..(buffer.len() - 1)]).is_err());
+    }
+}
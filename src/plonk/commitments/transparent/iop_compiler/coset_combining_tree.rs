@@ -0,0 +1,263 @@
+use crate::pairing::ff::PrimeField;
+use crate::multicore::Worker;
+use super::super::utils::log2_floor;
+use super::*;
+
+use byteorder::{BigEndian, WriteBytesExt, ReadBytesExt};
+
+/// Hash function of a `CosetCombiningTree`: compresses `values_per_leaf` consecutive
+/// values into a leaf and two children into a node. Implementors are marker types,
+/// anything that the hash needs (e.g. round constants) lives in `Params`
+pub trait CosetCombiningTreeHasher<F: PrimeField>: 'static + Sized + Send + Sync {
+    type Node: 'static + Copy + Clone + Eq + PartialEq + std::fmt::Debug + Send + Sync;
+    type Params: Clone + Eq + PartialEq + std::fmt::Debug + Send + Sync;
+
+    fn params_for_values_per_leaf(values_per_leaf: usize) -> Self::Params;
+    fn values_per_leaf(params: &Self::Params) -> usize;
+
+    fn empty_node() -> Self::Node;
+
+    /// `scratch_space` is reused for all leafs hashed by one thread
+    fn hash_leaf(params: &Self::Params, values: &[F], scratch_space: &mut Vec<u8>) -> Self::Node;
+    fn hash_node(params: &Self::Params, left: &Self::Node, right: &Self::Node) -> Self::Node;
+
+    fn write_node<W: Write>(node: &Self::Node, writer: W) -> io::Result<()>;
+    fn read_node<R: Read>(reader: R) -> io::Result<Self::Node>;
+}
+
+/// Merkle tree with `values_per_leaf` consecutive values in every leaf, so a coset
+/// folded by FRI with coset combining is opened with a single path.
+/// Nodes are stored level by level from the root, node with index 0 is unused
+#[derive(Debug)]
+pub struct CosetCombiningTree<F: PrimeField, H: CosetCombiningTreeHasher<F>> {
+    pub(crate) size: usize,
+    pub(crate) nodes: Vec<H::Node>,
+    pub(crate) params: H::Params,
+    _marker: std::marker::PhantomData<F>
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CosetCombinedQuery<F: PrimeField, N = [u8; 32]> {
+    pub(crate) indexes: Vec<usize>,
+    pub(crate) values: Vec<F>,
+    pub(crate) path: Vec<N>,
+}
+
+impl<F: PrimeField, N: 'static + Clone + Eq + PartialEq + std::fmt::Debug> IopQuery<F> for CosetCombinedQuery<F, N> {
+    fn indexes(&self) -> Vec<usize> {
+        self.indexes.clone()
+    }
+
+    fn values(&self) -> &[F] {
+        &self.values
+    }
+}
+
+impl<F: PrimeField, H: CosetCombiningTreeHasher<F>> CosetCombiningTree<F, H> {
+    fn hash_level(inputs: &[H::Node], outputs: &mut [H::Node], params: &H::Params, worker: &Worker) {
+        assert!(outputs.len() * 2 == inputs.len());
+        assert!(outputs.len().is_power_of_two());
+
+        worker.scope(outputs.len(), |scope, chunk| {
+            for (o, i) in outputs.chunks_mut(chunk)
+                            .zip(inputs.chunks(chunk*2)) {
+                scope.spawn(move |_| {
+                    for (o, i) in o.iter_mut().zip(i.chunks(2)) {
+                        *o = H::hash_node(params, &i[0], &i[1]);
+                    }
+                });
+            }
+        });
+    }
+
+    fn make_full_path(&self, leaf_index: usize, leaf_pair_hash: H::Node) -> Vec<H::Node> {
+        let mut nodes = &self.nodes[..];
+
+        let mut path = vec![leaf_pair_hash];
+
+        let mut idx = leaf_index;
+        idx >>= 1;
+
+        for _ in 0..log2_floor(nodes.len() / 2) {
+            let half_len = nodes.len() / 2;
+            let (next_level, this_level) = nodes.split_at(half_len);
+            let pair_idx = idx ^ 1usize;
+            path.push(this_level[pair_idx]);
+            idx >>= 1;
+            nodes = next_level;
+        }
+
+        path
+    }
+}
+
+impl<F: PrimeField, H: CosetCombiningTreeHasher<F>> IopInstance<F> for CosetCombiningTree<F, H> {
+    type Commitment = H::Node;
+    type Params = H::Params;
+    type Query = CosetCombinedQuery<F, H::Node>;
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn create(values: &[F], params: &Self::Params) -> Self {
+        let values_per_leaf = H::values_per_leaf(params);
+        assert!(values_per_leaf.is_power_of_two());
+
+        let num_leafs = values.len() / values_per_leaf;
+        assert!(num_leafs.is_power_of_two());
+        assert!(num_leafs >= 2);
+
+        // there are as many nodes as leafs, node with index 0 is unused
+        let mut nodes = vec![H::empty_node(); num_leafs];
+        let mut leaf_hashes = vec![H::empty_node(); num_leafs];
+
+        let worker = Worker::new();
+
+        worker.scope(leaf_hashes.len(), |scope, chunk| {
+            for (i, lh) in leaf_hashes.chunks_mut(chunk)
+                            .enumerate() {
+                scope.spawn(move |_| {
+                    let base_idx = i*chunk;
+                    let mut scratch_space = vec![];
+                    for (j, lh) in lh.iter_mut().enumerate() {
+                        let values_start = (base_idx + j) * values_per_leaf;
+                        let values_end = values_start + values_per_leaf;
+                        *lh = H::hash_leaf(params, &values[values_start..values_end], &mut scratch_space);
+                    }
+                });
+            }
+        });
+
+        // separately hash last level, which hashes leaf hashes into first nodes
+        {
+            let half_len = nodes.len() / 2;
+            let (_, outputs) = nodes.split_at_mut(half_len);
+            Self::hash_level(&leaf_hashes, outputs, params, &worker);
+        }
+
+        let mut nodes_for_hashing = &mut nodes[..];
+        for _ in 0..(log2_floor(num_leafs) as usize - 1) {
+            // do the trick - split
+            let half_len = nodes_for_hashing.len() / 2;
+            let (next_levels, inputs) = nodes_for_hashing.split_at_mut(half_len);
+            let quarter_len = next_levels.len() / 2;
+            let (_, outputs) = next_levels.split_at_mut(quarter_len);
+            Self::hash_level(inputs, outputs, params, &worker);
+
+            nodes_for_hashing = next_levels;
+        }
+
+        Self {
+            size: values.len(),
+            nodes,
+            params: params.clone(),
+            _marker: std::marker::PhantomData
+        }
+    }
+
+    fn get_commitment(&self) -> Self::Commitment {
+        self.nodes[1]
+    }
+
+    fn produce_query(&self, indexes: Vec<usize>, values: &[F]) -> Self::Query {
+        let values_per_leaf = H::values_per_leaf(&self.params);
+
+        // we never expect that query is mis-alligned, so check it
+        debug_assert_eq!(indexes[0] % values_per_leaf, 0);
+        debug_assert!(indexes == (indexes[0]..(indexes[0] + values_per_leaf)).collect::<Vec<_>>());
+        debug_assert!(*indexes.last().expect("is some") < self.size());
+        debug_assert!(*indexes.last().expect("is some") < values.len());
+
+        let query_values = Vec::from(&values[indexes[0]..(indexes[0] + values_per_leaf)]);
+
+        let leaf_index = indexes[0] / values_per_leaf;
+        let pair_index = leaf_index ^ 1;
+
+        let pair_values = &values[(pair_index * values_per_leaf)..((pair_index + 1) * values_per_leaf)];
+        let leaf_pair_hash = H::hash_leaf(&self.params, pair_values, &mut vec![]);
+
+        let path = self.make_full_path(leaf_index, leaf_pair_hash);
+
+        CosetCombinedQuery {
+            indexes,
+            values: query_values,
+            path,
+        }
+    }
+
+    fn verify_query(commitment: &Self::Commitment, query: &Self::Query, params: &Self::Params) -> bool {
+        let values_per_leaf = H::values_per_leaf(params);
+        if query.values().len() != values_per_leaf {
+            return false;
+        }
+
+        let mut hash = H::hash_leaf(params, query.values(), &mut vec![]);
+        let mut idx = query.indexes()[0] / values_per_leaf;
+
+        for el in query.path.iter() {
+            hash = if idx & 1usize == 0 {
+                H::hash_node(params, &hash, el)
+            } else {
+                H::hash_node(params, el, &hash)
+            };
+            idx >>= 1;
+        }
+
+        &hash == commitment
+    }
+}
+
+impl<F: PrimeField, H: CosetCombiningTreeHasher<F>> CosetCombiningIopInstance<F> for CosetCombiningTree<F, H> {
+    fn params_for_values_per_leaf(values_per_leaf: usize) -> Self::Params {
+        H::params_for_values_per_leaf(values_per_leaf)
+    }
+
+    fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_u32::<BigEndian>(self.size as u32)?;
+        writer.write_u32::<BigEndian>(H::values_per_leaf(&self.params) as u32)?;
+        for node in self.nodes.iter() {
+            H::write_node(node, &mut writer)?;
+        }
+
+        Ok(())
+    }
+
+    fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let size = reader.read_u32::<BigEndian>()? as usize;
+        let values_per_leaf = reader.read_u32::<BigEndian>()? as usize;
+
+        if !values_per_leaf.is_power_of_two() || !size.is_power_of_two() || size < 2 * values_per_leaf {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid tree dimensions"));
+        }
+
+        let num_nodes = size / values_per_leaf;
+        let mut nodes = Vec::with_capacity(num_nodes);
+        for _ in 0..num_nodes {
+            nodes.push(H::read_node(&mut reader)?);
+        }
+
+        Ok(Self {
+            size,
+            nodes,
+            params: H::params_for_values_per_leaf(values_per_leaf),
+            _marker: std::marker::PhantomData
+        })
+    }
+
+    fn write_commitment<W: Write>(commitment: &Self::Commitment, writer: W) -> io::Result<()> {
+        H::write_node(commitment, writer)
+    }
+
+    fn read_commitment<R: Read>(reader: R) -> io::Result<Self::Commitment> {
+        H::read_node(reader)
+    }
+}
+
+impl<F: PrimeField, H: CosetCombiningTreeHasher<F>> PartialEq for CosetCombiningTree<F, H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.get_commitment() == other.get_commitment()
+    }
+}
+
+impl<F: PrimeField, H: CosetCombiningTreeHasher<F>> Eq for CosetCombiningTree<F, H> {}
@@ -1,6 +1,10 @@
 use crate::ff::PrimeField;
+use std::io::{self, Read, Write};
 
+pub mod coset_combining_tree;
 pub mod coset_combining_blake2s_tree;
+pub mod coset_combining_keccak_tree;
+pub mod coset_combining_poseidon_tree;
 
 pub trait Commitment: Clone + Eq + PartialEq + std::fmt::Debug {}
 
@@ -21,6 +25,20 @@ pub trait IopQuery<F: PrimeField>: 'static + PartialEq + Eq + Clone + std::fmt::
     fn values(&self) -> &[F];
 }
 
+/// Oracle that places `values_per_leaf` consecutive values into a single leaf, so
+/// a coset that FRI with coset combining folds is opened by a single query.
+/// Trees with different hash functions can be used by FRI and Redshift
+/// through this trait
+pub trait CosetCombiningIopInstance<F: PrimeField>: IopInstance<F> + Sized {
+    fn params_for_values_per_leaf(values_per_leaf: usize) -> Self::Params;
+
+    fn write<W: Write>(&self, writer: W) -> io::Result<()>;
+    fn read<R: Read>(reader: R) -> io::Result<Self>;
+
+    fn write_commitment<W: Write>(commitment: &Self::Commitment, writer: W) -> io::Result<()>;
+    fn read_commitment<R: Read>(reader: R) -> io::Result<Self::Commitment>;
+}
+
 // const fn byte_size<F: PrimeField>() -> usize {
 //     (((F::NUM_BITS as usize) / 64) + 1) * 8
 // }
\ No newline at end of file
@@ -581,7 +581,7 @@ impl<
 
 
 // use single threaded Kate division for now
-fn kate_divison_with_same_return_size<F: PrimeField>(a: &[F], mut b: F) -> Vec<F>
+pub(crate) fn kate_divison_with_same_return_size<F: PrimeField>(a: &[F], mut b: F) -> Vec<F>
 {
     b.negate();
 
@@ -609,7 +609,7 @@ fn kate_divison_with_same_return_size<F: PrimeField>(a: &[F], mut b: F) -> Vec<F
 }
 
 // this one is not ZK cause will expose values not from LDE, but from the original domain too
-fn bytes_to_challenge_index<S: AsRef<[u8]>>(bytes: S, lde_size: usize) -> usize {
+pub(crate) fn bytes_to_challenge_index<S: AsRef<[u8]>>(bytes: S, lde_size: usize) -> usize {
     use byteorder::{BigEndian, ByteOrder};
 
     let as_ref = bytes.as_ref();
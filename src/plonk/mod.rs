@@ -1,8 +1,8 @@
 pub mod adaptor;
 pub mod cs;
-// pub mod generator;
-// pub mod prover;
-// pub mod verifier;
+pub mod generator;
+pub mod prover;
+pub mod verifier;
 // pub mod tester;
 pub mod polynomials;
 pub mod domains;
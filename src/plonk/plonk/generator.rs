@@ -474,6 +474,7 @@ impl<E: Engine> GeneratorAssembly<E> {
 }
 
 use super::prover::*;
+use super::srs::UniversalSrs;
 use crate::plonk::fft::cooley_tukey_ntt::CTPrecomputations;
 
 use crate::pairing::CurveAffine;
@@ -481,7 +482,7 @@ use crate::pairing::CurveAffine;
 pub fn setup_with_precomputations<E: Engine, C: Circuit<E>, CP: CTPrecomputations<E::Fr>>(
     circuit: &C,
     omegas_bitreversed: &CP,
-    bases: &[E::G1Affine]
+    srs: &UniversalSrs<E>
     ) -> Result<(PlonkSetup<E>, PlonkSetupPrecomputation<E>), SynthesisError> 
 {
     let mut assembly = GeneratorAssembly::<E>::new();
@@ -494,18 +495,19 @@ pub fn setup_with_precomputations<E: Engine, C: Circuit<E>, CP: CTPrecomputation
 
     let (q_l, q_r, q_o, q_m, q_c, s_id, sigma_1, sigma_2, sigma_3) = assembly.output_setup_polynomials(&worker)?;
 
-    let q_l_commitment_data = ProvingAssembly::<E>::commit_single_poly(&q_l, &bases, &worker)?;
-    let q_r_commitment_data = ProvingAssembly::<E>::commit_single_poly(&q_r, &bases, &worker)?;
-    let q_o_commitment_data = ProvingAssembly::<E>::commit_single_poly(&q_o, &bases, &worker)?;
-    let q_m_commitment_data = ProvingAssembly::<E>::commit_single_poly(&q_m, &bases, &worker)?;
-    let q_c_commitment_data = ProvingAssembly::<E>::commit_single_poly(&q_c, &bases, &worker)?;
-    let s_id_commitment_data = ProvingAssembly::<E>::commit_single_poly(&s_id, &bases, &worker)?;
-    let sigma_1_commitment_data = ProvingAssembly::<E>::commit_single_poly(&sigma_1, &bases, &worker)?;
-    let sigma_2_commitment_data = ProvingAssembly::<E>::commit_single_poly(&sigma_2, &bases, &worker)?;
-    let sigma_3_commitment_data = ProvingAssembly::<E>::commit_single_poly(&sigma_3, &bases, &worker)?;
+    let q_l_commitment_data = ProvingAssembly::<E>::commit_single_poly(&q_l, srs, &worker)?;
+    let q_r_commitment_data = ProvingAssembly::<E>::commit_single_poly(&q_r, srs, &worker)?;
+    let q_o_commitment_data = ProvingAssembly::<E>::commit_single_poly(&q_o, srs, &worker)?;
+    let q_m_commitment_data = ProvingAssembly::<E>::commit_single_poly(&q_m, srs, &worker)?;
+    let q_c_commitment_data = ProvingAssembly::<E>::commit_single_poly(&q_c, srs, &worker)?;
+    let s_id_commitment_data = ProvingAssembly::<E>::commit_single_poly(&s_id, srs, &worker)?;
+    let sigma_1_commitment_data = ProvingAssembly::<E>::commit_single_poly(&sigma_1, srs, &worker)?;
+    let sigma_2_commitment_data = ProvingAssembly::<E>::commit_single_poly(&sigma_2, srs, &worker)?;
+    let sigma_3_commitment_data = ProvingAssembly::<E>::commit_single_poly(&sigma_3, srs, &worker)?;
 
     let setup = PlonkSetup::<E> {
         n: n,
+        num_inputs: assembly.num_inputs,
         q_l: q_l_commitment_data,
         q_r: q_r_commitment_data,
         q_o: q_o_commitment_data,
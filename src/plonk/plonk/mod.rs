@@ -1,2 +1,4 @@
 pub mod prover;
-pub mod generator;
\ No newline at end of file
+pub mod generator;
+pub mod verifier;
+pub mod srs;
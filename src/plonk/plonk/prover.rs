@@ -652,7 +652,7 @@ impl<E: Engine> ProvingAssembly<E> {
         true
     }
 
-    fn calculate_inverse_vanishing_polynomial_in_a_coset(&self, worker: &Worker, poly_size:usize, vahisning_size: usize) -> Result<Polynomial::<E::Fr, Values>, SynthesisError> {
+    pub(crate) fn calculate_inverse_vanishing_polynomial_in_a_coset(&self, worker: &Worker, poly_size:usize, vahisning_size: usize) -> Result<Polynomial::<E::Fr, Values>, SynthesisError> {
         assert!(poly_size.is_power_of_two());
         assert!(vahisning_size.is_power_of_two());
 
@@ -697,7 +697,7 @@ impl<E: Engine> ProvingAssembly<E> {
         Ok(numerator)
     }
 
-    fn evaluate_inverse_vanishing_poly(&self, vahisning_size: usize, point: E::Fr) -> E::Fr {
+    pub(crate) fn evaluate_inverse_vanishing_poly(&self, vahisning_size: usize, point: E::Fr) -> E::Fr {
         assert!(vahisning_size.is_power_of_two());
 
         // update from the paper - it should not hold for the last generator, omega^(n) in original notations
@@ -721,7 +721,7 @@ impl<E: Engine> ProvingAssembly<E> {
         numerator
     }
 
-    fn calculate_lagrange_poly(&self, worker: &Worker, poly_size:usize, poly_number: usize) -> Result<Polynomial::<E::Fr, Coefficients>, SynthesisError> {
+    pub(crate) fn calculate_lagrange_poly(&self, worker: &Worker, poly_size:usize, poly_number: usize) -> Result<Polynomial::<E::Fr, Coefficients>, SynthesisError> {
         assert!(poly_size.is_power_of_two());
         assert!(poly_number < poly_size);
 
@@ -731,6 +731,22 @@ impl<E: Engine> ProvingAssembly<E> {
 
         Ok(poly.ifft(&worker))
     }
+
+    // input gates go first and enforce q_l*a = 0, so the public inputs polynomial
+    // takes values -input_i at omega^i for i < num_inputs and zero elsewhere
+    fn make_public_inputs_values(&self, poly_size: usize) -> Result<Polynomial::<E::Fr, Values>, SynthesisError> {
+        assert!(poly_size.is_power_of_two());
+        assert!(self.input_assingments.len() < poly_size);
+
+        let mut values = vec![E::Fr::zero(); poly_size];
+        for (value, input) in values.iter_mut().zip(self.input_assingments.iter()) {
+            let mut tmp = *input;
+            tmp.negate();
+            *value = tmp;
+        }
+
+        Polynomial::<E::Fr, Values>::from_values(values)
+    }
 }
 
 // pub struct RedshiftProof<F: PrimeField, I: IopInstance<F>, FRI: FriIop<F, IopType = I>>{
@@ -769,9 +785,14 @@ use crate::plonk::fft::cooley_tukey_ntt::CTPrecomputations;
 use crate::pairing::{CurveAffine, CurveProjective};
 use crate::pairing::EncodedPoint;
 
+use byteorder::{BigEndian, WriteBytesExt, ReadBytesExt};
+
+use std::io::{self, Read, Write};
+
 #[derive(Debug)]
 pub struct PlonkSetup<E: Engine>{
     pub n: usize,
+    pub num_inputs: usize,
     pub q_l: E::G1Affine,
     pub q_r: E::G1Affine,
     pub q_o: E::G1Affine,
@@ -796,13 +817,445 @@ pub struct PlonkSetupPrecomputation<E: Engine>{
     pub sigma_3_aux: Polynomial<E::Fr, Values>,
 }
 
-struct OpeningRequest<'a, E: Engine> {
-    polynomials: Vec<&'a Polynomial<E::Fr, Coefficients>>,
-    opening_point: E::Fr,
-    opening_values: Vec<E::Fr>
+impl<E: Engine> PlonkSetup<E> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        write_version_header(&mut writer)?;
+
+        writer.write_u64::<BigEndian>(self.n as u64)?;
+        writer.write_u64::<BigEndian>(self.num_inputs as u64)?;
+
+        for commitment in [
+            &self.q_l,
+            &self.q_r,
+            &self.q_o,
+            &self.q_m,
+            &self.q_c,
+            &self.s_id,
+            &self.sigma_1,
+            &self.sigma_2,
+            &self.sigma_3,
+        ].iter() {
+            write_point(*commitment, &mut writer)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        read_version_header(&mut reader)?;
+
+        let n = reader.read_u64::<BigEndian>()? as usize;
+        let num_inputs = reader.read_u64::<BigEndian>()? as usize;
+
+        let q_l = read_point(&mut reader)?;
+        let q_r = read_point(&mut reader)?;
+        let q_o = read_point(&mut reader)?;
+        let q_m = read_point(&mut reader)?;
+        let q_c = read_point(&mut reader)?;
+        let s_id = read_point(&mut reader)?;
+        let sigma_1 = read_point(&mut reader)?;
+        let sigma_2 = read_point(&mut reader)?;
+        let sigma_3 = read_point(&mut reader)?;
+
+        Ok(Self {
+            n,
+            num_inputs,
+            q_l,
+            q_r,
+            q_o,
+            q_m,
+            q_c,
+            s_id,
+            sigma_1,
+            sigma_2,
+            sigma_3,
+        })
+    }
+}
+
+impl<E: Engine> PlonkSetupPrecomputation<E> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        write_version_header(&mut writer)?;
+
+        for poly in [
+            &self.q_l_aux,
+            &self.q_r_aux,
+            &self.q_o_aux,
+            &self.q_m_aux,
+            &self.q_c_aux,
+            &self.s_id_aux,
+            &self.sigma_1_aux,
+            &self.sigma_2_aux,
+            &self.sigma_3_aux,
+        ].iter() {
+            write_polynomial_values(*poly, &mut writer)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        read_version_header(&mut reader)?;
+
+        let q_l_aux = read_polynomial_values(&mut reader)?;
+        let q_r_aux = read_polynomial_values(&mut reader)?;
+        let q_o_aux = read_polynomial_values(&mut reader)?;
+        let q_m_aux = read_polynomial_values(&mut reader)?;
+        let q_c_aux = read_polynomial_values(&mut reader)?;
+        let s_id_aux = read_polynomial_values(&mut reader)?;
+        let sigma_1_aux = read_polynomial_values(&mut reader)?;
+        let sigma_2_aux = read_polynomial_values(&mut reader)?;
+        let sigma_3_aux = read_polynomial_values(&mut reader)?;
+
+        Ok(Self {
+            q_l_aux,
+            q_r_aux,
+            q_o_aux,
+            q_m_aux,
+            q_c_aux,
+            s_id_aux,
+            sigma_1_aux,
+            sigma_2_aux,
+            sigma_3_aux,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Eq)]
+pub struct PlonkProof<E: Engine>{
+    pub a_commitment: E::G1Affine,
+    pub b_commitment: E::G1Affine,
+    pub c_commitment: E::G1Affine,
+    pub z_1_commitment: E::G1Affine,
+    pub z_2_commitment: E::G1Affine,
+    pub t_low_commitment: E::G1Affine,
+    pub t_mid_commitment: E::G1Affine,
+    pub t_high_commitment: E::G1Affine,
+
+    pub a_opening_value: E::Fr,
+    pub b_opening_value: E::Fr,
+    pub c_opening_value: E::Fr,
+    pub q_l_opening_value: E::Fr,
+    pub q_r_opening_value: E::Fr,
+    pub q_o_opening_value: E::Fr,
+    pub q_m_opening_value: E::Fr,
+    pub q_c_opening_value: E::Fr,
+    pub s_id_opening_value: E::Fr,
+    pub sigma_1_opening_value: E::Fr,
+    pub sigma_2_opening_value: E::Fr,
+    pub sigma_3_opening_value: E::Fr,
+    pub z_1_unshifted_opening_value: E::Fr,
+    pub z_2_unshifted_opening_value: E::Fr,
+    pub z_1_shifted_opening_value: E::Fr,
+    pub z_2_shifted_opening_value: E::Fr,
+    pub t_low_opening_value: E::Fr,
+    pub t_mid_opening_value: E::Fr,
+    pub t_high_opening_value: E::Fr,
+
+    // quotients of the aggregated polynomials opened at z and z*omega
+    pub opening_proof_at_z: E::G1Affine,
+    pub opening_proof_at_z_omega: E::G1Affine,
+}
+
+impl<E: Engine> PartialEq for PlonkProof<E> {
+    fn eq(&self, other: &PlonkProof<E>) -> bool {
+        self.a_commitment == other.a_commitment &&
+        self.b_commitment == other.b_commitment &&
+        self.c_commitment == other.c_commitment &&
+        self.z_1_commitment == other.z_1_commitment &&
+        self.z_2_commitment == other.z_2_commitment &&
+        self.t_low_commitment == other.t_low_commitment &&
+        self.t_mid_commitment == other.t_mid_commitment &&
+        self.t_high_commitment == other.t_high_commitment &&
+        self.a_opening_value == other.a_opening_value &&
+        self.b_opening_value == other.b_opening_value &&
+        self.c_opening_value == other.c_opening_value &&
+        self.q_l_opening_value == other.q_l_opening_value &&
+        self.q_r_opening_value == other.q_r_opening_value &&
+        self.q_o_opening_value == other.q_o_opening_value &&
+        self.q_m_opening_value == other.q_m_opening_value &&
+        self.q_c_opening_value == other.q_c_opening_value &&
+        self.s_id_opening_value == other.s_id_opening_value &&
+        self.sigma_1_opening_value == other.sigma_1_opening_value &&
+        self.sigma_2_opening_value == other.sigma_2_opening_value &&
+        self.sigma_3_opening_value == other.sigma_3_opening_value &&
+        self.z_1_unshifted_opening_value == other.z_1_unshifted_opening_value &&
+        self.z_2_unshifted_opening_value == other.z_2_unshifted_opening_value &&
+        self.z_1_shifted_opening_value == other.z_1_shifted_opening_value &&
+        self.z_2_shifted_opening_value == other.z_2_shifted_opening_value &&
+        self.t_low_opening_value == other.t_low_opening_value &&
+        self.t_mid_opening_value == other.t_mid_opening_value &&
+        self.t_high_opening_value == other.t_high_opening_value &&
+        self.opening_proof_at_z == other.opening_proof_at_z &&
+        self.opening_proof_at_z_omega == other.opening_proof_at_z_omega
+    }
+}
+
+impl<E: Engine> PlonkProof<E> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        for commitment in [
+            &self.a_commitment,
+            &self.b_commitment,
+            &self.c_commitment,
+            &self.z_1_commitment,
+            &self.z_2_commitment,
+            &self.t_low_commitment,
+            &self.t_mid_commitment,
+            &self.t_high_commitment,
+        ].iter() {
+            write_point(*commitment, &mut writer)?;
+        }
+
+        for value in [
+            &self.a_opening_value,
+            &self.b_opening_value,
+            &self.c_opening_value,
+            &self.q_l_opening_value,
+            &self.q_r_opening_value,
+            &self.q_o_opening_value,
+            &self.q_m_opening_value,
+            &self.q_c_opening_value,
+            &self.s_id_opening_value,
+            &self.sigma_1_opening_value,
+            &self.sigma_2_opening_value,
+            &self.sigma_3_opening_value,
+            &self.z_1_unshifted_opening_value,
+            &self.z_2_unshifted_opening_value,
+            &self.z_1_shifted_opening_value,
+            &self.z_2_shifted_opening_value,
+            &self.t_low_opening_value,
+            &self.t_mid_opening_value,
+            &self.t_high_opening_value,
+        ].iter() {
+            write_field_element(*value, &mut writer)?;
+        }
+
+        write_point(&self.opening_proof_at_z, &mut writer)?;
+        write_point(&self.opening_proof_at_z_omega, &mut writer)?;
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let a_commitment = read_point(&mut reader)?;
+        let b_commitment = read_point(&mut reader)?;
+        let c_commitment = read_point(&mut reader)?;
+        let z_1_commitment = read_point(&mut reader)?;
+        let z_2_commitment = read_point(&mut reader)?;
+        let t_low_commitment = read_point(&mut reader)?;
+        let t_mid_commitment = read_point(&mut reader)?;
+        let t_high_commitment = read_point(&mut reader)?;
+
+        let a_opening_value = read_field_element(&mut reader)?;
+        let b_opening_value = read_field_element(&mut reader)?;
+        let c_opening_value = read_field_element(&mut reader)?;
+        let q_l_opening_value = read_field_element(&mut reader)?;
+        let q_r_opening_value = read_field_element(&mut reader)?;
+        let q_o_opening_value = read_field_element(&mut reader)?;
+        let q_m_opening_value = read_field_element(&mut reader)?;
+        let q_c_opening_value = read_field_element(&mut reader)?;
+        let s_id_opening_value = read_field_element(&mut reader)?;
+        let sigma_1_opening_value = read_field_element(&mut reader)?;
+        let sigma_2_opening_value = read_field_element(&mut reader)?;
+        let sigma_3_opening_value = read_field_element(&mut reader)?;
+        let z_1_unshifted_opening_value = read_field_element(&mut reader)?;
+        let z_2_unshifted_opening_value = read_field_element(&mut reader)?;
+        let z_1_shifted_opening_value = read_field_element(&mut reader)?;
+        let z_2_shifted_opening_value = read_field_element(&mut reader)?;
+        let t_low_opening_value = read_field_element(&mut reader)?;
+        let t_mid_opening_value = read_field_element(&mut reader)?;
+        let t_high_opening_value = read_field_element(&mut reader)?;
+
+        let opening_proof_at_z = read_point(&mut reader)?;
+        let opening_proof_at_z_omega = read_point(&mut reader)?;
+
+        Ok(Self {
+            a_commitment,
+            b_commitment,
+            c_commitment,
+            z_1_commitment,
+            z_2_commitment,
+            t_low_commitment,
+            t_mid_commitment,
+            t_high_commitment,
+            a_opening_value,
+            b_opening_value,
+            c_opening_value,
+            q_l_opening_value,
+            q_r_opening_value,
+            q_o_opening_value,
+            q_m_opening_value,
+            q_c_opening_value,
+            s_id_opening_value,
+            sigma_1_opening_value,
+            sigma_2_opening_value,
+            sigma_3_opening_value,
+            z_1_unshifted_opening_value,
+            z_2_unshifted_opening_value,
+            z_1_shifted_opening_value,
+            z_2_shifted_opening_value,
+            t_low_opening_value,
+            t_mid_opening_value,
+            t_high_opening_value,
+            opening_proof_at_z,
+            opening_proof_at_z_omega,
+        })
+    }
+}
+
+/// PLONK proof that opens all the polynomials at z and z*omega with a single batched
+/// opening instead of `opening_proof_at_z` and `opening_proof_at_z_omega` of `PlonkProof`
+#[derive(Clone, Debug)]
+pub struct PlonkBatchedProof<E: Engine>{
+    pub a_commitment: E::G1Affine,
+    pub b_commitment: E::G1Affine,
+    pub c_commitment: E::G1Affine,
+    pub z_1_commitment: E::G1Affine,
+    pub z_2_commitment: E::G1Affine,
+    pub t_low_commitment: E::G1Affine,
+    pub t_mid_commitment: E::G1Affine,
+    pub t_high_commitment: E::G1Affine,
+
+    pub a_opening_value: E::Fr,
+    pub b_opening_value: E::Fr,
+    pub c_opening_value: E::Fr,
+    pub q_l_opening_value: E::Fr,
+    pub q_r_opening_value: E::Fr,
+    pub q_o_opening_value: E::Fr,
+    pub q_m_opening_value: E::Fr,
+    pub q_c_opening_value: E::Fr,
+    pub s_id_opening_value: E::Fr,
+    pub sigma_1_opening_value: E::Fr,
+    pub sigma_2_opening_value: E::Fr,
+    pub sigma_3_opening_value: E::Fr,
+    pub z_1_unshifted_opening_value: E::Fr,
+    pub z_2_unshifted_opening_value: E::Fr,
+    pub z_1_shifted_opening_value: E::Fr,
+    pub z_2_shifted_opening_value: E::Fr,
+    pub t_low_opening_value: E::Fr,
+    pub t_mid_opening_value: E::Fr,
+    pub t_high_opening_value: E::Fr,
+
+    pub batched_opening: BatchedOpeningProof<E>,
+}
+
+impl<E: Engine> PlonkBatchedProof<E> {
+    fn from_parts(proof: PlonkProof<E>, batched_opening: BatchedOpeningProof<E>) -> Self {
+        Self {
+            a_commitment: proof.a_commitment,
+            b_commitment: proof.b_commitment,
+            c_commitment: proof.c_commitment,
+            z_1_commitment: proof.z_1_commitment,
+            z_2_commitment: proof.z_2_commitment,
+            t_low_commitment: proof.t_low_commitment,
+            t_mid_commitment: proof.t_mid_commitment,
+            t_high_commitment: proof.t_high_commitment,
+
+            a_opening_value: proof.a_opening_value,
+            b_opening_value: proof.b_opening_value,
+            c_opening_value: proof.c_opening_value,
+            q_l_opening_value: proof.q_l_opening_value,
+            q_r_opening_value: proof.q_r_opening_value,
+            q_o_opening_value: proof.q_o_opening_value,
+            q_m_opening_value: proof.q_m_opening_value,
+            q_c_opening_value: proof.q_c_opening_value,
+            s_id_opening_value: proof.s_id_opening_value,
+            sigma_1_opening_value: proof.sigma_1_opening_value,
+            sigma_2_opening_value: proof.sigma_2_opening_value,
+            sigma_3_opening_value: proof.sigma_3_opening_value,
+            z_1_unshifted_opening_value: proof.z_1_unshifted_opening_value,
+            z_2_unshifted_opening_value: proof.z_2_unshifted_opening_value,
+            z_1_shifted_opening_value: proof.z_1_shifted_opening_value,
+            z_2_shifted_opening_value: proof.z_2_shifted_opening_value,
+            t_low_opening_value: proof.t_low_opening_value,
+            t_mid_opening_value: proof.t_mid_opening_value,
+            t_high_opening_value: proof.t_high_opening_value,
+
+            batched_opening,
+        }
+    }
+
+    // commitments and claimed values as a `PlonkProof` with zero opening proofs,
+    // so the constraints at z are checked by the same code for both kinds of proofs
+    pub(crate) fn claims(&self) -> PlonkProof<E> {
+        PlonkProof {
+            a_commitment: self.a_commitment,
+            b_commitment: self.b_commitment,
+            c_commitment: self.c_commitment,
+            z_1_commitment: self.z_1_commitment,
+            z_2_commitment: self.z_2_commitment,
+            t_low_commitment: self.t_low_commitment,
+            t_mid_commitment: self.t_mid_commitment,
+            t_high_commitment: self.t_high_commitment,
+
+            a_opening_value: self.a_opening_value,
+            b_opening_value: self.b_opening_value,
+            c_opening_value: self.c_opening_value,
+            q_l_opening_value: self.q_l_opening_value,
+            q_r_opening_value: self.q_r_opening_value,
+            q_o_opening_value: self.q_o_opening_value,
+            q_m_opening_value: self.q_m_opening_value,
+            q_c_opening_value: self.q_c_opening_value,
+            s_id_opening_value: self.s_id_opening_value,
+            sigma_1_opening_value: self.sigma_1_opening_value,
+            sigma_2_opening_value: self.sigma_2_opening_value,
+            sigma_3_opening_value: self.sigma_3_opening_value,
+            z_1_unshifted_opening_value: self.z_1_unshifted_opening_value,
+            z_2_unshifted_opening_value: self.z_2_unshifted_opening_value,
+            z_1_shifted_opening_value: self.z_1_shifted_opening_value,
+            z_2_shifted_opening_value: self.z_2_shifted_opening_value,
+            t_low_opening_value: self.t_low_opening_value,
+            t_mid_opening_value: self.t_mid_opening_value,
+            t_high_opening_value: self.t_high_opening_value,
+
+            opening_proof_at_z: E::G1Affine::zero(),
+            opening_proof_at_z_omega: E::G1Affine::zero(),
+        }
+    }
+}
+
+pub(crate) struct OpeningRequest<'a, E: Engine> {
+    pub(crate) polynomials: Vec<&'a Polynomial<E::Fr, Coefficients>>,
+    pub(crate) opening_point: E::Fr,
+    pub(crate) opening_values: Vec<E::Fr>
+}
+
+// polynomials of a proof with their values at z, in the order they are aggregated for the
+// opening at z. `at_z_omega` are indices of the polynomials that are opened at z*omega too
+struct ProofOpenings<E: Engine> {
+    polynomials: Vec<Polynomial<E::Fr, Coefficients>>,
+    z: E::Fr,
+    values_at_z: Vec<E::Fr>,
+    z_by_omega: E::Fr,
+    at_z_omega: Vec<usize>,
+    values_at_z_omega: Vec<E::Fr>
 }
 
 use crate::multiexp::dense_multiexp;
+use crate::shplonk::{BatchedOpeningProof, PolynomialOpening, prove_batched_opening_with_transcript};
+use crate::transcript::PlonkTranscriptAdapter;
+use super::srs::UniversalSrs;
 
 pub(crate) fn field_elements_into_representations<E: Engine>(
     worker: &Worker,
@@ -828,11 +1281,15 @@ pub(crate) fn field_elements_into_representations<E: Engine>(
 impl<E: Engine> ProvingAssembly<E> {
     pub(crate) fn commit_single_poly(
         poly: &Polynomial<E::Fr, Coefficients>, 
-        bases: &[E::G1Affine],
+        srs: &UniversalSrs<E>,
         worker: &Worker
     ) -> Result<E::G1Affine, SynthesisError> {
+        if poly.size() > srs.size() {
+            return Err(SynthesisError::PolynomialDegreeTooLarge);
+        }
+
         let reprs = field_elements_into_representations::<E>(&worker, poly.as_ref().to_owned())?;
-        let result = dense_multiexp(&worker, bases, &reprs)?;
+        let result = dense_multiexp(worker, &srs.g1_powers[..poly.size()], &reprs)?;
 
         Ok(result.into_affine())
     }
@@ -868,10 +1325,10 @@ impl<E: Engine> ProvingAssembly<E> {
         q
     }
 
-    fn multiopening<T: Transcript<E::Fr>>
+    pub(crate) fn multiopening<T: Transcript<E::Fr>>
         ( 
             opening_request: OpeningRequest<E>,
-            bases: &[E::G1Affine],
+            srs: &UniversalSrs<E>,
             worker: &Worker,
             transcript: &mut T
         ) -> Result<E::G1Affine, SynthesisError> {
@@ -893,23 +1350,108 @@ impl<E: Engine> ProvingAssembly<E> {
 
             let q = Polynomial::from_coeffs(q)?;
 
-            let opening = Self::commit_single_poly(&q, bases, &worker)?;
+            let opening = Self::commit_single_poly(&q, srs, worker)?;
 
             Ok(opening)
     }
 
-    fn prove_with_setup_precomputed<CP: CTPrecomputations<E::Fr>, CPI: CTPrecomputations<E::Fr>, T: Transcript<E::Fr> >(
+    pub fn prove_with_setup_precomputed<CP: CTPrecomputations<E::Fr>, CPI: CTPrecomputations<E::Fr>, T: Transcript<E::Fr> >(
         self,
         setup_precomp: &PlonkSetupPrecomputation<E>,
         worker: &Worker,
         omegas_bitreversed: &CP,
         omegas_inv_bitreversed: &CPI,
-        bases: &[E::G1Affine]
-    ) -> Result<(), SynthesisError> {
-        assert!(self.is_finalized);
+        srs: &UniversalSrs<E>
+    ) -> Result<PlonkProof<E>, SynthesisError> {
+        let mut transcript = T::new();
+
+        let (mut proof, openings) = self.commit_and_evaluate(
+            setup_precomp,
+            worker,
+            omegas_bitreversed,
+            omegas_inv_bitreversed,
+            srs,
+            &mut transcript
+        )?;
+
+        let ProofOpenings { polynomials, z, values_at_z, z_by_omega, at_z_omega, values_at_z_omega } = openings;
+
+        let request_at_z = OpeningRequest {
+            polynomials: polynomials.iter().collect(),
+            opening_point: z,
+            opening_values: values_at_z
+        };
+
+        let request_at_z_omega = OpeningRequest {
+            polynomials: at_z_omega.iter().map(|i| &polynomials[*i]).collect(),
+            opening_point: z_by_omega,
+            opening_values: values_at_z_omega
+        };
+
+        proof.opening_proof_at_z = Self::multiopening(request_at_z, srs, worker, &mut transcript)?;
+        proof.opening_proof_at_z_omega = Self::multiopening(request_at_z_omega, srs, worker, &mut transcript)?;
 
+        Ok(proof)
+    }
+
+    /// Same as `prove_with_setup_precomputed`, but all the polynomials are opened at z
+    /// and z*omega with a single batched opening, see `crate::shplonk`. The batching
+    /// challenge and the opening point are drawn from the transcript after the claimed
+    /// values. Proofs are checked by `verifier::verify_batched`
+    pub fn prove_with_batched_opening<CP: CTPrecomputations<E::Fr>, CPI: CTPrecomputations<E::Fr>, T: Transcript<E::Fr> >(
+        self,
+        setup_precomp: &PlonkSetupPrecomputation<E>,
+        worker: &Worker,
+        omegas_bitreversed: &CP,
+        omegas_inv_bitreversed: &CPI,
+        srs: &UniversalSrs<E>
+    ) -> Result<PlonkBatchedProof<E>, SynthesisError> {
         let mut transcript = T::new();
 
+        let (proof, openings) = self.commit_and_evaluate(
+            setup_precomp,
+            worker,
+            omegas_bitreversed,
+            omegas_inv_bitreversed,
+            srs,
+            &mut transcript
+        )?;
+
+        let polynomial_openings: Vec<_> = openings.polynomials.iter().enumerate().map(|(i, poly)| {
+            let mut points = vec![openings.z];
+            if openings.at_z_omega.contains(&i) {
+                points.push(openings.z_by_omega);
+            }
+
+            PolynomialOpening {
+                coefficients: poly.as_ref(),
+                points
+            }
+        }).collect();
+
+        let batched_opening = prove_batched_opening_with_transcript::<E, _>(
+            &polynomial_openings,
+            &srs.g1_powers,
+            worker,
+            &mut PlonkTranscriptAdapter::new(transcript)
+        )?;
+
+        Ok(PlonkBatchedProof::from_parts(proof, batched_opening))
+    }
+
+    // Commits to the polynomials of a proof and their values at z. Returns the proof
+    // without the opening proofs, they are left as zero, and the polynomials to open
+    fn commit_and_evaluate<CP: CTPrecomputations<E::Fr>, CPI: CTPrecomputations<E::Fr>, T: Transcript<E::Fr> >(
+        self,
+        setup_precomp: &PlonkSetupPrecomputation<E>,
+        worker: &Worker,
+        omegas_bitreversed: &CP,
+        omegas_inv_bitreversed: &CPI,
+        srs: &UniversalSrs<E>,
+        transcript: &mut T
+    ) -> Result<(PlonkProof<E>, ProofOpenings<E>), SynthesisError> {
+        assert!(self.is_finalized);
+
         let n = self.input_gates.len() + self.aux_gates.len();
 
         // we need n+1 to be a power of two and can not have n to be power of two
@@ -929,15 +1471,20 @@ impl<E: Engine> ProvingAssembly<E> {
 
         // polynomials inside of these is are values in cosets
 
-        let a_commitment_data = Self::commit_single_poly(&a_poly, bases, &worker)?;
-        let b_commitment_data = Self::commit_single_poly(&b_poly, bases, &worker)?;
-        let c_commitment_data = Self::commit_single_poly(&c_poly, bases, &worker)?;
+        let a_commitment_data = Self::commit_single_poly(&a_poly, srs, worker)?;
+        let b_commitment_data = Self::commit_single_poly(&b_poly, srs, worker)?;
+        let c_commitment_data = Self::commit_single_poly(&c_poly, srs, worker)?;
+
+        transcript.commit_point(&a_commitment_data);
+        transcript.commit_point(&b_commitment_data);
+        transcript.commit_point(&c_commitment_data);
 
-        transcript.commit_bytes(a_commitment_data.into_compressed().as_ref());
-        transcript.commit_bytes(b_commitment_data.into_compressed().as_ref());
-        transcript.commit_bytes(c_commitment_data.into_compressed().as_ref());
+        for input in self.input_assingments.iter() {
+            transcript.commit_field_element(input);
+        }
 
-        // TODO: Add public inputs
+        let public_inputs_poly = self.make_public_inputs_values(required_domain_size)?
+            .ifft_using_bitreversed_ntt(&worker, omegas_inv_bitreversed, &E::Fr::one())?;
 
         let beta = transcript.get_challenge();
         let gamma = transcript.get_challenge();
@@ -1041,11 +1588,11 @@ impl<E: Engine> ProvingAssembly<E> {
 
         // polynomials inside of these is are values in cosets
 
-        let z_1_commitment_data = Self::commit_single_poly(&z_1, &bases, &worker)?;
-        let z_2_commitment_data = Self::commit_single_poly(&z_2, &bases, &worker)?;
+        let z_1_commitment_data = Self::commit_single_poly(&z_1, srs, worker)?;
+        let z_2_commitment_data = Self::commit_single_poly(&z_2, srs, worker)?;
 
-        transcript.commit_bytes(z_1_commitment_data.into_compressed().as_ref());
-        transcript.commit_bytes(z_2_commitment_data.into_compressed().as_ref());
+        transcript.commit_point(&z_1_commitment_data);
+        transcript.commit_point(&z_2_commitment_data);
 
         let mut z_1_shifted = z_1.clone();
         z_1_shifted.distribute_powers(&worker, z_1.omega);
@@ -1123,6 +1670,15 @@ impl<E: Engine> ProvingAssembly<E> {
             t_1.add_assign(&worker, &q_m_by_ab);
             drop(q_m_by_ab);
 
+            let public_inputs_coset_lde_bitreversed = public_inputs_poly.clone().bitreversed_lde_using_bitreversed_ntt(
+                &worker, 
+                4, 
+                omegas_bitreversed, 
+                &E::Fr::multiplicative_generator()
+            )?;
+            t_1.add_assign(&worker, &public_inputs_coset_lde_bitreversed);
+            drop(public_inputs_coset_lde_bitreversed);
+
             vanishing_poly_inverse_bitreversed.scale(&worker, alpha);
 
             t_1.mul_assign(&worker, &vanishing_poly_inverse_bitreversed);
@@ -1326,13 +1882,13 @@ impl<E: Engine> ProvingAssembly<E> {
         let t_poly_mid = t_poly_parts.pop().expect("mid exists");
         let t_poly_low = t_poly_parts.pop().expect("low exists");
 
-        let t_poly_high_commitment_data = Self::commit_single_poly(&t_poly_high, &bases, &worker)?;
-        let t_poly_mid_commitment_data = Self::commit_single_poly(&t_poly_mid, &bases, &worker)?;
-        let t_poly_low_commitment_data = Self::commit_single_poly(&t_poly_low, &bases, &worker)?;
+        let t_poly_high_commitment_data = Self::commit_single_poly(&t_poly_high, srs, worker)?;
+        let t_poly_mid_commitment_data = Self::commit_single_poly(&t_poly_mid, srs, worker)?;
+        let t_poly_low_commitment_data = Self::commit_single_poly(&t_poly_low, srs, worker)?;
 
-        transcript.commit_bytes(t_poly_low_commitment_data.into_compressed().as_ref());
-        transcript.commit_bytes(t_poly_mid_commitment_data.into_compressed().as_ref());
-        transcript.commit_bytes(t_poly_high_commitment_data.into_compressed().as_ref());
+        transcript.commit_point(&t_poly_low_commitment_data);
+        transcript.commit_point(&t_poly_mid_commitment_data);
+        transcript.commit_point(&t_poly_high_commitment_data);
 
         let z = transcript.get_challenge();
 
@@ -1363,6 +1919,8 @@ impl<E: Engine> ProvingAssembly<E> {
         let t_mid_at_z = t_poly_mid.evaluate_at(&worker, z);
         let t_high_at_z = t_poly_high.evaluate_at(&worker, z);
 
+        let public_inputs_at_z = public_inputs_poly.evaluate_at(&worker, z);
+
         let l_0_at_z = l_0.evaluate_at(&worker, z);
         let l_n_minus_one_at_z = l_n_minus_one.evaluate_at(&worker, z);
 
@@ -1402,6 +1960,8 @@ impl<E: Engine> ProvingAssembly<E> {
             let mut t_1 = {
                 let mut res = q_c_at_z;
 
+                res.add_assign(&public_inputs_at_z);
+
                 let mut tmp = q_l_at_z;
                 tmp.mul_assign(&a_at_z);
                 res.add_assign(&tmp);
@@ -1525,124 +2085,43 @@ impl<E: Engine> ProvingAssembly<E> {
             t_at_z.add_assign(&tmp);
 
             if t_at_z != t_1 {
-                println!("Sanity check failed, may be due to public inputs ignored");
+                return Err(SynthesisError::Unsatisfiable);
             }
-
-            // assert_eq!(t_at_z, t_1, "sanity check failed");
         }
 
-        // Compute linearization polynomial
-
-        let mut linearization_poly = q_m.clone();
-        let mut linearization_multiplier = alpha;
-        {
-            let mut tmp = q_l_at_z;
-            tmp.mul_assign(&q_r_at_z);
-
-            linearization_poly.scale(&worker, tmp);
-
-            linearization_poly.add_assign_scaled(&worker, &q_l, &q_l_at_z);
-
-            linearization_poly.add_assign_scaled(&worker, &q_r, &q_r_at_z);
-
-            linearization_poly.add_assign_scaled(&worker, &q_o, &q_o_at_z);
-
-            linearization_poly.add_assign(&worker, &q_c);
-
-            linearization_poly.scale(&worker, linearization_multiplier);
-        }
-
-        linearization_multiplier.mul_assign(&alpha);
-        {
-            let mut factor = linearization_multiplier;
-            let mut tmp = s_id_at_z;
-            tmp.mul_assign(&beta);
-            tmp.add_assign(&gamma);
-            tmp.add_assign(&a_at_z);
-
-            factor.mul_assign(&tmp);
-
-            let mut tmp = s_id_at_z;
-            tmp.add_assign(&n_fe);
-            tmp.mul_assign(&beta);
-            tmp.add_assign(&gamma);
-            tmp.add_assign(&b_at_z);
-
-            factor.mul_assign(&tmp);
-
-            let mut tmp = s_id_at_z;
-            tmp.add_assign(&two_n_fe);
-            tmp.mul_assign(&beta);
-            tmp.add_assign(&gamma);
-            tmp.add_assign(&c_at_z);
-
-            factor.mul_assign(&tmp);
-
-            linearization_poly.add_assign_scaled(&worker, &z_1, &tmp);
-        }
-
-        linearization_multiplier.mul_assign(&alpha);
-        {
-            let mut factor = linearization_multiplier;
-            let mut tmp = sigma_1_at_z;
-            tmp.mul_assign(&beta);
-            tmp.add_assign(&gamma);
-            tmp.add_assign(&a_at_z);
-
-            factor.mul_assign(&tmp);
-
-            let mut tmp = sigma_2_at_z;
-            tmp.mul_assign(&beta);
-            tmp.add_assign(&gamma);
-            tmp.add_assign(&b_at_z);
-
-            factor.mul_assign(&tmp);
-
-            let mut tmp = sigma_3_at_z;
-            tmp.mul_assign(&beta);
-            tmp.add_assign(&gamma);
-            tmp.add_assign(&c_at_z);
-
-            factor.mul_assign(&tmp);
-
-            linearization_poly.add_assign_scaled(&worker, &z_2, &tmp);
-        }
-
-        linearization_multiplier.mul_assign(&alpha);
-        linearization_multiplier.mul_assign(&alpha);
-        {
-            let mut tmp = z_1.clone();
-            tmp.sub_assign(&worker, &z_2);
-            linearization_poly.add_assign_scaled(&worker, &tmp, &linearization_multiplier);
-        }
-
-        let linearization_poly_at_z = linearization_poly.evaluate_at(&worker, z);
-
-        transcript.commit_field_element(&linearization_poly_at_z);
-
         let mut z_by_omega = z;
         z_by_omega.mul_assign(&z_1.omega);
 
-        let request_at_z = OpeningRequest {
+        let openings = ProofOpenings {
             polynomials: vec![
-                &a_poly,
-                &b_poly,
-                &c_poly,
-                &z_1,
-                &z_2,
-                &s_id,
-                &sigma_1,
-                &sigma_2,
-                &sigma_3,
-                &t_poly_low,
-                &t_poly_mid,
-                &t_poly_high
+                a_poly,
+                b_poly,
+                c_poly,
+                q_l,
+                q_r,
+                q_o,
+                q_m,
+                q_c,
+                z_1,
+                z_2,
+                s_id,
+                sigma_1,
+                sigma_2,
+                sigma_3,
+                t_poly_low,
+                t_poly_mid,
+                t_poly_high
             ],
-            opening_point: z,
-            opening_values: vec![
+            z,
+            values_at_z: vec![
                 a_at_z,
                 b_at_z,
                 c_at_z,
+                q_l_at_z,
+                q_r_at_z,
+                q_o_at_z,
+                q_m_at_z,
+                q_c_at_z,
                 z_1_at_z,
                 z_2_at_z,
                 s_id_at_z,
@@ -1652,60 +2131,51 @@ impl<E: Engine> ProvingAssembly<E> {
                 t_low_at_z,
                 t_mid_at_z,
                 t_high_at_z,
-            ]
-        };
-
-        let request_at_z_omega = OpeningRequest {
-            polynomials: vec![
-                &z_1,
-                &z_2
             ],
-            opening_point: z_by_omega,
-            opening_values: vec![
+            z_by_omega,
+            // z_1 and z_2
+            at_z_omega: vec![8, 9],
+            values_at_z_omega: vec![
                 z_1_shifted_at_z,
                 z_2_shifted_at_z,
             ]
         };
 
-        let _ = Self::multiopening(request_at_z, &bases, &worker, &mut transcript);
-        let _ = Self::multiopening(request_at_z_omega, &bases, &worker, &mut transcript);
-
-
-        Ok(())
+        let proof = PlonkProof::<E> {
+            a_commitment: a_commitment_data,
+            b_commitment: b_commitment_data,
+            c_commitment: c_commitment_data,
+            z_1_commitment: z_1_commitment_data,
+            z_2_commitment: z_2_commitment_data,
+            t_low_commitment: t_poly_low_commitment_data,
+            t_mid_commitment: t_poly_mid_commitment_data,
+            t_high_commitment: t_poly_high_commitment_data,
+
+            a_opening_value: a_at_z,
+            b_opening_value: b_at_z,
+            c_opening_value: c_at_z,
+            q_l_opening_value: q_l_at_z,
+            q_r_opening_value: q_r_at_z,
+            q_o_opening_value: q_o_at_z,
+            q_m_opening_value: q_m_at_z,
+            q_c_opening_value: q_c_at_z,
+            s_id_opening_value: s_id_at_z,
+            sigma_1_opening_value: sigma_1_at_z,
+            sigma_2_opening_value: sigma_2_at_z,
+            sigma_3_opening_value: sigma_3_at_z,
+            z_1_unshifted_opening_value: z_1_at_z,
+            z_2_unshifted_opening_value: z_2_at_z,
+            z_1_shifted_opening_value: z_1_shifted_at_z,
+            z_2_shifted_opening_value: z_2_shifted_at_z,
+            t_low_opening_value: t_low_at_z,
+            t_mid_opening_value: t_mid_at_z,
+            t_high_opening_value: t_high_at_z,
+
+            opening_proof_at_z: E::G1Affine::zero(),
+            opening_proof_at_z_omega: E::G1Affine::zero(),
+        };
 
-        
-        // let proof = PlonkChunkedNonhomomorphicProof::<E, S> {
-        //     a_opening_value: a_at_z,
-        //     b_opening_value: b_at_z,
-        //     c_opening_value: c_at_z,
-        //     q_l_opening_value: q_l_at_z,
-        //     q_r_opening_value: q_r_at_z,
-        //     q_o_opening_value: q_o_at_z,
-        //     q_m_opening_value: q_m_at_z,
-        //     q_c_opening_value: q_c_at_z,
-        //     s_id_opening_value: s_id_at_z,
-        //     sigma_1_opening_value: sigma_1_at_z,
-        //     sigma_2_opening_value: sigma_2_at_z,
-        //     sigma_3_opening_value: sigma_3_at_z,
-        //     z_1_unshifted_opening_value: z_1_at_z,
-        //     z_2_unshifted_opening_value: z_2_at_z,
-        //     z_1_shifted_opening_value: z_1_shifted_at_z,
-        //     z_2_shifted_opening_value: z_2_shifted_at_z,
-        //     t_low_opening_value: t_low_at_z,
-        //     t_mid_opening_value: t_mid_at_z,
-        //     t_high_opening_value: t_high_at_z,
-        //     a_commitment: a_commitment,
-        //     b_commitment: b_commitment,
-        //     c_commitment: c_commitment,
-        //     z_1_commitment: z_1_commitment,
-        //     z_2_commitment: z_2_commitment,
-        //     t_low_commitment: t_low_commitment,
-        //     t_mid_commitment: t_mid_commitment,
-        //     t_high_commitment: t_high_commitment,
-        //     openings_proof: multiopen_proof,
-        // };
-
-        // Ok(proof)
+        Ok((proof, openings))
     }
 }
 
@@ -1800,46 +2270,9 @@ mod test {
 
         let worker = Worker::new();
 
-        println!("Making bases");
-        let bases = {
-            use crate::pairing::Wnaf;
-            let tau = Fr::from_str("42").unwrap();
-            let powers_of_tau = vec![Fr::one(); max_size.next_power_of_two()];
-            let mut powers_of_tau = Polynomial::<Fr, _>::from_coeffs(powers_of_tau).unwrap();
-
-            powers_of_tau.distribute_powers(&worker, tau);
-
-            let powers_of_tau = powers_of_tau.into_coeffs();
-
-            // Compute G1 window table
-            let mut g1_wnaf = Wnaf::new();
-            let g1 = <Eng as Engine>::G1::one();
-            let g1_wnaf = g1_wnaf.base(g1, max_size.next_power_of_two());
-
-            let mut bases = vec![g1; max_size.next_power_of_two()];
-
-            // Compute the H query with multiple threads
-            worker.scope(bases.len(), |scope, chunk| {
-                for (h, p) in bases.chunks_mut(chunk).zip(powers_of_tau.chunks(chunk))
-                {
-                    let mut g1_wnaf = g1_wnaf.shared();
-                    scope.spawn(move |_| {
-                        // Set values of the H query to g1^{(tau^i * t(tau)) / delta}
-                        for (h, p) in h.iter_mut().zip(p.iter())
-                        {
-                            // Exponentiate
-                            *h = g1_wnaf.scalar(p.into_repr());
-                        }
-
-                        // Batch normalize
-                        <<Eng as Engine>::G1 as CurveProjective>::batch_normalization(h);
-                    });
-                }
-            });
-
-            bases.iter().map(|el| el.into_affine()).collect::<Vec<_>>()
-        };
-        println!("Done making bases");
+        println!("Making SRS");
+        let srs = UniversalSrs::<Eng>::new(max_size.next_power_of_two(), Fr::from_str("42").unwrap());
+        println!("Done making SRS");
 
         for size in sizes.into_iter() {
 
@@ -1856,7 +2289,7 @@ mod test {
             let (_, setup_precomp) = setup_with_precomputations::<Eng, _, _>(
                 &circuit,
                 &omegas_bitreversed,
-                &bases[0..size.next_power_of_two()]
+                &srs
             ).unwrap();
 
             let mut prover = ProvingAssembly::<Eng>::new();
@@ -1874,7 +2307,7 @@ mod test {
                 &worker, 
                 &omegas_bitreversed, 
                 &omegas_inv_bitreversed,
-                &bases[0..size.next_power_of_two()]
+                &srs
             ).unwrap();
 
             println!("Proving taken {:?} for size {}", start.elapsed(), size);
@@ -1936,46 +2369,9 @@ mod test {
 
         let worker = Worker::new();
 
-        println!("Making bases");
-        let bases = {
-            use crate::pairing::Wnaf;
-            let tau = Fr::from_str("42").unwrap();
-            let powers_of_tau = vec![Fr::one(); max_size.next_power_of_two()];
-            let mut powers_of_tau = Polynomial::<Fr, _>::from_coeffs(powers_of_tau).unwrap();
-
-            powers_of_tau.distribute_powers(&worker, tau);
-
-            let powers_of_tau = powers_of_tau.into_coeffs();
-
-            // Compute G1 window table
-            let mut g1_wnaf = Wnaf::new();
-            let g1 = <Eng as Engine>::G1::one();
-            let g1_wnaf = g1_wnaf.base(g1, max_size.next_power_of_two());
-
-            let mut bases = vec![g1; max_size.next_power_of_two()];
-
-            // Compute the H query with multiple threads
-            worker.scope(bases.len(), |scope, chunk| {
-                for (h, p) in bases.chunks_mut(chunk).zip(powers_of_tau.chunks(chunk))
-                {
-                    let mut g1_wnaf = g1_wnaf.shared();
-                    scope.spawn(move |_| {
-                        // Set values of the H query to g1^{(tau^i * t(tau)) / delta}
-                        for (h, p) in h.iter_mut().zip(p.iter())
-                        {
-                            // Exponentiate
-                            *h = g1_wnaf.scalar(p.into_repr());
-                        }
-
-                        // Batch normalize
-                        <<Eng as Engine>::G1 as CurveProjective>::batch_normalization(h);
-                    });
-                }
-            });
-
-            bases.iter().map(|el| el.into_affine()).collect::<Vec<_>>()
-        };
-        println!("Done making bases");
+        println!("Making SRS");
+        let srs = UniversalSrs::<Eng>::new(max_size.next_power_of_two(), Fr::from_str("42").unwrap());
+        println!("Done making SRS");
 
         for size in sizes.into_iter() {
             println!("Working for size {}", size);
@@ -1993,7 +2389,7 @@ mod test {
             let (_, setup_precomp) = setup_with_precomputations::<Eng, _, _>(
                 &circuit,
                 &omegas_bitreversed,
-                &bases[0..size.next_power_of_two()]
+                &srs
             ).unwrap();
 
             let mut prover = ProvingAssembly::<Eng>::new();
@@ -2011,7 +2407,7 @@ mod test {
                 &worker, 
                 &omegas_bitreversed, 
                 &omegas_inv_bitreversed,
-                &bases[0..size.next_power_of_two()]
+                &srs
             ).unwrap();
 
             println!("Proving taken {:?} for size {}", start.elapsed(), size);
@@ -0,0 +1,384 @@
+use crate::pairing::ff::{Field, PrimeField};
+use crate::pairing::{Engine, CurveAffine, CurveProjective, EncodedPoint, Wnaf};
+
+use crate::multicore::Worker;
+use crate::multiexp::dense_multiexp;
+use crate::plonk::polynomials::*;
+use crate::plonk::utils::{write_version_header, read_version_header};
+
+use std::io::{self, Read, Write};
+use byteorder::{BigEndian, WriteBytesExt, ReadBytesExt};
+use rand::Rng;
+
+/// Structured reference string for KZG commitments that can be shared
+/// between all the circuits up to the maximum size
+#[derive(Clone, Debug, Eq)]
+pub struct UniversalSrs<E: Engine> {
+    // g^{x^0}, g^{x^{1}}, g^{x^{2}}, ..., g^{x^{size - 1}}
+    pub g1_powers: Vec<E::G1Affine>,
+
+    // h^{x^0}, h^{x^{1}}
+    pub g2_powers: [E::G2Affine; 2],
+}
+
+impl<E: Engine> PartialEq for UniversalSrs<E> {
+    fn eq(&self, other: &UniversalSrs<E>) -> bool {
+        self.g1_powers == other.g1_powers &&
+        self.g2_powers == other.g2_powers
+    }
+}
+
+/// Point encoding used by a powers-of-tau transcript file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowersOfTauEncoding {
+    // challenge files store an uncompressed accumulator
+    Uncompressed,
+    // response files store a compressed accumulator
+    Compressed,
+}
+
+impl<E: Engine> UniversalSrs<E> {
+    /// Creates an SRS from the known secret `x`, so it's only useful for testing
+    pub fn new(size: usize, x: E::Fr) -> Self {
+        assert!(size > 0);
+
+        let worker = Worker::new();
+
+        let mut powers_of_x = Polynomial::<E::Fr, _>::from_coeffs(vec![E::Fr::one(); size.next_power_of_two()]).expect("must fit into the domain");
+        powers_of_x.distribute_powers(&worker, x);
+        let powers_of_x = powers_of_x.into_coeffs();
+
+        let mut g1_wnaf = Wnaf::new();
+        let g1_wnaf = g1_wnaf.base(E::G1::one(), size);
+
+        let mut g1_powers = vec![E::G1::zero(); size];
+
+        worker.scope(g1_powers.len(), |scope, chunk| {
+            for (g, p) in g1_powers.chunks_mut(chunk).zip(powers_of_x.chunks(chunk)) {
+                let mut g1_wnaf = g1_wnaf.shared();
+                scope.spawn(move |_| {
+                    for (g, p) in g.iter_mut().zip(p.iter()) {
+                        *g = g1_wnaf.scalar(p.into_repr());
+                    }
+
+                    E::G1::batch_normalization(g);
+                });
+            }
+        });
+
+        let g1_powers = g1_powers.into_iter().map(|el| el.into_affine()).collect();
+
+        let g2 = E::G2Affine::one();
+        let g2_powers = [g2, g2.mul(x.into_repr()).into_affine()];
+
+        Self {
+            g1_powers,
+            g2_powers,
+        }
+    }
+
+    /// Maximum number of coefficients of the polynomial that can be committed to
+    pub fn size(&self) -> usize {
+        self.g1_powers.len()
+    }
+
+    /// Keeps only the powers required to commit to polynomials with up to `size` coefficients.
+    /// Verifier only needs the G2 elements, so it can use an SRS trimmed to size 1
+    pub fn trim(&self, size: usize) -> Self {
+        assert!(size > 0);
+        assert!(size <= self.size(), "SRS of size {} can not be trimmed to size {}", self.size(), size);
+
+        Self {
+            g1_powers: self.g1_powers[..size].to_vec(),
+            g2_powers: self.g2_powers,
+        }
+    }
+
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        write_version_header(&mut writer)?;
+        writer.write_u32::<BigEndian>(self.g1_powers.len() as u32)?;
+
+        for g in &self.g1_powers[..] {
+            writer.write_all(g.into_uncompressed().as_ref())?;
+        }
+
+        for g in &self.g2_powers[..] {
+            writer.write_all(g.into_uncompressed().as_ref())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R,
+        checked: bool
+    ) -> io::Result<Self>
+    {
+        read_version_header(&mut reader)?;
+        let size = reader.read_u32::<BigEndian>()? as usize;
+        if size == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "empty SRS"));
+        }
+
+        let mut g1_powers = vec![];
+        for _ in 0..size {
+            g1_powers.push(read_point::<E::G1Affine, _>(&mut reader, PowersOfTauEncoding::Uncompressed, checked)?);
+        }
+
+        let g2 = read_point::<E::G2Affine, _>(&mut reader, PowersOfTauEncoding::Uncompressed, checked)?;
+        let g2_x = read_point::<E::G2Affine, _>(&mut reader, PowersOfTauEncoding::Uncompressed, checked)?;
+
+        Ok(Self {
+            g1_powers,
+            g2_powers: [g2, g2_x],
+        })
+    }
+
+    /// Imports first `size` powers from the transcript of the powers-of-tau ceremony
+    /// with `2^ceremony_power` powers in G2. Transcript is expected to start with
+    /// 64 bytes of the previous contribution hash, followed by `2^(ceremony_power + 1) - 1`
+    /// powers of tau in G1 and `2^ceremony_power` powers of tau in G2
+    pub fn read_from_powers_of_tau<R: Read>(
+        mut reader: R,
+        ceremony_power: usize,
+        size: usize,
+        encoding: PowersOfTauEncoding
+    ) -> io::Result<Self>
+    {
+        let tau_powers_length = 1usize << ceremony_power;
+        let tau_powers_g1_length = (tau_powers_length << 1) - 1;
+
+        if size == 0 || size > tau_powers_g1_length {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("ceremony has {} powers in G1, but {} were requested", tau_powers_g1_length, size)));
+        }
+
+        // skip the hash of the previous contribution
+        skip_bytes(&mut reader, 64)?;
+
+        let mut g1_powers = vec![];
+        for _ in 0..size {
+            g1_powers.push(read_point::<E::G1Affine, _>(&mut reader, encoding, true)?);
+        }
+
+        let g1_point_size = match encoding {
+            PowersOfTauEncoding::Uncompressed => <E::G1Affine as CurveAffine>::Uncompressed::size(),
+            PowersOfTauEncoding::Compressed => <E::G1Affine as CurveAffine>::Compressed::size(),
+        };
+
+        skip_bytes(&mut reader, ((tau_powers_g1_length - size) * g1_point_size) as u64)?;
+
+        let g2 = read_point::<E::G2Affine, _>(&mut reader, encoding, true)?;
+        let g2_x = read_point::<E::G2Affine, _>(&mut reader, encoding, true)?;
+
+        let srs = Self {
+            g1_powers,
+            g2_powers: [g2, g2_x],
+        };
+
+        if !srs.is_consistent() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "powers of tau are inconsistent"));
+        }
+
+        Ok(srs)
+    }
+
+    // ceremony uses the standard generators and the same tau in both groups.
+    // Pairs of consecutive powers are merged with random coefficients, so that
+    // the whole sequence is checked by a single pairing equation
+    fn is_consistent(&self) -> bool {
+        if self.g1_powers[0] != E::G1Affine::one() || self.g2_powers[0] != E::G2Affine::one() {
+            return false;
+        }
+
+        if self.g1_powers.len() == 1 {
+            return true;
+        }
+
+        let num_pairs = self.g1_powers.len() - 1;
+
+        let mut rng = rand::thread_rng();
+        let coeffs: Vec<_> = (0..num_pairs).map(|_| rng.gen::<E::Fr>().into_repr()).collect();
+
+        let worker = Worker::new();
+
+        let powers = dense_multiexp(&worker, &self.g1_powers[..num_pairs], &coeffs);
+        let shifted_powers = dense_multiexp(&worker, &self.g1_powers[1..], &coeffs);
+
+        match (powers, shifted_powers) {
+            (Ok(powers), Ok(shifted_powers)) => {
+                E::pairing(shifted_powers, self.g2_powers[0]) == E::pairing(powers, self.g2_powers[1])
+            },
+            _ => false
+        }
+    }
+}
+
+fn read_point<G: CurveAffine, R: Read>(
+    mut reader: R,
+    encoding: PowersOfTauEncoding,
+    checked: bool
+) -> io::Result<G>
+{
+    let point = match encoding {
+        PowersOfTauEncoding::Uncompressed => {
+            let mut repr = G::Uncompressed::empty();
+            reader.read_exact(repr.as_mut())?;
+
+            if checked {
+                repr.into_affine()
+            } else {
+                repr.into_affine_unchecked()
+            }
+        },
+        PowersOfTauEncoding::Compressed => {
+            let mut repr = G::Compressed::empty();
+            reader.read_exact(repr.as_mut())?;
+
+            if checked {
+                repr.into_affine()
+            } else {
+                repr.into_affine_unchecked()
+            }
+        }
+    };
+
+    point
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        .and_then(|e| if e.is_zero() {
+            Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
+        } else {
+            Ok(e)
+        })
+}
+
+fn skip_bytes<R: Read>(reader: R, num_bytes: u64) -> io::Result<()> {
+    let skipped = io::copy(&mut reader.take(num_bytes), &mut io::sink())?;
+    if skipped != num_bytes {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "transcript is too short"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pairing::bn256::{Bn256, Fr, G1Affine, G2Affine};
+
+    fn make_powers_of_tau_transcript(ceremony_power: usize, tau: Fr, encoding: PowersOfTauEncoding) -> Vec<u8> {
+        let tau_powers_length = 1usize << ceremony_power;
+        let tau_powers_g1_length = (tau_powers_length << 1) - 1;
+
+        let srs = UniversalSrs::<Bn256>::new(tau_powers_g1_length, tau);
+
+        let mut g2_powers = vec![];
+        let mut power = Fr::one();
+        for _ in 0..tau_powers_length {
+            g2_powers.push(G2Affine::one().mul(power.into_repr()).into_affine());
+            power.mul_assign(&tau);
+        }
+
+        fn write_encoded<G: CurveAffine>(point: &G, encoding: PowersOfTauEncoding, buffer: &mut Vec<u8>) {
+            match encoding {
+                PowersOfTauEncoding::Uncompressed => buffer.extend_from_slice(point.into_uncompressed().as_ref()),
+                PowersOfTauEncoding::Compressed => buffer.extend_from_slice(point.into_compressed().as_ref()),
+            }
+        }
+
+        let mut buffer = vec![0u8; 64];
+        for g in srs.g1_powers.iter() {
+            write_encoded(g, encoding, &mut buffer);
+        }
+        for g in g2_powers.iter() {
+            write_encoded(g, encoding, &mut buffer);
+        }
+
+        // alpha and beta powers follow in real transcripts
+        for _ in 0..tau_powers_length {
+            write_encoded(&G1Affine::one(), encoding, &mut buffer);
+        }
+
+        buffer
+    }
+
+    #[test]
+    fn test_srs_serialization_round_trip() {
+        let srs = UniversalSrs::<Bn256>::new(16, Fr::from_str("42").unwrap());
+
+        let mut buffer = vec![];
+        srs.write(&mut buffer).unwrap();
+
+        let deserialized = UniversalSrs::<Bn256>::read(&buffer[..], true).unwrap();
+
+        assert_eq!(srs, deserialized);
+
+        // data without a version header is rejected
+        assert!(UniversalSrs::<Bn256>::read(&buffer[4..], true).is_err());
+    }
+
+    #[test]
+    fn test_srs_trim() {
+        let x = Fr::from_str("42").unwrap();
+        let srs = UniversalSrs::<Bn256>::new(16, x);
+
+        let trimmed = srs.trim(5);
+
+        assert_eq!(trimmed.size(), 5);
+        assert_eq!(trimmed, UniversalSrs::<Bn256>::new(5, x));
+    }
+
+    #[test]
+    fn test_import_from_powers_of_tau() {
+        let tau = Fr::from_str("123456789").unwrap();
+
+        for encoding in [PowersOfTauEncoding::Uncompressed, PowersOfTauEncoding::Compressed].iter() {
+            let transcript = make_powers_of_tau_transcript(3, tau, *encoding);
+
+            let srs = UniversalSrs::<Bn256>::read_from_powers_of_tau(&transcript[..], 3, 8, *encoding).unwrap();
+            assert_eq!(srs, UniversalSrs::<Bn256>::new(8, tau));
+
+            // all the powers in G1
+            let srs = UniversalSrs::<Bn256>::read_from_powers_of_tau(&transcript[..], 3, 15, *encoding).unwrap();
+            assert_eq!(srs, UniversalSrs::<Bn256>::new(15, tau));
+
+            assert!(UniversalSrs::<Bn256>::read_from_powers_of_tau(&transcript[..], 3, 16, *encoding).is_err());
+            assert!(UniversalSrs::<Bn256>::read_from_powers_of_tau(&transcript[..200], 3, 8, *encoding).is_err());
+        }
+    }
+
+    #[test]
+    fn test_import_inconsistent_powers_of_tau() {
+        let tau = Fr::from_str("123456789").unwrap();
+        let encoding = PowersOfTauEncoding::Uncompressed;
+        let mut transcript = make_powers_of_tau_transcript(3, tau, encoding);
+
+        // replace h^{tau} by h^{tau^2}
+        let g1_point_size = <G1Affine as CurveAffine>::Uncompressed::size();
+        let g2_point_size = <G2Affine as CurveAffine>::Uncompressed::size();
+        let g2_start = 64 + 15 * g1_point_size;
+        let (first, second) = transcript.split_at_mut(g2_start + 2 * g2_point_size);
+        first[(g2_start + g2_point_size)..].copy_from_slice(&second[..g2_point_size]);
+
+        assert!(UniversalSrs::<Bn256>::read_from_powers_of_tau(&transcript[..], 3, 8, encoding).is_err());
+    }
+
+    #[test]
+    fn test_import_powers_of_tau_with_inconsistent_g1_power() {
+        let tau = Fr::from_str("123456789").unwrap();
+        let encoding = PowersOfTauEncoding::Uncompressed;
+        let mut transcript = make_powers_of_tau_transcript(3, tau, encoding);
+
+        // replace g^{tau^5} by g^{tau^6}, first two powers are still consistent
+        let g1_point_size = <G1Affine as CurveAffine>::Uncompressed::size();
+        let g1_start = 64 + 5 * g1_point_size;
+        let (first, second) = transcript.split_at_mut(g1_start + g1_point_size);
+        first[g1_start..].copy_from_slice(&second[..g1_point_size]);
+
+        assert!(UniversalSrs::<Bn256>::read_from_powers_of_tau(&transcript[..], 3, 5, encoding).is_ok());
+        assert!(UniversalSrs::<Bn256>::read_from_powers_of_tau(&transcript[..], 3, 8, encoding).is_err());
+    }
+}
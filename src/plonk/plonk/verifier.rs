@@ -0,0 +1,930 @@
+use crate::pairing::ff::{Field, PrimeField};
+use crate::pairing::{Engine, CurveAffine, CurveProjective};
+
+use crate::{SynthesisError};
+
+use crate::plonk::domains::*;
+use crate::plonk::commitments::transcript::*;
+use crate::transcript::{ProtocolTranscript, PlonkTranscriptAdapter};
+use crate::plonk::utils::{evaluate_inverse_vanishing_poly, evaluate_lagrange_poly};
+
+use crate::shplonk::{CommitmentOpening, reduce_batched_opening_with_transcript};
+
+use super::prover::{PlonkProof, PlonkBatchedProof, PlonkSetup};
+use super::srs::UniversalSrs;
+
+// aggregates commitments and claimed values with powers of the challenge
+// in the same order as prover did, and returns (sum of commitments - [sum of values]*G1)
+pub(crate) fn aggregate_for_opening<E: Engine>(
+    commitments: &[E::G1Affine],
+    claimed_values: &[E::Fr],
+    aggregation_challenge: E::Fr
+) -> E::G1 {
+    assert_eq!(commitments.len(), claimed_values.len());
+
+    let mut aggregated_commitment = E::G1::zero();
+    let mut aggregated_value = E::Fr::zero();
+
+    let mut alpha = E::Fr::one();
+
+    for (commitment, value) in commitments.iter().zip(claimed_values.iter()) {
+        aggregated_commitment.add_assign(&commitment.mul(alpha.into_repr()));
+
+        let mut tmp = *value;
+        tmp.mul_assign(&alpha);
+        aggregated_value.add_assign(&tmp);
+
+        alpha.mul_assign(&aggregation_challenge);
+    }
+
+    aggregated_commitment.sub_assign(&E::G1Affine::one().mul(aggregated_value.into_repr()));
+
+    aggregated_commitment
+}
+
+/// Verifies a proof produced by `ProvingAssembly::prove_with_setup_precomputed`.
+/// `srs` must be the same that was used for setup and proving, only its G2
+/// elements are used, so it can be trimmed to size 1.
+pub fn verify<E: Engine, T: Transcript<E::Fr>>(
+    proof: &PlonkProof<E>,
+    public_inputs: &[E::Fr],
+    setup: &PlonkSetup<E>,
+    srs: &UniversalSrs<E>
+) -> Result<bool, SynthesisError> {
+    verify_with_transcript(proof, public_inputs, setup, srs, PlonkTranscriptAdapter::new(T::new()))
+}
+
+/// Same as `verify`, but draws challenges from the given transcript, that must be
+/// in the same state as the transcript of the prover when it started
+pub fn verify_with_transcript<E: Engine, T: ProtocolTranscript<E::Fr>>(
+    proof: &PlonkProof<E>,
+    public_inputs: &[E::Fr],
+    setup: &PlonkSetup<E>,
+    srs: &UniversalSrs<E>,
+    mut transcript: T
+) -> Result<bool, SynthesisError> {
+    let z = match check_claimed_values(proof, public_inputs, setup, &mut transcript)? {
+        Some(z) => z,
+        None => return Ok(false)
+    };
+
+    // now check the openings, challenges are drawn in the same order as in the prover
+
+    let aggregation_challenge_at_z = transcript.squeeze_scalar();
+    let aggregation_challenge_at_z_omega = transcript.squeeze_scalar();
+
+    let aggregate_at_z = aggregate_for_opening::<E>(
+        &opened_commitments(proof, setup),
+        &[
+            proof.a_opening_value,
+            proof.b_opening_value,
+            proof.c_opening_value,
+            proof.q_l_opening_value,
+            proof.q_r_opening_value,
+            proof.q_o_opening_value,
+            proof.q_m_opening_value,
+            proof.q_c_opening_value,
+            proof.z_1_unshifted_opening_value,
+            proof.z_2_unshifted_opening_value,
+            proof.s_id_opening_value,
+            proof.sigma_1_opening_value,
+            proof.sigma_2_opening_value,
+            proof.sigma_3_opening_value,
+            proof.t_low_opening_value,
+            proof.t_mid_opening_value,
+            proof.t_high_opening_value,
+        ],
+        aggregation_challenge_at_z
+    );
+
+    let aggregate_at_z_omega = aggregate_for_opening::<E>(
+        &[
+            proof.z_1_commitment,
+            proof.z_2_commitment,
+        ],
+        &[
+            proof.z_1_shifted_opening_value,
+            proof.z_2_shifted_opening_value,
+        ],
+        aggregation_challenge_at_z_omega
+    );
+
+    let z_by_omega = multiply_by_generator(setup, z)?;
+
+    // both openings are checked at once with a random linear combination
+
+    transcript.commit_point(&proof.opening_proof_at_z);
+    transcript.commit_point(&proof.opening_proof_at_z_omega);
+
+    let u = transcript.squeeze_scalar();
+
+    // e(W_z + u*W_zw, [x]) = e(z*W_z + u*z*omega*W_zw + F_z + u*F_zw, [1])
+
+    let mut pair_with_x = proof.opening_proof_at_z_omega.mul(u.into_repr());
+    pair_with_x.add_assign_mixed(&proof.opening_proof_at_z);
+
+    let mut pair_with_generator = aggregate_at_z;
+    pair_with_generator.add_assign(&proof.opening_proof_at_z.mul(z.into_repr()));
+
+    let mut tmp = aggregate_at_z_omega;
+    tmp.add_assign(&proof.opening_proof_at_z_omega.mul(z_by_omega.into_repr()));
+    tmp.mul_assign(u.into_repr());
+
+    pair_with_generator.add_assign(&tmp);
+    pair_with_generator.negate();
+
+    let valid = E::final_exponentiation(
+        &E::miller_loop([
+            (&pair_with_x.into_affine().prepare(), &srs.g2_powers[1].prepare()),
+            (&pair_with_generator.into_affine().prepare(), &srs.g2_powers[0].prepare())
+        ].iter())
+    ).ok_or(SynthesisError::Unsatisfiable)? == E::Fqk::one();
+
+    Ok(valid)
+}
+
+/// Verifies a proof produced by `ProvingAssembly::prove_with_batched_opening`, `srs`
+/// is the same as in `verify`
+pub fn verify_batched<E: Engine, T: Transcript<E::Fr>>(
+    proof: &PlonkBatchedProof<E>,
+    public_inputs: &[E::Fr],
+    setup: &PlonkSetup<E>,
+    srs: &UniversalSrs<E>
+) -> Result<bool, SynthesisError> {
+    verify_batched_with_transcript(proof, public_inputs, setup, srs, PlonkTranscriptAdapter::new(T::new()))
+}
+
+/// Same as `verify_batched`, but draws challenges from the given transcript like
+/// `verify_with_transcript` does
+pub fn verify_batched_with_transcript<E: Engine, T: ProtocolTranscript<E::Fr>>(
+    proof: &PlonkBatchedProof<E>,
+    public_inputs: &[E::Fr],
+    setup: &PlonkSetup<E>,
+    srs: &UniversalSrs<E>,
+    mut transcript: T
+) -> Result<bool, SynthesisError> {
+    let claims = proof.claims();
+    let z = match check_claimed_values(&claims, public_inputs, setup, &mut transcript)? {
+        Some(z) => z,
+        None => return Ok(false)
+    };
+
+    let z_by_omega = multiply_by_generator(setup, z)?;
+
+    // same order as in the prover, z_1 and z_2 are opened at z*omega as well
+    let openings: Vec<_> = opened_commitments(&claims, setup).iter().zip([
+        vec![claims.a_opening_value],
+        vec![claims.b_opening_value],
+        vec![claims.c_opening_value],
+        vec![claims.q_l_opening_value],
+        vec![claims.q_r_opening_value],
+        vec![claims.q_o_opening_value],
+        vec![claims.q_m_opening_value],
+        vec![claims.q_c_opening_value],
+        vec![claims.z_1_unshifted_opening_value, claims.z_1_shifted_opening_value],
+        vec![claims.z_2_unshifted_opening_value, claims.z_2_shifted_opening_value],
+        vec![claims.s_id_opening_value],
+        vec![claims.sigma_1_opening_value],
+        vec![claims.sigma_2_opening_value],
+        vec![claims.sigma_3_opening_value],
+        vec![claims.t_low_opening_value],
+        vec![claims.t_mid_opening_value],
+        vec![claims.t_high_opening_value],
+    ].iter()).map(|(commitment, values)| {
+        CommitmentOpening {
+            commitment: *commitment,
+            points: [z, z_by_omega][..values.len()].to_vec(),
+            values: values.clone()
+        }
+    }).collect();
+
+    let reduced = reduce_batched_opening_with_transcript(&openings, &proof.batched_opening, &mut transcript)?;
+
+    Ok(reduced.verify(E::G1Affine::one(), srs.g2_powers[0], srs.g2_powers[1]))
+}
+
+// commitments in the order the prover aggregates them for the opening at z
+fn opened_commitments<E: Engine>(proof: &PlonkProof<E>, setup: &PlonkSetup<E>) -> [E::G1Affine; 17] {
+    [
+        proof.a_commitment,
+        proof.b_commitment,
+        proof.c_commitment,
+        setup.q_l,
+        setup.q_r,
+        setup.q_o,
+        setup.q_m,
+        setup.q_c,
+        proof.z_1_commitment,
+        proof.z_2_commitment,
+        setup.s_id,
+        setup.sigma_1,
+        setup.sigma_2,
+        setup.sigma_3,
+        proof.t_low_commitment,
+        proof.t_mid_commitment,
+        proof.t_high_commitment,
+    ]
+}
+
+fn multiply_by_generator<E: Engine>(setup: &PlonkSetup<E>, z: E::Fr) -> Result<E::Fr, SynthesisError> {
+    let domain = Domain::<E::Fr>::new_for_size((setup.n + 1) as u64)?;
+    let mut z_by_omega = z;
+    z_by_omega.mul_assign(&domain.generator);
+
+    Ok(z_by_omega)
+}
+
+// Commits the proof up to the claimed values to the transcript and checks that t(z)
+// agrees with the constraints at z. Returns z, or None if the values don't satisfy them
+fn check_claimed_values<E: Engine, T: ProtocolTranscript<E::Fr>>(
+    proof: &PlonkProof<E>,
+    public_inputs: &[E::Fr],
+    setup: &PlonkSetup<E>,
+    transcript: &mut T
+) -> Result<Option<E::Fr>, SynthesisError> {
+    if public_inputs.len() != setup.num_inputs {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+
+    let n = setup.n;
+
+    // we need n+1 to be a power of two and can not have n to be power of two
+    let required_domain_size = n + 1;
+    if !required_domain_size.is_power_of_two() {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+
+    transcript.commit_point(&proof.a_commitment);
+    transcript.commit_point(&proof.b_commitment);
+    transcript.commit_point(&proof.c_commitment);
+
+    for input in public_inputs.iter() {
+        transcript.commit_scalar(input);
+    }
+
+    let beta = transcript.squeeze_scalar();
+    let gamma = transcript.squeeze_scalar();
+
+    transcript.commit_point(&proof.z_1_commitment);
+    transcript.commit_point(&proof.z_2_commitment);
+
+    let n_fe = E::Fr::from_str(&n.to_string()).expect("must be valid field element");
+    let mut two_n_fe = n_fe;
+    two_n_fe.double();
+
+    let alpha = transcript.squeeze_scalar();
+
+    transcript.commit_point(&proof.t_low_commitment);
+    transcript.commit_point(&proof.t_mid_commitment);
+    transcript.commit_point(&proof.t_high_commitment);
+
+    let z = transcript.squeeze_scalar();
+
+    let a_at_z = proof.a_opening_value;
+    let b_at_z = proof.b_opening_value;
+    let c_at_z = proof.c_opening_value;
+
+    let q_l_at_z = proof.q_l_opening_value;
+    let q_r_at_z = proof.q_r_opening_value;
+    let q_o_at_z = proof.q_o_opening_value;
+    let q_m_at_z = proof.q_m_opening_value;
+    let q_c_at_z = proof.q_c_opening_value;
+
+    let s_id_at_z = proof.s_id_opening_value;
+    let sigma_1_at_z = proof.sigma_1_opening_value;
+    let sigma_2_at_z = proof.sigma_2_opening_value;
+    let sigma_3_at_z = proof.sigma_3_opening_value;
+
+    let z_1_at_z = proof.z_1_unshifted_opening_value;
+    let z_2_at_z = proof.z_2_unshifted_opening_value;
+
+    let z_1_shifted_at_z = proof.z_1_shifted_opening_value;
+    let z_2_shifted_at_z = proof.z_2_shifted_opening_value;
+
+    let t_low_at_z = proof.t_low_opening_value;
+    let t_mid_at_z = proof.t_mid_opening_value;
+    let t_high_at_z = proof.t_high_opening_value;
+
+    {
+        transcript.commit_scalar(&a_at_z);
+        transcript.commit_scalar(&b_at_z);
+        transcript.commit_scalar(&c_at_z);
+
+        transcript.commit_scalar(&q_l_at_z);
+        transcript.commit_scalar(&q_r_at_z);
+        transcript.commit_scalar(&q_o_at_z);
+        transcript.commit_scalar(&q_m_at_z);
+        transcript.commit_scalar(&q_c_at_z);
+
+        transcript.commit_scalar(&s_id_at_z);
+        transcript.commit_scalar(&sigma_1_at_z);
+        transcript.commit_scalar(&sigma_2_at_z);
+        transcript.commit_scalar(&sigma_3_at_z);
+
+        transcript.commit_scalar(&t_low_at_z);
+        transcript.commit_scalar(&t_mid_at_z);
+        transcript.commit_scalar(&t_high_at_z);
+
+        transcript.commit_scalar(&z_1_at_z);
+        transcript.commit_scalar(&z_2_at_z);
+
+        transcript.commit_scalar(&z_1_shifted_at_z);
+        transcript.commit_scalar(&z_2_shifted_at_z);
+    }
+
+    let mut inverse_vanishing_at_z = evaluate_inverse_vanishing_poly::<E::Fr>(required_domain_size, z);
+
+    let l_0_at_z = evaluate_lagrange_poly::<E::Fr>(required_domain_size, 0, z);
+    let l_n_minus_one_at_z = evaluate_lagrange_poly::<E::Fr>(required_domain_size, n - 1, z);
+
+    // PI(X) = - sum input_i * L_i(X)
+    let mut public_inputs_at_z = E::Fr::zero();
+    for (i, input) in public_inputs.iter().enumerate() {
+        let mut tmp = evaluate_lagrange_poly::<E::Fr>(required_domain_size, i, z);
+        tmp.mul_assign(&input);
+        public_inputs_at_z.sub_assign(&tmp);
+    }
+
+    let z_in_pow_of_domain_size = z.pow([required_domain_size as u64]);
+
+    // check that t(z) * Z_H(z) is equal to the combination of the constraints at z
+    let mut t_1 = {
+        let mut res = q_c_at_z;
+
+        res.add_assign(&public_inputs_at_z);
+
+        let mut tmp = q_l_at_z;
+        tmp.mul_assign(&a_at_z);
+        res.add_assign(&tmp);
+
+        let mut tmp = q_r_at_z;
+        tmp.mul_assign(&b_at_z);
+        res.add_assign(&tmp);
+
+        let mut tmp = q_o_at_z;
+        tmp.mul_assign(&c_at_z);
+        res.add_assign(&tmp);
+
+        let mut tmp = q_m_at_z;
+        tmp.mul_assign(&a_at_z);
+        tmp.mul_assign(&b_at_z);
+        res.add_assign(&tmp);
+
+        inverse_vanishing_at_z.mul_assign(&alpha);
+
+        res.mul_assign(&inverse_vanishing_at_z);
+
+        res
+    };
+
+    {
+        let mut res = z_1_at_z;
+
+        let mut tmp = s_id_at_z;
+        tmp.mul_assign(&beta);
+        tmp.add_assign(&a_at_z);
+        tmp.add_assign(&gamma);
+        res.mul_assign(&tmp);
+
+        let mut tmp = s_id_at_z;
+        tmp.add_assign(&n_fe);
+        tmp.mul_assign(&beta);
+        tmp.add_assign(&b_at_z);
+        tmp.add_assign(&gamma);
+        res.mul_assign(&tmp);
+
+        let mut tmp = s_id_at_z;
+        tmp.add_assign(&two_n_fe);
+        tmp.mul_assign(&beta);
+        tmp.add_assign(&c_at_z);
+        tmp.add_assign(&gamma);
+        res.mul_assign(&tmp);
+
+        res.sub_assign(&z_1_shifted_at_z);
+
+        inverse_vanishing_at_z.mul_assign(&alpha);
+
+        res.mul_assign(&inverse_vanishing_at_z);
+
+        t_1.add_assign(&res);
+    }
+
+    {
+        let mut res = z_2_at_z;
+
+        let mut tmp = sigma_1_at_z;
+        tmp.mul_assign(&beta);
+        tmp.add_assign(&a_at_z);
+        tmp.add_assign(&gamma);
+        res.mul_assign(&tmp);
+
+        let mut tmp = sigma_2_at_z;
+        tmp.mul_assign(&beta);
+        tmp.add_assign(&b_at_z);
+        tmp.add_assign(&gamma);
+        res.mul_assign(&tmp);
+
+        let mut tmp = sigma_3_at_z;
+        tmp.mul_assign(&beta);
+        tmp.add_assign(&c_at_z);
+        tmp.add_assign(&gamma);
+        res.mul_assign(&tmp);
+
+        res.sub_assign(&z_2_shifted_at_z);
+
+        inverse_vanishing_at_z.mul_assign(&alpha);
+
+        res.mul_assign(&inverse_vanishing_at_z);
+
+        t_1.add_assign(&res);
+    }
+
+    {
+        let mut res = z_1_shifted_at_z;
+        res.sub_assign(&z_2_shifted_at_z);
+        res.mul_assign(&l_n_minus_one_at_z);
+
+        inverse_vanishing_at_z.mul_assign(&alpha);
+
+        res.mul_assign(&inverse_vanishing_at_z);
+
+        t_1.add_assign(&res);
+    }
+
+    {
+        let mut res = z_1_at_z;
+        res.sub_assign(&z_2_at_z);
+        res.mul_assign(&l_0_at_z);
+
+        inverse_vanishing_at_z.mul_assign(&alpha);
+
+        res.mul_assign(&inverse_vanishing_at_z);
+
+        t_1.add_assign(&res);
+    }
+
+    let mut t_at_z = E::Fr::zero();
+    t_at_z.add_assign(&t_low_at_z);
+
+    let mut tmp = z_in_pow_of_domain_size;
+    tmp.mul_assign(&t_mid_at_z);
+    t_at_z.add_assign(&tmp);
+
+    let mut tmp = z_in_pow_of_domain_size;
+    tmp.mul_assign(&z_in_pow_of_domain_size);
+    tmp.mul_assign(&t_high_at_z);
+    t_at_z.add_assign(&tmp);
+
+    if t_at_z != t_1 {
+        return Ok(None);
+    }
+
+    Ok(Some(z))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::super::generator::*;
+    use super::super::prover::*;
+
+    use crate::pairing::Engine;
+    use crate::pairing::ff::{Field, PrimeField};
+    use crate::pairing::{CurveAffine, CurveProjective};
+    use crate::plonk::cs::*;
+    use crate::plonk::polynomials::*;
+    use crate::plonk::fft::cooley_tukey_ntt::*;
+    use crate::plonk::commitments::transparent::fri::coset_combining_fri::precomputation::*;
+    use crate::multicore::Worker;
+    use crate::SynthesisError;
+
+    #[derive(Clone)]
+    struct TestCircuit<E: Engine>{
+        num_steps: usize,
+        _marker: std::marker::PhantomData<E>
+    }
+
+    impl<E: Engine> Circuit<E> for TestCircuit<E> {
+        fn synthesize<CS: ConstraintSystem<E>>(&self, cs: &mut CS) -> Result<(), SynthesisError> {
+            let one = E::Fr::one();
+            let mut negative_one = one;
+            negative_one.negate();
+
+            let mut two = one;
+            two.double();
+
+            let mut a = cs.alloc(|| {
+                Ok(E::Fr::one())
+            })?;
+
+            let mut b = cs.alloc(|| {
+                Ok(E::Fr::one())
+            })?;
+
+            cs.enforce_zero_2((a, b), (one, negative_one))?;
+
+            let mut c = cs.alloc(|| {
+                Ok(two)
+            })?;
+
+            cs.enforce_zero_3((a, b, c), (one, one, negative_one))?;
+
+            let mut b_value = one;
+            let mut c_value = two;
+
+            for _ in 0..self.num_steps {
+                a = b;
+                b = c;
+
+                let a_value = b_value;
+                b_value = c_value;
+                c_value.add_assign(&a_value);
+
+                c = cs.alloc(|| {
+                    Ok(c_value)
+                })?;
+
+                cs.enforce_zero_3((a, b, c), (one, one, negative_one))?;
+
+                let d = cs.alloc(|| {
+                    let mut tmp = b_value;
+                    tmp.mul_assign(&c_value);
+
+                    Ok(tmp)
+                })?;
+
+                cs.enforce_mul_3((b, c, d))?;
+            }
+
+            let output = cs.alloc_input(|| {
+                Ok(c_value)
+            })?;
+
+            cs.enforce_zero_2((c, output), (one, negative_one))?;
+
+            Ok(())
+        }
+    }
+
+    fn test_circuit_output<E: Engine>(num_steps: usize) -> E::Fr {
+        let mut b_value = E::Fr::one();
+        let mut c_value = b_value;
+        c_value.double();
+
+        for _ in 0..num_steps {
+            let a_value = b_value;
+            b_value = c_value;
+            c_value.add_assign(&a_value);
+        }
+
+        c_value
+    }
+
+    fn make_test_srs<E: Engine>(size: usize) -> UniversalSrs<E> {
+        UniversalSrs::<E>::new(size, E::Fr::from_str("42").unwrap())
+    }
+
+    fn prove_test_circuit<E: Engine>(num_steps: usize) -> (PlonkSetup<E>, PlonkProof<E>, Vec<E::Fr>, UniversalSrs<E>) {
+        use crate::plonk::commitments::transcript::Blake2sTranscript;
+
+        prove_test_circuit_with_transcript::<E, Blake2sTranscript<E::Fr>>(num_steps)
+    }
+
+    fn prove_test_circuit_with_transcript<E: Engine, T: Transcript<E::Fr>>(num_steps: usize) -> (PlonkSetup<E>, PlonkProof<E>, Vec<E::Fr>, UniversalSrs<E>) {
+        let circuit = TestCircuit::<E> {
+            num_steps,
+            _marker: std::marker::PhantomData
+        };
+
+        let mut prover = ProvingAssembly::<E>::new();
+        circuit.synthesize(&mut prover).unwrap();
+        prover.finalize();
+
+        let size = prover.num_gates() + 1;
+
+        // universal SRS is larger than required for this circuit
+        let srs = make_test_srs::<E>(size * 2);
+
+        let omegas_bitreversed = BitReversedOmegas::<E::Fr>::new_for_domain_size(size);
+        let omegas_inv_bitreversed = <OmegasInvBitreversed::<E::Fr> as CTPrecomputations::<E::Fr>>::new_for_domain_size(size);
+
+        let (setup, setup_precomp) = setup_with_precomputations::<E, _, _>(
+            &circuit,
+            &omegas_bitreversed,
+            &srs
+        ).unwrap();
+
+        let worker = Worker::new();
+
+        let proof = prover.prove_with_setup_precomputed::<_, _, T>(
+            &setup_precomp,
+            &worker,
+            &omegas_bitreversed,
+            &omegas_inv_bitreversed,
+            &srs
+        ).unwrap();
+
+        (setup, proof, vec![test_circuit_output::<E>(num_steps)], srs)
+    }
+
+    #[test]
+    fn test_prove_and_verify_bn256() {
+        use crate::pairing::bn256::{Bn256, Fr};
+        use crate::plonk::commitments::transcript::Blake2sTranscript;
+
+        let (setup, proof, inputs, srs) = prove_test_circuit::<Bn256>(100);
+
+        let valid = verify::<Bn256, Blake2sTranscript<Fr>>(&proof, &inputs, &setup, &srs).unwrap();
+
+        assert!(valid);
+
+        let valid = verify::<Bn256, Blake2sTranscript<Fr>>(&proof, &inputs, &setup, &srs.trim(1)).unwrap();
+
+        assert!(valid);
+    }
+
+    #[test]
+    fn test_verify_through_protocol_transcript() {
+        use crate::pairing::bn256::{Bn256, Fr};
+        use crate::plonk::commitments::transcript::keccak::Keccak256Transcript;
+
+        let (setup, proof, inputs, srs) = prove_test_circuit_with_transcript::<Bn256, Keccak256Transcript<Fr>>(10);
+
+        let transcript = PlonkTranscriptAdapter::new(Keccak256Transcript::<Fr>::new());
+        assert!(verify_with_transcript(&proof, &inputs, &setup, &srs, transcript).unwrap());
+
+        // challenges of a transcript in another state don't match the prover's
+        let mut transcript = PlonkTranscriptAdapter::new(Keccak256Transcript::<Fr>::new());
+        transcript.commit_scalar(&Fr::one());
+        assert!(!verify_with_transcript(&proof, &inputs, &setup, &srs, transcript).unwrap());
+
+        assert!(!verify::<Bn256, Blake2sTranscript<Fr>>(&proof, &inputs, &setup, &srs).unwrap());
+    }
+
+    #[test]
+    fn test_prove_and_verify_bls12() {
+        use crate::pairing::bls12_381::{Bls12, Fr};
+        use crate::plonk::commitments::transcript::Blake2sTranscript;
+
+        let (setup, proof, inputs, srs) = prove_test_circuit::<Bls12>(100);
+
+        let valid = verify::<Bls12, Blake2sTranscript<Fr>>(&proof, &inputs, &setup, &srs).unwrap();
+
+        assert!(valid);
+    }
+
+    #[test]
+    fn test_tampered_proof_is_rejected() {
+        use crate::pairing::bn256::{Bn256, Fr};
+        use crate::plonk::commitments::transcript::Blake2sTranscript;
+
+        let (setup, proof, inputs, srs) = prove_test_circuit::<Bn256>(10);
+
+        let mut bad_proof = proof.clone();
+        bad_proof.a_opening_value.add_assign(&Fr::one());
+        let valid = verify::<Bn256, Blake2sTranscript<Fr>>(&bad_proof, &inputs, &setup, &srs).unwrap();
+        assert!(!valid);
+
+        let mut bad_proof = proof;
+        bad_proof.opening_proof_at_z_omega = bad_proof.opening_proof_at_z;
+        let valid = verify::<Bn256, Blake2sTranscript<Fr>>(&bad_proof, &inputs, &setup, &srs).unwrap();
+        assert!(!valid);
+    }
+
+    #[test]
+    fn test_proof_is_bound_to_public_inputs() {
+        use crate::pairing::bn256::{Bn256, Fr};
+        use crate::plonk::commitments::transcript::Blake2sTranscript;
+
+        let (setup, proof, inputs, srs) = prove_test_circuit::<Bn256>(10);
+
+        let valid = verify::<Bn256, Blake2sTranscript<Fr>>(&proof, &inputs, &setup, &srs).unwrap();
+        assert!(valid);
+
+        let mut wrong_inputs = inputs.clone();
+        wrong_inputs[0].add_assign(&Fr::one());
+        let valid = verify::<Bn256, Blake2sTranscript<Fr>>(&proof, &wrong_inputs, &setup, &srs).unwrap();
+        assert!(!valid);
+
+        assert!(verify::<Bn256, Blake2sTranscript<Fr>>(&proof, &[], &setup, &srs).is_err());
+    }
+
+    #[derive(Clone)]
+    struct UnsatisfiedCircuit<E: Engine>{
+        _marker: std::marker::PhantomData<E>
+    }
+
+    impl<E: Engine> Circuit<E> for UnsatisfiedCircuit<E> {
+        fn synthesize<CS: ConstraintSystem<E>>(&self, cs: &mut CS) -> Result<(), SynthesisError> {
+            let one = E::Fr::one();
+            let mut negative_one = one;
+            negative_one.negate();
+
+            let mut two = one;
+            two.double();
+
+            let a = cs.alloc(|| {
+                Ok(one)
+            })?;
+
+            let b = cs.alloc(|| {
+                Ok(two)
+            })?;
+
+            // a - b = 0 does not hold for this witness
+            cs.enforce_zero_2((a, b), (one, negative_one))?;
+
+            cs.enforce_mul_3((a, a, a))?;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_unsatisfied_circuit_can_not_be_proven() {
+        use crate::pairing::bn256::{Bn256, Fr};
+        use crate::plonk::commitments::transcript::Blake2sTranscript;
+
+        let circuit = UnsatisfiedCircuit::<Bn256> {
+            _marker: std::marker::PhantomData
+        };
+
+        let mut prover = ProvingAssembly::<Bn256>::new();
+        circuit.synthesize(&mut prover).unwrap();
+        prover.finalize();
+
+        let size = prover.num_gates() + 1;
+        let srs = make_test_srs::<Bn256>(size * 2);
+
+        let omegas_bitreversed = BitReversedOmegas::<Fr>::new_for_domain_size(size);
+        let omegas_inv_bitreversed = <OmegasInvBitreversed::<Fr> as CTPrecomputations::<Fr>>::new_for_domain_size(size);
+
+        let (_, setup_precomp) = setup_with_precomputations::<Bn256, _, _>(
+            &circuit,
+            &omegas_bitreversed,
+            &srs
+        ).unwrap();
+
+        let proof = prover.prove_with_setup_precomputed::<_, _, Blake2sTranscript<Fr>>(
+            &setup_precomp,
+            &Worker::new(),
+            &omegas_bitreversed,
+            &omegas_inv_bitreversed,
+            &srs
+        );
+
+        assert!(proof.is_err());
+    }
+
+    #[test]
+    fn test_proof_serialization_round_trip() {
+        use crate::pairing::bn256::Bn256;
+
+        let (_, proof, _, _) = prove_test_circuit::<Bn256>(10);
+
+        let mut buffer = vec![];
+        proof.write(&mut buffer).unwrap();
+
+        let deserialized = PlonkProof::<Bn256>::read(&buffer[..]).unwrap();
+
+        assert_eq!(proof, deserialized);
+    }
+
+    #[test]
+    fn test_setup_serialization_round_trip() {
+        use crate::pairing::bn256::{Bn256, Fr};
+        use crate::plonk::commitments::transcript::Blake2sTranscript;
+
+        let num_steps = 10;
+        let circuit = TestCircuit::<Bn256> {
+            num_steps,
+            _marker: std::marker::PhantomData
+        };
+
+        let mut prover = ProvingAssembly::<Bn256>::new();
+        circuit.synthesize(&mut prover).unwrap();
+        prover.finalize();
+
+        let size = prover.num_gates() + 1;
+
+        let srs = make_test_srs::<Bn256>(size);
+
+        let omegas_bitreversed = BitReversedOmegas::<Fr>::new_for_domain_size(size);
+        let omegas_inv_bitreversed = <OmegasInvBitreversed::<Fr> as CTPrecomputations::<Fr>>::new_for_domain_size(size);
+
+        let (setup, setup_precomp) = setup_with_precomputations::<Bn256, _, _>(
+            &circuit,
+            &omegas_bitreversed,
+            &srs
+        ).unwrap();
+
+        let mut setup_buffer = vec![];
+        setup.write(&mut setup_buffer).unwrap();
+        let mut precomp_buffer = vec![];
+        setup_precomp.write(&mut precomp_buffer).unwrap();
+
+        let deserialized_setup = PlonkSetup::<Bn256>::read(&setup_buffer[..]).unwrap();
+        let deserialized_precomp = PlonkSetupPrecomputation::<Bn256>::read(&precomp_buffer[..]).unwrap();
+
+        let mut buffer = vec![];
+        deserialized_setup.write(&mut buffer).unwrap();
+        assert_eq!(setup_buffer, buffer);
+
+        let mut buffer = vec![];
+        deserialized_precomp.write(&mut buffer).unwrap();
+        assert_eq!(precomp_buffer, buffer);
+
+        // unknown version is rejected
+        let mut bad_buffer = setup_buffer.clone();
+        bad_buffer[3] ^= 0xff;
+        assert!(PlonkSetup::<Bn256>::read(&bad_buffer[..]).is_err());
+
+        let worker = Worker::new();
+
+        let proof = prover.prove_with_setup_precomputed::<_, _, Blake2sTranscript<Fr>>(
+            &deserialized_precomp,
+            &worker,
+            &omegas_bitreversed,
+            &omegas_inv_bitreversed,
+            &srs
+        ).unwrap();
+
+        let inputs = vec![test_circuit_output::<Bn256>(num_steps)];
+        let valid = verify::<Bn256, Blake2sTranscript<Fr>>(&proof, &inputs, &deserialized_setup, &srs).unwrap();
+
+        assert!(valid);
+    }
+
+    #[test]
+    fn test_prove_and_verify_with_batched_opening() {
+        use crate::pairing::bn256::{Bn256, Fr};
+        use crate::plonk::commitments::transcript::Blake2sTranscript;
+
+        let num_steps = 10;
+        let circuit = TestCircuit::<Bn256> {
+            num_steps,
+            _marker: std::marker::PhantomData
+        };
+
+        let mut prover = ProvingAssembly::<Bn256>::new();
+        circuit.synthesize(&mut prover).unwrap();
+        prover.finalize();
+
+        let size = prover.num_gates() + 1;
+
+        let srs = make_test_srs::<Bn256>(size * 2);
+
+        let omegas_bitreversed = BitReversedOmegas::<Fr>::new_for_domain_size(size);
+        let omegas_inv_bitreversed = <OmegasInvBitreversed::<Fr> as CTPrecomputations::<Fr>>::new_for_domain_size(size);
+
+        let (setup, setup_precomp) = setup_with_precomputations::<Bn256, _, _>(
+            &circuit,
+            &omegas_bitreversed,
+            &srs
+        ).unwrap();
+
+        let worker = Worker::new();
+
+        let proof = prover.prove_with_batched_opening::<_, _, Blake2sTranscript<Fr>>(
+            &setup_precomp,
+            &worker,
+            &omegas_bitreversed,
+            &omegas_inv_bitreversed,
+            &srs
+        ).unwrap();
+
+        let inputs = vec![test_circuit_output::<Bn256>(num_steps)];
+
+        assert!(verify_batched::<Bn256, Blake2sTranscript<Fr>>(&proof, &inputs, &setup, &srs).unwrap());
+        assert!(verify_batched::<Bn256, Blake2sTranscript<Fr>>(&proof, &inputs, &setup, &srs.trim(1)).unwrap());
+
+        let mut other_inputs = inputs.clone();
+        other_inputs[0].add_assign(&Fr::one());
+        assert!(!verify_batched::<Bn256, Blake2sTranscript<Fr>>(&proof, &other_inputs, &setup, &srs).unwrap());
+
+        // moving a part of t(z) from t_mid to t_low keeps the constraints at z satisfied,
+        // so only the batched opening catches the wrong values
+        let z = {
+            let mut transcript = PlonkTranscriptAdapter::new(Blake2sTranscript::<Fr>::new());
+            let claims = proof.claims();
+            check_claimed_values(&claims, &inputs, &setup, &mut transcript).unwrap().unwrap()
+        };
+
+        let mut bad_proof = proof.clone();
+        bad_proof.t_low_opening_value.add_assign(&z.pow([(setup.n + 1) as u64]));
+        bad_proof.t_mid_opening_value.sub_assign(&Fr::one());
+        {
+            let mut transcript = PlonkTranscriptAdapter::new(Blake2sTranscript::<Fr>::new());
+            assert_eq!(Some(z), check_claimed_values(&bad_proof.claims(), &inputs, &setup, &mut transcript).unwrap());
+        }
+        assert!(!verify_batched::<Bn256, Blake2sTranscript<Fr>>(&bad_proof, &inputs, &setup, &srs).unwrap());
+
+        let mut bad_proof = proof.clone();
+        bad_proof.z_1_shifted_opening_value.add_assign(&Fr::one());
+        assert!(!verify_batched::<Bn256, Blake2sTranscript<Fr>>(&bad_proof, &inputs, &setup, &srs).unwrap());
+
+        let mut bad_proof = proof;
+        bad_proof.batched_opening.quotient_commitment = bad_proof.batched_opening.opening_proof;
+        assert!(!verify_batched::<Bn256, Blake2sTranscript<Fr>>(&bad_proof, &inputs, &setup, &srs).unwrap());
+    }
+}
@@ -178,9 +178,6 @@ impl<E: Engine> GeneratorAssembly<E> {
         let zero = tmp.alloc(|| Ok(E::Fr::zero())).expect("should have no issues");
         tmp.enforce_constant(zero, E::Fr::zero()).expect("should have no issues");
 
-        let one = tmp.alloc(|| Ok(E::Fr::one())).expect("should have no issues");
-        tmp.enforce_constant(one, E::Fr::one()).expect("should have no issues");
-
         // match (zero, <Self as ConstraintSystem<E>>::ZERO) {
         //     (Variable(Index::Aux(1)), Variable(Index::Aux(1))) => {},
         //     _ => panic!("zero variable is incorrect")
@@ -196,6 +193,9 @@ impl<E: Engine> GeneratorAssembly<E> {
             _ => panic!("zero variable is incorrect")
         }
 
+        assert_eq!(tmp.num_inputs, 0);
+        assert_eq!(tmp.num_aux, 1);
+
         tmp
     }
 
@@ -479,11 +479,11 @@ use crate::plonk::transparent_engine::PartialTwoBitReductionField;
 
 use crate::plonk::commitments::transcript::*;
 
-pub fn setup_with_precomputations<E: Engine, C: Circuit<E>, CP: CTPrecomputations<E::Fr>, T: Transcript<E::Fr, Input = <FriSpecificBlake2sTree<E::Fr> as IopInstance<E::Fr>> :: Commitment> >(
+pub fn setup_with_precomputations<E: Engine, C: Circuit<E>, CP: CTPrecomputations<E::Fr>, T: Transcript<E::Fr, Input = I::Commitment>, I: CosetCombiningIopInstance<E::Fr> >(
     circuit: &C,
     params: &RedshiftParameters<E::Fr>,
     omegas_bitreversed: &CP,
-    ) -> Result<(RedshiftSetup<E::Fr, FriSpecificBlake2sTree<E::Fr>>, RedshiftSetupPrecomputation<E::Fr, FriSpecificBlake2sTree<E::Fr>>), SynthesisError> 
+    ) -> Result<(RedshiftSetup<E::Fr, I>, RedshiftSetupPrecomputation<E::Fr, I>), SynthesisError> 
         where E::Fr : PartialTwoBitReductionField 
 {
     let mut assembly = GeneratorAssembly::<E>::new();
@@ -498,15 +498,15 @@ pub fn setup_with_precomputations<E: Engine, C: Circuit<E>, CP: CTPrecomputation
 
     let (q_l, q_r, q_o, q_m, q_c, s_id, sigma_1, sigma_2, sigma_3) = assembly.output_setup_polynomials(&worker)?;
 
-    let q_l_commitment_data = ProvingAssembly::<E>::commit_single_poly(&q_l, omegas_bitreversed, &params, &worker)?;
-    let q_r_commitment_data = ProvingAssembly::<E>::commit_single_poly(&q_r, omegas_bitreversed, &params, &worker)?;
-    let q_o_commitment_data = ProvingAssembly::<E>::commit_single_poly(&q_o, omegas_bitreversed, &params, &worker)?;
-    let q_m_commitment_data = ProvingAssembly::<E>::commit_single_poly(&q_m, omegas_bitreversed, &params, &worker)?;
-    let q_c_commitment_data = ProvingAssembly::<E>::commit_single_poly(&q_c, omegas_bitreversed, &params, &worker)?;
-    let s_id_commitment_data = ProvingAssembly::<E>::commit_single_poly(&s_id, omegas_bitreversed, &params, &worker)?;
-    let sigma_1_commitment_data = ProvingAssembly::<E>::commit_single_poly(&sigma_1, omegas_bitreversed, &params, &worker)?;
-    let sigma_2_commitment_data = ProvingAssembly::<E>::commit_single_poly(&sigma_2, omegas_bitreversed, &params, &worker)?;
-    let sigma_3_commitment_data = ProvingAssembly::<E>::commit_single_poly(&sigma_3, omegas_bitreversed, &params, &worker)?;
+    let q_l_commitment_data = ProvingAssembly::<E>::commit_single_poly::<_, I>(&q_l, omegas_bitreversed, &params, &worker)?;
+    let q_r_commitment_data = ProvingAssembly::<E>::commit_single_poly::<_, I>(&q_r, omegas_bitreversed, &params, &worker)?;
+    let q_o_commitment_data = ProvingAssembly::<E>::commit_single_poly::<_, I>(&q_o, omegas_bitreversed, &params, &worker)?;
+    let q_m_commitment_data = ProvingAssembly::<E>::commit_single_poly::<_, I>(&q_m, omegas_bitreversed, &params, &worker)?;
+    let q_c_commitment_data = ProvingAssembly::<E>::commit_single_poly::<_, I>(&q_c, omegas_bitreversed, &params, &worker)?;
+    let s_id_commitment_data = ProvingAssembly::<E>::commit_single_poly::<_, I>(&s_id, omegas_bitreversed, &params, &worker)?;
+    let sigma_1_commitment_data = ProvingAssembly::<E>::commit_single_poly::<_, I>(&sigma_1, omegas_bitreversed, &params, &worker)?;
+    let sigma_2_commitment_data = ProvingAssembly::<E>::commit_single_poly::<_, I>(&sigma_2, omegas_bitreversed, &params, &worker)?;
+    let sigma_3_commitment_data = ProvingAssembly::<E>::commit_single_poly::<_, I>(&sigma_3, omegas_bitreversed, &params, &worker)?;
 
     transcript.commit_input(&q_l_commitment_data.oracle.get_commitment());
     transcript.commit_input(&q_r_commitment_data.oracle.get_commitment());
@@ -531,8 +531,9 @@ pub fn setup_with_precomputations<E: Engine, C: Circuit<E>, CP: CTPrecomputation
     let sigma_2_setup_value = sigma_2.evaluate_at(&worker, setup_point);
     let sigma_3_setup_value = sigma_3.evaluate_at(&worker, setup_point);
 
-    let setup = RedshiftSetup::<E::Fr, FriSpecificBlake2sTree<E::Fr>> {
+    let setup = RedshiftSetup::<E::Fr, I> {
         n: n,
+        num_inputs: assembly.num_inputs,
         q_l: q_l_commitment_data.oracle.get_commitment(),
         q_r: q_r_commitment_data.oracle.get_commitment(),
         q_o: q_o_commitment_data.oracle.get_commitment(),
@@ -542,58 +543,69 @@ pub fn setup_with_precomputations<E: Engine, C: Circuit<E>, CP: CTPrecomputation
         sigma_1: sigma_1_commitment_data.oracle.get_commitment(),
         sigma_2: sigma_2_commitment_data.oracle.get_commitment(),
         sigma_3: sigma_3_commitment_data.oracle.get_commitment(),
+
+        setup_point: setup_point,
+        q_l_setup_value: q_l_setup_value,
+        q_r_setup_value: q_r_setup_value,
+        q_o_setup_value: q_o_setup_value,
+        q_m_setup_value: q_m_setup_value,
+        q_c_setup_value: q_c_setup_value,
+        s_id_setup_value: s_id_setup_value,
+        sigma_1_setup_value: sigma_1_setup_value,
+        sigma_2_setup_value: sigma_2_setup_value,
+        sigma_3_setup_value: sigma_3_setup_value,
     };
 
-    let precomputation = RedshiftSetupPrecomputation::<E::Fr, FriSpecificBlake2sTree<E::Fr>> {
-        q_l_aux: SinglePolySetupData::<E::Fr, FriSpecificBlake2sTree<E::Fr>> {
+    let precomputation = RedshiftSetupPrecomputation::<E::Fr, I> {
+        q_l_aux: SinglePolySetupData::<E::Fr, I> {
             poly: q_l_commitment_data.poly,
             oracle: q_l_commitment_data.oracle,
             setup_point: setup_point,
             setup_value: q_l_setup_value,
         },
-        q_r_aux: SinglePolySetupData::<E::Fr, FriSpecificBlake2sTree<E::Fr>> {
+        q_r_aux: SinglePolySetupData::<E::Fr, I> {
             poly: q_r_commitment_data.poly,
             oracle: q_r_commitment_data.oracle,
             setup_point: setup_point,
             setup_value: q_r_setup_value,
         },
-        q_o_aux: SinglePolySetupData::<E::Fr, FriSpecificBlake2sTree<E::Fr>> {
+        q_o_aux: SinglePolySetupData::<E::Fr, I> {
             poly: q_o_commitment_data.poly,
             oracle: q_o_commitment_data.oracle,
             setup_point: setup_point,
             setup_value: q_o_setup_value,
         },
-        q_m_aux: SinglePolySetupData::<E::Fr, FriSpecificBlake2sTree<E::Fr>> {
+        q_m_aux: SinglePolySetupData::<E::Fr, I> {
             poly: q_m_commitment_data.poly,
             oracle: q_m_commitment_data.oracle,
             setup_point: setup_point,
             setup_value: q_m_setup_value,
         },
-        q_c_aux: SinglePolySetupData::<E::Fr, FriSpecificBlake2sTree<E::Fr>> {
+        q_c_aux: SinglePolySetupData::<E::Fr, I> {
             poly: q_c_commitment_data.poly,
             oracle: q_c_commitment_data.oracle,
             setup_point: setup_point,
             setup_value: q_c_setup_value,
         },
-        s_id_aux: SinglePolySetupData::<E::Fr, FriSpecificBlake2sTree<E::Fr>> {
+        s_id_aux: SinglePolySetupData::<E::Fr, I> {
             poly: s_id_commitment_data.poly,
             oracle: s_id_commitment_data.oracle,
             setup_point: setup_point,
             setup_value: s_id_setup_value,
         },
-        sigma_1_aux: SinglePolySetupData::<E::Fr, FriSpecificBlake2sTree<E::Fr>> {
+        sigma_1_aux: SinglePolySetupData::<E::Fr, I> {
             poly: sigma_1_commitment_data.poly,
             oracle: sigma_1_commitment_data.oracle,
             setup_point: setup_point,
             setup_value: sigma_1_setup_value,
         },
-        sigma_2_aux: SinglePolySetupData::<E::Fr, FriSpecificBlake2sTree<E::Fr>> {
+        sigma_2_aux: SinglePolySetupData::<E::Fr, I> {
             poly: sigma_2_commitment_data.poly,
             oracle: sigma_2_commitment_data.oracle,
             setup_point: setup_point,
             setup_value: sigma_2_setup_value,
         },
-        sigma_3_aux: SinglePolySetupData::<E::Fr, FriSpecificBlake2sTree<E::Fr>> {
+        sigma_3_aux: SinglePolySetupData::<E::Fr, I> {
             poly: sigma_3_commitment_data.poly,
             oracle: sigma_3_commitment_data.oracle,
             setup_point: setup_point,
@@ -1,2 +1,3 @@
-mod prover;
-mod generator;
\ No newline at end of file
+pub mod prover;
+pub mod generator;
+pub mod verifier;
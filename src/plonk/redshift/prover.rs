@@ -16,7 +16,7 @@ use crate::plonk::utils::*;
 use crate::plonk::polynomials::*;
 
 #[derive(Debug)]
-pub(crate) struct ProvingAssembly<E: Engine> {
+pub struct ProvingAssembly<E: Engine> {
     m: usize,
     n: usize,
     input_gates: Vec<Gate<E::Fr>>,
@@ -156,7 +156,7 @@ impl<E: Engine> ConstraintSystem<E> for ProvingAssembly<E> {
 }
 
 impl<E: Engine> ProvingAssembly<E> {
-    pub(crate) fn new() -> Self {
+    pub fn new() -> Self {
         let mut tmp = Self {
             n: 0,
             m: 0,
@@ -495,13 +495,13 @@ impl<E: Engine> ProvingAssembly<E> {
         Ok((q_l, q_r, q_o, q_m, q_c, s_id, sigma_1, sigma_2, sigma_3))
     }
 
-    pub(crate) fn num_gates(&self) -> usize {
+    pub fn num_gates(&self) -> usize {
         assert!(self.is_finalized);
 
         self.input_gates.len() + self.aux_gates.len()
     }
 
-    fn finalize(&mut self) {
+    pub fn finalize(&mut self) {
         if self.is_finalized {
             return;
         }
@@ -599,38 +599,62 @@ impl<E: Engine> ProvingAssembly<E> {
 
         Ok(poly.ifft(&worker))
     }
+
+    // input gates go first and enforce q_l*a = 0, so the public inputs polynomial
+    // takes values -input_i at omega^i for i < num_inputs and zero elsewhere
+    fn make_public_inputs_values(&self, poly_size: usize) -> Result<Polynomial::<E::Fr, Values>, SynthesisError> {
+        assert!(poly_size.is_power_of_two());
+        assert!(self.input_assingments.len() < poly_size);
+
+        let mut values = vec![E::Fr::zero(); poly_size];
+        for (value, input) in values.iter_mut().zip(self.input_assingments.iter()) {
+            let mut tmp = *input;
+            tmp.negate();
+            *value = tmp;
+        }
+
+        Polynomial::<E::Fr, Values>::from_values(values)
+    }
 }
 
-// pub struct RedshiftProof<F: PrimeField, I: IopInstance<F>, FRI: FriIop<F, IopType = I>>{
-//     pub a_opening_value: F,
-//     pub b_opening_value: F,
-//     pub c_opening_value: F,
-//     pub q_l_opening_value: F,
-//     pub q_r_opening_value: F,
-//     pub q_o_opening_value: F,
-//     pub q_m_opening_value: F,
-//     pub q_c_opening_value: F,
-//     pub s_id_opening_value: F,
-//     pub sigma_1_opening_value: F,
-//     pub sigma_2_opening_value: F,
-//     pub sigma_3_opening_value: F,
-//     pub z_1_unshifted_opening_value: F,
-//     pub z_2_unshifted_opening_value: F,
-//     pub z_1_shifted_opening_value: F,
-//     pub z_2_shifted_opening_value: F,
-//     pub t_low_opening_value: F,
-//     pub t_mid_opening_value: F,
-//     pub t_high_opening_value: F,
-//     pub a_commitment: I::Commitment,
-//     pub b_commitment: I::Commitment,
-//     pub c_commitment: I::Commitment,
-//     pub z_1_commitment: I::Commitment,
-//     pub z_2_commitment: I::Commitment,
-//     pub t_low_commitment: I::Commitment,
-//     pub t_mid_commitment: I::Commitment,
-//     pub t_high_commitment: I::Commitment,
-//     pub openings_proof: FRI::Proof,
-// }
+#[derive(PartialEq, Eq)]
+pub struct RedshiftProof<F: PrimeField, I: IopInstance<F>>{
+    pub a_opening_value: F,
+    pub b_opening_value: F,
+    pub c_opening_value: F,
+    pub q_l_opening_value: F,
+    pub q_r_opening_value: F,
+    pub q_o_opening_value: F,
+    pub q_m_opening_value: F,
+    pub q_c_opening_value: F,
+    pub s_id_opening_value: F,
+    pub sigma_1_opening_value: F,
+    pub sigma_2_opening_value: F,
+    pub sigma_3_opening_value: F,
+    pub z_1_unshifted_opening_value: F,
+    pub z_2_unshifted_opening_value: F,
+    pub z_1_shifted_opening_value: F,
+    pub z_2_shifted_opening_value: F,
+    pub t_low_opening_value: F,
+    pub t_mid_opening_value: F,
+    pub t_high_opening_value: F,
+    pub a_commitment: I::Commitment,
+    pub b_commitment: I::Commitment,
+    pub c_commitment: I::Commitment,
+    pub z_1_commitment: I::Commitment,
+    pub z_2_commitment: I::Commitment,
+    pub t_low_commitment: I::Commitment,
+    pub t_mid_commitment: I::Commitment,
+    pub t_high_commitment: I::Commitment,
+
+    // for every FRI query there are queries to the original oracles at the same coset,
+    // in order a, b, c, z_1, z_2, t_low, t_mid, t_high
+    pub witness_queries: Vec<Vec<I::Query>>,
+    // in order q_l, q_r, q_o, q_m, q_c, s_id, sigma_1, sigma_2, sigma_3
+    pub setup_queries: Vec<Vec<I::Query>>,
+    pub openings_proof: FRIProof<F, I>,
+    pub proof_of_work_nonce: u64,
+}
 
 use crate::plonk::fft::cooley_tukey_ntt::CTPrecomputations;
 use crate::plonk::commitments::transparent::fri::coset_combining_fri::*;
@@ -638,10 +662,18 @@ use crate::plonk::commitments::transparent::fri::coset_combining_fri::fri::*;
 use crate::plonk::commitments::transparent::iop_compiler::*;
 use crate::plonk::commitments::transparent::iop_compiler::coset_combining_blake2s_tree::*;
 use crate::plonk::transparent_engine::PartialTwoBitReductionField;
+use crate::plonk::commitments::transparent::bytes_to_challenge_index;
+use crate::plonk::commitments::transparent::utils::log2_floor;
+use crate::plonk::commitments::transparent::fri::coset_combining_fri::grinding::*;
+
+use byteorder::{BigEndian, WriteBytesExt, ReadBytesExt};
+
+use std::io::{self, Read, Write};
 
 #[derive(Debug)]
 pub struct RedshiftSetup<F: PrimeField, I: IopInstance<F>>{
     pub n: usize,
+    pub num_inputs: usize,
     pub q_l: I::Commitment,
     pub q_r: I::Commitment,
     pub q_o: I::Commitment,
@@ -651,6 +683,19 @@ pub struct RedshiftSetup<F: PrimeField, I: IopInstance<F>>{
     pub sigma_1: I::Commitment,
     pub sigma_2: I::Commitment,
     pub sigma_3: I::Commitment,
+
+    // setup polynomials are additionally opened at the setup point
+    // and verifier needs to know those values
+    pub setup_point: F,
+    pub q_l_setup_value: F,
+    pub q_r_setup_value: F,
+    pub q_o_setup_value: F,
+    pub q_m_setup_value: F,
+    pub q_c_setup_value: F,
+    pub s_id_setup_value: F,
+    pub sigma_1_setup_value: F,
+    pub sigma_2_setup_value: F,
+    pub sigma_3_setup_value: F,
 }
 
 pub struct SinglePolySetupData<F: PrimeField, I: IopInstance<F>>{
@@ -678,12 +723,262 @@ pub struct RedshiftSetupPrecomputation<F: PrimeField, I: IopInstance<F>>{
     pub sigma_3_aux: SinglePolySetupData<F, I>,
 }
 
+impl<F: PrimeField, I: CosetCombiningIopInstance<F>> RedshiftSetup<F, I> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        write_version_header(&mut writer)?;
+
+        writer.write_u64::<BigEndian>(self.n as u64)?;
+        writer.write_u64::<BigEndian>(self.num_inputs as u64)?;
+
+        for commitment in [
+            &self.q_l,
+            &self.q_r,
+            &self.q_o,
+            &self.q_m,
+            &self.q_c,
+            &self.s_id,
+            &self.sigma_1,
+            &self.sigma_2,
+            &self.sigma_3,
+        ].iter() {
+            I::write_commitment(commitment, &mut writer)?;
+        }
+
+        for value in [
+            &self.setup_point,
+            &self.q_l_setup_value,
+            &self.q_r_setup_value,
+            &self.q_o_setup_value,
+            &self.q_m_setup_value,
+            &self.q_c_setup_value,
+            &self.s_id_setup_value,
+            &self.sigma_1_setup_value,
+            &self.sigma_2_setup_value,
+            &self.sigma_3_setup_value,
+        ].iter() {
+            write_field_element(*value, &mut writer)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        read_version_header(&mut reader)?;
+
+        let n = reader.read_u64::<BigEndian>()? as usize;
+        let num_inputs = reader.read_u64::<BigEndian>()? as usize;
+
+        let q_l = I::read_commitment(&mut reader)?;
+        let q_r = I::read_commitment(&mut reader)?;
+        let q_o = I::read_commitment(&mut reader)?;
+        let q_m = I::read_commitment(&mut reader)?;
+        let q_c = I::read_commitment(&mut reader)?;
+        let s_id = I::read_commitment(&mut reader)?;
+        let sigma_1 = I::read_commitment(&mut reader)?;
+        let sigma_2 = I::read_commitment(&mut reader)?;
+        let sigma_3 = I::read_commitment(&mut reader)?;
+
+        let setup_point = read_field_element(&mut reader)?;
+        let q_l_setup_value = read_field_element(&mut reader)?;
+        let q_r_setup_value = read_field_element(&mut reader)?;
+        let q_o_setup_value = read_field_element(&mut reader)?;
+        let q_m_setup_value = read_field_element(&mut reader)?;
+        let q_c_setup_value = read_field_element(&mut reader)?;
+        let s_id_setup_value = read_field_element(&mut reader)?;
+        let sigma_1_setup_value = read_field_element(&mut reader)?;
+        let sigma_2_setup_value = read_field_element(&mut reader)?;
+        let sigma_3_setup_value = read_field_element(&mut reader)?;
+
+        Ok(Self {
+            n,
+            num_inputs,
+            q_l,
+            q_r,
+            q_o,
+            q_m,
+            q_c,
+            s_id,
+            sigma_1,
+            sigma_2,
+            sigma_3,
+            setup_point,
+            q_l_setup_value,
+            q_r_setup_value,
+            q_o_setup_value,
+            q_m_setup_value,
+            q_c_setup_value,
+            s_id_setup_value,
+            sigma_1_setup_value,
+            sigma_2_setup_value,
+            sigma_3_setup_value,
+        })
+    }
+}
+
+impl<F: PrimeField, I: CosetCombiningIopInstance<F>> SinglePolySetupData<F, I> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        write_polynomial_values(&self.poly, &mut writer)?;
+        self.oracle.write(&mut writer)?;
+        write_field_element(&self.setup_point, &mut writer)?;
+        write_field_element(&self.setup_value, &mut writer)?;
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let poly = read_polynomial_values(&mut reader)?;
+        let oracle = I::read(&mut reader)?;
+        if oracle.size() != poly.size() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "oracle size does not match the polynomial size"));
+        }
+
+        let setup_point = read_field_element(&mut reader)?;
+        let setup_value = read_field_element(&mut reader)?;
+
+        Ok(Self {
+            poly,
+            oracle,
+            setup_point,
+            setup_value,
+        })
+    }
+}
+
+impl<F: PrimeField, I: CosetCombiningIopInstance<F>> RedshiftSetupPrecomputation<F, I> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        write_version_header(&mut writer)?;
+
+        for data in [
+            &self.q_l_aux,
+            &self.q_r_aux,
+            &self.q_o_aux,
+            &self.q_m_aux,
+            &self.q_c_aux,
+            &self.s_id_aux,
+            &self.sigma_1_aux,
+            &self.sigma_2_aux,
+            &self.sigma_3_aux,
+        ].iter() {
+            data.write(&mut writer)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        read_version_header(&mut reader)?;
+
+        let q_l_aux = SinglePolySetupData::read(&mut reader)?;
+        let q_r_aux = SinglePolySetupData::read(&mut reader)?;
+        let q_o_aux = SinglePolySetupData::read(&mut reader)?;
+        let q_m_aux = SinglePolySetupData::read(&mut reader)?;
+        let q_c_aux = SinglePolySetupData::read(&mut reader)?;
+        let s_id_aux = SinglePolySetupData::read(&mut reader)?;
+        let sigma_1_aux = SinglePolySetupData::read(&mut reader)?;
+        let sigma_2_aux = SinglePolySetupData::read(&mut reader)?;
+        let sigma_3_aux = SinglePolySetupData::read(&mut reader)?;
+
+        Ok(Self {
+            q_l_aux,
+            q_r_aux,
+            q_o_aux,
+            q_m_aux,
+            q_c_aux,
+            s_id_aux,
+            sigma_1_aux,
+            sigma_2_aux,
+            sigma_3_aux,
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RedshiftParameters<F: PrimeField>{
     pub lde_factor: usize,
     pub num_queries: usize,
     pub output_coeffs_at_degree_plus_one: usize,
     pub coset_params: CosetParams<F>,
+    // number of zero bits required from the challenge drawn after the proof of work nonce,
+    // zero disables grinding
+    pub proof_of_work_bits: u32,
+}
+
+/// Security of FRI openings for some parameters and circuit size. Conjectured bits follow the
+/// common conjecture that every query gives log2(lde_factor) bits, proven bits use the unique
+/// decoding radius, so every query gives -log2((1 + rate) / 2) bits. Both are capped by
+/// the field size over the size of the evaluation domain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RedshiftSoundness {
+    pub conjectured_bits: u32,
+    pub proven_bits: u32,
+}
+
+impl<F: PrimeField> RedshiftParameters<F> {
+    const DEFAULT_PROOF_OF_WORK_BITS: u32 = 16;
+
+    /// Picks the number of queries so that conjectured security is at least `security_bits`,
+    /// with up to 16 bits of it coming from grinding
+    pub fn new_for_security_level(
+        security_bits: u32,
+        lde_factor: usize,
+        output_coeffs_at_degree_plus_one: usize,
+        coset_params: CosetParams<F>
+    ) -> Self {
+        assert!(lde_factor.is_power_of_two() && lde_factor > 1);
+        let bits_per_query = log2_floor(lde_factor);
+
+        let proof_of_work_bits = std::cmp::min(Self::DEFAULT_PROOF_OF_WORK_BITS, security_bits);
+        let bits_from_queries = security_bits - proof_of_work_bits;
+        let num_queries = ((bits_from_queries + bits_per_query - 1) / bits_per_query) as usize;
+
+        Self {
+            lde_factor,
+            num_queries: std::cmp::max(num_queries, 1),
+            output_coeffs_at_degree_plus_one,
+            coset_params,
+            proof_of_work_bits,
+        }
+    }
+
+    pub fn soundness(&self, domain_size: usize) -> RedshiftSoundness {
+        assert!(domain_size.is_power_of_two());
+        assert!(self.lde_factor.is_power_of_two());
+
+        let lde_size = domain_size * self.lde_factor;
+        let field_bits = F::CAPACITY.saturating_sub(log2_floor(lde_size));
+
+        let conjectured_bits = (self.num_queries as u32) * log2_floor(self.lde_factor) + self.proof_of_work_bits;
+
+        let rate = 1f64 / (self.lde_factor as f64);
+        let bits_per_query = -((1f64 + rate) / 2f64).log2();
+        let proven_bits = ((self.num_queries as f64) * bits_per_query).floor() as u32 + self.proof_of_work_bits;
+
+        RedshiftSoundness {
+            conjectured_bits: std::cmp::min(conjectured_bits, field_bits),
+            proven_bits: std::cmp::min(proven_bits, field_bits),
+        }
+    }
 }
 
 struct WitnessOpeningRequest<'a, F: PrimeField> {
@@ -701,12 +996,12 @@ struct SetupOpeningRequest<'a, F: PrimeField> {
 }
 
 impl<E: Engine> ProvingAssembly<E> where E::Fr : PartialTwoBitReductionField {
-    pub(crate) fn commit_single_poly<CP: CTPrecomputations<E::Fr>>(
+    pub(crate) fn commit_single_poly<CP: CTPrecomputations<E::Fr>, I: CosetCombiningIopInstance<E::Fr>>(
         poly: &Polynomial<E::Fr, Coefficients>, 
         omegas_bitreversed: &CP,
         params: &RedshiftParameters<E::Fr>,
         worker: &Worker
-    ) -> Result<SinglePolyCommitmentData<E::Fr, FriSpecificBlake2sTree<E::Fr>>, SynthesisError> {
+    ) -> Result<SinglePolyCommitmentData<E::Fr, I>, SynthesisError> {
         let lde = poly.clone().bitreversed_lde_using_bitreversed_ntt_with_partial_reduction(
             worker, 
             params.lde_factor, 
@@ -714,11 +1009,9 @@ impl<E: Engine> ProvingAssembly<E> where E::Fr : PartialTwoBitReductionField {
             &E::Fr::multiplicative_generator()
         )?;
 
-        let oracle_params = FriSpecificBlake2sTreeParams {
-            values_per_leaf: (1 << params.coset_params.cosets_schedule[0])
-        };
+        let oracle_params = I::params_for_values_per_leaf(1 << params.coset_params.cosets_schedule[0]);
 
-        let oracle = FriSpecificBlake2sTree::create(&lde.as_ref(), &oracle_params);
+        let oracle = I::create(&lde.as_ref(), &oracle_params);
 
         Ok(SinglePolyCommitmentData::<E::Fr, _> {
             poly: lde,
@@ -757,7 +1050,7 @@ impl<E: Engine> ProvingAssembly<E> where E::Fr : PartialTwoBitReductionField {
     //     q
     // }
 
-    fn multiopening<P: FriPrecomputations<E::Fr>, T: Transcript<E::Fr, Input = <FriSpecificBlake2sTree<E::Fr> as IopInstance<E::Fr>> :: Commitment> >
+    fn multiopening<P: FriPrecomputations<E::Fr>, I: CosetCombiningIopInstance<E::Fr>, T: Transcript<E::Fr, Input = I::Commitment> >
         ( 
             witness_opening_requests: Vec<WitnessOpeningRequest<E::Fr>>,
             setup_opening_requests: Vec<SetupOpeningRequest<E::Fr>>,
@@ -765,7 +1058,7 @@ impl<E: Engine> ProvingAssembly<E> where E::Fr : PartialTwoBitReductionField {
             params: &RedshiftParameters<E::Fr>,
             worker: &Worker,
             transcript: &mut T
-        ) -> Result<(), SynthesisError> {
+        ) -> Result<FRIProofPrototype<E::Fr, I>, SynthesisError> {
             let required_divisor_size = witness_opening_requests[0].polynomials[0].size();
 
             let mut final_aggregate = Polynomial::from_values(vec![E::Fr::zero(); required_divisor_size])?;
@@ -887,7 +1180,7 @@ impl<E: Engine> ProvingAssembly<E> where E::Fr : PartialTwoBitReductionField {
                 }
             }
 
-            let fri_proto = CosetCombiningFriIop::proof_from_lde(
+            let fri_proto = CosetCombiningFriIop::<E::Fr, I>::proof_from_lde(
                 &final_aggregate,
                 params.lde_factor,
                 params.output_coeffs_at_degree_plus_one,
@@ -897,18 +1190,20 @@ impl<E: Engine> ProvingAssembly<E> where E::Fr : PartialTwoBitReductionField {
                 &params.coset_params
             )?;
 
-            Ok(())
+            Ok(fri_proto)
     }
 
-    fn prove_with_setup_precomputed<CP: CTPrecomputations<E::Fr>, CPI: CTPrecomputations<E::Fr>, FP: FriPrecomputations<E::Fr>, T: Transcript<E::Fr, Input = <FriSpecificBlake2sTree<E::Fr> as IopInstance<E::Fr>> :: Commitment> >(
+    /// Produces a proof using precomputed setup data. FRI precomputations must be
+    /// the inverses of the LDE coset, e.g. `CosetOmegasInvBitreversed`.
+    pub fn prove_with_setup_precomputed<CP: CTPrecomputations<E::Fr>, CPI: CTPrecomputations<E::Fr>, FP: FriPrecomputations<E::Fr>, T: Transcript<E::Fr, Input = I::Commitment>, I: CosetCombiningIopInstance<E::Fr> >(
         self,
-        setup_precomp: &RedshiftSetupPrecomputation<E::Fr, FriSpecificBlake2sTree<E::Fr>>,
+        setup_precomp: &RedshiftSetupPrecomputation<E::Fr, I>,
         params: &RedshiftParameters<E::Fr>,
         worker: &Worker,
         omegas_bitreversed: &CP,
         omegas_inv_bitreversed: &CPI,
         bitreversed_omegas_for_fri: &FP      
-    ) -> Result<(), SynthesisError> {
+    ) -> Result<RedshiftProof<E::Fr, I>, SynthesisError> {
         assert!(self.is_finalized);
 
         let mut transcript = T::new();
@@ -932,15 +1227,20 @@ impl<E: Engine> ProvingAssembly<E> where E::Fr : PartialTwoBitReductionField {
 
         // polynomials inside of these is are values in cosets
 
-        let a_commitment_data = Self::commit_single_poly(&a_poly, omegas_bitreversed, &params, &worker)?;
-        let b_commitment_data = Self::commit_single_poly(&b_poly, omegas_bitreversed, &params, &worker)?;
-        let c_commitment_data = Self::commit_single_poly(&c_poly, omegas_bitreversed, &params, &worker)?;
+        let a_commitment_data = Self::commit_single_poly::<_, I>(&a_poly, omegas_bitreversed, &params, &worker)?;
+        let b_commitment_data = Self::commit_single_poly::<_, I>(&b_poly, omegas_bitreversed, &params, &worker)?;
+        let c_commitment_data = Self::commit_single_poly::<_, I>(&c_poly, omegas_bitreversed, &params, &worker)?;
 
         transcript.commit_input(&a_commitment_data.oracle.get_commitment());
         transcript.commit_input(&b_commitment_data.oracle.get_commitment());
         transcript.commit_input(&c_commitment_data.oracle.get_commitment());
 
-        // TODO: Add public inputs
+        for input in self.input_assingments.iter() {
+            transcript.commit_field_element(input);
+        }
+
+        let public_inputs_poly = self.make_public_inputs_values(required_domain_size)?
+            .ifft_using_bitreversed_ntt_with_partial_reduction(&worker, omegas_inv_bitreversed, &E::Fr::one())?;
 
         let beta = transcript.get_challenge();
         let gamma = transcript.get_challenge();
@@ -1044,8 +1344,8 @@ impl<E: Engine> ProvingAssembly<E> where E::Fr : PartialTwoBitReductionField {
 
         // polynomials inside of these is are values in cosets
 
-        let z_1_commitment_data = Self::commit_single_poly(&z_1, omegas_bitreversed, &params, &worker)?;
-        let z_2_commitment_data = Self::commit_single_poly(&z_2, omegas_bitreversed, &params, &worker)?;
+        let z_1_commitment_data = Self::commit_single_poly::<_, I>(&z_1, omegas_bitreversed, &params, &worker)?;
+        let z_2_commitment_data = Self::commit_single_poly::<_, I>(&z_2, omegas_bitreversed, &params, &worker)?;
 
         transcript.commit_input(&z_1_commitment_data.oracle.get_commitment());
         transcript.commit_input(&z_2_commitment_data.oracle.get_commitment());
@@ -1113,6 +1413,15 @@ impl<E: Engine> ProvingAssembly<E> where E::Fr : PartialTwoBitReductionField {
             t_1.add_assign(&worker, &q_m_by_ab);
             drop(q_m_by_ab);
 
+            let public_inputs_coset_lde_bitreversed = public_inputs_poly.clone().bitreversed_lde_using_bitreversed_ntt_with_partial_reduction(
+                &worker, 
+                4, 
+                omegas_bitreversed, 
+                &E::Fr::multiplicative_generator()
+            )?;
+            t_1.add_assign(&worker, &public_inputs_coset_lde_bitreversed);
+            drop(public_inputs_coset_lde_bitreversed);
+
             vanishing_poly_inverse_bitreversed.scale(&worker, alpha);
 
             t_1.mul_assign(&worker, &vanishing_poly_inverse_bitreversed);
@@ -1306,9 +1615,9 @@ impl<E: Engine> ProvingAssembly<E> where E::Fr : PartialTwoBitReductionField {
         let t_poly_mid = t_poly_parts.pop().expect("mid exists");
         let t_poly_low = t_poly_parts.pop().expect("low exists");
 
-        let t_poly_high_commitment_data = Self::commit_single_poly(&t_poly_high, omegas_bitreversed, &params, &worker)?;
-        let t_poly_mid_commitment_data = Self::commit_single_poly(&t_poly_mid, omegas_bitreversed, &params, &worker)?;
-        let t_poly_low_commitment_data = Self::commit_single_poly(&t_poly_low, omegas_bitreversed, &params, &worker)?;
+        let t_poly_high_commitment_data = Self::commit_single_poly::<_, I>(&t_poly_high, omegas_bitreversed, &params, &worker)?;
+        let t_poly_mid_commitment_data = Self::commit_single_poly::<_, I>(&t_poly_mid, omegas_bitreversed, &params, &worker)?;
+        let t_poly_low_commitment_data = Self::commit_single_poly::<_, I>(&t_poly_low, omegas_bitreversed, &params, &worker)?;
 
         transcript.commit_input(&t_poly_low_commitment_data.oracle.get_commitment());
         transcript.commit_input(&t_poly_mid_commitment_data.oracle.get_commitment());
@@ -1343,6 +1652,8 @@ impl<E: Engine> ProvingAssembly<E> where E::Fr : PartialTwoBitReductionField {
         let t_mid_at_z = t_poly_mid.evaluate_at(&worker, z);
         let t_high_at_z = t_poly_high.evaluate_at(&worker, z);
 
+        let public_inputs_at_z = public_inputs_poly.evaluate_at(&worker, z);
+
         let l_0_at_z = l_0.evaluate_at(&worker, z);
         let l_n_minus_one_at_z = l_n_minus_one.evaluate_at(&worker, z);
 
@@ -1382,6 +1693,8 @@ impl<E: Engine> ProvingAssembly<E> where E::Fr : PartialTwoBitReductionField {
             let mut t_1 = {
                 let mut res = q_c_at_z;
 
+                res.add_assign(&public_inputs_at_z);
+
                 let mut tmp = q_l_at_z;
                 tmp.mul_assign(&a_at_z);
                 res.add_assign(&tmp);
@@ -1586,7 +1899,7 @@ impl<E: Engine> ProvingAssembly<E> where E::Fr : PartialTwoBitReductionField {
             ]
         };
 
-        let _ = Self::multiopening(vec![witness_opening_request_at_z, witness_opening_request_at_z_omega], 
+        let fri_proto = Self::multiopening(vec![witness_opening_request_at_z, witness_opening_request_at_z_omega], 
             vec![setup_opening_request], 
             bitreversed_omegas_for_fri, 
             &params, 
@@ -1594,41 +1907,95 @@ impl<E: Engine> ProvingAssembly<E> where E::Fr : PartialTwoBitReductionField {
             &mut transcript
         )?;
 
-        Ok(())
+        for c in fri_proto.get_final_coefficients().iter() {
+            transcript.commit_field_element(&c);
+        }
 
-        
-        // let proof = PlonkChunkedNonhomomorphicProof::<E, S> {
-        //     a_opening_value: a_at_z,
-        //     b_opening_value: b_at_z,
-        //     c_opening_value: c_at_z,
-        //     q_l_opening_value: q_l_at_z,
-        //     q_r_opening_value: q_r_at_z,
-        //     q_o_opening_value: q_o_at_z,
-        //     q_m_opening_value: q_m_at_z,
-        //     q_c_opening_value: q_c_at_z,
-        //     s_id_opening_value: s_id_at_z,
-        //     sigma_1_opening_value: sigma_1_at_z,
-        //     sigma_2_opening_value: sigma_2_at_z,
-        //     sigma_3_opening_value: sigma_3_at_z,
-        //     z_1_unshifted_opening_value: z_1_at_z,
-        //     z_2_unshifted_opening_value: z_2_at_z,
-        //     z_1_shifted_opening_value: z_1_shifted_at_z,
-        //     z_2_shifted_opening_value: z_2_shifted_at_z,
-        //     t_low_opening_value: t_low_at_z,
-        //     t_mid_opening_value: t_mid_at_z,
-        //     t_high_opening_value: t_high_at_z,
-        //     a_commitment: a_commitment,
-        //     b_commitment: b_commitment,
-        //     c_commitment: c_commitment,
-        //     z_1_commitment: z_1_commitment,
-        //     z_2_commitment: z_2_commitment,
-        //     t_low_commitment: t_low_commitment,
-        //     t_mid_commitment: t_mid_commitment,
-        //     t_high_commitment: t_high_commitment,
-        //     openings_proof: multiopen_proof,
-        // };
-
-        // Ok(proof)
+        let lde_size = a_commitment_data.poly.size();
+
+        let proof_of_work_nonce = grind(&transcript, params.proof_of_work_bits);
+        let valid = absorb_proof_of_work(&mut transcript, proof_of_work_nonce, params.proof_of_work_bits);
+        debug_assert!(valid);
+
+        let mut domain_indexes = vec![];
+        for _ in 0..params.num_queries {
+            let domain_idx = bytes_to_challenge_index(transcript.get_challenge_bytes(), lde_size);
+            domain_indexes.push(domain_idx);
+        }
+
+        let openings_proof = CosetCombiningFriIop::<E::Fr, I>::prototype_into_proof(fri_proto, domain_indexes.clone(), &params.coset_params)?;
+
+        let values_per_leaf = 1 << params.coset_params.cosets_schedule[0];
+
+        let mut witness_queries = vec![];
+        let mut setup_queries = vec![];
+
+        for idx in domain_indexes.into_iter() {
+            let coset = CosetCombiningFriIop::<E::Fr, I>::get_coset_for_natural_index(idx, lde_size, values_per_leaf);
+
+            let queries: Vec<_> = [
+                &a_commitment_data,
+                &b_commitment_data,
+                &c_commitment_data,
+                &z_1_commitment_data,
+                &z_2_commitment_data,
+                &t_poly_low_commitment_data,
+                &t_poly_mid_commitment_data,
+                &t_poly_high_commitment_data,
+            ].iter().map(|data| data.oracle.produce_query(coset.clone(), data.poly.as_ref())).collect();
+
+            witness_queries.push(queries);
+
+            let queries: Vec<_> = [
+                &setup_precomp.q_l_aux,
+                &setup_precomp.q_r_aux,
+                &setup_precomp.q_o_aux,
+                &setup_precomp.q_m_aux,
+                &setup_precomp.q_c_aux,
+                &setup_precomp.s_id_aux,
+                &setup_precomp.sigma_1_aux,
+                &setup_precomp.sigma_2_aux,
+                &setup_precomp.sigma_3_aux,
+            ].iter().map(|data| data.oracle.produce_query(coset.clone(), data.poly.as_ref())).collect();
+
+            setup_queries.push(queries);
+        }
+
+        let proof = RedshiftProof::<E::Fr, I> {
+            a_opening_value: a_at_z,
+            b_opening_value: b_at_z,
+            c_opening_value: c_at_z,
+            q_l_opening_value: q_l_at_z,
+            q_r_opening_value: q_r_at_z,
+            q_o_opening_value: q_o_at_z,
+            q_m_opening_value: q_m_at_z,
+            q_c_opening_value: q_c_at_z,
+            s_id_opening_value: s_id_at_z,
+            sigma_1_opening_value: sigma_1_at_z,
+            sigma_2_opening_value: sigma_2_at_z,
+            sigma_3_opening_value: sigma_3_at_z,
+            z_1_unshifted_opening_value: z_1_at_z,
+            z_2_unshifted_opening_value: z_2_at_z,
+            z_1_shifted_opening_value: z_1_shifted_at_z,
+            z_2_shifted_opening_value: z_2_shifted_at_z,
+            t_low_opening_value: t_low_at_z,
+            t_mid_opening_value: t_mid_at_z,
+            t_high_opening_value: t_high_at_z,
+            a_commitment: a_commitment_data.oracle.get_commitment(),
+            b_commitment: b_commitment_data.oracle.get_commitment(),
+            c_commitment: c_commitment_data.oracle.get_commitment(),
+            z_1_commitment: z_1_commitment_data.oracle.get_commitment(),
+            z_2_commitment: z_2_commitment_data.oracle.get_commitment(),
+            t_low_commitment: t_poly_low_commitment_data.oracle.get_commitment(),
+            t_mid_commitment: t_poly_mid_commitment_data.oracle.get_commitment(),
+            t_high_commitment: t_poly_high_commitment_data.oracle.get_commitment(),
+            witness_queries,
+            setup_queries,
+            openings_proof,
+            proof_of_work_nonce,
+        };
+
+        Ok(proof)
     }
 }
 
@@ -1752,7 +2119,8 @@ mod test {
                 lde_factor: rate,
                 num_queries: 4,
                 output_coeffs_at_degree_plus_one: 1,
-                coset_params: coset_params
+                coset_params: coset_params,
+                proof_of_work_bits: 0
             };
 
             let circuit = BenchmarkCircuit::<Transparent252> {
@@ -1764,7 +2132,7 @@ mod test {
             let omegas_inv_bitreversed = <OmegasInvBitreversed::<Fr> as CTPrecomputations::<Fr>>::new_for_domain_size(size.next_power_of_two());
             let omegas_inv_bitreversed_for_fri = <CosetOmegasInvBitreversed::<Fr> as FriPrecomputations::<Fr>>::new_for_domain_size(size.next_power_of_two() * rate);
 
-            let (_, setup_precomp) = setup_with_precomputations::<Transparent252, _, _, Transcr>(
+            let (_, setup_precomp) = setup_with_precomputations::<Transparent252, _, _, Transcr, FriSpecificBlake2sTree<Fr>>(
                 &circuit,
                 &params,
                 &omegas_bitreversed
@@ -1778,7 +2146,7 @@ mod test {
 
             let start = Instant::now();
 
-            let _ = prover.prove_with_setup_precomputed::<_, _, _, Transcr>(
+            let _ = prover.prove_with_setup_precomputed::<_, _, _, Transcr, FriSpecificBlake2sTree<Fr>>(
                 &setup_precomp, 
                 &params, 
                 &worker, 
@@ -0,0 +1,811 @@
+use crate::pairing::ff::{Field, PrimeField};
+
+use crate::{SynthesisError};
+
+use crate::plonk::domains::*;
+use crate::plonk::fft::cooley_tukey_ntt::{bitreverse, log2_floor};
+use crate::plonk::commitments::transcript::*;
+use crate::plonk::utils::{evaluate_inverse_vanishing_poly, evaluate_lagrange_poly};
+use crate::plonk::commitments::transparent::bytes_to_challenge_index;
+use crate::plonk::commitments::transparent::fri::coset_combining_fri::*;
+use crate::plonk::commitments::transparent::fri::coset_combining_fri::fri::*;
+use crate::plonk::commitments::transparent::fri::coset_combining_fri::grinding::absorb_proof_of_work;
+use crate::plonk::commitments::transparent::iop_compiler::*;
+use crate::plonk::commitments::transparent::iop_compiler::coset_combining_blake2s_tree::*;
+
+use super::prover::{RedshiftProof, RedshiftSetup, RedshiftParameters};
+
+/// Verifies a proof produced by `ProvingAssembly::prove_with_setup_precomputed`.
+/// Parameters must be the same as used by the prover.
+pub fn verify_redshift<F: PrimeField, T: Transcript<F, Input = I::Commitment>, I: CosetCombiningIopInstance<F> >(
+    proof: &RedshiftProof<F, I>,
+    public_inputs: &[F],
+    setup: &RedshiftSetup<F, I>,
+    params: &RedshiftParameters<F>
+) -> Result<bool, SynthesisError> {
+    if public_inputs.len() != setup.num_inputs {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+
+    let n = setup.n;
+
+    // we need n+1 to be a power of two and can not have n to be power of two
+    let required_domain_size = n + 1;
+    if !required_domain_size.is_power_of_two() {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+
+    if params.coset_params.cosets_schedule.is_empty() || !params.lde_factor.is_power_of_two() {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+
+    if proof.openings_proof.lde_factor != params.lde_factor
+        || proof.openings_proof.initial_degree_plus_one != required_domain_size
+        || proof.openings_proof.output_coeffs_at_degree_plus_one != params.output_coeffs_at_degree_plus_one {
+        return Ok(false);
+    }
+
+    let mut transcript = T::new();
+
+    transcript.commit_input(&proof.a_commitment);
+    transcript.commit_input(&proof.b_commitment);
+    transcript.commit_input(&proof.c_commitment);
+
+    for input in public_inputs.iter() {
+        transcript.commit_field_element(input);
+    }
+
+    let beta = transcript.get_challenge();
+    let gamma = transcript.get_challenge();
+
+    transcript.commit_input(&proof.z_1_commitment);
+    transcript.commit_input(&proof.z_2_commitment);
+
+    let n_fe = F::from_str(&n.to_string()).expect("must be valid field element");
+    let mut two_n_fe = n_fe;
+    two_n_fe.double();
+
+    let alpha = transcript.get_challenge();
+
+    transcript.commit_input(&proof.t_low_commitment);
+    transcript.commit_input(&proof.t_mid_commitment);
+    transcript.commit_input(&proof.t_high_commitment);
+
+    let z = transcript.get_challenge();
+
+    let a_at_z = proof.a_opening_value;
+    let b_at_z = proof.b_opening_value;
+    let c_at_z = proof.c_opening_value;
+
+    let q_l_at_z = proof.q_l_opening_value;
+    let q_r_at_z = proof.q_r_opening_value;
+    let q_o_at_z = proof.q_o_opening_value;
+    let q_m_at_z = proof.q_m_opening_value;
+    let q_c_at_z = proof.q_c_opening_value;
+
+    let s_id_at_z = proof.s_id_opening_value;
+    let sigma_1_at_z = proof.sigma_1_opening_value;
+    let sigma_2_at_z = proof.sigma_2_opening_value;
+    let sigma_3_at_z = proof.sigma_3_opening_value;
+
+    let z_1_at_z = proof.z_1_unshifted_opening_value;
+    let z_2_at_z = proof.z_2_unshifted_opening_value;
+
+    let z_1_shifted_at_z = proof.z_1_shifted_opening_value;
+    let z_2_shifted_at_z = proof.z_2_shifted_opening_value;
+
+    let t_low_at_z = proof.t_low_opening_value;
+    let t_mid_at_z = proof.t_mid_opening_value;
+    let t_high_at_z = proof.t_high_opening_value;
+
+    {
+        transcript.commit_field_element(&a_at_z);
+        transcript.commit_field_element(&b_at_z);
+        transcript.commit_field_element(&c_at_z);
+
+        transcript.commit_field_element(&q_l_at_z);
+        transcript.commit_field_element(&q_r_at_z);
+        transcript.commit_field_element(&q_o_at_z);
+        transcript.commit_field_element(&q_m_at_z);
+        transcript.commit_field_element(&q_c_at_z);
+
+        transcript.commit_field_element(&s_id_at_z);
+        transcript.commit_field_element(&sigma_1_at_z);
+        transcript.commit_field_element(&sigma_2_at_z);
+        transcript.commit_field_element(&sigma_3_at_z);
+
+        transcript.commit_field_element(&t_low_at_z);
+        transcript.commit_field_element(&t_mid_at_z);
+        transcript.commit_field_element(&t_high_at_z);
+
+        transcript.commit_field_element(&z_1_at_z);
+        transcript.commit_field_element(&z_2_at_z);
+
+        transcript.commit_field_element(&z_1_shifted_at_z);
+        transcript.commit_field_element(&z_2_shifted_at_z);
+    }
+
+    let mut inverse_vanishing_at_z = evaluate_inverse_vanishing_poly(required_domain_size, z);
+
+    let l_0_at_z = evaluate_lagrange_poly(required_domain_size, 0, z);
+    let l_n_minus_one_at_z = evaluate_lagrange_poly(required_domain_size, n - 1, z);
+
+    // PI(X) = - sum input_i * L_i(X)
+    let mut public_inputs_at_z = F::zero();
+    for (i, input) in public_inputs.iter().enumerate() {
+        let mut tmp = evaluate_lagrange_poly(required_domain_size, i, z);
+        tmp.mul_assign(input);
+        public_inputs_at_z.sub_assign(&tmp);
+    }
+
+    let z_in_pow_of_domain_size = z.pow([required_domain_size as u64]);
+
+    // check that t(z) * Z_H(z) is equal to the combination of the constraints at z
+    let mut t_1 = {
+        let mut res = q_c_at_z;
+
+        res.add_assign(&public_inputs_at_z);
+
+        let mut tmp = q_l_at_z;
+        tmp.mul_assign(&a_at_z);
+        res.add_assign(&tmp);
+
+        let mut tmp = q_r_at_z;
+        tmp.mul_assign(&b_at_z);
+        res.add_assign(&tmp);
+
+        let mut tmp = q_o_at_z;
+        tmp.mul_assign(&c_at_z);
+        res.add_assign(&tmp);
+
+        let mut tmp = q_m_at_z;
+        tmp.mul_assign(&a_at_z);
+        tmp.mul_assign(&b_at_z);
+        res.add_assign(&tmp);
+
+        inverse_vanishing_at_z.mul_assign(&alpha);
+
+        res.mul_assign(&inverse_vanishing_at_z);
+
+        res
+    };
+
+    {
+        let mut res = z_1_at_z;
+
+        let mut tmp = s_id_at_z;
+        tmp.mul_assign(&beta);
+        tmp.add_assign(&a_at_z);
+        tmp.add_assign(&gamma);
+        res.mul_assign(&tmp);
+
+        let mut tmp = s_id_at_z;
+        tmp.add_assign(&n_fe);
+        tmp.mul_assign(&beta);
+        tmp.add_assign(&b_at_z);
+        tmp.add_assign(&gamma);
+        res.mul_assign(&tmp);
+
+        let mut tmp = s_id_at_z;
+        tmp.add_assign(&two_n_fe);
+        tmp.mul_assign(&beta);
+        tmp.add_assign(&c_at_z);
+        tmp.add_assign(&gamma);
+        res.mul_assign(&tmp);
+
+        res.sub_assign(&z_1_shifted_at_z);
+
+        inverse_vanishing_at_z.mul_assign(&alpha);
+
+        res.mul_assign(&inverse_vanishing_at_z);
+
+        t_1.add_assign(&res);
+    }
+
+    {
+        let mut res = z_2_at_z;
+
+        let mut tmp = sigma_1_at_z;
+        tmp.mul_assign(&beta);
+        tmp.add_assign(&a_at_z);
+        tmp.add_assign(&gamma);
+        res.mul_assign(&tmp);
+
+        let mut tmp = sigma_2_at_z;
+        tmp.mul_assign(&beta);
+        tmp.add_assign(&b_at_z);
+        tmp.add_assign(&gamma);
+        res.mul_assign(&tmp);
+
+        let mut tmp = sigma_3_at_z;
+        tmp.mul_assign(&beta);
+        tmp.add_assign(&c_at_z);
+        tmp.add_assign(&gamma);
+        res.mul_assign(&tmp);
+
+        res.sub_assign(&z_2_shifted_at_z);
+
+        inverse_vanishing_at_z.mul_assign(&alpha);
+
+        res.mul_assign(&inverse_vanishing_at_z);
+
+        t_1.add_assign(&res);
+    }
+
+    {
+        let mut res = z_1_shifted_at_z;
+        res.sub_assign(&z_2_shifted_at_z);
+        res.mul_assign(&l_n_minus_one_at_z);
+
+        inverse_vanishing_at_z.mul_assign(&alpha);
+
+        res.mul_assign(&inverse_vanishing_at_z);
+
+        t_1.add_assign(&res);
+    }
+
+    {
+        let mut res = z_1_at_z;
+        res.sub_assign(&z_2_at_z);
+        res.mul_assign(&l_0_at_z);
+
+        inverse_vanishing_at_z.mul_assign(&alpha);
+
+        res.mul_assign(&inverse_vanishing_at_z);
+
+        t_1.add_assign(&res);
+    }
+
+    let mut t_at_z = F::zero();
+    t_at_z.add_assign(&t_low_at_z);
+
+    let mut tmp = z_in_pow_of_domain_size;
+    tmp.mul_assign(&t_mid_at_z);
+    t_at_z.add_assign(&tmp);
+
+    let mut tmp = z_in_pow_of_domain_size;
+    tmp.mul_assign(&z_in_pow_of_domain_size);
+    tmp.mul_assign(&t_high_at_z);
+    t_at_z.add_assign(&tmp);
+
+    if t_at_z != t_1 {
+        return Ok(false);
+    }
+
+    // now check the openings, challenges are drawn in the same order as in the prover
+
+    let aggregation_challenge = transcript.get_challenge();
+
+    let fri_challenges = CosetCombiningFriIop::<F, I>::get_fri_challenges(&proof.openings_proof, &mut transcript, &params.coset_params);
+
+    for c in proof.openings_proof.final_coefficients.iter() {
+        transcript.commit_field_element(c);
+    }
+
+    let lde_size = required_domain_size * params.lde_factor;
+
+    if !absorb_proof_of_work(&mut transcript, proof.proof_of_work_nonce, params.proof_of_work_bits) {
+        return Ok(false);
+    }
+
+    let mut domain_indexes = vec![];
+    for _ in 0..params.num_queries {
+        let domain_idx = bytes_to_challenge_index(transcript.get_challenge_bytes(), lde_size);
+        domain_indexes.push(domain_idx);
+    }
+
+    if proof.witness_queries.len() != params.num_queries || proof.setup_queries.len() != params.num_queries {
+        return Ok(false);
+    }
+
+    let domain = Domain::<F>::new_for_size(required_domain_size as u64)?;
+    let mut z_by_omega = z;
+    z_by_omega.mul_assign(&domain.generator);
+
+    let witness_commitments = [
+        &proof.a_commitment,
+        &proof.b_commitment,
+        &proof.c_commitment,
+        &proof.z_1_commitment,
+        &proof.z_2_commitment,
+        &proof.t_low_commitment,
+        &proof.t_mid_commitment,
+        &proof.t_high_commitment,
+    ];
+
+    let setup_commitments = [
+        &setup.q_l,
+        &setup.q_r,
+        &setup.q_o,
+        &setup.q_m,
+        &setup.q_c,
+        &setup.s_id,
+        &setup.sigma_1,
+        &setup.sigma_2,
+        &setup.sigma_3,
+    ];
+
+    // witness polynomials are opened at z and z*omega
+    let witness_openings_at_z = [
+        a_at_z,
+        b_at_z,
+        c_at_z,
+        z_1_at_z,
+        z_2_at_z,
+        t_low_at_z,
+        t_mid_at_z,
+        t_high_at_z,
+    ];
+
+    let witness_openings_at_z_omega = [
+        z_1_shifted_at_z,
+        z_2_shifted_at_z,
+    ];
+
+    // setup polynomials are opened at z and the setup point
+    let setup_openings_at_z = [
+        q_l_at_z,
+        q_r_at_z,
+        q_o_at_z,
+        q_m_at_z,
+        q_c_at_z,
+        s_id_at_z,
+        sigma_1_at_z,
+        sigma_2_at_z,
+        sigma_3_at_z,
+    ];
+
+    let setup_values = [
+        setup.q_l_setup_value,
+        setup.q_r_setup_value,
+        setup.q_o_setup_value,
+        setup.q_m_setup_value,
+        setup.q_c_setup_value,
+        setup.s_id_setup_value,
+        setup.sigma_1_setup_value,
+        setup.sigma_2_setup_value,
+        setup.sigma_3_setup_value,
+    ];
+
+    let values_per_leaf = 1 << params.coset_params.cosets_schedule[0];
+    let tree_params = I::params_for_values_per_leaf(values_per_leaf);
+
+    let lde_domain = Domain::<F>::new_for_size(lde_size as u64)?;
+    let log_lde_size = log2_floor(lde_size) as usize;
+    let coset_generator = F::multiplicative_generator();
+
+    let setup_point = setup.setup_point;
+
+    let mut expected_values = vec![];
+
+    for ((idx, witness_queries), setup_queries) in domain_indexes.iter()
+                                                    .zip(proof.witness_queries.iter())
+                                                    .zip(proof.setup_queries.iter())
+    {
+        if witness_queries.len() != witness_commitments.len() || setup_queries.len() != setup_commitments.len() {
+            return Ok(false);
+        }
+
+        let coset = CosetCombiningFriIop::<F, I>::get_coset_for_natural_index(*idx, lde_size, values_per_leaf);
+
+        for (query, commitment) in witness_queries.iter().zip(witness_commitments.iter())
+                                    .chain(setup_queries.iter().zip(setup_commitments.iter())) {
+            if query.indexes() != coset {
+                return Ok(false);
+            }
+
+            if !I::verify_query(commitment, query, &tree_params) {
+                return Ok(false);
+            }
+        }
+
+        // aggregate in the same way as the prover did for every element of the coset
+
+        let mut aggregated_values = vec![];
+
+        for (i, position) in coset.iter().enumerate() {
+            let natural_index = bitreverse(*position, log_lde_size);
+            let mut x = lde_domain.generator.pow([natural_index as u64]);
+            x.mul_assign(&coset_generator);
+
+            let mut aggregate = F::zero();
+            let mut alpha = F::one();
+
+            for (queries, values, point) in [
+                (&witness_queries[..], &witness_openings_at_z[..], z),
+                (&witness_queries[3..5], &witness_openings_at_z_omega[..], z_by_omega)
+            ].iter() {
+                let mut denominator = x;
+                denominator.sub_assign(point);
+                let denominator = denominator.inverse().ok_or(SynthesisError::DivisionByZero)?;
+
+                for (query, value) in queries.iter().zip(values.iter()) {
+                    let mut tmp = query.values()[i];
+                    tmp.sub_assign(value);
+                    tmp.mul_assign(&denominator);
+                    tmp.mul_assign(&alpha);
+
+                    aggregate.add_assign(&tmp);
+
+                    alpha.mul_assign(&aggregation_challenge);
+                }
+            }
+
+            {
+                // (x - setup_point)(x - z)
+                let mut denominator = x;
+                denominator.sub_assign(&setup_point);
+                let mut tmp = x;
+                tmp.sub_assign(&z);
+                denominator.mul_assign(&tmp);
+                let denominator = denominator.inverse().ok_or(SynthesisError::DivisionByZero)?;
+
+                for ((query, value), setup_value) in setup_queries.iter().zip(setup_openings_at_z.iter()).zip(setup_values.iter()) {
+                    // remove the linear polynomial that goes through (setup_point, setup_value) and (z, value)
+                    let mut slope = z;
+                    slope.sub_assign(&setup_point);
+                    let mut slope = slope.inverse().ok_or(SynthesisError::DivisionByZero)?;
+
+                    let mut t1 = *value;
+                    t1.sub_assign(setup_value);
+                    slope.mul_assign(&t1);
+
+                    let mut interpolant = x;
+                    interpolant.sub_assign(&setup_point);
+                    interpolant.mul_assign(&slope);
+                    interpolant.add_assign(setup_value);
+
+                    let mut tmp = query.values()[i];
+                    tmp.sub_assign(&interpolant);
+                    tmp.mul_assign(&denominator);
+                    tmp.mul_assign(&alpha);
+
+                    aggregate.add_assign(&tmp);
+
+                    alpha.mul_assign(&aggregation_challenge);
+                }
+            }
+
+            aggregated_values.push(aggregate);
+        }
+
+        expected_values.push(aggregated_values);
+    }
+
+    let valid = CosetCombiningFriIop::<F, I>::verify_proof_with_challenges(
+        &proof.openings_proof,
+        domain_indexes,
+        &expected_values,
+        &fri_challenges,
+        &params.coset_params
+    )?;
+
+    Ok(valid)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pairing::Engine;
+    use crate::pairing::ff::{Field, PrimeField};
+    use crate::plonk::cs::*;
+    use crate::SynthesisError;
+    use crate::multicore::Worker;
+
+    use crate::plonk::transparent_engine::proth_engine::Transparent252;
+    use crate::plonk::commitments::transcript::Blake2sTranscript;
+    use crate::plonk::commitments::transcript::poseidon::PoseidonTranscript;
+    use crate::plonk::commitments::transcript::keccak::Keccak256Transcript;
+    use crate::plonk::commitments::transparent::fri::coset_combining_fri::*;
+    use crate::plonk::commitments::transparent::fri::coset_combining_fri::precomputation::*;
+    use crate::plonk::commitments::transparent::iop_compiler::coset_combining_blake2s_tree::*;
+    use crate::plonk::commitments::transparent::iop_compiler::coset_combining_keccak_tree::*;
+    use crate::plonk::commitments::transparent::iop_compiler::coset_combining_poseidon_tree::*;
+    use crate::plonk::fft::cooley_tukey_ntt::*;
+
+    use super::*;
+    use super::super::prover::*;
+    use super::super::generator::*;
+
+    type Fr = crate::plonk::transparent_engine::proth::Fr;
+    type Transcr = Blake2sTranscript<Fr>;
+
+    #[derive(Clone)]
+    struct TestCircuit<E: Engine>{
+        num_steps: usize,
+        _marker: std::marker::PhantomData<E>
+    }
+
+    impl<E: Engine> Circuit<E> for TestCircuit<E> {
+        fn synthesize<CS: ConstraintSystem<E>>(&self, cs: &mut CS) -> Result<(), SynthesisError> {
+            let one = E::Fr::one();
+            let mut negative_one = one;
+            negative_one.negate();
+
+            let mut two = one;
+            two.double();
+
+            let mut a = cs.alloc(|| {
+                Ok(E::Fr::one())
+            })?;
+
+            let mut b = cs.alloc(|| {
+                Ok(E::Fr::one())
+            })?;
+
+            cs.enforce_zero_2((a, b), (one, negative_one))?;
+
+            let mut c = cs.alloc(|| {
+                Ok(two)
+            })?;
+
+            cs.enforce_zero_3((a, b, c), (one, one, negative_one))?;
+
+            let mut b_value = one;
+            let mut c_value = two;
+
+            for _ in 0..self.num_steps {
+                a = b;
+                b = c;
+
+                let a_value = b_value;
+                b_value = c_value;
+                c_value.add_assign(&a_value);
+
+                c = cs.alloc(|| {
+                    Ok(c_value)
+                })?;
+
+                cs.enforce_zero_3((a, b, c), (one, one, negative_one))?;
+            }
+
+            let output = cs.alloc_input(|| {
+                Ok(c_value)
+            })?;
+
+            cs.enforce_zero_2((c, output), (one, negative_one))?;
+
+            Ok(())
+        }
+    }
+
+    fn test_circuit_output(num_steps: usize) -> Fr {
+        let mut b_value = Fr::one();
+        let mut c_value = b_value;
+        c_value.double();
+
+        for _ in 0..num_steps {
+            let a_value = b_value;
+            b_value = c_value;
+            c_value.add_assign(&a_value);
+        }
+
+        c_value
+    }
+
+    fn prove_test_circuit<I: CosetCombiningIopInstance<Fr>, T: Transcript<Fr, Input = I::Commitment>>(num_steps: usize, params: &RedshiftParameters<Fr>) -> (
+        RedshiftSetup<Fr, I>,
+        RedshiftProof<Fr, I>,
+        Vec<Fr>
+    ) {
+        let circuit = TestCircuit::<Transparent252> {
+            num_steps,
+            _marker: std::marker::PhantomData
+        };
+
+        let mut prover = ProvingAssembly::<Transparent252>::new();
+        circuit.synthesize(&mut prover).unwrap();
+        prover.finalize();
+
+        let size = prover.num_gates() + 1;
+        assert!(size.is_power_of_two());
+
+        let omegas_bitreversed = BitReversedOmegas::<Fr>::new_for_domain_size(size);
+        let omegas_inv_bitreversed = <OmegasInvBitreversed::<Fr> as CTPrecomputations::<Fr>>::new_for_domain_size(size);
+        let omegas_inv_bitreversed_for_fri = <CosetOmegasInvBitreversed::<Fr> as FriPrecomputations::<Fr>>::new_for_domain_size(size * params.lde_factor);
+
+        let (setup, setup_precomp) = setup_with_precomputations::<Transparent252, _, _, T, I>(
+            &circuit,
+            params,
+            &omegas_bitreversed
+        ).unwrap();
+
+        let worker = Worker::new();
+
+        let proof = prover.prove_with_setup_precomputed::<_, _, _, T, _>(
+            &setup_precomp,
+            params,
+            &worker,
+            &omegas_bitreversed,
+            &omegas_inv_bitreversed,
+            &omegas_inv_bitreversed_for_fri
+        ).unwrap();
+
+        (setup, proof, vec![test_circuit_output(num_steps)])
+    }
+
+    fn test_params() -> RedshiftParameters<Fr> {
+        RedshiftParameters {
+            lde_factor: 16,
+            num_queries: 8,
+            output_coeffs_at_degree_plus_one: 1,
+            coset_params: CosetParams {
+                cosets_schedule: vec![3, 3, 3],
+                coset_factor: Fr::multiplicative_generator()
+            },
+            proof_of_work_bits: 0
+        }
+    }
+
+    #[test]
+    fn test_prove_and_verify_redshift() {
+        let params = test_params();
+        let (setup, proof, inputs) = prove_test_circuit::<FriSpecificBlake2sTree<Fr>, Transcr>(251, &params);
+
+        let valid = verify_redshift::<Fr, Transcr, _>(&proof, &inputs, &setup, &params).unwrap();
+        assert!(valid);
+    }
+
+    #[test]
+    fn test_prove_and_verify_redshift_with_keccak_tree() {
+        type KeccakTranscr = Keccak256Transcript<Fr>;
+
+        let params = test_params();
+        let (setup, mut proof, inputs) = prove_test_circuit::<FriSpecificKeccakTree<Fr>, KeccakTranscr>(251, &params);
+
+        let valid = verify_redshift::<Fr, KeccakTranscr, _>(&proof, &inputs, &setup, &params).unwrap();
+        assert!(valid);
+
+        proof.openings_proof.final_coefficients[0].add_assign(&Fr::one());
+        let valid = verify_redshift::<Fr, KeccakTranscr, _>(&proof, &inputs, &setup, &params).unwrap();
+        assert!(!valid);
+    }
+
+    #[test]
+    fn test_prove_and_verify_redshift_with_poseidon_tree() {
+        type PoseidonTranscr = PoseidonTranscript<Fr>;
+
+        let params = test_params();
+        let (setup, mut proof, inputs) = prove_test_circuit::<FriSpecificPoseidonTree<Fr>, PoseidonTranscr>(251, &params);
+
+        let valid = verify_redshift::<Fr, PoseidonTranscr, _>(&proof, &inputs, &setup, &params).unwrap();
+        assert!(valid);
+
+        proof.openings_proof.final_coefficients[0].add_assign(&Fr::one());
+        let valid = verify_redshift::<Fr, PoseidonTranscr, _>(&proof, &inputs, &setup, &params).unwrap();
+        assert!(!valid);
+    }
+
+    #[test]
+    fn test_prove_and_verify_redshift_with_grinding() {
+        let mut params = test_params();
+        params.proof_of_work_bits = 10;
+        let (setup, mut proof, inputs) = prove_test_circuit::<FriSpecificBlake2sTree<Fr>, Transcr>(251, &params);
+
+        let valid = verify_redshift::<Fr, Transcr, _>(&proof, &inputs, &setup, &params).unwrap();
+        assert!(valid);
+
+        proof.proof_of_work_nonce += 1;
+        let valid = verify_redshift::<Fr, Transcr, _>(&proof, &inputs, &setup, &params).unwrap();
+        assert!(!valid);
+    }
+
+    #[test]
+    fn test_redshift_parameters_for_security_level() {
+        let coset_params = test_params().coset_params;
+        let params = RedshiftParameters::<Fr>::new_for_security_level(80, 16, 1, coset_params.clone());
+        assert_eq!(params.proof_of_work_bits, 16);
+        assert_eq!(params.num_queries, 16);
+
+        let soundness = params.soundness(256);
+        assert_eq!(soundness.conjectured_bits, 80);
+        assert!(soundness.proven_bits < soundness.conjectured_bits);
+
+        // proven bits per query are log2(32 / 17) for rate 1/16
+        assert_eq!(soundness.proven_bits, 16 + 14);
+
+        // limited by the field size
+        let params = RedshiftParameters::<Fr>::new_for_security_level(512, 16, 1, coset_params);
+        let soundness = params.soundness(256);
+        assert_eq!(soundness.conjectured_bits, Fr::CAPACITY - 12);
+    }
+
+    #[test]
+    fn test_redshift_proof_is_bound_to_public_inputs() {
+        let params = test_params();
+        let (setup, proof, inputs) = prove_test_circuit::<FriSpecificBlake2sTree<Fr>, Transcr>(251, &params);
+
+        let mut wrong_inputs = inputs.clone();
+        wrong_inputs[0].add_assign(&Fr::one());
+        let valid = verify_redshift::<Fr, Transcr, _>(&proof, &wrong_inputs, &setup, &params).unwrap();
+        assert!(!valid);
+
+        assert!(verify_redshift::<Fr, Transcr, _>(&proof, &[], &setup, &params).is_err());
+    }
+
+    #[test]
+    fn test_tampered_redshift_proof_is_rejected() {
+        let params = test_params();
+        let (setup, mut proof, inputs) = prove_test_circuit::<FriSpecificBlake2sTree<Fr>, Transcr>(251, &params);
+
+        proof.t_low_opening_value.add_assign(&Fr::one());
+        let valid = verify_redshift::<Fr, Transcr, _>(&proof, &inputs, &setup, &params).unwrap();
+        assert!(!valid);
+        proof.t_low_opening_value.sub_assign(&Fr::one());
+
+        // consistent with the polynomial identity, but not with the committed polynomial
+        proof.q_c_opening_value.add_assign(&Fr::one());
+        proof.t_low_opening_value.add_assign(&Fr::one());
+        let valid = verify_redshift::<Fr, Transcr, _>(&proof, &inputs, &setup, &params).unwrap();
+        assert!(!valid);
+        proof.q_c_opening_value.sub_assign(&Fr::one());
+        proof.t_low_opening_value.sub_assign(&Fr::one());
+
+        proof.openings_proof.final_coefficients[0].add_assign(&Fr::one());
+        let valid = verify_redshift::<Fr, Transcr, _>(&proof, &inputs, &setup, &params).unwrap();
+        assert!(!valid);
+        proof.openings_proof.final_coefficients[0].sub_assign(&Fr::one());
+
+        let valid = verify_redshift::<Fr, Transcr, _>(&proof, &inputs, &setup, &params).unwrap();
+        assert!(valid);
+    }
+
+    #[test]
+    fn test_redshift_setup_serialization_round_trip() {
+        let params = test_params();
+        let num_steps = 251;
+
+        let circuit = TestCircuit::<Transparent252> {
+            num_steps,
+            _marker: std::marker::PhantomData
+        };
+
+        let mut prover = ProvingAssembly::<Transparent252>::new();
+        circuit.synthesize(&mut prover).unwrap();
+        prover.finalize();
+
+        let size = prover.num_gates() + 1;
+
+        let omegas_bitreversed = BitReversedOmegas::<Fr>::new_for_domain_size(size);
+        let omegas_inv_bitreversed = <OmegasInvBitreversed::<Fr> as CTPrecomputations::<Fr>>::new_for_domain_size(size);
+        let omegas_inv_bitreversed_for_fri = <CosetOmegasInvBitreversed::<Fr> as FriPrecomputations::<Fr>>::new_for_domain_size(size * params.lde_factor);
+
+        let (setup, setup_precomp) = setup_with_precomputations::<Transparent252, _, _, Transcr, FriSpecificBlake2sTree<Fr>>(
+            &circuit,
+            &params,
+            &omegas_bitreversed
+        ).unwrap();
+
+        let mut setup_buffer = vec![];
+        setup.write(&mut setup_buffer).unwrap();
+        let mut precomp_buffer = vec![];
+        setup_precomp.write(&mut precomp_buffer).unwrap();
+
+        let deserialized_setup = RedshiftSetup::<Fr, FriSpecificBlake2sTree<Fr>>::read(&setup_buffer[..]).unwrap();
+        let deserialized_precomp = RedshiftSetupPrecomputation::<Fr, FriSpecificBlake2sTree<Fr>>::read(&precomp_buffer[..]).unwrap();
+
+        let mut buffer = vec![];
+        deserialized_setup.write(&mut buffer).unwrap();
+        assert_eq!(setup_buffer, buffer);
+
+        let mut buffer = vec![];
+        deserialized_precomp.write(&mut buffer).unwrap();
+        assert_eq!(precomp_buffer, buffer);
+
+        assert!(RedshiftSetupPrecomputation::<Fr, FriSpecificBlake2sTree<Fr>>::read(&precomp_buffer[..(precomp_buffer.len() - 1)]).is_err());
+
+        let worker = Worker::new();
+
+        let proof = prover.prove_with_setup_precomputed::<_, _, _, Transcr, _>(
+            &deserialized_precomp,
+            &params,
+            &worker,
+            &omegas_bitreversed,
+            &omegas_inv_bitreversed,
+            &omegas_inv_bitreversed_for_fri
+        ).unwrap();
+
+        let inputs = vec![test_circuit_output(num_steps)];
+        let valid = verify_redshift::<Fr, Transcr, _>(&proof, &inputs, &deserialized_setup, &params).unwrap();
+        assert!(valid);
+    }
+}
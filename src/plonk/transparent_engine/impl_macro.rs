@@ -198,7 +198,7 @@ macro_rules! transparent_engine_impl {
                 <$fr as crate::ff::Field>::zero()
             }
 
-            fn from_xy_checked(x: Self::Base, y: Self::Base) -> Result<Self, GroupDecodingError> {
+            fn from_xy_checked(x: Self::Base, y: Self::Base) -> Result<Self, crate::pairing::GroupDecodingError> {
                 Ok(<$fr as crate::ff::Field>::zero())
             }
         }
@@ -125,9 +125,9 @@ mod test {
         let mut tmp1 = one_optimized;
         tmp1.mul_assign(&one_optimized);
 
-        assert_eq!(tmp0.to_hex(), tmp1.to_hex());
+        assert_eq!(crate::ff::to_hex(&tmp0), crate::ff::to_hex(&tmp1));
 
-        assert_eq!(FrMontNaive::multiplicative_generator().to_hex(), FrOptimized::multiplicative_generator().to_hex());
+        assert_eq!(crate::ff::to_hex(&FrMontNaive::multiplicative_generator()), crate::ff::to_hex(&FrOptimized::multiplicative_generator()));
     }
 
     #[test]
@@ -1,5 +1,13 @@
 use crate::multicore::*;
-use crate::pairing::ff::PrimeField;
+use crate::pairing::ff::{Field, PrimeField, PrimeFieldRepr};
+use crate::plonk::polynomials::*;
+use crate::plonk::domains::Domain;
+
+use byteorder::{BigEndian, WriteBytesExt, ReadBytesExt};
+
+use std::io::{self, Read, Write};
+
+pub(crate) use crate::serialization::{write_field_element, read_field_element, write_point, read_point};
 
 pub(crate) fn convert_to_field_elements<F: PrimeField>(indexes: &[usize], worker: &Worker) -> Vec<F> {
     let mut result = vec![F::zero(); indexes.len()];
@@ -20,3 +28,101 @@ pub(crate) fn convert_to_field_elements<F: PrimeField>(indexes: &[usize], worker
     result
 }
 
+
+pub(crate) fn evaluate_inverse_vanishing_poly<F: PrimeField>(vanishing_size: usize, point: F) -> F {
+    assert!(vanishing_size.is_power_of_two());
+
+    // update from the paper - it should not hold for the last generator, omega^(n) in original notations
+
+    // Z(X) = (X^(n+1) - 1) / (X - omega^(n)) => Z^{-1}(X) = (X - omega^(n)) / (X^(n+1) - 1)
+
+    let domain = Domain::<F>::new_for_size(vanishing_size as u64).expect("should fit");
+    let n_domain_omega = domain.generator;
+    let root = n_domain_omega.pow([(vanishing_size - 1) as u64]);
+
+    let mut numerator = point;
+    numerator.sub_assign(&root);
+
+    let mut denominator = point.pow([vanishing_size as u64]);
+    denominator.sub_assign(&F::one());
+
+    let denominator = denominator.inverse().expect("must exist");
+
+    numerator.mul_assign(&denominator);
+
+    numerator
+}
+
+pub(crate) fn evaluate_lagrange_poly<F: PrimeField>(vanishing_size: usize, poly_number: usize, at: F) -> F {
+    assert!(vanishing_size.is_power_of_two());
+
+    let mut repr = F::zero().into_repr();
+    repr.as_mut()[0] = vanishing_size as u64;
+
+    let size_fe = F::from_repr(repr).expect("is a valid representation");
+
+    // L_0(X) = (Z_H(X) / (X - 1)).(1/n) and L_0(1) = 1
+    // L_1(omega) = 1 = L_0(omega * omega^-1)
+
+    let domain = Domain::<F>::new_for_size(vanishing_size as u64).expect("domain of this size should exist");
+    let omega = domain.generator;
+
+    let omega_inv = omega.inverse().expect("must exist");
+
+    let argument_multiplier = omega_inv.pow([poly_number as u64]);
+    let mut argument = at;
+    argument.mul_assign(&argument_multiplier);
+
+    let mut numerator = argument.pow([vanishing_size as u64]);
+    numerator.sub_assign(&F::one());
+
+    let mut denom = argument;
+    denom.sub_assign(&F::one());
+    denom.mul_assign(&size_fe);
+
+    let denom_inv = denom.inverse().expect("must exist");
+
+    numerator.mul_assign(&denom_inv);
+
+    numerator
+}
+
+// serialized setups start with this version, so that data in an outdated
+// format is rejected instead of being misinterpreted
+pub(crate) const SETUP_FORMAT_VERSION: u32 = 1;
+
+pub(crate) fn write_version_header<W: Write>(mut writer: W) -> io::Result<()> {
+    writer.write_u32::<BigEndian>(SETUP_FORMAT_VERSION)
+}
+
+pub(crate) fn read_version_header<R: Read>(mut reader: R) -> io::Result<()> {
+    let version = reader.read_u32::<BigEndian>()?;
+    if version != SETUP_FORMAT_VERSION {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("unsupported setup format version {}", version)));
+    }
+
+    Ok(())
+}
+
+pub(crate) fn write_polynomial_values<F: PrimeField, W: Write>(poly: &Polynomial<F, Values>, mut writer: W) -> io::Result<()> {
+    writer.write_u32::<BigEndian>(poly.size() as u32)?;
+    for value in poly.as_ref().iter() {
+        write_field_element(value, &mut writer)?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn read_polynomial_values<F: PrimeField, R: Read>(mut reader: R) -> io::Result<Polynomial<F, Values>> {
+    let size = reader.read_u32::<BigEndian>()? as usize;
+    if !size.is_power_of_two() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "polynomial size must be a power of two"));
+    }
+
+    let mut values = vec![];
+    for _ in 0..size {
+        values.push(read_field_element(&mut reader)?);
+    }
+
+    Polynomial::from_values(values).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,30 @@
+//! Encoding of field elements and curve points shared by the serialization
+//! of PLONK setups and proofs and of Sonic advice and aggregates.
+
+use crate::pairing::ff::{PrimeField, PrimeFieldRepr};
+use crate::pairing::{CurveAffine, EncodedPoint};
+
+use std::io::{self, Read, Write};
+
+pub(crate) fn write_field_element<F: PrimeField, W: Write>(element: &F, mut writer: W) -> io::Result<()> {
+    element.into_repr().write_be(&mut writer)
+}
+
+pub(crate) fn read_field_element<F: PrimeField, R: Read>(mut reader: R) -> io::Result<F> {
+    let mut repr = F::zero().into_repr();
+    repr.read_be(&mut reader)?;
+
+    F::from_repr(repr).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+// points at infinity are allowed, e.g. a commitment to the zero polynomial
+pub(crate) fn write_point<G: CurveAffine, W: Write>(point: &G, mut writer: W) -> io::Result<()> {
+    writer.write_all(point.into_compressed().as_ref())
+}
+
+pub(crate) fn read_point<G: CurveAffine, R: Read>(mut reader: R) -> io::Result<G> {
+    let mut repr = G::Compressed::empty();
+    reader.read_exact(repr.as_mut())?;
+
+    repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
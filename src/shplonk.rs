@@ -0,0 +1,451 @@
+//! Batched Kate openings of many polynomials at many points, following
+//! the SHPLONK construction of Boneh, Drake, Fisch and Gabizon. A prover
+//! sends two G1 elements, and a verifier reduces all the claimed
+//! evaluations to a single opening of a combined commitment at a random
+//! point.
+//!
+//! Challenges are supplied by the caller, so the same routines work with
+//! PLONK and Sonic transcripts: `gamma` should be drawn after the
+//! commitments and claimed values are known, and the opening point
+//! after the quotient commitment was seen. `*_with_transcript` variants
+//! draw them from a `ProtocolTranscript` in this order.
+
+use crate::pairing::ff::{Field, PrimeField};
+use crate::pairing::{Engine, CurveAffine, CurveProjective};
+
+use crate::SynthesisError;
+use crate::multiexp::dense_multiexp;
+use crate::transcript::ProtocolTranscript;
+use crate::worker::Worker;
+
+/// Polynomial in coefficient form and the set of points it's opened at
+pub struct PolynomialOpening<'a, F: PrimeField> {
+    pub coefficients: &'a [F],
+    pub points: Vec<F>,
+}
+
+/// Commitment to a polynomial with claimed values at the set of points
+#[derive(Clone, Debug)]
+pub struct CommitmentOpening<E: Engine> {
+    pub commitment: E::G1Affine,
+    pub points: Vec<E::Fr>,
+    pub values: Vec<E::Fr>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchedOpeningProof<E: Engine> {
+    pub quotient_commitment: E::G1Affine,
+    pub opening_proof: E::G1Affine,
+}
+
+/// All the openings reduced to the single claim
+/// sum c_i * C_i - quotient_scalar * W - value * [1] = (x - point) * opening_proof
+#[derive(Clone, Debug)]
+pub struct ReducedOpening<E: Engine> {
+    pub commitments: Vec<(E::G1Affine, E::Fr)>,
+    pub quotient_commitment: E::G1Affine,
+    pub quotient_scalar: E::Fr,
+    pub value: E::Fr,
+    pub point: E::Fr,
+    pub opening_proof: E::G1Affine,
+}
+
+fn commit<E: Engine>(coeffs: &[E::Fr], bases: &[E::G1Affine], worker: &Worker) -> Result<E::G1Affine, SynthesisError> {
+    let mut len = coeffs.len();
+    while len > 0 && coeffs[len - 1].is_zero() {
+        len -= 1;
+    }
+
+    if len > bases.len() {
+        return Err(SynthesisError::PolynomialDegreeTooLarge);
+    }
+
+    if len == 0 {
+        return Ok(E::G1Affine::zero());
+    }
+
+    let reprs: Vec<_> = coeffs[..len].iter().map(|c| c.into_repr()).collect();
+
+    Ok(dense_multiexp(worker, &bases[..len], &reprs)?.into_affine())
+}
+
+// quotient of the division by (X - point), the remainder is dropped
+fn divide_by_linear<F: PrimeField>(coeffs: &[F], point: F) -> Vec<F> {
+    if coeffs.len() < 2 {
+        return vec![];
+    }
+
+    let mut quotient = vec![F::zero(); coeffs.len() - 1];
+    let mut carry = F::zero();
+    for (q, c) in quotient.iter_mut().rev().zip(coeffs.iter().rev()) {
+        carry.mul_assign(&point);
+        carry.add_assign(c);
+        *q = carry;
+    }
+
+    quotient
+}
+
+fn evaluate<F: PrimeField>(coeffs: &[F], at: F) -> F {
+    let mut result = F::zero();
+    for c in coeffs.iter().rev() {
+        result.mul_assign(&at);
+        result.add_assign(c);
+    }
+
+    result
+}
+
+fn vanishing_at<F: PrimeField>(points: &[F], at: F) -> F {
+    let mut result = F::one();
+    for p in points.iter() {
+        let mut tmp = at;
+        tmp.sub_assign(p);
+        result.mul_assign(&tmp);
+    }
+
+    result
+}
+
+// value at `at` of the polynomial interpolating `values` over `points`
+fn interpolate_at<F: PrimeField>(points: &[F], values: &[F], at: F) -> Result<F, SynthesisError> {
+    let mut result = F::zero();
+    for (i, (point, value)) in points.iter().zip(values.iter()).enumerate() {
+        let mut num = *value;
+        let mut den = F::one();
+        for (j, other) in points.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+
+            let mut tmp = at;
+            tmp.sub_assign(other);
+            num.mul_assign(&tmp);
+
+            let mut tmp = *point;
+            tmp.sub_assign(other);
+            den.mul_assign(&tmp);
+        }
+
+        let den = den.inverse().ok_or(SynthesisError::DivisionByZero)?;
+        num.mul_assign(&den);
+        result.add_assign(&num);
+    }
+
+    Ok(result)
+}
+
+// distinct points of all the sets, in order of the first appearance
+fn union_of_points<F: PrimeField>(sets: &[&[F]]) -> Vec<F> {
+    let mut union: Vec<F> = vec![];
+    for p in sets.iter().flat_map(|s| s.iter()) {
+        if !union.contains(p) {
+            union.push(*p);
+        }
+    }
+
+    union
+}
+
+// Z_{T \ S}(at)
+fn vanishing_at_complement<F: PrimeField>(all_points: &[F], points: &[F], at: F) -> F {
+    let complement: Vec<F> = all_points.iter().filter(|p| !points.contains(p)).cloned().collect();
+
+    vanishing_at(&complement, at)
+}
+
+/// Makes a proof for evaluations of every polynomial at its own set of points.
+/// `bases` are used to commit to the quotient and the opening proof and should be the
+/// same the polynomials were committed with, e.g. `g1_powers` of PLONK's SRS.
+/// `get_point` receives the quotient commitment and returns the opening point challenge
+pub fn prove_batched_opening<E: Engine, P: FnOnce(&E::G1Affine) -> E::Fr>(
+    openings: &[PolynomialOpening<E::Fr>],
+    gamma: E::Fr,
+    bases: &[E::G1Affine],
+    worker: &Worker,
+    get_point: P
+) -> Result<BatchedOpeningProof<E>, SynthesisError> {
+    // h = sum gamma^i * (f_i - r_i) / Z_{S_i} where r_i interpolates f_i over S_i,
+    // and the quotient of f_i by Z_{S_i} is the same since r_i has a smaller degree
+    let size = openings.iter().map(|o| o.coefficients.len()).max().unwrap_or(0);
+    let mut h = vec![E::Fr::zero(); size];
+    let mut challenge = E::Fr::one();
+    for opening in openings.iter() {
+        if opening.points.is_empty() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        let mut quotient = opening.coefficients.to_vec();
+        for p in opening.points.iter() {
+            quotient = divide_by_linear(&quotient, *p);
+        }
+
+        for (h, q) in h.iter_mut().zip(quotient.iter()) {
+            let mut tmp = *q;
+            tmp.mul_assign(&challenge);
+            h.add_assign(&tmp);
+        }
+
+        challenge.mul_assign(&gamma);
+    }
+
+    let quotient_commitment = commit::<E>(&h, bases, worker)?;
+
+    let z = get_point(&quotient_commitment);
+
+    // L = sum gamma^i * Z_{T \ S_i}(z) * (f_i - r_i(z)) - Z_T(z) * h is zero at z,
+    // constant terms don't change the quotient by (X - z), so they are skipped
+    let point_sets: Vec<&[E::Fr]> = openings.iter().map(|o| &o.points[..]).collect();
+    let all_points = union_of_points(&point_sets);
+
+    let mut l = h;
+    let mut minus_z_t = vanishing_at(&all_points, z);
+    minus_z_t.negate();
+    for c in l.iter_mut() {
+        c.mul_assign(&minus_z_t);
+    }
+
+    let mut challenge = E::Fr::one();
+    for opening in openings.iter() {
+        let mut scalar = vanishing_at_complement(&all_points, &opening.points, z);
+        scalar.mul_assign(&challenge);
+        for (l, c) in l.iter_mut().zip(opening.coefficients.iter()) {
+            let mut tmp = *c;
+            tmp.mul_assign(&scalar);
+            l.add_assign(&tmp);
+        }
+
+        challenge.mul_assign(&gamma);
+    }
+
+    let opening_proof = commit::<E>(&divide_by_linear(&l, z), bases, worker)?;
+
+    Ok(BatchedOpeningProof {
+        quotient_commitment,
+        opening_proof
+    })
+}
+
+/// Reduces the claimed openings and the proof to a single opening. The challenges
+/// must be the same the prover used, and `z` can't be one of the opened points
+/// since the claimed values at the other points would not be checked
+pub fn reduce_batched_opening<E: Engine>(
+    openings: &[CommitmentOpening<E>],
+    gamma: E::Fr,
+    z: E::Fr,
+    proof: &BatchedOpeningProof<E>
+) -> Result<ReducedOpening<E>, SynthesisError> {
+    for opening in openings.iter() {
+        if opening.points.is_empty() || opening.points.len() != opening.values.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+    }
+
+    let point_sets: Vec<&[E::Fr]> = openings.iter().map(|o| &o.points[..]).collect();
+    let all_points = union_of_points(&point_sets);
+    if all_points.contains(&z) {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+
+    let mut commitments = Vec::with_capacity(openings.len());
+    let mut value = E::Fr::zero();
+    let mut challenge = E::Fr::one();
+    for opening in openings.iter() {
+        let mut scalar = vanishing_at_complement(&all_points, &opening.points, z);
+        scalar.mul_assign(&challenge);
+
+        let mut tmp = interpolate_at(&opening.points, &opening.values, z)?;
+        tmp.mul_assign(&scalar);
+        value.add_assign(&tmp);
+
+        commitments.push((opening.commitment, scalar));
+
+        challenge.mul_assign(&gamma);
+    }
+
+    Ok(ReducedOpening {
+        commitments,
+        quotient_commitment: proof.quotient_commitment,
+        quotient_scalar: vanishing_at(&all_points, z),
+        value,
+        point: z,
+        opening_proof: proof.opening_proof
+    })
+}
+
+/// Same as `prove_batched_opening`, but `gamma` and the opening point are drawn from the
+/// transcript, which must already contain the commitments and the claimed values.
+/// The quotient commitment is committed to the transcript before the opening point
+pub fn prove_batched_opening_with_transcript<E: Engine, T: ProtocolTranscript<E::Fr>>(
+    openings: &[PolynomialOpening<E::Fr>],
+    bases: &[E::G1Affine],
+    worker: &Worker,
+    transcript: &mut T
+) -> Result<BatchedOpeningProof<E>, SynthesisError> {
+    let gamma = transcript.squeeze_scalar();
+
+    prove_batched_opening(openings, gamma, bases, worker, |quotient_commitment| {
+        transcript.commit_point(quotient_commitment);
+        transcript.squeeze_scalar()
+    })
+}
+
+/// Reduces the openings with the challenges drawn the same way as in
+/// `prove_batched_opening_with_transcript`
+pub fn reduce_batched_opening_with_transcript<E: Engine, T: ProtocolTranscript<E::Fr>>(
+    openings: &[CommitmentOpening<E>],
+    proof: &BatchedOpeningProof<E>,
+    transcript: &mut T
+) -> Result<ReducedOpening<E>, SynthesisError> {
+    let gamma = transcript.squeeze_scalar();
+    transcript.commit_point(&proof.quotient_commitment);
+    let z = transcript.squeeze_scalar();
+
+    reduce_batched_opening(openings, gamma, z, proof)
+}
+
+impl<E: Engine> ReducedOpening<E> {
+    /// Checks e(W', [x]) = e(sum c_i * C_i - Z_T(z) * W - v * [1] + z * W', [1])
+    /// for the commitments made with powers of x
+    pub fn verify(&self, g1: E::G1Affine, g2: E::G2Affine, g2_x: E::G2Affine) -> bool {
+        let mut pair_with_generator = self.opening_proof.mul(self.point.into_repr());
+        for (commitment, scalar) in self.commitments.iter() {
+            pair_with_generator.add_assign(&commitment.mul(scalar.into_repr()));
+        }
+
+        let mut tmp = self.quotient_commitment.mul(self.quotient_scalar.into_repr());
+        tmp.add_assign(&g1.mul(self.value.into_repr()));
+        pair_with_generator.sub_assign(&tmp);
+        pair_with_generator.negate();
+
+        let result = E::final_exponentiation(
+            &E::miller_loop([
+                (&self.opening_proof.prepare(), &g2_x.prepare()),
+                (&pair_with_generator.into_affine().prepare(), &g2.prepare())
+            ].iter())
+        );
+
+        match result {
+            Some(value) => value == E::Fqk::one(),
+            None => false
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pairing::bn256::{Bn256, Fr, G1Affine, G2Affine};
+    use rand::{XorShiftRng, SeedableRng, Rand};
+
+    struct TestSetup {
+        g1_powers: Vec<G1Affine>,
+        g2: G2Affine,
+        g2_x: G2Affine,
+    }
+
+    fn make_setup(size: usize) -> TestSetup {
+        let x = Fr::from_str("42").unwrap();
+        let mut g1_powers = Vec::with_capacity(size);
+        let mut power = Fr::one();
+        for _ in 0..size {
+            g1_powers.push(G1Affine::one().mul(power.into_repr()).into_affine());
+            power.mul_assign(&x);
+        }
+
+        TestSetup {
+            g1_powers,
+            g2: G2Affine::one(),
+            g2_x: G2Affine::one().mul(x.into_repr()).into_affine()
+        }
+    }
+
+    fn make_openings(setup: &TestSetup, polys: &[Vec<Fr>], points: &[Vec<Fr>]) -> Vec<CommitmentOpening<Bn256>> {
+        let worker = Worker::new();
+
+        polys.iter().zip(points.iter()).map(|(poly, points)| {
+            CommitmentOpening {
+                commitment: commit::<Bn256>(poly, &setup.g1_powers, &worker).unwrap(),
+                points: points.clone(),
+                values: points.iter().map(|p| evaluate(poly, *p)).collect()
+            }
+        }).collect()
+    }
+
+    // gamma and the opening point
+    fn test_challenges() -> (Fr, Fr) {
+        (Fr::from_str("17").unwrap(), Fr::from_str("1234567").unwrap())
+    }
+
+    fn prove_and_reduce(
+        setup: &TestSetup,
+        polys: &[Vec<Fr>],
+        openings: &[CommitmentOpening<Bn256>]
+    ) -> (BatchedOpeningProof<Bn256>, ReducedOpening<Bn256>) {
+        let (gamma, z) = test_challenges();
+
+        let polynomial_openings: Vec<_> = polys.iter().zip(openings.iter()).map(|(poly, opening)| {
+            PolynomialOpening {
+                coefficients: &poly[..],
+                points: opening.points.clone()
+            }
+        }).collect();
+
+        let proof = prove_batched_opening::<Bn256, _>(
+            &polynomial_openings,
+            gamma,
+            &setup.g1_powers,
+            &Worker::new(),
+            |_| z
+        ).unwrap();
+
+        let reduced = reduce_batched_opening(openings, gamma, z, &proof).unwrap();
+
+        (proof, reduced)
+    }
+
+    #[test]
+    fn test_batched_opening_at_many_points() {
+        let rng = &mut XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let setup = make_setup(16);
+
+        let polys: Vec<Vec<Fr>> = (0..4).map(|_| (0..16).map(|_| Fr::rand(rng)).collect()).collect();
+        let a = Fr::from_str("3").unwrap();
+        let b = Fr::from_str("5").unwrap();
+        let c = Fr::from_str("7").unwrap();
+        let points = vec![vec![a], vec![a, b], vec![b, c], vec![a, b, c]];
+
+        let openings = make_openings(&setup, &polys, &points);
+        let (_, reduced) = prove_and_reduce(&setup, &polys, &openings);
+        assert!(reduced.verify(setup.g1_powers[0], setup.g2, setup.g2_x));
+
+        let mut wrong_openings = openings.clone();
+        wrong_openings[2].values[1].add_assign(&Fr::one());
+        let (_, reduced) = prove_and_reduce(&setup, &polys, &wrong_openings);
+        assert!(!reduced.verify(setup.g1_powers[0], setup.g2, setup.g2_x));
+    }
+
+    #[test]
+    fn test_batched_opening_with_other_challenges() {
+        let rng = &mut XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let setup = make_setup(8);
+
+        let polys: Vec<Vec<Fr>> = (0..2).map(|_| (0..8).map(|_| Fr::rand(rng)).collect()).collect();
+        let points = vec![vec![Fr::from_str("2").unwrap()], vec![Fr::from_str("9").unwrap()]];
+        let openings = make_openings(&setup, &polys, &points);
+
+        let (gamma, z) = test_challenges();
+        let (proof, reduced) = prove_and_reduce(&setup, &polys, &openings);
+        assert!(reduced.verify(setup.g1_powers[0], setup.g2, setup.g2_x));
+
+        let mut other_z = z;
+        other_z.add_assign(&Fr::one());
+        let reduced = reduce_batched_opening(&openings, gamma, other_z, &proof).unwrap();
+        assert!(!reduced.verify(setup.g1_powers[0], setup.g2, setup.g2_x));
+
+        let reduced = reduce_batched_opening(&openings, Fr::one(), z, &proof).unwrap();
+        assert!(!reduced.verify(setup.g1_powers[0], setup.g2, setup.g2_x));
+
+        assert!(reduce_batched_opening(&openings, gamma, points[0][0], &proof).is_err());
+    }
+}
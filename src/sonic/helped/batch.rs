@@ -21,6 +21,8 @@ use super::parameters::VerifyingKey;
 use crate::sonic::srs::SRS;
 use crate::sonic::util::multiexp;
 
+use crate::shplonk::ReducedOpening;
+
 use std::marker::PhantomData;
 
 // One of the primary functions of the `Batch` abstraction is handling
@@ -100,7 +102,7 @@ impl<E: Engine> Batch<E> {
             neg_x_n_minus_d_precomp: vk.neg_x_n_minus_d.prepare(),
 
             value: E::Fr::zero(),
-            g: E::G1Affine::one(),
+            g: vk.g,
         }
     }
 
@@ -128,6 +130,24 @@ impl<E: Engine> Batch<E> {
         self.value.add_assign(&r);
     }
 
+    /// add the single opening that SHPLONK batched openings are reduced to, where
+    /// the commitments are made with alpha and the quotient and the proof without
+    pub fn add_batched_opening(&mut self, opening: &ReducedOpening<E>, r: E::Fr) {
+        self.add_opening(opening.opening_proof, r, opening.point);
+
+        for (commitment, scalar) in opening.commitments.iter() {
+            let mut tmp = *scalar;
+            tmp.mul_assign(&r);
+            self.add_commitment(*commitment, tmp);
+        }
+
+        let mut tmp = opening.quotient_scalar;
+        tmp.mul_assign(&r);
+        self.alpha.push((opening.quotient_commitment, tmp));
+
+        self.add_opening_value(r, opening.value);
+    }
+
     pub fn check_all(mut self) -> bool {
         self.alpha.push((self.g, self.value));
 
@@ -37,6 +37,8 @@ use crate::multicore::{
 };
 
 use std::marker::PhantomData;
+use std::io::{self, Read, Write};
+use byteorder::{BigEndian, WriteBytesExt, ReadBytesExt};
 
 use crate::sonic::cs::{Backend, SynthesisDriver};
 use crate::sonic::srs::SRS;
@@ -84,6 +86,68 @@ pub struct CircuitParameters<E: Engine> {
     _marker: PhantomData<E>
 }
 
+impl<E: Engine> PartialEq for CircuitParameters<E> {
+    fn eq(&self, other: &CircuitParameters<E>) -> bool {
+        self.num_inputs == other.num_inputs &&
+        self.num_aux == other.num_aux &&
+        self.num_constraints == other.num_constraints &&
+        self.k_map == other.k_map &&
+        self.n == other.n &&
+        self.q == other.q
+    }
+}
+
+impl<E: Engine> Eq for CircuitParameters<E> {}
+
+impl<E: Engine> CircuitParameters<E> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        writer.write_u32::<BigEndian>(self.num_inputs as u32)?;
+        writer.write_u32::<BigEndian>(self.num_aux as u32)?;
+        writer.write_u32::<BigEndian>(self.num_constraints as u32)?;
+
+        writer.write_u32::<BigEndian>(self.k_map.len() as u32)?;
+        for k in &self.k_map {
+            writer.write_u32::<BigEndian>(*k as u32)?;
+        }
+        writer.write_u32::<BigEndian>(self.n as u32)?;
+        writer.write_u32::<BigEndian>(self.q as u32)?;
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let num_inputs = reader.read_u32::<BigEndian>()? as usize;
+        let num_aux = reader.read_u32::<BigEndian>()? as usize;
+        let num_constraints = reader.read_u32::<BigEndian>()? as usize;
+
+        let k_map_len = reader.read_u32::<BigEndian>()? as usize;
+        let mut k_map = vec![];
+        for _ in 0..k_map_len {
+            k_map.push(reader.read_u32::<BigEndian>()? as usize);
+        }
+
+        let n = reader.read_u32::<BigEndian>()? as usize;
+        let q = reader.read_u32::<BigEndian>()? as usize;
+
+        Ok(CircuitParameters {
+            num_inputs,
+            num_aux,
+            num_constraints,
+            k_map,
+            n,
+            q,
+            _marker: PhantomData
+        })
+    }
+}
+
 /// This is our assembly structure that we'll use to synthesize the
 /// circuit into
 struct GeneratorAssembly<'a, E: Engine, CS: SonicConstraintSystem<E> + 'a> {
@@ -351,26 +415,59 @@ pub fn generate_parameters_on_srs_and_information<E: Engine>(
     information: CircuitParameters<E>
 ) -> Result<Parameters<E>, SynthesisError>
 {
-    assert!(srs.d >= information.n * 4 + 2*NUM_BLINDINGS);
     let min_d = information.n * 4 + 2*NUM_BLINDINGS;
 
-    let trimmed_srs: SRS<E> = SRS {
-        d: min_d,
-        g_negative_x: srs.g_negative_x[0..min_d+1].to_vec(),
-        g_positive_x: srs.g_positive_x[0..min_d+1].to_vec().clone(),
+    generate_parameters_on_trimmed_srs(srs, information, min_d)
+}
+
+/// Generates parameters for the unhelped Sonic, where the aggregate proves
+/// s(z, w) with grand product arguments over permutations of length 3n + 1,
+/// so the SRS must be larger than for the helped one
+pub fn generate_parameters_for_succinct_sonic<E, C>(
+    circuit: C,
+    alpha: E::Fr,
+    x: E::Fr
+) -> Result<Parameters<E>, SynthesisError>
+    where E: Engine, C: Circuit<E>
+{
+    let circuit_parameters = get_circuit_parameters_for_succinct_sonic::<E, C>(circuit)?;
+    let min_d = succinct_sonic_min_d(circuit_parameters.n);
+
+    let srs = generate_srs(alpha, x, min_d)?;
 
-        h_negative_x: srs.h_negative_x[0..min_d+1].to_vec(),
-        h_positive_x: srs.h_positive_x[0..min_d+1].to_vec(),
+    let parameters = generate_parameters_on_trimmed_srs::<E>(&srs, circuit_parameters, min_d)?;
 
-        g_negative_x_alpha: srs.g_negative_x_alpha[0..min_d].to_vec(),
-        g_positive_x_alpha: srs.g_positive_x_alpha[0..min_d].to_vec(),
+    Ok(parameters)
+}
 
-        h_negative_x_alpha: srs.h_negative_x_alpha[0..min_d+1].to_vec(),
-        h_positive_x_alpha: srs.h_positive_x_alpha[0..min_d+1].to_vec(),
+pub fn generate_parameters_for_succinct_sonic_on_srs<E, C>(
+    circuit: C,
+    srs: &SRS<E>,
+) -> Result<Parameters<E>, SynthesisError>
+    where E: Engine, C: Circuit<E>
+{
+    let circuit_parameters = get_circuit_parameters_for_succinct_sonic::<E, C>(circuit)?;
+    let min_d = succinct_sonic_min_d(circuit_parameters.n);
+    let parameters = generate_parameters_on_trimmed_srs(srs, circuit_parameters, min_d)?;
 
-    };
+    Ok(parameters)
+}
+
+fn succinct_sonic_min_d(n: usize) -> usize {
+    std::cmp::max(n * 6 + 3, n * 4 + 2*NUM_BLINDINGS)
+}
+
+fn generate_parameters_on_trimmed_srs<E: Engine>(
+    srs: &SRS<E>,
+    information: CircuitParameters<E>,
+    min_d: usize
+) -> Result<Parameters<E>, SynthesisError>
+{
+    let trimmed_srs = srs.trim(min_d);
 
     let vk = VerifyingKey {
+        g: trimmed_srs.g_positive_x[0],
+
         alpha_x: trimmed_srs.h_positive_x_alpha[1],
 
         alpha: trimmed_srs.h_positive_x_alpha[0],
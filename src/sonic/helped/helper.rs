@@ -1,6 +1,8 @@
 use crate::pairing::ff::{Field};
 use crate::pairing::{Engine, CurveProjective};
 use std::marker::PhantomData;
+use std::io::{self, Read, Write};
+use byteorder::{BigEndian, WriteBytesExt, ReadBytesExt};
 
 use super::{Proof, SxyAdvice};
 use super::batch::Batch;
@@ -31,6 +33,86 @@ pub struct Aggregate<E: Engine> {
     pub w: E::Fr,
 }
 
+impl<E: Engine> PartialEq for Aggregate<E> {
+    fn eq(&self, other: &Aggregate<E>) -> bool {
+        self.c == other.c &&
+        self.s_opening == other.s_opening &&
+        self.c_openings == other.c_openings &&
+        self.opening == other.opening &&
+        self.z == other.z &&
+        self.w == other.w
+    }
+}
+
+impl<E: Engine> Eq for Aggregate<E> {}
+
+impl<E: Engine> Aggregate<E> {
+    pub fn write<W: Write>(
+        &self,
+        writer: W
+    ) -> io::Result<()>
+    {
+        write_aggregate_openings::<E, _>(&self.c, &self.s_opening, &self.c_openings, &self.opening, &self.z, &self.w, writer)
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let c = read_point(&mut reader)?;
+        let s_opening = read_point(&mut reader)?;
+
+        let num_openings = reader.read_u32::<BigEndian>()? as usize;
+        let mut c_openings = vec![];
+        for _ in 0..num_openings {
+            let opening = read_point(&mut reader)?;
+            let value = read_field_element(&mut reader)?;
+
+            c_openings.push((opening, value));
+        }
+
+        let opening = read_point(&mut reader)?;
+        let z = read_field_element(&mut reader)?;
+        let w = read_field_element(&mut reader)?;
+
+        Ok(Aggregate {
+            c,
+            s_opening,
+            c_openings,
+            opening,
+            z,
+            w
+        })
+    }
+}
+
+// the succinct aggregate carries the same openings after its own arguments
+pub(crate) fn write_aggregate_openings<E: Engine, W: Write>(
+    c: &E::G1Affine,
+    s_opening: &E::G1Affine,
+    c_openings: &[(E::G1Affine, E::Fr)],
+    opening: &E::G1Affine,
+    z: &E::Fr,
+    w: &E::Fr,
+    mut writer: W
+) -> io::Result<()>
+{
+    write_point(c, &mut writer)?;
+    write_point(s_opening, &mut writer)?;
+
+    writer.write_u32::<BigEndian>(c_openings.len() as u32)?;
+    for (opening, value) in c_openings.iter() {
+        write_point(opening, &mut writer)?;
+        write_field_element(value, &mut writer)?;
+    }
+
+    write_point(opening, &mut writer)?;
+    write_field_element(z, &mut writer)?;
+    write_field_element(w, &mut writer)?;
+
+    Ok(())
+}
+
 pub fn create_aggregate<E: Engine, C: Circuit<E>, S: SynthesisDriver>(
     circuit: &C,
     inputs: &[(Proof<E>, SxyAdvice<E>)],
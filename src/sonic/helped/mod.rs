@@ -9,6 +9,7 @@ pub mod verifier;
 pub mod helper;
 pub mod parameters;
 pub mod generator;
+pub mod solidity;
 mod adapted_prover;
 mod adapted_verifier;
 mod adapted_helper;
@@ -21,6 +22,8 @@ pub use self::generator::{
     generate_parameters, 
     generate_parameters_on_srs, 
     generate_parameters_on_srs_and_information, 
+    generate_parameters_for_succinct_sonic,
+    generate_parameters_for_succinct_sonic_on_srs,
     generate_random_parameters, 
     generate_srs,
     get_circuit_parameters,
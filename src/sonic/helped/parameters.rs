@@ -19,6 +19,8 @@ use std::io::{self, Read, Write};
 use std::sync::Arc;
 use byteorder::{BigEndian, WriteBytesExt, ReadBytesExt};
 
+use crate::sonic::util::{write_point, read_point, write_field_element, read_field_element};
+
 pub const NUM_BLINDINGS: usize = 6;
 // pub const NUM_BLINDINGS: usize = 0;
 
@@ -37,6 +39,35 @@ impl<E: Engine> PartialEq for SxyAdvice<E> {
     }
 }
 
+impl<E: Engine> SxyAdvice<E> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        write_point(&self.s, &mut writer)?;
+        write_point(&self.opening, &mut writer)?;
+        write_field_element(&self.szy, &mut writer)?;
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let s = read_point(&mut reader)?;
+        let opening = read_point(&mut reader)?;
+        let szy = read_field_element(&mut reader)?;
+
+        Ok(SxyAdvice {
+            s,
+            opening,
+            szy
+        })
+    }
+}
+
 #[derive(Clone, Debug, Eq)]
 pub struct Proof<E: Engine> {
     pub r: E::G1Affine,
@@ -158,6 +189,10 @@ impl<E: Engine> Proof<E> {
 
 #[derive(Clone, Debug, Eq)]
 pub struct VerifyingKey<E: Engine> {
+    // generator of G1 used by the SRS, which is not the standard one
+    // if the SRS was imported from powers of tau
+    pub g: E::G1Affine,
+
     pub alpha_x: E::G2Affine,
 
     pub alpha: E::G2Affine,
@@ -175,6 +210,7 @@ pub struct VerifyingKey<E: Engine> {
 
 impl<E: Engine> PartialEq for VerifyingKey<E> {
     fn eq(&self, other: &VerifyingKey<E>) -> bool {
+        self.g == other.g &&
         self.alpha_x == other.alpha_x &&
         self.alpha == other.alpha &&
         self.neg_h == other.neg_h &&
@@ -191,6 +227,7 @@ impl<E: Engine> VerifyingKey<E> {
         mut writer: W
     ) -> io::Result<()>
     {
+        writer.write_all(self.g.into_uncompressed().as_ref())?;
         writer.write_all(self.alpha_x.into_uncompressed().as_ref())?;
         writer.write_all(self.alpha.into_uncompressed().as_ref())?;
         writer.write_all(self.neg_h.into_uncompressed().as_ref())?;
@@ -210,6 +247,11 @@ impl<E: Engine> VerifyingKey<E> {
         mut reader: R
     ) -> io::Result<Self>
     {
+        let mut g1_repr = <E::G1Affine as CurveAffine>::Uncompressed::empty();
+
+        reader.read_exact(g1_repr.as_mut())?;
+        let g = g1_repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
         let mut g2_repr = <E::G2Affine as CurveAffine>::Uncompressed::empty();
 
         reader.read_exact(g2_repr.as_mut())?;
@@ -239,6 +281,7 @@ impl<E: Engine> VerifyingKey<E> {
         let q = reader.read_u32::<BigEndian>()? as usize;
 
         Ok(VerifyingKey {
+            g: g,
             alpha_x: alpha_x,
             alpha: alpha,
             neg_h: neg_h,
@@ -264,6 +307,8 @@ impl<E: Engine> VerifyingKey<E> {
         S::synthesize(&mut preprocess, &circuit)?;
 
         Ok(Self {
+            g: srs.g_positive_x[0],
+
             alpha_x: srs.h_positive_x_alpha[1],
 
             alpha: srs.h_positive_x_alpha[0],
@@ -8,6 +8,7 @@ use super::poly::{SxEval, SyEval};
 use super::parameters::{Parameters, NUM_BLINDINGS};
 
 use crate::SynthesisError;
+use crate::multicore::Worker;
 
 use crate::sonic::transcript::{Transcript, TranscriptProtocol};
 use crate::sonic::util::*;
@@ -22,6 +23,19 @@ pub fn create_advice_on_information_and_srs<E: Engine, C: Circuit<E>, S: Synthes
     srs: &SRS<E>,
     n: usize
 ) -> Result<SxyAdvice<E>, SynthesisError>
+{
+    create_advice_on_information_and_srs_with_worker::<E, C, S>(circuit, proof, srs, n, &Worker::new())
+}
+
+/// Same as `create_advice_on_information_and_srs`, but all the multiexps,
+/// divisions and commitments run on the given `worker`
+pub fn create_advice_on_information_and_srs_with_worker<E: Engine, C: Circuit<E>, S: SynthesisDriver>(
+    circuit: &C,
+    proof: &Proof<E>,
+    srs: &SRS<E>,
+    n: usize,
+    worker: &Worker
+) -> Result<SxyAdvice<E>, SynthesisError>
 {
     let z: E::Fr;
     let y: E::Fr;
@@ -43,12 +57,12 @@ pub fn create_advice_on_information_and_srs<E: Engine, C: Circuit<E>, S: Synthes
     };
 
     // Compute S commitment
-    let s = multiexp(
-        srs.g_positive_x_alpha[0..(2 * n)]
-            .iter()
-            .chain_ext(srs.g_negative_x_alpha[0..(n)].iter()),
-        s_poly_positive.iter().chain_ext(s_poly_negative.iter())
-    ).into_affine();
+    let s = {
+        let mut s = parallel_multiexp(&srs.g_positive_x_alpha[0..(2 * n)], &s_poly_positive, worker);
+        s.add_assign(&parallel_multiexp(&srs.g_negative_x_alpha[0..n], &s_poly_negative, worker));
+
+        s.into_affine()
+    };
 
     // Compute s(z, y)
     let mut szy = E::Fr::zero();
@@ -80,19 +94,13 @@ pub fn create_advice_on_information_and_srs<E: Engine, C: Circuit<E>, S: Synthes
         let mut open = szy;
         open.negate();
 
-        let poly = kate_divison(
-            s_poly_negative.iter().rev().chain_ext(Some(open).iter()).chain_ext(s_poly_positive.iter()),
-            z,
-        );
-
-        let negative_poly = poly[0..n].iter().rev();
-        let positive_poly = poly[n..].iter();
-        multiexp(
-            srs.g_negative_x[1..(negative_poly.len() + 1)].iter().chain_ext(
-                srs.g_positive_x[0..positive_poly.len()].iter()
-            ),
-            negative_poly.chain_ext(positive_poly)
-        ).into_affine()
+        // s(X, y) - s(z, y) for powers X^{-n}...X^{2n}
+        let mut poly = s_poly_negative;
+        poly.reverse();
+        poly.push(open);
+        poly.extend(s_poly_positive);
+
+        parallel_polynomial_commitment_opening(n, 2 * n, &poly, z, srs, worker)
     };
 
     Ok(SxyAdvice {
@@ -145,6 +153,17 @@ pub fn create_proof_on_srs<E: Engine, C: Circuit<E>, S: SynthesisDriver>(
     circuit: &C,
     srs: &SRS<E>
 ) -> Result<Proof<E>, SynthesisError>
+{
+    create_proof_on_srs_with_worker::<E, C, S>(circuit, srs, &Worker::new())
+}
+
+/// Same as `create_proof_on_srs`, but the FFT multiplication, the divisions
+/// and the commitments run on the given `worker`
+pub fn create_proof_on_srs_with_worker<E: Engine, C: Circuit<E>, S: SynthesisDriver>(
+    circuit: &C,
+    srs: &SRS<E>,
+    worker: &Worker
+) -> Result<Proof<E>, SynthesisError>
 {
     let mut wires = Wires::new();
 
@@ -159,23 +178,6 @@ pub fn create_proof_on_srs<E: Engine, C: Circuit<E>, S: SynthesisDriver>(
     // c_{n+1}, c_{n+2}, c_{n+3}, c_{n+4}
     let blindings: Vec<E::Fr> = (0..NUM_BLINDINGS).into_iter().map(|_| E::Fr::rand(rng)).collect();
 
-    // r is a commitment to r(X, 1)
-    let r = polynomial_commitment::<E, _>(
-        n, 
-        2*n + NUM_BLINDINGS, 
-        n, 
-        &srs,
-        blindings.iter().rev()
-            .chain_ext(wires.c.iter().rev())
-            .chain_ext(wires.b.iter().rev())
-            .chain_ext(Some(E::Fr::zero()).iter())
-            .chain_ext(wires.a.iter()),
-    );
-
-    transcript.commit_point(&r);
-
-    let y: E::Fr = transcript.get_challenge_scalar();
-
     // create r(X, 1) by observation that it's just a series of coefficients.
     // Used representation is for powers X^{-2n}...X^{-n-1}, X^{-n}...X^{-1}, X^{1}...X^{n}
     // Same representation is ok for r(X, Y) too cause powers always match
@@ -186,6 +188,13 @@ pub fn create_proof_on_srs<E: Engine, C: Circuit<E>, S: SynthesisDriver>(
     rx1.push(E::Fr::zero());
     rx1.extend(wires.a);
 
+    // r is a commitment to r(X, 1)
+    let r = parallel_polynomial_commitment(n, 2*n + NUM_BLINDINGS, n, srs, &rx1, worker);
+
+    transcript.commit_point(&r);
+
+    let y: E::Fr = transcript.get_challenge_scalar();
+
     let mut rxy = rx1.clone();
 
     let y_inv = y.inverse().ok_or(SynthesisError::DivisionByZero)?;
@@ -237,19 +246,17 @@ pub fn create_proof_on_srs<E: Engine, C: Circuit<E>, S: SynthesisDriver>(
 
     // t(X, y) = r'(X, y)*r(X, 1) and will be later evaluated at z
     // contained degree in respect to X are from -4*n to 3*n including X^0
-    let mut txy = multiply_polynomials::<E>(rx1.clone(), rxy_prime);
+    let mut txy = parallel_multiply_polynomials::<E>(rx1.clone(), rxy_prime, worker);
     txy[4 * n + 2 * NUM_BLINDINGS] = E::Fr::zero(); // -k(y)
 
     // commit to t(X, y) to later open at z
-    let t = polynomial_commitment(
-        srs.d, 
-        (4 * n) + 2*NUM_BLINDINGS,
-        3 * n,
-        srs,
+    let t = {
         // skip what would be zero power
-        txy[0..(4 * n) + 2*NUM_BLINDINGS].iter()
-            .chain_ext(txy[(4 * n + 2*NUM_BLINDINGS + 1)..].iter()),
-    );
+        let mut coeffs = txy[0..(4 * n + 2*NUM_BLINDINGS)].to_vec();
+        coeffs.extend_from_slice(&txy[(4 * n + 2*NUM_BLINDINGS + 1)..]);
+
+        parallel_polynomial_commitment(srs.d, 4 * n + 2*NUM_BLINDINGS, 3 * n, srs, &coeffs, worker)
+    };
 
     transcript.commit_point(&t);
 
@@ -282,13 +289,7 @@ pub fn create_proof_on_srs<E: Engine, C: Circuit<E>, S: SynthesisDriver>(
         let mut point = y;
         point.mul_assign(&z);
 
-        polynomial_commitment_opening(
-            2 * n + NUM_BLINDINGS,
-            n, 
-            &rx1,
-            point,
-            srs
-        )
+        parallel_polynomial_commitment_opening(2 * n + NUM_BLINDINGS, n, &rx1, point, srs, worker)
     };
 
     assert_eq!(rx1.len(), 3*n + NUM_BLINDINGS + 1);
@@ -315,12 +316,7 @@ pub fn create_proof_on_srs<E: Engine, C: Circuit<E>, S: SynthesisDriver>(
 
         txy[(4 * n + 2*NUM_BLINDINGS)].sub_assign(&val);
 
-        polynomial_commitment_opening(
-            4*n + 2*NUM_BLINDINGS,
-            3*n, 
-            &txy,
-            z, 
-            srs)
+        parallel_polynomial_commitment_opening(4*n + 2*NUM_BLINDINGS, 3*n, &txy, z, srs, worker)
     };
 
     Ok(Proof {
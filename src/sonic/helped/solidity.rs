@@ -0,0 +1,907 @@
+//! Generation of Solidity verifiers for helped Sonic proofs on BN256.
+//!
+//! The contract replays the `Keccak256Hasher` transcript of `MultiVerifier::add_proof`
+//! and performs the `Batch` pairing check through the EVM precompiles. Since the
+//! verifying key does not contain `s(X, Y)`, the circuit is synthesized once and its
+//! sparse representation is embedded into the contract, so the verifier evaluates
+//! `s(z, y)` on its own. The random coefficients used by `Batch` are replaced by
+//! challenges drawn from the transcript after the proof and the public inputs are
+//! committed to.
+
+use crate::pairing::ff::{Field, PrimeField, PrimeFieldRepr};
+use crate::pairing::{CurveAffine};
+use crate::pairing::bn256::{Bn256, Fq, Fr, G1Affine, G2Affine};
+
+use std::collections::BTreeMap;
+
+use super::{Proof, VerifyingKey};
+use super::batch::Batch;
+
+use crate::SynthesisError;
+
+use crate::sonic::cs::{Backend, SynthesisDriver};
+use crate::sonic::cs::{Circuit, Variable, Coeff};
+use crate::sonic::transcript::{Transcript, TranscriptProtocol};
+use crate::sonic::transcript::hasher::{Hasher, Keccak256Hasher};
+
+/// Signature of the verifying function of the generated contract.
+pub const VERIFY_FUNCTION_SIGNATURE: &str = "verify(uint256[10],uint256[])";
+
+/// A single monomial `coeff * X^{x_exp} * Y^{y_exp}` of `s(X, Y)`, where
+/// the power of `X` is negative if `x_inverse` is set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SxyTerm {
+    pub x_inverse: bool,
+    pub x_exp: u64,
+    pub y_exp: u64,
+    pub coeff: Fr,
+}
+
+/// Sparse representation of `s(X, Y)` without the `-Y^i - Y^{-i}` terms
+/// on `X^{i+N}`, which are the same for every circuit of size `n`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SxyTerms {
+    pub n: usize,
+    pub terms: Vec<SxyTerm>,
+}
+
+struct SxyTermsRecorder {
+    n: usize,
+    q: usize,
+    // (wire kind, gate index, constraint index) -> accumulated coefficient
+    coeffs: BTreeMap<(u8, usize, usize), Fr>,
+}
+
+impl Backend<Bn256> for &mut SxyTermsRecorder {
+    type LinearConstraintIndex = usize;
+
+    fn new_multiplication_gate(&mut self) {
+        self.n += 1;
+    }
+
+    fn new_linear_constraint(&mut self) -> usize {
+        self.q += 1;
+
+        self.q
+    }
+
+    fn get_for_q(&self, q: usize) -> usize {
+        q
+    }
+
+    fn insert_coefficient(&mut self, var: Variable, coeff: Coeff<Bn256>, q: &usize) {
+        let key = match var {
+            Variable::A(index) => (0u8, index, *q),
+            Variable::B(index) => (1u8, index, *q),
+            Variable::C(index) => (2u8, index, *q),
+        };
+
+        let mut value = Fr::one();
+        coeff.multiply(&mut value);
+
+        self.coeffs.entry(key).or_insert_with(Fr::zero).add_assign(&value);
+    }
+}
+
+impl SxyTerms {
+    /// Collect the monomials of `s(X, Y)` by synthesizing the circuit.
+    pub fn new<C: Circuit<Bn256>, S: SynthesisDriver>(circuit: &C) -> Result<Self, SynthesisError> {
+        let mut recorder = SxyTermsRecorder {
+            n: 0,
+            q: 0,
+            coeffs: BTreeMap::new(),
+        };
+
+        S::synthesize(&mut recorder, circuit)?;
+
+        let n = recorder.n;
+        let terms = recorder.coeffs.into_iter()
+            .filter(|(_, coeff)| !coeff.is_zero())
+            .map(|((kind, index, q), coeff)| {
+                let (x_inverse, x_exp) = match kind {
+                    0 => (true, index),
+                    1 => (false, index),
+                    _ => (false, index + n),
+                };
+
+                SxyTerm {
+                    x_inverse,
+                    x_exp: x_exp as u64,
+                    y_exp: (n + q) as u64,
+                    coeff
+                }
+            })
+            .collect();
+
+        Ok(SxyTerms {
+            n,
+            terms
+        })
+    }
+
+    /// Evaluate `s(z, y)` the same way the generated contract does.
+    /// Both `z` and `y` are inverted, so neither of them may be zero.
+    pub fn evaluate(&self, z: Fr, y: Fr) -> Result<Fr, SynthesisError> {
+        let z_inv = z.inverse().ok_or(SynthesisError::DivisionByZero)?;
+
+        let mut acc = Fr::zero();
+        for term in self.terms.iter() {
+            let base = if term.x_inverse { z_inv } else { z };
+            let mut tmp = base.pow([term.x_exp]);
+            tmp.mul_assign(&y.pow([term.y_exp]));
+            tmp.mul_assign(&term.coeff);
+            acc.add_assign(&tmp);
+        }
+
+        let y_inv = y.inverse().ok_or(SynthesisError::DivisionByZero)?;
+
+        let mut y_i = y;
+        let mut y_inv_i = y_inv;
+        let mut z_i = z.pow([(self.n + 1) as u64]);
+        for _ in 0..self.n {
+            let mut tmp = y_i;
+            tmp.add_assign(&y_inv_i);
+            tmp.mul_assign(&z_i);
+            acc.sub_assign(&tmp);
+
+            y_i.mul_assign(&y);
+            y_inv_i.mul_assign(&y_inv);
+            z_i.mul_assign(&z);
+        }
+
+        Ok(acc)
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(self.terms.len() * 64);
+        for term in self.terms.iter() {
+            let mut header = [0u8; 32];
+            if term.x_inverse {
+                header[15] = 1;
+            }
+            header[16..24].copy_from_slice(&term.x_exp.to_be_bytes());
+            header[24..32].copy_from_slice(&term.y_exp.to_be_bytes());
+            result.extend_from_slice(&header);
+            result.extend_from_slice(&field_to_bytes(&term.coeff));
+        }
+
+        result
+    }
+}
+
+fn field_to_bytes<F: PrimeField>(el: &F) -> Vec<u8> {
+    let mut buffer = vec![];
+    el.into_repr().write_be(&mut buffer).unwrap();
+
+    buffer
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn field_to_hex<F: PrimeField>(el: &F) -> String {
+    format!("0x{}", to_hex(&field_to_bytes(el)))
+}
+
+fn g1_to_words(p: &G1Affine) -> [Vec<u8>; 2] {
+    if p.is_zero() {
+        return [vec![0u8; 32], vec![0u8; 32]];
+    }
+    let (x, y) = p.into_xy_unchecked();
+
+    [field_to_bytes(&x), field_to_bytes(&y)]
+}
+
+// precompile order is (x.c1, x.c0, y.c1, y.c0)
+fn g2_to_hex(p: &G2Affine) -> [String; 4] {
+    let (x, y) = p.into_xy_unchecked();
+
+    [field_to_hex(&x.c1), field_to_hex(&x.c0), field_to_hex(&y.c1), field_to_hex(&y.c0)]
+}
+
+fn function_selector() -> [u8; 4] {
+    let digest = Keccak256Hasher::new(VERIFY_FUNCTION_SIGNATURE.as_bytes()).finalize();
+    let mut selector = [0u8; 4];
+    selector.copy_from_slice(&digest[0..4]);
+
+    selector
+}
+
+/// ABI encode a call of `verify(uint256[10] proof, uint256[] inputs)` of the
+/// generated contract. Points at infinity are encoded as `(0, 0)`.
+pub fn encode_proof_calldata(proof: &Proof<Bn256>, inputs: &[Fr]) -> Vec<u8> {
+    let mut result = function_selector().to_vec();
+
+    let [r_x, r_y] = g1_to_words(&proof.r);
+    let [t_x, t_y] = g1_to_words(&proof.t);
+    let [z_x, z_y] = g1_to_words(&proof.z_opening);
+    let [zy_x, zy_y] = g1_to_words(&proof.zy_opening);
+    for word in [r_x, r_y, t_x, t_y, field_to_bytes(&proof.rz), field_to_bytes(&proof.rzy), z_x, z_y, zy_x, zy_y].iter() {
+        result.extend_from_slice(word);
+    }
+
+    // offset of the dynamic array follows the ten static words
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&(11u64 * 32).to_be_bytes());
+    result.extend_from_slice(&word);
+    word[24..].copy_from_slice(&(inputs.len() as u64).to_be_bytes());
+    result.extend_from_slice(&word);
+
+    for input in inputs.iter() {
+        result.extend_from_slice(&field_to_bytes(input));
+    }
+
+    result
+}
+
+fn read_word<F: PrimeField>(word: &[u8]) -> Option<F> {
+    let mut repr = F::Repr::default();
+    repr.read_be(word).ok()?;
+
+    F::from_repr(repr).ok()
+}
+
+fn read_point(words: &[u8]) -> Option<G1Affine> {
+    let x: Fq = read_word(&words[0..32])?;
+    let y: Fq = read_word(&words[32..64])?;
+
+    // the precompiles take (0, 0) as the point at infinity
+    if x.is_zero() && y.is_zero() {
+        return Some(G1Affine::zero());
+    }
+
+    G1Affine::from_xy_checked(x, y).ok()
+}
+
+fn decode_calldata(calldata: &[u8]) -> Option<(Proof<Bn256>, Vec<Fr>)> {
+    if calldata.len() < 4 + 12 * 32 || calldata[0..4] != function_selector() {
+        return None;
+    }
+    let words = &calldata[4..];
+
+    let proof = Proof {
+        r: read_point(&words[0..64])?,
+        t: read_point(&words[64..128])?,
+        rz: read_word(&words[128..160])?,
+        rzy: read_word(&words[160..192])?,
+        z_opening: read_point(&words[192..256])?,
+        zy_opening: read_point(&words[256..320])?,
+    };
+
+    let mut offset = [0u8; 8];
+    offset.copy_from_slice(&words[344..352]);
+    if words[320..344].iter().any(|b| *b != 0) || u64::from_be_bytes(offset) != 11 * 32 {
+        return None;
+    }
+    let mut length = [0u8; 8];
+    length.copy_from_slice(&words[376..384]);
+    if words[352..376].iter().any(|b| *b != 0) {
+        return None;
+    }
+    let length = u64::from_be_bytes(length) as usize;
+    if words.len() != 12 * 32 + length * 32 {
+        return None;
+    }
+
+    let mut inputs = Vec::with_capacity(length);
+    for word in words[384..].chunks(32) {
+        inputs.push(read_word(word)?);
+    }
+
+    Some((proof, inputs))
+}
+
+/// Challenges drawn by `replayTranscript` of the generated contract.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Challenges {
+    y: Fr,
+    z: Fr,
+    r1: Fr,
+    random_zy: Fr,
+    random_z: Fr,
+}
+
+fn replay_transcript(proof: &Proof<Bn256>, inputs: &[Fr]) -> Challenges {
+    let mut transcript = Transcript::new(&[]);
+
+    transcript.commit_point(&proof.r);
+
+    let y: Fr = transcript.get_challenge_scalar();
+
+    transcript.commit_point(&proof.t);
+
+    let z: Fr = transcript.get_challenge_scalar();
+
+    transcript.commit_scalar(&proof.rz);
+    transcript.commit_scalar(&proof.rzy);
+
+    let r1: Fr = transcript.get_challenge_scalar();
+
+    transcript.commit_point(&proof.z_opening);
+    transcript.commit_point(&proof.zy_opening);
+
+    // the batching coefficients must depend on everything entering the check
+    for input in inputs.iter() {
+        transcript.commit_scalar(input);
+    }
+
+    let random_zy: Fr = transcript.get_challenge_scalar();
+    let random_z: Fr = transcript.get_challenge_scalar();
+
+    Challenges {
+        y,
+        z,
+        r1,
+        random_zy,
+        random_z
+    }
+}
+
+/// Verify the calldata of `verify` natively following the logic of the generated
+/// contract. Malformed calldata, such as a point that is not on the curve, gives
+/// `false`. A zero challenge gives `DivisionByZero`, where the contract reverts.
+pub fn verify_calldata<C: Circuit<Bn256>, S: SynthesisDriver>(
+    circuit: &C,
+    vk: &VerifyingKey<Bn256>,
+    calldata: &[u8]
+) -> Result<bool, SynthesisError> {
+    let terms = SxyTerms::new::<C, S>(circuit)?;
+    if terms.n != vk.n {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+
+    let (proof, inputs) = match decode_calldata(calldata) {
+        Some(decoded) => decoded,
+        None => return Ok(false)
+    };
+    if inputs.len() + 1 != vk.k_map.len() {
+        return Ok(false);
+    }
+
+    let Challenges { y, z, r1, random_zy, mut random_z } = replay_transcript(&proof, &inputs);
+
+    let mut batch = Batch::<Bn256>::new_from_key(vk);
+
+    {
+        let mut zy = z;
+        zy.mul_assign(&y);
+        batch.add_opening(proof.zy_opening, random_zy, zy);
+        batch.add_commitment_max_n(proof.r, random_zy);
+        batch.add_opening_value(proof.rzy, random_zy);
+    }
+
+    let mut ky = Fr::zero();
+    for (exp, input) in vk.k_map.iter().zip(Some(Fr::one()).iter().chain(inputs.iter())) {
+        let mut term = y.pow([(*exp + vk.n) as u64]);
+        term.mul_assign(input);
+        ky.add_assign(&term);
+    }
+
+    let szy = terms.evaluate(z, y)?;
+
+    // t(z, y) = (r(z, y) + s(z,y))*r(z, 1) - k(y)
+    let mut tzy = proof.rzy;
+    tzy.add_assign(&szy);
+    tzy.mul_assign(&proof.rz);
+    tzy.sub_assign(&ky);
+
+    batch.add_opening(proof.z_opening, random_z, z);
+    batch.add_opening_value(tzy, random_z);
+    batch.add_commitment(proof.t, random_z);
+
+    random_z.mul_assign(&r1);
+
+    batch.add_opening_value(proof.rz, random_z);
+    batch.add_commitment_max_n(proof.r, random_z);
+
+    Ok(batch.check_all())
+}
+
+/// Generate a Solidity contract verifying helped proofs of `circuit` under `vk`.
+pub fn generate_verifier_contract<C: Circuit<Bn256>, S: SynthesisDriver>(
+    circuit: &C,
+    vk: &VerifyingKey<Bn256>
+) -> Result<String, SynthesisError> {
+    let terms = SxyTerms::new::<C, S>(circuit)?;
+    if terms.n != vk.n || vk.k_map.is_empty() {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+
+    let mut ky = String::new();
+    for (i, exp) in vk.k_map.iter().enumerate() {
+        let exp = exp + vk.n;
+        if i == 0 {
+            ky.push_str(&format!("        ky = expmod(y, {});\n", exp));
+        } else {
+            ky.push_str(&format!(
+                "        ky = addmod(ky, mulmod(expmod(y, {}), inputs[{}], R_MOD), R_MOD);\n",
+                exp,
+                i - 1
+            ));
+        }
+    }
+
+    let mut contract = CONTRACT_TEMPLATE.to_owned();
+    let g2_points = [
+        ("ALPHA_X", &vk.alpha_x),
+        ("ALPHA", &vk.alpha),
+        ("NEG_H", &vk.neg_h),
+        ("NEG_X_N_MINUS_D", &vk.neg_x_n_minus_d),
+    ];
+    for (name, point) in g2_points.iter() {
+        let coordinates = g2_to_hex(point);
+        for (i, coordinate) in coordinates.iter().enumerate() {
+            contract = contract.replace(&format!("{{{{{}_{}}}}}", name, i), coordinate);
+        }
+    }
+
+    let (g_x, g_y) = vk.g.into_xy_unchecked();
+
+    let contract = contract
+        .replace("{{G_X}}", &field_to_hex(&g_x))
+        .replace("{{G_Y}}", &field_to_hex(&g_y))
+        .replace("{{N}}", &vk.n.to_string())
+        .replace("{{NUM_INPUTS}}", &(vk.k_map.len() - 1).to_string())
+        .replace("{{S_TERMS}}", &to_hex(&terms.encode()))
+        .replace("{{KY}}", ky.trim_end());
+
+    Ok(contract)
+}
+
+const CONTRACT_TEMPLATE: &str = r#"// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity ^0.8.0;
+
+/// Verifier of helped Sonic proofs, generated by bellman.
+contract SonicVerifier {
+    uint256 constant R_MOD = 0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001;
+    uint256 constant Q_MOD = 0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47;
+
+    // keccak256 of the empty personalization
+    bytes32 constant TRANSCRIPT_INIT = 0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470;
+    // uncompressed encoding of the point at infinity
+    uint256 constant INFINITY_FLAG = 1 << 254;
+
+    uint256 constant N = {{N}};
+    uint256 constant NUM_INPUTS = {{NUM_INPUTS}};
+
+    // generator of G1 used by the SRS
+    uint256 constant G_X = {{G_X}};
+    uint256 constant G_Y = {{G_Y}};
+
+    // G2 points in the precompile order (x.c1, x.c0, y.c1, y.c0)
+    uint256 constant ALPHA_X_0 = {{ALPHA_X_0}};
+    uint256 constant ALPHA_X_1 = {{ALPHA_X_1}};
+    uint256 constant ALPHA_X_2 = {{ALPHA_X_2}};
+    uint256 constant ALPHA_X_3 = {{ALPHA_X_3}};
+    uint256 constant ALPHA_0 = {{ALPHA_0}};
+    uint256 constant ALPHA_1 = {{ALPHA_1}};
+    uint256 constant ALPHA_2 = {{ALPHA_2}};
+    uint256 constant ALPHA_3 = {{ALPHA_3}};
+    uint256 constant NEG_H_0 = {{NEG_H_0}};
+    uint256 constant NEG_H_1 = {{NEG_H_1}};
+    uint256 constant NEG_H_2 = {{NEG_H_2}};
+    uint256 constant NEG_H_3 = {{NEG_H_3}};
+    uint256 constant NEG_X_N_MINUS_D_0 = {{NEG_X_N_MINUS_D_0}};
+    uint256 constant NEG_X_N_MINUS_D_1 = {{NEG_X_N_MINUS_D_1}};
+    uint256 constant NEG_X_N_MINUS_D_2 = {{NEG_X_N_MINUS_D_2}};
+    uint256 constant NEG_X_N_MINUS_D_3 = {{NEG_X_N_MINUS_D_3}};
+
+    // monomials of s(X, Y), 64 bytes each: a header
+    // (x_inverse << 128 | x_exp << 64 | y_exp) followed by the coefficient
+    bytes constant S_TERMS = hex"{{S_TERMS}}";
+
+    struct Challenges {
+        uint256 y;
+        uint256 z;
+        uint256 r1;
+        uint256 randomZy;
+        uint256 randomZ;
+    }
+
+    /// proof = [r.x, r.y, t.x, t.y, rz, rzy, z_opening.x, z_opening.y, zy_opening.x, zy_opening.y]
+    /// Returns false if a point is not on the curve or a value is not reduced.
+    /// Reverts if a challenge is zero.
+    function verify(uint256[10] calldata proof, uint256[] calldata inputs) external view returns (bool) {
+        if (inputs.length != NUM_INPUTS) {
+            return false;
+        }
+        if (!checkEncoding(proof, inputs)) {
+            return false;
+        }
+
+        Challenges memory c = replayTranscript(proof, inputs);
+        uint256 tzy = computeTzy(proof, inputs, c);
+
+        return checkPairing(proof, c, tzy);
+    }
+
+    function checkEncoding(uint256[10] calldata proof, uint256[] calldata inputs) internal pure returns (bool) {
+        for (uint256 i = 0; i < 10; i++) {
+            uint256 modulus = (i == 4 || i == 5) ? R_MOD : Q_MOD;
+            if (proof[i] >= modulus) {
+                return false;
+            }
+        }
+        for (uint256 i = 0; i < inputs.length; i++) {
+            if (inputs[i] >= R_MOD) {
+                return false;
+            }
+        }
+        for (uint256 i = 0; i < 10; i += 2) {
+            if (i != 4 && !isOnCurve(proof[i], proof[i + 1])) {
+                return false;
+            }
+        }
+
+        return true;
+    }
+
+    // y^2 = x^3 + 3, (0, 0) is the point at infinity
+    function isOnCurve(uint256 x, uint256 y) internal pure returns (bool) {
+        if (x == 0 && y == 0) {
+            return true;
+        }
+
+        return mulmod(y, y, Q_MOD) == addmod(mulmod(mulmod(x, x, Q_MOD), x, Q_MOD), 3, Q_MOD);
+    }
+
+    function replayTranscript(uint256[10] calldata proof, uint256[] calldata inputs) internal pure returns (Challenges memory c) {
+        bytes32 state = TRANSCRIPT_INIT;
+        uint32 nonce;
+
+        state = commitPoint(state, proof[0], proof[1]);
+        (c.y, nonce) = challenge(state, 0);
+
+        state = commitPoint(state, proof[2], proof[3]);
+        (c.z, nonce) = challenge(state, 0);
+
+        state = commitScalar(state, proof[4]);
+        state = commitScalar(state, proof[5]);
+        (c.r1, nonce) = challenge(state, 0);
+
+        state = commitPoint(state, proof[6], proof[7]);
+        state = commitPoint(state, proof[8], proof[9]);
+
+        for (uint256 i = 0; i < inputs.length; i++) {
+            state = commitScalar(state, inputs[i]);
+        }
+
+        (c.randomZy, nonce) = challenge(state, 0);
+        (c.randomZ, nonce) = challenge(state, nonce);
+    }
+
+    function commitPoint(bytes32 state, uint256 x, uint256 y) internal pure returns (bytes32) {
+        if (x == 0 && y == 0) {
+            return keccak256(abi.encodePacked(state, "point", INFINITY_FLAG, uint256(0)));
+        }
+
+        return keccak256(abi.encodePacked(state, "point", x, y));
+    }
+
+    function commitScalar(bytes32 state, uint256 s) internal pure returns (bytes32) {
+        return keccak256(abi.encodePacked(state, "scalar", s));
+    }
+
+    function challenge(bytes32 state, uint32 nonce) internal pure returns (uint256, uint32) {
+        while (true) {
+            uint256 candidate = uint256(keccak256(abi.encodePacked(state, nonce)));
+            nonce += 1;
+            if (candidate < R_MOD) {
+                return (candidate, nonce);
+            }
+        }
+    }
+
+    function computeTzy(uint256[10] calldata proof, uint256[] calldata inputs, Challenges memory c) internal view returns (uint256) {
+        uint256 y = c.y;
+        uint256 ky;
+{{KY}}
+
+        uint256 szy = evaluateS(c.z, y);
+
+        // t(z, y) = (r(z, y) + s(z, y)) * r(z, 1) - k(y)
+        uint256 tzy = mulmod(addmod(proof[5], szy, R_MOD), proof[4], R_MOD);
+
+        return addmod(tzy, R_MOD - ky, R_MOD);
+    }
+
+    function evaluateS(uint256 z, uint256 y) internal view returns (uint256 s) {
+        uint256 zInv = inverse(z);
+        bytes memory terms = S_TERMS;
+        for (uint256 offset = 0; offset < terms.length; offset += 64) {
+            uint256 header;
+            uint256 coeff;
+            assembly {
+                header := mload(add(terms, add(offset, 0x20)))
+                coeff := mload(add(terms, add(offset, 0x40)))
+            }
+            uint256 base = (header >> 128) == 0 ? z : zInv;
+            uint256 term = mulmod(
+                expmod(base, (header >> 64) & 0xffffffffffffffff),
+                expmod(y, header & 0xffffffffffffffff),
+                R_MOD
+            );
+            s = addmod(s, mulmod(term, coeff, R_MOD), R_MOD);
+        }
+
+        // -Y^i - Y^{-i} on X^{i+N}
+        uint256 yInv = inverse(y);
+        uint256 yI = y;
+        uint256 yInvI = yInv;
+        uint256 zI = expmod(z, N + 1);
+        for (uint256 i = 0; i < N; i++) {
+            s = addmod(s, R_MOD - mulmod(addmod(yI, yInvI, R_MOD), zI, R_MOD), R_MOD);
+            yI = mulmod(yI, y, R_MOD);
+            yInvI = mulmod(yInvI, yInv, R_MOD);
+            zI = mulmod(zI, z, R_MOD);
+        }
+    }
+
+    function checkPairing(uint256[10] calldata proof, Challenges memory c, uint256 tzy) internal view returns (bool) {
+        uint256[2] memory zOpening = [proof[6], proof[7]];
+        uint256[2] memory zyOpening = [proof[8], proof[9]];
+
+        // e(P', [alpha x] H) e([-point] P' + [value] G, [alpha] H) e(P, -H) e(R, -[x^{n - d}] H)
+        uint256[2] memory alphaX = ecAdd(ecMul(zyOpening, c.randomZy), ecMul(zOpening, c.randomZ));
+
+        uint256[2] memory alpha = ecMul([G_X, G_Y], openingValue(proof, c, tzy));
+        alpha = ecAdd(alpha, ecMul(zyOpening, R_MOD - mulmod(c.randomZy, mulmod(c.z, c.y, R_MOD), R_MOD)));
+        alpha = ecAdd(alpha, ecMul(zOpening, R_MOD - mulmod(c.randomZ, c.z, R_MOD)));
+
+        uint256[2] memory negH = ecMul([proof[2], proof[3]], c.randomZ);
+        uint256 randomR = addmod(c.randomZy, mulmod(c.randomZ, c.r1, R_MOD), R_MOD);
+        uint256[2] memory negXNMinusD = ecMul([proof[0], proof[1]], randomR);
+
+        uint256[24] memory input;
+        input[0] = alphaX[0];
+        input[1] = alphaX[1];
+        input[2] = ALPHA_X_0;
+        input[3] = ALPHA_X_1;
+        input[4] = ALPHA_X_2;
+        input[5] = ALPHA_X_3;
+        input[6] = alpha[0];
+        input[7] = alpha[1];
+        input[8] = ALPHA_0;
+        input[9] = ALPHA_1;
+        input[10] = ALPHA_2;
+        input[11] = ALPHA_3;
+        input[12] = negH[0];
+        input[13] = negH[1];
+        input[14] = NEG_H_0;
+        input[15] = NEG_H_1;
+        input[16] = NEG_H_2;
+        input[17] = NEG_H_3;
+        input[18] = negXNMinusD[0];
+        input[19] = negXNMinusD[1];
+        input[20] = NEG_X_N_MINUS_D_0;
+        input[21] = NEG_X_N_MINUS_D_1;
+        input[22] = NEG_X_N_MINUS_D_2;
+        input[23] = NEG_X_N_MINUS_D_3;
+
+        uint256[1] memory result;
+        bool success;
+        assembly {
+            success := staticcall(gas(), 8, input, 0x300, result, 0x20)
+        }
+        require(success, "pairing failed");
+
+        return result[0] == 1;
+    }
+
+    // randomZy * rzy + randomZ * tzy + randomZ * r1 * rz
+    function openingValue(uint256[10] calldata proof, Challenges memory c, uint256 tzy) internal pure returns (uint256 value) {
+        value = mulmod(c.randomZy, proof[5], R_MOD);
+        value = addmod(value, mulmod(c.randomZ, tzy, R_MOD), R_MOD);
+        value = addmod(value, mulmod(mulmod(c.randomZ, c.r1, R_MOD), proof[4], R_MOD), R_MOD);
+    }
+
+    function ecAdd(uint256[2] memory p1, uint256[2] memory p2) internal view returns (uint256[2] memory r) {
+        uint256[4] memory input = [p1[0], p1[1], p2[0], p2[1]];
+        bool success;
+        assembly {
+            success := staticcall(gas(), 6, input, 0x80, r, 0x40)
+        }
+        require(success, "ecAdd failed");
+    }
+
+    function ecMul(uint256[2] memory p, uint256 s) internal view returns (uint256[2] memory r) {
+        uint256[3] memory input = [p[0], p[1], s];
+        bool success;
+        assembly {
+            success := staticcall(gas(), 7, input, 0x60, r, 0x40)
+        }
+        require(success, "ecMul failed");
+    }
+
+    function expmod(uint256 base, uint256 exponent) internal view returns (uint256) {
+        uint256[6] memory input = [uint256(32), uint256(32), uint256(32), base, exponent, R_MOD];
+        uint256[1] memory result;
+        bool success;
+        assembly {
+            success := staticcall(gas(), 5, input, 0xc0, result, 0x20)
+        }
+        require(success, "expmod failed");
+
+        return result[0];
+    }
+
+    function inverse(uint256 a) internal view returns (uint256) {
+        require(a != 0, "division by zero");
+
+        return expmod(a, R_MOD - 2);
+    }
+}
+"#;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pairing::bn256::{Bn256, Fr};
+    use crate::pairing::ff::PrimeField;
+    use crate::sonic::helped::{generate_parameters, create_proof, verify_proofs};
+    use crate::sonic::helped::poly::SxEval;
+    use crate::sonic::sonic::{AdaptorCircuit, Nonassigning};
+    use crate::{Circuit as BellmanCircuit, ConstraintSystem};
+    use rand::{thread_rng, Rng};
+
+    #[derive(Clone)]
+    struct CubeDemo {
+        x: Option<Fr>
+    }
+
+    impl BellmanCircuit<Bn256> for CubeDemo {
+        fn synthesize<CS: ConstraintSystem<Bn256>>(
+            self,
+            cs: &mut CS
+        ) -> Result<(), SynthesisError>
+        {
+            let x_value = self.x;
+            let x = cs.alloc(|| "x", || x_value.ok_or(SynthesisError::AssignmentMissing))?;
+
+            let x_cubed_value = x_value.map(|x| { let mut tmp = x; tmp.square(); tmp.mul_assign(&x); tmp });
+            let x_squared = cs.alloc(|| "x^2", || x_value.map(|mut x| { x.square(); x }).ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce(|| "x * x = x^2", |lc| lc + x, |lc| lc + x, |lc| lc + x_squared);
+
+            let out = cs.alloc_input(|| "out", || x_cubed_value.ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce(|| "x^2 * x = out", |lc| lc + x_squared, |lc| lc + x, |lc| lc + out);
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_sxy_terms_match_sx_eval() {
+        let rng = &mut thread_rng();
+        let circuit = AdaptorCircuit(CubeDemo { x: None });
+        let terms = SxyTerms::new::<_, Nonassigning>(&circuit).unwrap();
+
+        for _ in 0..4 {
+            let y: Fr = rng.gen();
+            let z: Fr = rng.gen();
+
+            let mut tmp = SxEval::new(y, terms.n);
+            Nonassigning::synthesize(&mut tmp, &circuit).unwrap();
+
+            assert_eq!(terms.evaluate(z, y).unwrap(), tmp.finalize(z));
+        }
+
+        match terms.evaluate(Fr::zero(), Fr::one()) {
+            Err(SynthesisError::DivisionByZero) => {},
+            _ => panic!("z = 0 can not be inverted")
+        }
+        match terms.evaluate(Fr::one(), Fr::zero()) {
+            Err(SynthesisError::DivisionByZero) => {},
+            _ => panic!("y = 0 can not be inverted")
+        }
+    }
+
+    #[test]
+    fn test_solidity_verifier_cross_check() {
+        let srs_x = Fr::from_str("23923").unwrap();
+        let srs_alpha = Fr::from_str("23728792").unwrap();
+
+        let empty_circuit = CubeDemo { x: None };
+        let params = generate_parameters::<Bn256, _>(empty_circuit.clone(), srs_alpha, srs_x).unwrap();
+        let vk = &params.vk;
+        let adapted = AdaptorCircuit(empty_circuit.clone());
+
+        let circuit = CubeDemo { x: Some(Fr::from_str("3").unwrap()) };
+        let proof = create_proof(circuit, &params).unwrap();
+        let inputs = vec![Fr::from_str("27").unwrap()];
+
+        assert!(verify_proofs(&[proof.clone()], &[inputs.clone()], empty_circuit.clone(), thread_rng(), &params).unwrap());
+
+        let calldata = encode_proof_calldata(&proof, &inputs);
+        assert_eq!(calldata.len(), 4 + 13 * 32);
+        assert!(verify_calldata::<_, Nonassigning>(&adapted, vk, &calldata).unwrap());
+
+        // wrong public input
+        let wrong_inputs = vec![Fr::from_str("28").unwrap()];
+        assert!(!verify_proofs(&[proof.clone()], &[wrong_inputs.clone()], empty_circuit.clone(), thread_rng(), &params).unwrap());
+        let calldata = encode_proof_calldata(&proof, &wrong_inputs);
+        assert!(!verify_calldata::<_, Nonassigning>(&adapted, vk, &calldata).unwrap());
+
+        // tampered evaluation
+        let mut tampered = proof.clone();
+        tampered.rz.add_assign(&Fr::one());
+        assert!(!verify_proofs(&[tampered.clone()], &[inputs.clone()], empty_circuit.clone(), thread_rng(), &params).unwrap());
+        let calldata = encode_proof_calldata(&tampered, &inputs);
+        assert!(!verify_calldata::<_, Nonassigning>(&adapted, vk, &calldata).unwrap());
+
+        // malformed calldata
+        let calldata = encode_proof_calldata(&proof, &inputs);
+        assert!(!verify_calldata::<_, Nonassigning>(&adapted, vk, &calldata[..(calldata.len() - 1)]).unwrap());
+        assert!(!verify_calldata::<_, Nonassigning>(&adapted, vk, &encode_proof_calldata(&proof, &[])).unwrap());
+        let mut bad_scalar = calldata.clone();
+        for b in bad_scalar[(4 + 4 * 32)..(4 + 5 * 32)].iter_mut() {
+            *b = 0xff;
+        }
+        assert!(!verify_calldata::<_, Nonassigning>(&adapted, vk, &bad_scalar).unwrap());
+
+        // t.y = 1 is not on the curve, the contract returns false for it as well
+        let mut off_curve = calldata.clone();
+        for b in off_curve[(4 + 3 * 32)..(4 + 4 * 32)].iter_mut() {
+            *b = 0;
+        }
+        off_curve[4 + 4 * 32 - 1] = 1;
+        assert!(!verify_calldata::<_, Nonassigning>(&adapted, vk, &off_curve).unwrap());
+
+        let contract = generate_verifier_contract::<_, Nonassigning>(&adapted, vk).unwrap();
+        assert!(!contract.contains("{{"));
+        assert!(contract.contains(&format!("uint256 constant N = {};", vk.n)));
+        assert!(contract.contains("uint256 constant NUM_INPUTS = 1;"));
+        let (x, _) = vk.alpha_x.into_xy_unchecked();
+        assert!(contract.contains(&format!("uint256 constant ALPHA_X_0 = {};", field_to_hex(&x.c1))));
+        assert!(contract.contains(&format!("uint256 constant ALPHA_X_1 = {};", field_to_hex(&x.c0))));
+        let terms = SxyTerms::new::<_, Nonassigning>(&adapted).unwrap();
+        assert!(contract.contains(&format!("hex\"{}\"", to_hex(&terms.encode()))));
+        let (g_x, g_y) = vk.g.into_xy_unchecked();
+        assert!(contract.contains(&format!("uint256 constant G_X = {};", field_to_hex(&g_x))));
+        assert!(contract.contains(&format!("uint256 constant G_Y = {};", field_to_hex(&g_y))));
+    }
+
+    // Challenges that `replayTranscript` of the contract derives for
+    // proof = [g, 2g, 3, 4, infinity, g] and inputs = [5],
+    // computed independently of the Rust transcript
+    #[test]
+    fn test_transcript_golden_vectors() {
+        use crate::pairing::CurveProjective;
+
+        fn from_hex(hex: &str) -> Fr {
+            let bytes: Vec<u8> = (2..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..(i + 2)], 16).unwrap()).collect();
+
+            read_word(&bytes).unwrap()
+        }
+
+        let g = G1Affine::one();
+        let mut g2 = g.into_projective();
+        g2.double();
+
+        let proof = Proof::<Bn256> {
+            r: g,
+            t: g2.into_affine(),
+            rz: Fr::from_str("3").unwrap(),
+            rzy: Fr::from_str("4").unwrap(),
+            z_opening: G1Affine::zero(),
+            zy_opening: g,
+        };
+        let inputs = vec![Fr::from_str("5").unwrap()];
+
+        let expected = Challenges {
+            y: from_hex("0x0ffe59233d3b26f725d84b29611090f7dfa3a5161cc1c06d54af41db5796bf7d"),
+            z: from_hex("0x014413e6501eb1deecf6fb0c5f515fca6e7dcb17d88e12fea96b7b13addacd86"),
+            r1: from_hex("0x1e98cc81cb3b1cab002edd3f7639518db0015fb63922af990deebddb7300dd32"),
+            random_zy: from_hex("0x0b2eda38462f6a2d62ee64d5b752e45c48c008a153151dc3df5d751c8e3cd287"),
+            random_z: from_hex("0x03ae2aba08d9bb71052650e0e9aa843f60631feb0365f70171af0b6253434102"),
+        };
+
+        assert_eq!(replay_transcript(&proof, &inputs), expected);
+
+        // calldata round trip keeps the point at infinity
+        let calldata = encode_proof_calldata(&proof, &inputs);
+        let (decoded, decoded_inputs) = decode_calldata(&calldata).unwrap();
+        assert!(decoded.z_opening.is_zero());
+        assert_eq!(replay_transcript(&decoded, &decoded_inputs), expected);
+    }
+}
@@ -7,7 +7,7 @@ pub mod helped;
 pub mod cs;
 pub mod unhelped;
 
-mod transcript;
+pub mod transcript;
 
 #[cfg(test)]
 mod tests;
@@ -1,2 +1,7 @@
 mod srs;
-pub use self::srs::SRS;
\ No newline at end of file
+mod update;
+
+pub use self::srs::{SRS, PowersOfTauEncoding};
+#[cfg(test)]
+pub(crate) use self::srs::make_powers_of_tau_transcript;
+pub use self::update::{contribute, verify_update, verify_transcript, verify_srs, PublicKey, UpdateProof};
@@ -1,9 +1,14 @@
 use crate::pairing::ff::{Field, PrimeField};
-use crate::pairing::{CurveAffine, CurveProjective, Engine, Wnaf};
+use crate::pairing::{CurveAffine, CurveProjective, EncodedPoint, Engine, Wnaf};
 
 use std::io::{self, Read, Write};
 use std::sync::Arc;
 use byteorder::{BigEndian, WriteBytesExt, ReadBytesExt};
+use rand::Rng;
+
+use crate::multicore::Worker;
+
+use super::update::{nonzero_scalar, scale_powers, verify_srs};
 
 #[derive(Clone, Eq)]
 pub struct SRS<E: Engine> {
@@ -111,6 +116,164 @@ impl<E: Engine> SRS<E> {
             h_positive_x_alpha: table(alpha, x, d + 1, &mut g2),
         }
     }
+
+    /// Keeps the powers up to `new_d`, so the SRS of a large ceremony
+    /// can be used for circuits that need a smaller one
+    pub fn trim(&self, new_d: usize) -> Self {
+        assert!(new_d > 0);
+        assert!(new_d <= self.d, "SRS of degree {} can not be trimmed to degree {}", self.d, new_d);
+
+        SRS {
+            d: new_d,
+            g_negative_x: self.g_negative_x[0..=new_d].to_vec(),
+            g_positive_x: self.g_positive_x[0..=new_d].to_vec(),
+
+            h_negative_x: self.h_negative_x[0..=new_d].to_vec(),
+            h_positive_x: self.h_positive_x[0..=new_d].to_vec(),
+
+            g_negative_x_alpha: self.g_negative_x_alpha[0..new_d].to_vec(),
+            g_positive_x_alpha: self.g_positive_x_alpha[0..new_d].to_vec(),
+
+            h_negative_x_alpha: self.h_negative_x_alpha[0..=new_d].to_vec(),
+            h_positive_x_alpha: self.h_positive_x_alpha[0..=new_d].to_vec(),
+        }
+    }
+
+    /// Imports an SRS of degree `d` from the transcript of the powers-of-tau ceremony
+    /// with `2^ceremony_power` powers in G2. Transcript is expected to start with
+    /// 64 bytes of the previous contribution hash, followed by `2^(ceremony_power + 1) - 1`
+    /// powers of tau in G1 and `2^ceremony_power` powers of tau in G2.
+    ///
+    /// Powers of tau are the positive half of the SRS, but the transcript has no negative
+    /// powers. They are obtained by taking g^{tau^d} and h^{tau^d} as generators: then
+    /// tau^{d + i} are powers x^i for i in [-d, d], so `2d + 1` powers are taken from both
+    /// groups. Provers and verifiers take the generators from the SRS and the verifying
+    /// key carries the one of G1, and the relations are checked by `verify_srs`.
+    ///
+    /// The transcript has no alpha in G2, so alpha is sampled from `rng`. Whoever imports
+    /// the SRS knows alpha, so it has to be followed by at least one honest `contribute`
+    pub fn read_from_powers_of_tau<R: Read, Rg: Rng>(
+        mut reader: R,
+        ceremony_power: usize,
+        d: usize,
+        encoding: PowersOfTauEncoding,
+        rng: &mut Rg
+    ) -> io::Result<Self>
+    {
+        let tau_powers_length = 1usize << ceremony_power;
+        let tau_powers_g1_length = (tau_powers_length << 1) - 1;
+
+        // G2 has less powers
+        if d == 0 || 2 * d + 1 > tau_powers_length {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("ceremony has {} powers in G2, but SRS of degree {} needs {}", tau_powers_length, d, 2 * d + 1)));
+        }
+
+        // skip the hash of the previous contribution
+        skip_bytes(&mut reader, 64)?;
+
+        let mut g1_powers = vec![];
+        for _ in 0..(2 * d + 1) {
+            g1_powers.push(read_encoded_point::<E::G1Affine, _>(&mut reader, encoding)?);
+        }
+
+        let g1_point_size = match encoding {
+            PowersOfTauEncoding::Uncompressed => <E::G1Affine as CurveAffine>::Uncompressed::size(),
+            PowersOfTauEncoding::Compressed => <E::G1Affine as CurveAffine>::Compressed::size(),
+        };
+
+        skip_bytes(&mut reader, ((tau_powers_g1_length - 2 * d - 1) * g1_point_size) as u64)?;
+
+        let mut g2_powers = vec![];
+        for _ in 0..(2 * d + 1) {
+            g2_powers.push(read_encoded_point::<E::G2Affine, _>(&mut reader, encoding)?);
+        }
+
+        let g_positive_x = g1_powers[d..].to_vec();
+        let h_positive_x = g2_powers[d..].to_vec();
+
+        let mut g_negative_x = g1_powers[..=d].to_vec();
+        g_negative_x.reverse();
+        let mut h_negative_x = g2_powers[..=d].to_vec();
+        h_negative_x.reverse();
+
+        let alpha = nonzero_scalar::<E, _>(rng);
+        let worker = Worker::new();
+
+        let mut g_negative_x_alpha = g_negative_x[1..].to_vec();
+        let mut g_positive_x_alpha = g_positive_x[1..].to_vec();
+        let mut h_negative_x_alpha = h_negative_x.clone();
+        let mut h_positive_x_alpha = h_positive_x.clone();
+
+        scale_powers(&mut g_negative_x_alpha, alpha, E::Fr::one(), &worker);
+        scale_powers(&mut g_positive_x_alpha, alpha, E::Fr::one(), &worker);
+        scale_powers(&mut h_negative_x_alpha, alpha, E::Fr::one(), &worker);
+        scale_powers(&mut h_positive_x_alpha, alpha, E::Fr::one(), &worker);
+
+        let srs = SRS {
+            d,
+            g_negative_x,
+            g_positive_x,
+            h_negative_x,
+            h_positive_x,
+            g_negative_x_alpha,
+            g_positive_x_alpha,
+            h_negative_x_alpha,
+            h_positive_x_alpha
+        };
+
+        if !verify_srs(&srs) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "powers of tau are inconsistent"));
+        }
+
+        Ok(srs)
+    }
+}
+
+/// Point encoding used by a powers-of-tau transcript file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowersOfTauEncoding {
+    // challenge files store an uncompressed accumulator
+    Uncompressed,
+    // response files store a compressed accumulator
+    Compressed,
+}
+
+fn read_encoded_point<G: CurveAffine, R: Read>(
+    mut reader: R,
+    encoding: PowersOfTauEncoding
+) -> io::Result<G>
+{
+    let point = match encoding {
+        PowersOfTauEncoding::Uncompressed => {
+            let mut repr = G::Uncompressed::empty();
+            reader.read_exact(repr.as_mut())?;
+
+            repr.into_affine()
+        },
+        PowersOfTauEncoding::Compressed => {
+            let mut repr = G::Compressed::empty();
+            reader.read_exact(repr.as_mut())?;
+
+            repr.into_affine()
+        }
+    };
+
+    point
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        .and_then(|e| if e.is_zero() {
+            Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
+        } else {
+            Ok(e)
+        })
+}
+
+fn skip_bytes<R: Read>(reader: R, num_bytes: u64) -> io::Result<()> {
+    let skipped = io::copy(&mut reader.take(num_bytes), &mut io::sink())?;
+    if skipped != num_bytes {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "transcript is too short"));
+    }
+
+    Ok(())
 }
 
 impl<E: Engine> SRS<E> {
@@ -169,8 +332,6 @@ impl<E: Engine> SRS<E> {
         checked: bool
     ) -> io::Result<Self>
     {
-        use crate::pairing::EncodedPoint;
-
         let read_g1 = |reader: &mut R| -> io::Result<E::G1Affine> {
             let mut repr = <E::G1Affine as CurveAffine>::Uncompressed::empty();
             reader.read_exact(repr.as_mut())?;
@@ -271,4 +432,91 @@ impl<E: Engine> SRS<E> {
             h_positive_x_alpha: h_positive_x_alpha
         })
     }
-}
\ No newline at end of file
+}
+#[test]
+fn test_srs_trim() {
+    use crate::pairing::bls12_381::{Bls12, Fr};
+
+    let x = Fr::from_str("7").unwrap();
+    let alpha = Fr::from_str("11").unwrap();
+
+    let srs = SRS::<Bls12>::new(16, x, alpha);
+    assert!(srs.trim(5) == SRS::<Bls12>::new(5, x, alpha));
+    assert!(srs.trim(16) == srs);
+}
+
+#[cfg(test)]
+pub(crate) fn make_powers_of_tau_transcript<E: Engine>(ceremony_power: usize, tau: E::Fr, encoding: PowersOfTauEncoding) -> Vec<u8> {
+    fn write_encoded<G: CurveAffine>(point: &G, encoding: PowersOfTauEncoding, buffer: &mut Vec<u8>) {
+        match encoding {
+            PowersOfTauEncoding::Uncompressed => buffer.extend_from_slice(point.into_uncompressed().as_ref()),
+            PowersOfTauEncoding::Compressed => buffer.extend_from_slice(point.into_compressed().as_ref()),
+        }
+    }
+
+    let tau_powers_length = 1usize << ceremony_power;
+    let tau_powers_g1_length = (tau_powers_length << 1) - 1;
+
+    let mut buffer = vec![0u8; 64];
+
+    let mut power = E::Fr::one();
+    for _ in 0..tau_powers_g1_length {
+        write_encoded(&E::G1Affine::one().mul(power.into_repr()).into_affine(), encoding, &mut buffer);
+        power.mul_assign(&tau);
+    }
+
+    let mut power = E::Fr::one();
+    for _ in 0..tau_powers_length {
+        write_encoded(&E::G2Affine::one().mul(power.into_repr()).into_affine(), encoding, &mut buffer);
+        power.mul_assign(&tau);
+    }
+
+    // alpha and beta powers follow in real transcripts
+    for _ in 0..tau_powers_length {
+        write_encoded(&E::G1Affine::one(), encoding, &mut buffer);
+    }
+
+    buffer
+}
+
+#[test]
+fn test_import_from_powers_of_tau() {
+    use crate::pairing::bls12_381::{Bls12, Fr, G1Affine, G2Affine};
+    use super::update::contribute;
+
+    let ceremony_power = 4;
+    let d = 7;
+    let tau = Fr::from_str("42").unwrap();
+    let rng = &mut rand::thread_rng();
+
+    for &encoding in [PowersOfTauEncoding::Uncompressed, PowersOfTauEncoding::Compressed].iter() {
+        let transcript = make_powers_of_tau_transcript::<Bls12>(ceremony_power, tau, encoding);
+
+        let mut srs = SRS::<Bls12>::read_from_powers_of_tau(&transcript[..], ceremony_power, d, encoding, rng).unwrap();
+        assert_eq!(srs.d, d);
+        assert!(verify_srs(&srs));
+
+        // generators are shifted by tau^d
+        let tau_d = tau.pow([d as u64]);
+        assert!(srs.g_positive_x[0] == G1Affine::one().mul(tau_d.into_repr()).into_affine());
+        assert!(srs.h_negative_x[0] == G2Affine::one().mul(tau_d.into_repr()).into_affine());
+        assert!(srs.g_positive_x[1] == G1Affine::one().mul(tau.pow([(d + 1) as u64]).into_repr()).into_affine());
+        assert!(srs.g_negative_x[d] == G1Affine::one());
+
+        // alpha known to the importer is replaced by a contribution
+        let before = srs.clone();
+        let proof = contribute(&mut srs, rng);
+        assert!(super::update::verify_update(&before, &srs, &proof));
+        assert!(verify_srs(&srs.trim(3)));
+
+        // G2 has 16 powers, 2d + 1 of them are needed
+        assert!(SRS::<Bls12>::read_from_powers_of_tau(&transcript[..], ceremony_power, 8, encoding, rng).is_err());
+
+        // truncated transcript
+        assert!(SRS::<Bls12>::read_from_powers_of_tau(&transcript[..(transcript.len() / 2)], ceremony_power, d, encoding, rng).is_err());
+
+        // transcript of a larger ceremony read with a wrong power
+        let larger = make_powers_of_tau_transcript::<Bls12>(ceremony_power + 1, tau, encoding);
+        assert!(SRS::<Bls12>::read_from_powers_of_tau(&larger[..], ceremony_power, d, encoding, rng).is_err());
+    }
+}
@@ -0,0 +1,448 @@
+extern crate blake2_rfc;
+
+use crate::pairing::ff::{Field, PrimeField};
+use crate::pairing::{CurveAffine, CurveProjective, EncodedPoint, Engine};
+
+use std::io::{self, Read, Write};
+use rand::{Rand, Rng, SeedableRng, ChaChaRng};
+use byteorder::{BigEndian, ByteOrder};
+
+use self::blake2_rfc::blake2b::Blake2b;
+
+use crate::multicore::Worker;
+use crate::sonic::util::multiexp;
+
+use super::srs::SRS;
+
+/*
+An update multiplies the secrets of the SRS by new secrets x' and alpha',
+so no one knows x and alpha as long as one of the contributors has forgotten
+theirs. Every contribution comes with proofs of knowledge of x' and alpha'
+bound to the state of the SRS before the update, so that every contribution
+builds upon the previous one.
+
+A ceremony can start from `SRS::dummy(d, ...)`, that is an SRS for x = 1 and alpha = 1.
+*/
+
+const X_PERSONALIZATION: &[u8] = b"sonic_srs_x";
+const ALPHA_PERSONALIZATION: &[u8] = b"sonic_srs_alpha";
+
+/// Proof of knowledge of a secret s
+#[derive(Clone, Eq)]
+pub struct PublicKey<E: Engine> {
+    // g^r and g^{rs} for a random r
+    pub r_g1: E::G1Affine,
+    pub rs_g1: E::G1Affine,
+
+    // H^s, where H is hashed from the SRS before update and the points above
+    pub s_g2: E::G2Affine,
+}
+
+impl<E: Engine> PartialEq for PublicKey<E> {
+    fn eq(&self, other: &PublicKey<E>) -> bool {
+        self.r_g1 == other.r_g1 &&
+        self.rs_g1 == other.rs_g1 &&
+        self.s_g2 == other.s_g2
+    }
+}
+
+#[derive(Clone, Eq)]
+pub struct UpdateProof<E: Engine> {
+    // g^x and h^alpha after the update
+    pub g_x: E::G1Affine,
+    pub h_alpha: E::G2Affine,
+
+    pub x_key: PublicKey<E>,
+    pub alpha_key: PublicKey<E>,
+}
+
+impl<E: Engine> PartialEq for UpdateProof<E> {
+    fn eq(&self, other: &UpdateProof<E>) -> bool {
+        self.g_x == other.g_x &&
+        self.h_alpha == other.h_alpha &&
+        self.x_key == other.x_key &&
+        self.alpha_key == other.alpha_key
+    }
+}
+
+/// Contributes new random secrets to the SRS in place. The returned proof
+/// has to be published for others to check the update
+pub fn contribute<E: Engine, R: Rng>(srs: &mut SRS<E>, rng: &mut R) -> UpdateProof<E> {
+    assert!(srs.d > 0);
+
+    let x = nonzero_scalar::<E, _>(rng);
+    let alpha = nonzero_scalar::<E, _>(rng);
+
+    let previous_g_x = srs.g_positive_x[1];
+    let previous_h_alpha = srs.h_positive_x_alpha[0];
+
+    let x_key = PublicKey::<E>::new(X_PERSONALIZATION, previous_g_x, previous_h_alpha, x, rng);
+    let alpha_key = PublicKey::<E>::new(ALPHA_PERSONALIZATION, previous_g_x, previous_h_alpha, alpha, rng);
+
+    let x_inv = x.inverse().unwrap();
+
+    let mut x_alpha = x;
+    x_alpha.mul_assign(&alpha);
+
+    let mut inv_x_alpha = x_inv;
+    inv_x_alpha.mul_assign(&alpha);
+
+    let worker = Worker::new();
+
+    scale_powers(&mut srs.g_negative_x, E::Fr::one(), x_inv, &worker);
+    scale_powers(&mut srs.g_positive_x, E::Fr::one(), x, &worker);
+
+    scale_powers(&mut srs.h_negative_x, E::Fr::one(), x_inv, &worker);
+    scale_powers(&mut srs.h_positive_x, E::Fr::one(), x, &worker);
+
+    scale_powers(&mut srs.g_negative_x_alpha, inv_x_alpha, x_inv, &worker);
+    scale_powers(&mut srs.g_positive_x_alpha, x_alpha, x, &worker);
+
+    scale_powers(&mut srs.h_negative_x_alpha, alpha, x_inv, &worker);
+    scale_powers(&mut srs.h_positive_x_alpha, alpha, x, &worker);
+
+    UpdateProof {
+        g_x: srs.g_positive_x[1],
+        h_alpha: srs.h_positive_x_alpha[0],
+        x_key,
+        alpha_key
+    }
+}
+
+/// Checks that `after` is a well formed SRS that was obtained from
+/// `before` by the contribution with a given proof
+pub fn verify_update<E: Engine>(before: &SRS<E>, after: &SRS<E>, proof: &UpdateProof<E>) -> bool {
+    if before.d != after.d || !has_valid_lengths(before) {
+        return false;
+    }
+
+    verify_update_proof(before.g_positive_x[1], before.h_positive_x_alpha[0], proof) &&
+    proof.g_x == after.g_positive_x[1] &&
+    proof.h_alpha == after.h_positive_x_alpha[0] &&
+    verify_srs(after)
+}
+
+/// Checks a transcript of updates, that starts from `initial` and
+/// ends with `last`, without intermediate SRSs
+pub fn verify_transcript<E: Engine>(initial: &SRS<E>, proofs: &[UpdateProof<E>], last: &SRS<E>) -> bool {
+    if initial.d != last.d || !has_valid_lengths(initial) {
+        return false;
+    }
+
+    let mut g_x = initial.g_positive_x[1];
+    let mut h_alpha = initial.h_positive_x_alpha[0];
+
+    for proof in proofs.iter() {
+        if !verify_update_proof(g_x, h_alpha, proof) {
+            return false;
+        }
+
+        g_x = proof.g_x;
+        h_alpha = proof.h_alpha;
+    }
+
+    g_x == last.g_positive_x[1] &&
+    h_alpha == last.h_positive_x_alpha[0] &&
+    verify_srs(last)
+}
+
+/// Checks that all vectors of the SRS are powers of the same x and alpha.
+/// Pairs of consequitive elements are merged with random coefficients
+/// to make a single pairing check for every vector
+pub fn verify_srs<E: Engine>(srs: &SRS<E>) -> bool {
+    if srs.d == 0 || !has_valid_lengths(srs) {
+        return false;
+    }
+
+    // generators are the standard ones, unless the SRS was imported from powers of tau
+    let d = srs.d;
+    let g = srs.g_positive_x[0];
+    let h = srs.h_positive_x[0];
+
+    if g.is_zero() || h.is_zero() || srs.g_negative_x[0] != g || srs.h_negative_x[0] != h {
+        return false;
+    }
+
+    let g_x = srs.g_positive_x[1];
+    let h_x = srs.h_positive_x[1];
+    let h_alpha = srs.h_positive_x_alpha[0];
+    let g_x_alpha = srs.g_positive_x_alpha[0];
+
+    if g_x.is_zero() || h_x.is_zero() || h_alpha.is_zero() || srs.h_negative_x_alpha[0] != h_alpha {
+        return false;
+    }
+
+    // x in both groups, x^{-1} is an inverse of x
+    same_ratio((g, g_x), (h, h_x)) &&
+    same_ratio((g, g_x), (srs.h_negative_x[1], h)) &&
+    same_ratio((g, srs.g_negative_x[1]), (h, srs.h_negative_x[1])) &&
+
+    // powers of x and x^{-1}
+    same_ratio(merge_pairs(&srs.g_positive_x[0..d], &srs.g_positive_x[1..]), (h, h_x)) &&
+    same_ratio(merge_pairs(&srs.g_negative_x[0..d], &srs.g_negative_x[1..]), (h_x, h)) &&
+    same_ratio((g, g_x), merge_pairs(&srs.h_positive_x[0..d], &srs.h_positive_x[1..])) &&
+    same_ratio((g_x, g), merge_pairs(&srs.h_negative_x[0..d], &srs.h_negative_x[1..])) &&
+
+    // alpha multiples, h^alpha is checked against g^{alpha x}
+    same_ratio(merge_pairs(&srs.g_positive_x[1..], &srs.g_positive_x_alpha), (h, h_alpha)) &&
+    same_ratio(merge_pairs(&srs.g_negative_x[1..], &srs.g_negative_x_alpha), (h, h_alpha)) &&
+    same_ratio((g_x, g_x_alpha), merge_pairs(&srs.h_positive_x, &srs.h_positive_x_alpha)) &&
+    same_ratio((g_x, g_x_alpha), merge_pairs(&srs.h_negative_x, &srs.h_negative_x_alpha))
+}
+
+fn verify_update_proof<E: Engine>(
+    previous_g_x: E::G1Affine,
+    previous_h_alpha: E::G2Affine,
+    proof: &UpdateProof<E>
+) -> bool {
+    if proof.g_x.is_zero() || proof.h_alpha.is_zero() {
+        return false;
+    }
+
+    let x_challenge = proof.x_key.challenge(X_PERSONALIZATION, previous_g_x, previous_h_alpha);
+    let alpha_challenge = proof.alpha_key.challenge(ALPHA_PERSONALIZATION, previous_g_x, previous_h_alpha);
+
+    // contributor knows x' and alpha', and g^x and h^alpha are multiplied by them
+    proof.x_key.is_valid(x_challenge) &&
+    proof.alpha_key.is_valid(alpha_challenge) &&
+    same_ratio((previous_g_x, proof.g_x), (x_challenge, proof.x_key.s_g2)) &&
+    same_ratio((proof.alpha_key.r_g1, proof.alpha_key.rs_g1), (previous_h_alpha, proof.h_alpha))
+}
+
+impl<E: Engine> PublicKey<E> {
+    fn new<R: Rng>(
+        personalization: &[u8],
+        previous_g_x: E::G1Affine,
+        previous_h_alpha: E::G2Affine,
+        s: E::Fr,
+        rng: &mut R
+    ) -> Self {
+        let r = nonzero_scalar::<E, _>(rng);
+        let r_g1 = E::G1Affine::one().mul(r.into_repr()).into_affine();
+        let rs_g1 = r_g1.mul(s.into_repr()).into_affine();
+
+        let mut key = PublicKey {
+            r_g1,
+            rs_g1,
+            s_g2: E::G2Affine::zero()
+        };
+
+        let challenge: E::G2Affine = key.challenge(personalization, previous_g_x, previous_h_alpha);
+        key.s_g2 = challenge.mul(s.into_repr()).into_affine();
+
+        key
+    }
+
+    fn challenge(
+        &self,
+        personalization: &[u8],
+        previous_g_x: E::G1Affine,
+        previous_h_alpha: E::G2Affine,
+    ) -> E::G2Affine {
+        let mut h = Blake2b::new(64);
+        h.update(personalization);
+        h.update(previous_g_x.into_uncompressed().as_ref());
+        h.update(previous_h_alpha.into_uncompressed().as_ref());
+        h.update(self.r_g1.into_uncompressed().as_ref());
+        h.update(self.rs_g1.into_uncompressed().as_ref());
+        let digest = h.finalize();
+
+        let seed: Vec<u32> = digest.as_bytes()[0..32].chunks(4).map(BigEndian::read_u32).collect();
+        let mut rng = ChaChaRng::from_seed(&seed);
+
+        E::G2::rand(&mut rng).into_affine()
+    }
+
+    fn is_valid(&self, challenge: E::G2Affine) -> bool {
+        !self.r_g1.is_zero() && same_ratio((self.r_g1, self.rs_g1), (challenge, self.s_g2))
+    }
+
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        writer.write_all(self.r_g1.into_uncompressed().as_ref())?;
+        writer.write_all(self.rs_g1.into_uncompressed().as_ref())?;
+        writer.write_all(self.s_g2.into_uncompressed().as_ref())?;
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        Ok(PublicKey {
+            r_g1: read_point::<E::G1Affine, _>(&mut reader)?,
+            rs_g1: read_point::<E::G1Affine, _>(&mut reader)?,
+            s_g2: read_point::<E::G2Affine, _>(&mut reader)?
+        })
+    }
+}
+
+impl<E: Engine> UpdateProof<E> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        writer.write_all(self.g_x.into_uncompressed().as_ref())?;
+        writer.write_all(self.h_alpha.into_uncompressed().as_ref())?;
+        self.x_key.write(&mut writer)?;
+        self.alpha_key.write(&mut writer)?;
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        Ok(UpdateProof {
+            g_x: read_point::<E::G1Affine, _>(&mut reader)?,
+            h_alpha: read_point::<E::G2Affine, _>(&mut reader)?,
+            x_key: PublicKey::read(&mut reader)?,
+            alpha_key: PublicKey::read(&mut reader)?
+        })
+    }
+}
+
+fn read_point<G: CurveAffine, R: Read>(reader: &mut R) -> io::Result<G> {
+    let mut repr = G::Uncompressed::empty();
+    reader.read_exact(repr.as_mut())?;
+
+    repr.into_affine()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        .and_then(|e| if e.is_zero() {
+            Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
+        } else {
+            Ok(e)
+        })
+}
+
+fn has_valid_lengths<E: Engine>(srs: &SRS<E>) -> bool {
+    let d = srs.d;
+
+    d > 0 &&
+    srs.g_negative_x.len() == d + 1 &&
+    srs.g_positive_x.len() == d + 1 &&
+    srs.h_negative_x.len() == d + 1 &&
+    srs.h_positive_x.len() == d + 1 &&
+    srs.g_negative_x_alpha.len() == d &&
+    srs.g_positive_x_alpha.len() == d &&
+    srs.h_negative_x_alpha.len() == d + 1 &&
+    srs.h_positive_x_alpha.len() == d + 1
+}
+
+pub(super) fn nonzero_scalar<E: Engine, R: Rng>(rng: &mut R) -> E::Fr {
+    loop {
+        let s: E::Fr = rng.gen();
+        if !s.is_zero() {
+            return s;
+        }
+    }
+}
+
+// multiplies bases[i] by first * step^i
+pub(super) fn scale_powers<C: CurveAffine>(bases: &mut [C], first: C::Scalar, step: C::Scalar, worker: &Worker) {
+    worker.scope(bases.len(), |scope, chunk| {
+        for (i, bases) in bases.chunks_mut(chunk).enumerate() {
+            scope.spawn(move |_| {
+                let mut current = step.pow([(i*chunk) as u64]);
+                current.mul_assign(&first);
+
+                let mut scaled = Vec::with_capacity(bases.len());
+                for base in bases.iter() {
+                    scaled.push(base.mul(current.into_repr()));
+                    current.mul_assign(&step);
+                }
+
+                C::Projective::batch_normalization(&mut scaled);
+
+                for (base, scaled) in bases.iter_mut().zip(scaled) {
+                    *base = scaled.into_affine();
+                }
+            });
+        }
+    });
+}
+
+// e(a.0, b.1) = e(a.1, b.0)
+fn same_ratio<G1: CurveAffine>(a: (G1, G1), b: (G1::Pair, G1::Pair)) -> bool {
+    a.0.pairing_with(&b.1) == a.1.pairing_with(&b.0)
+}
+
+// \sum r_i a_i and \sum r_i b_i for random r_i
+fn merge_pairs<G: CurveAffine>(a: &[G], b: &[G]) -> (G, G) {
+    assert_eq!(a.len(), b.len());
+
+    let mut rng = rand::thread_rng();
+    let coeffs: Vec<G::Scalar> = (0..a.len()).map(|_| rng.gen()).collect();
+
+    (
+        multiexp(a.iter(), coeffs.iter()).into_affine(),
+        multiexp(b.iter(), coeffs.iter()).into_affine()
+    )
+}
+
+#[test]
+fn test_srs_update() {
+    use crate::pairing::bls12_381::{Bls12, Fr};
+    use rand::{XorShiftRng};
+
+    let d = 20;
+    let seed = [0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654];
+
+    let initial = SRS::<Bls12>::dummy(d, Fr::one(), Fr::one());
+    assert!(verify_srs(&initial));
+
+    let mut srs = initial.clone();
+    let first = contribute(&mut srs, &mut XorShiftRng::from_seed(seed));
+    assert!(verify_update(&initial, &srs, &first));
+
+    // secrets are the first values drawn from the rng
+    {
+        let rng = &mut XorShiftRng::from_seed(seed);
+        let x: Fr = rng.gen();
+        let alpha: Fr = rng.gen();
+
+        assert!(srs == SRS::<Bls12>::new(d, x, alpha));
+    }
+
+    let after_first = srs.clone();
+    let second = contribute(&mut srs, &mut rand::thread_rng());
+    assert!(verify_update(&after_first, &srs, &second));
+
+    let mut serialized = vec![];
+    first.write(&mut serialized).unwrap();
+    second.write(&mut serialized).unwrap();
+    let mut reader = &serialized[..];
+    let transcript = vec![
+        UpdateProof::<Bls12>::read(&mut reader).unwrap(),
+        UpdateProof::<Bls12>::read(&mut reader).unwrap()
+    ];
+    assert!(transcript[0] == first && transcript[1] == second);
+
+    assert!(verify_transcript(&initial, &transcript, &srs));
+
+    // updates must be applied in order and can't be skipped
+    assert!(!verify_update(&initial, &srs, &second));
+    assert!(!verify_transcript(&initial, &[second.clone(), first.clone()], &srs));
+    assert!(!verify_transcript(&initial, &[second.clone()], &srs));
+
+    // proof of knowledge must be valid
+    let mut forged = second.clone();
+    forged.x_key = first.x_key.clone();
+    assert!(!verify_update(&after_first, &srs, &forged));
+
+    // every element of the SRS is checked
+    let mut broken = srs.clone();
+    broken.g_negative_x_alpha[d - 3] = broken.g_negative_x_alpha[d - 2];
+    assert!(!verify_srs(&broken));
+    assert!(!verify_transcript(&initial, &transcript, &broken));
+
+    let mut broken = srs.clone();
+    broken.h_positive_x[d] = broken.h_positive_x[d - 1];
+    assert!(!verify_srs(&broken));
+}
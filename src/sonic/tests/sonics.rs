@@ -13,6 +13,7 @@ use crate::pairing::{
 
 use crate::pairing::ff::{
     Field,
+    PrimeField
 };
 
 // We're going to use the BLS12-381 pairing-friendly elliptic curve.
@@ -36,14 +37,24 @@ use crate::{
 const MIMC_ROUNDS: usize = 1000000;
 
 fn mimc<E: Engine>(
-    mut xl: E::Fr,
-    mut xr: E::Fr,
+    xl: E::Fr,
+    xr: E::Fr,
     constants: &[E::Fr]
 ) -> E::Fr
 {
     assert_eq!(constants.len(), MIMC_ROUNDS);
 
-    for i in 0..MIMC_ROUNDS {
+    mimc_rounds::<E>(xl, xr, constants)
+}
+
+// MiMC with one round per constant
+fn mimc_rounds<E: Engine>(
+    mut xl: E::Fr,
+    mut xr: E::Fr,
+    constants: &[E::Fr]
+) -> E::Fr
+{
+    for i in 0..constants.len() {
         let mut tmp1 = xl;
         tmp1.add_assign(&constants[i]);
         let mut tmp2 = tmp1;
@@ -77,6 +88,32 @@ impl<'a, E: Engine> Circuit<E> for MiMCDemo<'a, E> {
     {
         assert_eq!(self.constants.len(), MIMC_ROUNDS);
 
+        self.synthesize_rounds(cs)
+    }
+}
+
+/// `MiMCDemo` with one round per constant instead of `MIMC_ROUNDS`
+#[derive(Clone)]
+struct MiMCRounds<'a, E: Engine>(MiMCDemo<'a, E>);
+
+impl<'a, E: Engine> Circuit<E> for MiMCRounds<'a, E> {
+    fn synthesize<CS: ConstraintSystem<E>>(
+        self,
+        cs: &mut CS
+    ) -> Result<(), SynthesisError>
+    {
+        self.0.synthesize_rounds(cs)
+    }
+}
+
+impl<'a, E: Engine> MiMCDemo<'a, E> {
+    fn synthesize_rounds<CS: ConstraintSystem<E>>(
+        self,
+        cs: &mut CS
+    ) -> Result<(), SynthesisError>
+    {
+        let rounds = self.constants.len();
+
         // Allocate the first component of the preimage.
         let mut xl_value = self.xl;
         let mut xl = cs.alloc(|| "preimage xl", || {
@@ -89,7 +126,7 @@ impl<'a, E: Engine> Circuit<E> for MiMCDemo<'a, E> {
             xr_value.ok_or(SynthesisError::AssignmentMissing)
         })?;
 
-        for i in 0..MIMC_ROUNDS {
+        for i in 0..rounds {
             // xL, xR := xR + (xL + Ci)^3, xL
             let cs = &mut cs.namespace(|| format!("round {}", i));
 
@@ -120,7 +157,7 @@ impl<'a, E: Engine> Circuit<E> for MiMCDemo<'a, E> {
                 e
             });
 
-            let new_xl = if i == (MIMC_ROUNDS-1) {
+            let new_xl = if i == (rounds-1) {
                 // This is the last round, xL is our image and so
                 // we allocate a public input.
                 cs.alloc_input(|| "image", || {
@@ -504,7 +541,7 @@ fn test_succinct_sonic_mimc() {
         use crate::sonic::sonic::Permutation3;
         use crate::sonic::unhelped::permutation_structure::*;
         use crate::sonic::unhelped::SuccinctMultiVerifier;
-        use crate::sonic::unhelped::{create_aggregate_on_srs};
+        use crate::sonic::unhelped::aggregate::{create_aggregate_on_srs};
 
         use crate::sonic::cs::{Circuit, ConstraintSystem, LinearCombination, Coeff};
 
@@ -772,6 +809,326 @@ fn test_high_level_sonic_api() {
     }
 }
 
+// x^3 + x + 5 = out, where `out` is a public input
+#[derive(Clone)]
+struct CubeDemo<E: Engine> {
+    x: Option<E::Fr>
+}
+
+impl<E: Engine> Circuit<E> for CubeDemo<E> {
+    fn synthesize<CS: ConstraintSystem<E>>(
+        self,
+        cs: &mut CS
+    ) -> Result<(), SynthesisError>
+    {
+        let x_value = self.x;
+        let x = cs.alloc(|| "x", || x_value.ok_or(SynthesisError::AssignmentMissing))?;
+
+        let x_squared_value = x_value.map(|mut x| { x.square(); x });
+        let x_squared = cs.alloc(|| "x^2", || x_squared_value.ok_or(SynthesisError::AssignmentMissing))?;
+        cs.enforce(|| "x * x = x^2", |lc| lc + x, |lc| lc + x, |lc| lc + x_squared);
+
+        let x_cubed_value = x_squared_value.map(|mut x_squared| { x_squared.mul_assign(&x_value.unwrap()); x_squared });
+        let x_cubed = cs.alloc(|| "x^3", || x_cubed_value.ok_or(SynthesisError::AssignmentMissing))?;
+        cs.enforce(|| "x^2 * x = x^3", |lc| lc + x_squared, |lc| lc + x, |lc| lc + x_cubed);
+
+        let out = cs.alloc_input(|| "out", || {
+            let mut out = x_cubed_value.ok_or(SynthesisError::AssignmentMissing)?;
+            out.add_assign(&x_value.unwrap());
+            out.add_assign(&E::Fr::from_str("5").unwrap());
+
+            Ok(out)
+        })?;
+        cs.enforce(
+            || "x^3 + x + 5 = out", 
+            |lc| lc + x_cubed + x + (E::Fr::from_str("5").unwrap(), CS::one()), 
+            |lc| lc + CS::one(), 
+            |lc| lc + out
+        );
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_high_level_succinct_sonic_api() {
+    use crate::pairing::ff::PrimeField;
+    use crate::pairing::bls12_381::{Bls12, Fr};
+    use crate::sonic::srs::SRS;
+    use crate::sonic::helped::generate_parameters_for_succinct_sonic;
+    use crate::sonic::sonic::{AdaptorCircuit, Permutation3};
+    use crate::sonic::unhelped::{
+        verify_aggregate,
+        verify_aggregate_on_srs,
+        verify_proofs,
+        verify_proofs_on_srs,
+        create_proof,
+        create_proof_on_srs,
+        create_advice,
+        create_advice_on_srs,
+        create_adapted_aggregate,
+        create_adapted_aggregate_on_srs
+    };
+
+    let circuit = CubeDemo::<Bls12> {
+        x: Some(Fr::from_str("3").unwrap())
+    };
+    let out = Fr::from_str("35").unwrap();
+
+    // verifier doesn't need a witness
+    let empty_circuit = CubeDemo::<Bls12> {
+        x: None
+    };
+
+    let srs_x = Fr::from_str("23923").unwrap();
+    let srs_alpha = Fr::from_str("23728792").unwrap();
+    let params = generate_parameters_for_succinct_sonic(empty_circuit.clone(), srs_alpha, srs_x).unwrap();
+
+    let proof = create_proof(circuit.clone(), &params).unwrap();
+    let advice = create_advice(circuit.clone(), &proof, &params).unwrap();
+
+    let proofs: Vec<_> = (0..3).map(|_| (proof.clone(), advice.clone())).collect();
+    let aggregate = create_adapted_aggregate(circuit.clone(), &proofs, &params);
+
+    assert!(verify_proofs(&vec![proof.clone()], &vec![vec![out]], empty_circuit.clone(), thread_rng(), &params).unwrap());
+    assert!(verify_aggregate(&proofs, &aggregate, &vec![vec![out]; 3], empty_circuit.clone(), thread_rng(), &params).unwrap());
+
+    let wrong_out = Fr::from_str("36").unwrap();
+    assert!(!verify_proofs(&vec![proof.clone()], &vec![vec![wrong_out]], empty_circuit.clone(), thread_rng(), &params).unwrap());
+    assert!(!verify_aggregate(&proofs, &aggregate, &vec![vec![wrong_out]; 3], empty_circuit.clone(), thread_rng(), &params).unwrap());
+
+    // `create_aggregate` still takes a sonic circuit with its driver
+    let sonic_aggregate = crate::sonic::unhelped::create_aggregate::<Bls12, _, Permutation3>(&AdaptorCircuit(circuit.clone()), &proofs, &params);
+    assert!(verify_aggregate(&proofs, &sonic_aggregate, &vec![vec![out]; 3], empty_circuit.clone(), thread_rng(), &params).unwrap());
+
+    // aggregate with a wrong s(z, w) signature must not pass
+    let mut wrong_aggregate = aggregate.clone();
+    wrong_aggregate.signature.perm_argument_proof.s_zy.add_assign(&Fr::one());
+    assert!(!verify_aggregate(&proofs, &wrong_aggregate, &vec![vec![out]; 3], empty_circuit.clone(), thread_rng(), &params).unwrap());
+
+    // the same flow works on a larger SRS directly
+    let srs = SRS::<Bls12>::new(200, srs_x, srs_alpha);
+    let proof = create_proof_on_srs(circuit.clone(), &srs).unwrap();
+    let advice = create_advice_on_srs(circuit.clone(), &proof, &srs).unwrap();
+    let proofs = vec![(proof.clone(), advice)];
+    let aggregate = create_adapted_aggregate_on_srs(circuit.clone(), &proofs, &srs);
+
+    assert!(verify_proofs_on_srs(&vec![proof], &vec![vec![out]], empty_circuit.clone(), thread_rng(), &srs).unwrap());
+    assert!(verify_aggregate_on_srs(&proofs, &aggregate, &vec![vec![out]], empty_circuit, thread_rng(), &srs).unwrap());
+}
+
+#[test]
+fn test_sonic_on_imported_powers_of_tau() {
+    use crate::pairing::ff::PrimeField;
+    use crate::pairing::bn256::{Bn256, Fr};
+    use crate::sonic::srs::{SRS, PowersOfTauEncoding, contribute, make_powers_of_tau_transcript};
+    use crate::sonic::sonic::{AdaptorCircuit, Nonassigning};
+    use crate::sonic::helped::{generate_parameters_on_srs, generate_parameters_for_succinct_sonic_on_srs};
+    use crate::sonic::helped::solidity::{encode_proof_calldata, verify_calldata};
+
+    let circuit = CubeDemo::<Bn256> {
+        x: Some(Fr::from_str("3").unwrap())
+    };
+    let out = Fr::from_str("35").unwrap();
+    let wrong_out = Fr::from_str("36").unwrap();
+
+    let empty_circuit = CubeDemo::<Bn256> {
+        x: None
+    };
+
+    // generators of the imported SRS are g^{tau^d} and h^{tau^d}
+    let ceremony_power = 7;
+    let tau = Fr::from_str("23923").unwrap();
+    let transcript = make_powers_of_tau_transcript::<Bn256>(ceremony_power, tau, PowersOfTauEncoding::Uncompressed);
+    let mut srs = SRS::<Bn256>::read_from_powers_of_tau(&transcript[..], ceremony_power, 63, PowersOfTauEncoding::Uncompressed, &mut thread_rng()).unwrap();
+    contribute(&mut srs, &mut thread_rng());
+
+    {
+        use crate::sonic::helped::{create_proof, verify_proofs};
+
+        let params = generate_parameters_on_srs(empty_circuit.clone(), &srs).unwrap();
+        assert!(params.vk.g == srs.g_positive_x[0]);
+
+        let proof = create_proof(circuit.clone(), &params).unwrap();
+
+        assert!(verify_proofs(&vec![proof.clone()], &vec![vec![out]], empty_circuit.clone(), thread_rng(), &params).unwrap());
+        assert!(!verify_proofs(&vec![proof.clone()], &vec![vec![wrong_out]], empty_circuit.clone(), thread_rng(), &params).unwrap());
+
+        let adapted = AdaptorCircuit(empty_circuit.clone());
+        let calldata = encode_proof_calldata(&proof, &[out]);
+        assert!(verify_calldata::<_, Nonassigning>(&adapted, &params.vk, &calldata).unwrap());
+        let calldata = encode_proof_calldata(&proof, &[wrong_out]);
+        assert!(!verify_calldata::<_, Nonassigning>(&adapted, &params.vk, &calldata).unwrap());
+    }
+
+    {
+        use crate::sonic::unhelped::{create_proof, create_advice, create_adapted_aggregate, verify_proofs, verify_aggregate};
+
+        let params = generate_parameters_for_succinct_sonic_on_srs(empty_circuit.clone(), &srs).unwrap();
+
+        let proof = create_proof(circuit.clone(), &params).unwrap();
+        let advice = create_advice(circuit.clone(), &proof, &params).unwrap();
+        let proofs = vec![(proof.clone(), advice)];
+        let aggregate = create_adapted_aggregate(circuit.clone(), &proofs, &params);
+
+        assert!(verify_proofs(&vec![proof], &vec![vec![out]], empty_circuit.clone(), thread_rng(), &params).unwrap());
+        assert!(verify_aggregate(&proofs, &aggregate, &vec![vec![out]], empty_circuit.clone(), thread_rng(), &params).unwrap());
+        assert!(!verify_aggregate(&proofs, &aggregate, &vec![vec![wrong_out]], empty_circuit, thread_rng(), &params).unwrap());
+    }
+}
+
+#[test]
+fn test_serialization_of_advice_and_aggregates() {
+    use crate::pairing::bls12_381::{Bls12, Fr};
+    use crate::sonic::helped::{
+        self,
+        generate_parameters,
+        generate_parameters_for_succinct_sonic,
+        get_circuit_parameters,
+        CircuitParameters,
+        SxyAdvice
+    };
+    use crate::sonic::helped::helper::Aggregate;
+    use crate::sonic::unhelped::{self, SuccinctAggregate};
+
+    let circuit = CubeDemo::<Bls12> {
+        x: Some(Fr::from_str("3").unwrap())
+    };
+    let empty_circuit = CubeDemo::<Bls12> {
+        x: None
+    };
+    let inputs = vec![vec![Fr::from_str("35").unwrap()]; 2];
+
+    let srs_x = Fr::from_str("23923").unwrap();
+    let srs_alpha = Fr::from_str("23728792").unwrap();
+
+    let info = get_circuit_parameters::<Bls12, _>(empty_circuit.clone()).unwrap();
+    let mut buffer = vec![];
+    info.write(&mut buffer).unwrap();
+    assert!(info == CircuitParameters::<Bls12>::read(&buffer[..]).unwrap());
+
+    // helped aggregate
+    {
+        let params = generate_parameters::<Bls12, _>(empty_circuit.clone(), srs_alpha, srs_x).unwrap();
+
+        let proof = helped::create_proof(circuit.clone(), &params).unwrap();
+        let advice = helped::create_advice(circuit.clone(), &proof, &params).unwrap();
+
+        let mut buffer = vec![];
+        advice.write(&mut buffer).unwrap();
+        let advice = SxyAdvice::<Bls12>::read(&buffer[..]).unwrap();
+
+        let proofs = vec![(proof.clone(), advice.clone()), (proof, advice)];
+        let aggregate = helped::create_aggregate(circuit.clone(), &proofs, &params);
+
+        let mut buffer = vec![];
+        aggregate.write(&mut buffer).unwrap();
+        let read_aggregate = Aggregate::<Bls12>::read(&buffer[..]).unwrap();
+        assert!(aggregate == read_aggregate);
+
+        assert!(helped::verify_aggregate(&proofs, &read_aggregate, &inputs, empty_circuit.clone(), thread_rng(), &params).unwrap());
+
+        // truncated data is rejected
+        assert!(Aggregate::<Bls12>::read(&buffer[..(buffer.len() - 1)]).is_err());
+    }
+
+    // succinct aggregate
+    {
+        let params = generate_parameters_for_succinct_sonic::<Bls12, _>(empty_circuit.clone(), srs_alpha, srs_x).unwrap();
+
+        let proof = unhelped::create_proof(circuit.clone(), &params).unwrap();
+        let advice = unhelped::create_advice(circuit.clone(), &proof, &params).unwrap();
+        let proofs = vec![(proof.clone(), advice.clone()), (proof, advice)];
+        let aggregate = unhelped::create_adapted_aggregate(circuit.clone(), &proofs, &params);
+
+        let mut buffer = vec![];
+        aggregate.write(&mut buffer).unwrap();
+        let read_aggregate = SuccinctAggregate::<Bls12>::read(&buffer[..]).unwrap();
+
+        // nested arguments have no equality, so compare encodings
+        let mut second_buffer = vec![];
+        read_aggregate.write(&mut second_buffer).unwrap();
+        assert_eq!(buffer, second_buffer);
+
+        assert!(unhelped::verify_aggregate(&proofs, &read_aggregate, &inputs, empty_circuit.clone(), thread_rng(), &params).unwrap());
+
+        assert!(SuccinctAggregate::<Bls12>::read(&buffer[..(buffer.len() - 1)]).is_err());
+    }
+}
+
+#[test]
+fn test_shplonk_openings_in_batch() {
+    use crate::pairing::{CurveAffine, CurveProjective};
+    use crate::pairing::ff::PrimeField;
+    use crate::pairing::bn256::{Fr, G1Affine};
+    use crate::sonic::helped::Batch;
+    use crate::sonic::srs::SRS;
+    use crate::sonic::util::multiexp;
+    use crate::shplonk::*;
+    use crate::worker::Worker;
+
+    let d = 16;
+    let srs = SRS::<Bn256>::new(d, Fr::from_str("42").unwrap(), Fr::from_str("7").unwrap());
+
+    // Sonic has no [alpha] G, so polynomials have no constant term
+    let rng = &mut thread_rng();
+    let polys: Vec<Vec<Fr>> = (0..3).map(|_| {
+        let mut poly: Vec<Fr> = (0..d).map(|_| rng.gen()).collect();
+        poly[0] = Fr::zero();
+        poly
+    }).collect();
+
+    let a = Fr::from_str("3").unwrap();
+    let b = Fr::from_str("5").unwrap();
+    let points = vec![vec![a], vec![a, b], vec![b]];
+
+    let evaluate = |poly: &[Fr], at: Fr| {
+        let mut result = Fr::zero();
+        for c in poly.iter().rev() {
+            result.mul_assign(&at);
+            result.add_assign(c);
+        }
+        result
+    };
+
+    let openings: Vec<CommitmentOpening<Bn256>> = polys.iter().zip(points.iter()).map(|(poly, points)| {
+        CommitmentOpening {
+            commitment: multiexp(srs.g_positive_x_alpha[..(d - 1)].iter(), poly[1..].iter()).into_affine(),
+            points: points.clone(),
+            values: points.iter().map(|p| evaluate(poly, *p)).collect()
+        }
+    }).collect();
+
+    let polynomial_openings: Vec<_> = polys.iter().zip(points.iter()).map(|(poly, points)| {
+        PolynomialOpening {
+            coefficients: &poly[..],
+            points: points.clone()
+        }
+    }).collect();
+
+    let gamma: Fr = rng.gen();
+    let z: Fr = rng.gen();
+    let proof = prove_batched_opening::<Bn256, _>(
+        &polynomial_openings,
+        gamma,
+        &srs.g_positive_x,
+        &Worker::new(),
+        |_: &G1Affine| z
+    ).unwrap();
+
+    let reduced = reduce_batched_opening(&openings, gamma, z, &proof).unwrap();
+    let mut batch = Batch::new(&srs, d);
+    batch.add_batched_opening(&reduced, rng.gen());
+    assert!(batch.check_all());
+
+    let mut wrong_openings = openings.clone();
+    wrong_openings[1].values[0].add_assign(&Fr::one());
+    let reduced = reduce_batched_opening(&wrong_openings, gamma, z, &proof).unwrap();
+    let mut batch = Batch::new(&srs, d);
+    batch.add_batched_opening(&reduced, rng.gen());
+    assert!(!batch.check_all());
+}
+
 // #[test]
 // fn test_constraints_info() {
 //     use crate::pairing::bn256::{Bn256};
@@ -868,4 +1225,93 @@ fn test_high_level_sonic_api() {
 //             println!("done in {:?}", start.elapsed());
 //         }
 //     }
-// }
\ No newline at end of file
+// }
+
+#[test]
+fn test_parallel_helped_prover() {
+    use crate::pairing::ff::PrimeField;
+    use crate::pairing::bls12_381::{Bls12, Fr};
+    use crate::multicore::Worker;
+    use crate::sonic::srs::SRS;
+    use crate::sonic::sonic::{AdaptorCircuit, Basic};
+    use crate::sonic::helped::MultiVerifier;
+    use crate::sonic::helped::prover::{
+        create_advice_on_srs,
+        create_advice_on_information_and_srs_with_worker,
+        create_proof_on_srs,
+        create_proof_on_srs_with_worker
+    };
+
+    let rng = &mut thread_rng();
+    let single_thread = Worker::new_with_cpus(1);
+
+    let srs = SRS::<Bls12>::new(1000, Fr::from_str("23923").unwrap(), Fr::from_str("23728792").unwrap());
+
+    let constants = (0..50).map(|_| rng.gen()).collect::<Vec<Fr>>();
+    let xl = rng.gen();
+    let xr = rng.gen();
+    let image = mimc_rounds::<Bls12>(xl, xr, &constants);
+
+    let circuit = AdaptorCircuit(MiMCRounds(MiMCDemo::<Bls12> { xl: Some(xl), xr: Some(xr), constants: &constants }));
+
+    let proof = create_proof_on_srs::<Bls12, _, Basic>(&circuit, &srs).unwrap();
+    let single_thread_proof = create_proof_on_srs_with_worker::<Bls12, _, Basic>(&circuit, &srs, &single_thread).unwrap();
+
+    // advice is deterministic for a given proof
+    let advice = create_advice_on_srs::<Bls12, _, Basic>(&circuit, &proof, &srs).unwrap();
+    let n = count_n(&circuit);
+    assert!(advice == create_advice_on_information_and_srs_with_worker::<Bls12, _, Basic>(&circuit, &proof, &srs, n, &single_thread).unwrap());
+
+    let mut verifier = MultiVerifier::<Bls12, _, Basic, _>::new(circuit.clone(), &srs, thread_rng()).unwrap();
+    verifier.add_proof(&proof, &[image], |_, _| None);
+    verifier.add_proof(&single_thread_proof, &[image], |_, _| None);
+    verifier.add_proof_with_advice(&proof, &[image], &advice);
+    assert!(verifier.check_all());
+
+    let mut wrong_image = image;
+    wrong_image.add_assign(&Fr::one());
+    let mut verifier = MultiVerifier::<Bls12, _, Basic, _>::new(circuit.clone(), &srs, thread_rng()).unwrap();
+    verifier.add_proof(&proof, &[wrong_image], |_, _| None);
+    assert!(!verifier.check_all());
+}
+
+#[test]
+#[ignore]
+fn test_bench_parallel_helped_prover() {
+    use crate::pairing::bls12_381::{Bls12, Fr};
+    use crate::multicore::Worker;
+    use crate::sonic::srs::SRS;
+    use crate::sonic::sonic::{AdaptorCircuit, Basic};
+    use crate::sonic::helped::prover::{
+        create_advice_on_information_and_srs_with_worker,
+        create_proof_on_srs_with_worker
+    };
+
+    let rng = &mut thread_rng();
+    let rounds = 1 << 12;
+    let srs = SRS::<Bls12>::dummy(1 << 16, Fr::one(), Fr::one());
+
+    let constants = (0..rounds).map(|_| rng.gen()).collect::<Vec<Fr>>();
+    let circuit = AdaptorCircuit(MiMCRounds(MiMCDemo::<Bls12> { xl: Some(rng.gen()), xr: Some(rng.gen()), constants: &constants }));
+    let n = count_n(&circuit);
+
+    for worker in [Worker::new_with_cpus(1), Worker::new()].iter() {
+        let start = Instant::now();
+        let proof = create_proof_on_srs_with_worker::<Bls12, _, Basic>(&circuit, &srs, worker).unwrap();
+        println!("proof for {} MiMC rounds on {} threads: {:?}", rounds, 1 << worker.log_num_cpus(), start.elapsed());
+
+        let start = Instant::now();
+        create_advice_on_information_and_srs_with_worker::<Bls12, _, Basic>(&circuit, &proof, &srs, n, worker).unwrap();
+        println!("advice for {} MiMC rounds on {} threads: {:?}", rounds, 1 << worker.log_num_cpus(), start.elapsed());
+    }
+}
+
+fn count_n<C: crate::sonic::cs::Circuit<Bls12>>(circuit: &C) -> usize {
+    use crate::sonic::sonic::{Basic, CountN};
+    use crate::sonic::cs::SynthesisDriver;
+
+    let mut tmp = CountN::<Basic>::new();
+    Basic::synthesize(&mut tmp, circuit).unwrap();
+
+    tmp.n
+}
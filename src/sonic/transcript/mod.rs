@@ -2,7 +2,7 @@ use crate::pairing::ff::{Field, PrimeField, PrimeFieldRepr};
 use crate::pairing::{CurveAffine, CurveProjective, Engine};
 use std::io;
 
-mod hasher;
+pub mod hasher;
 
 use self::hasher::{Hasher, Keccak256Hasher, BlakeHasher};
 
@@ -123,6 +123,63 @@ impl<H:Hasher> TranscriptProtocol for RollingHashTranscript<H> {
     }
 }
 
+// labels are prefixed with their length, so they can not be confused with the
+// fixed personalizations of points and scalars
+impl<F: PrimeField, H: Hasher + Clone> crate::transcript::ProtocolTranscript<F> for RollingHashTranscript<H> {
+    fn commit_point<G: CurveAffine>(&mut self, point: &G) {
+        TranscriptProtocol::commit_point(self, point);
+    }
+
+    fn commit_scalar(&mut self, scalar: &F) {
+        TranscriptProtocol::commit_scalar(self, scalar);
+    }
+
+    fn commit_labelled_bytes(&mut self, label: &[u8], bytes: &[u8]) {
+        use byteorder::ByteOrder;
+        let mut personalization = vec![0u8; 4];
+        byteorder::BigEndian::write_u32(&mut personalization, label.len() as u32);
+        personalization.extend_from_slice(label);
+
+        self.commit_bytes(&personalization, bytes);
+        self.repeated_request_nonce = 0u32;
+    }
+
+    fn squeeze_scalar(&mut self) -> F {
+        self.get_challenge_scalar()
+    }
+
+    fn squeeze_bytes(&mut self) -> Vec<u8> {
+        use byteorder::ByteOrder;
+        let mut nonce_bytes = vec![0u8; 4];
+        byteorder::BigEndian::write_u32(&mut nonce_bytes, self.repeated_request_nonce);
+        self.repeated_request_nonce += 1;
+
+        self.get_challenge_bytes(&nonce_bytes)
+    }
+}
+
+impl<F: PrimeField> crate::transcript::ProtocolTranscript<F> for Transcript {
+    fn commit_point<G: CurveAffine>(&mut self, point: &G) {
+        TranscriptProtocol::commit_point(&mut self.transcriptor, point);
+    }
+
+    fn commit_scalar(&mut self, scalar: &F) {
+        TranscriptProtocol::commit_scalar(&mut self.transcriptor, scalar);
+    }
+
+    fn commit_labelled_bytes(&mut self, label: &[u8], bytes: &[u8]) {
+        crate::transcript::ProtocolTranscript::<F>::commit_labelled_bytes(&mut self.transcriptor, label, bytes);
+    }
+
+    fn squeeze_scalar(&mut self) -> F {
+        self.transcriptor.get_challenge_scalar()
+    }
+
+    fn squeeze_bytes(&mut self) -> Vec<u8> {
+        crate::transcript::ProtocolTranscript::<F>::squeeze_bytes(&mut self.transcriptor)
+    }
+}
+
 // struct TranscriptReader<'a, H:Hasher>(&'a mut Transcript<H>);
 
 // impl<'a, H:Hasher> io::Read for TranscriptReader<'a, H: Hasher> {
@@ -0,0 +1,36 @@
+use crate::pairing::{Engine};
+
+use crate::sonic::helped::{Proof, SxyAdvice, Parameters};
+
+use crate::{Circuit};
+use crate::sonic::sonic::AdaptorCircuit;
+use crate::sonic::srs::SRS;
+use crate::sonic::sonic::Permutation3;
+
+use super::aggregate::SuccinctAggregate;
+use super::aggregate::create_aggregate as create_aggregate_sonic_circuit;
+use super::aggregate::create_aggregate_on_srs as create_aggregate_on_srs_sonic_circuit;
+use super::permutation_structure::create_permutation_structure;
+
+pub fn create_adapted_aggregate<E: Engine, C: Circuit<E> + Clone>(
+    circuit: C,
+    inputs: &[(Proof<E>, SxyAdvice<E>)],
+    params: &Parameters<E>,
+) -> SuccinctAggregate<E>
+{
+    let adapted_circuit = AdaptorCircuit(circuit);
+
+    create_aggregate_sonic_circuit::<_, _, Permutation3>(&adapted_circuit, inputs, params)
+}
+
+pub fn create_adapted_aggregate_on_srs<E: Engine, C: Circuit<E> + Clone>(
+    circuit: C,
+    inputs: &[(Proof<E>, SxyAdvice<E>)],
+    srs: &SRS<E>,
+) -> SuccinctAggregate<E>
+{
+    let adapted_circuit = AdaptorCircuit(circuit);
+    let specialized_srs = create_permutation_structure(&adapted_circuit).create_permutation_special_reference(srs);
+
+    create_aggregate_on_srs_sonic_circuit::<_, _, Permutation3>(&adapted_circuit, inputs, srs, &specialized_srs)
+}
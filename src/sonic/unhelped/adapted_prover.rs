@@ -0,0 +1,73 @@
+use crate::pairing::{Engine};
+
+use crate::sonic::helped::{Proof, SxyAdvice, Parameters};
+use crate::sonic::helped::prover::create_advice_on_information_and_srs as create_advice_on_information_and_srs_sonic_circuit;
+use crate::sonic::helped::prover::create_proof_on_srs as create_proof_on_srs_sonic_circuit;
+
+use crate::SynthesisError;
+
+use crate::sonic::cs::SynthesisDriver;
+use crate::{Circuit};
+use crate::sonic::sonic::AdaptorCircuit;
+use crate::sonic::srs::SRS;
+use crate::sonic::sonic::{CountN, Permutation3};
+
+// Proofs and advices are the same as for helped Sonic, but circuit
+// is synthesized with `Permutation3` so that the aggregate can use
+// succinct signature of s(z, y)
+
+pub fn create_advice_on_information_and_srs<E: Engine, C: Circuit<E> + Clone>(
+    circuit: C,
+    proof: &Proof<E>,
+    srs: &SRS<E>,
+    n: usize
+) -> Result<SxyAdvice<E>, SynthesisError>
+{
+    let adapted_circuit = AdaptorCircuit(circuit);
+
+    create_advice_on_information_and_srs_sonic_circuit::<_, _, Permutation3>(&adapted_circuit, proof, srs, n)
+}
+
+pub fn create_advice<E: Engine, C: Circuit<E> + Clone>(
+    circuit: C,
+    proof: &Proof<E>,
+    parameters: &Parameters<E>,
+) -> Result<SxyAdvice<E>, SynthesisError>
+{
+    let n = parameters.vk.n;
+    create_advice_on_information_and_srs::<E, C>(circuit, proof, &parameters.srs, n)
+}
+
+pub fn create_advice_on_srs<E: Engine, C: Circuit<E> + Clone>(
+    circuit: C,
+    proof: &Proof<E>,
+    srs: &SRS<E>
+) -> Result<SxyAdvice<E>, SynthesisError>
+{
+    let adapted_circuit = AdaptorCircuit(circuit.clone());
+    let n = {
+        let mut tmp = CountN::<Permutation3>::new();
+        Permutation3::synthesize(&mut tmp, &adapted_circuit)?;
+
+        tmp.n
+    };
+
+    create_advice_on_information_and_srs::<E, C>(circuit, proof, srs, n)
+}
+
+pub fn create_proof<E: Engine, C: Circuit<E> + Clone>(
+    circuit: C,
+    parameters: &Parameters<E>
+) -> Result<Proof<E>, SynthesisError> {
+    create_proof_on_srs::<E, C>(circuit, &parameters.srs)
+}
+
+pub fn create_proof_on_srs<E: Engine, C: Circuit<E> + Clone>(
+    circuit: C,
+    srs: &SRS<E>
+) -> Result<Proof<E>, SynthesisError>
+{
+    let adapted_circuit = AdaptorCircuit(circuit);
+
+    create_proof_on_srs_sonic_circuit::<_, _, Permutation3>(&adapted_circuit, srs)
+}
@@ -0,0 +1,67 @@
+use crate::pairing::{Engine};
+
+use rand::{Rng};
+
+use crate::sonic::helped::{Proof, SxyAdvice, Parameters};
+
+use crate::SynthesisError;
+
+use crate::{Circuit};
+use crate::sonic::sonic::AdaptorCircuit;
+use crate::sonic::srs::SRS;
+use crate::sonic::sonic::Permutation3;
+use super::aggregate::SuccinctAggregate;
+use super::verifier::verify_aggregate_on_srs as verify_aggregate_on_srs_sonic_circuit;
+use super::verifier::verify_proofs_on_srs as verify_proofs_on_srs_sonic_circuit;
+
+pub fn verify_proofs<E: Engine, C: Circuit<E> + Clone, R: Rng>(
+    proofs: &[Proof<E>],
+    inputs: &[Vec<E::Fr>],
+    circuit: C,
+    rng: R,
+    params: &Parameters<E>,
+) -> Result<bool, SynthesisError>
+{
+    verify_proofs_on_srs::<E, C, R>(proofs, inputs, circuit, rng, &params.srs)
+}
+
+pub fn verify_proofs_on_srs<E: Engine, C: Circuit<E> + Clone, R: Rng>(
+    proofs: &[Proof<E>],
+    inputs: &[Vec<E::Fr>],
+    circuit: C,
+    rng: R,
+    srs: &SRS<E>,
+) -> Result<bool, SynthesisError>
+{
+    let adapted_circuit = AdaptorCircuit(circuit);
+
+    verify_proofs_on_srs_sonic_circuit::<_, _, Permutation3, _>(proofs, inputs, adapted_circuit, rng, srs)
+}
+
+/// Check multiple proofs with the succinct aggregate, so verifier's
+/// work doesn't include `S(X, Y)` evaluation
+pub fn verify_aggregate<E: Engine, C: Circuit<E> + Clone, R: Rng>(
+    proofs: &[(Proof<E>, SxyAdvice<E>)],
+    aggregate: &SuccinctAggregate<E>,
+    inputs: &[Vec<E::Fr>],
+    circuit: C,
+    rng: R,
+    params: &Parameters<E>,
+) -> Result<bool, SynthesisError> {
+    verify_aggregate_on_srs::<E, C, R>(proofs, aggregate, inputs, circuit, rng, &params.srs)
+}
+
+/// Check multiple proofs with the succinct aggregate, so verifier's
+/// work doesn't include `S(X, Y)` evaluation
+pub fn verify_aggregate_on_srs<E: Engine, C: Circuit<E> + Clone, R: Rng>(
+    proofs: &[(Proof<E>, SxyAdvice<E>)],
+    aggregate: &SuccinctAggregate<E>,
+    inputs: &[Vec<E::Fr>],
+    circuit: C,
+    rng: R,
+    srs: &SRS<E>,
+) -> Result<bool, SynthesisError> {
+    let adapted_circuit = AdaptorCircuit(circuit);
+
+    verify_aggregate_on_srs_sonic_circuit::<_, _, Permutation3, _>(proofs, aggregate, inputs, adapted_circuit, rng, srs)
+}
@@ -1,11 +1,13 @@
 use crate::pairing::ff::{Field};
 use crate::pairing::{Engine, CurveProjective};
 use std::marker::PhantomData;
+use std::io::{self, Read, Write};
 
 use crate::sonic::helped::{Proof, SxyAdvice};
 use crate::sonic::helped::batch::Batch;
 use crate::sonic::helped::poly::{SxEval, SyEval};
 use crate::sonic::helped::Parameters;
+use crate::sonic::helped::helper::{Aggregate, write_aggregate_openings};
 
 use crate::SynthesisError;
 
@@ -39,17 +41,53 @@ pub struct SuccinctAggregate<E: Engine> {
 
 }
 
-// pub fn create_aggregate<E: Engine, C: Circuit<E>, S: SynthesisDriver>(
-//     circuit: &C,
-//     inputs: &[(Proof<E>, SxyAdvice<E>)],
-//     params: &Parameters<E>,
-// ) -> SuccinctAggregate<E>
-// {
-//     let n = params.vk.n;
-//     let q = params.vk.q;
-
-//     create_aggregate_on_srs_using_information::<E, C, S>(circuit, inputs, &params.srs, n, q)
-// }
+impl<E: Engine> SuccinctAggregate<E> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        self.signature.write(&mut writer)?;
+        self.s2_proof.write(&mut writer)?;
+
+        write_aggregate_openings::<E, _>(&self.c, &self.s_opening, &self.c_openings, &self.opening, &self.z, &self.w, writer)
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let signature = SignatureOfCorrectComputation::read(&mut reader)?;
+        let s2_proof = S2Proof::read(&mut reader)?;
+
+        let Aggregate { c, s_opening, c_openings, opening, z, w } = Aggregate::<E>::read(&mut reader)?;
+
+        Ok(SuccinctAggregate {
+            signature,
+            s2_proof,
+            c,
+            s_opening,
+            c_openings,
+            opening,
+            z,
+            w
+        })
+    }
+}
+
+pub fn create_aggregate<E: Engine, C: Circuit<E>, S: SynthesisDriver>(
+    circuit: &C,
+    inputs: &[(Proof<E>, SxyAdvice<E>)],
+    params: &Parameters<E>,
+) -> SuccinctAggregate<E>
+{
+    let n = params.vk.n;
+    let q = params.vk.q;
+
+    let specialized_srs = create_permutation_structure(circuit).create_permutation_special_reference(&params.srs);
+
+    create_aggregate_on_srs_using_information::<E, C, S>(circuit, inputs, &params.srs, &specialized_srs, n, q)
+}
 
 pub fn create_aggregate_on_srs<E: Engine, C: Circuit<E>, S: SynthesisDriver>(
     circuit: &C,
@@ -5,6 +5,8 @@
 use crate::pairing::ff::{Field, PrimeField, PrimeFieldRepr};
 use crate::pairing::{Engine, CurveProjective, CurveAffine};
 use std::marker::PhantomData;
+use std::io::{self, Read, Write};
+use byteorder::{BigEndian, WriteBytesExt, ReadBytesExt};
 
 use crate::sonic::srs::SRS;
 use crate::sonic::util::*;
@@ -38,6 +40,104 @@ pub struct GrandProductSignature<E: Engine> {
     pub wellformedness_signature: WellformednessSignature<E>,
 }
 
+impl<E: Engine> GrandProductProof<E> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        write_point(&self.t_opening, &mut writer)?;
+        write_field_element(&self.e_zinv, &mut writer)?;
+        write_point(&self.e_opening, &mut writer)?;
+        write_field_element(&self.f_y, &mut writer)?;
+        write_point(&self.f_opening, &mut writer)?;
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let t_opening = read_point(&mut reader)?;
+        let e_zinv = read_field_element(&mut reader)?;
+        let e_opening = read_point(&mut reader)?;
+        let f_y = read_field_element(&mut reader)?;
+        let f_opening = read_point(&mut reader)?;
+
+        Ok(GrandProductProof {
+            t_opening,
+            e_zinv,
+            e_opening,
+            f_y,
+            f_opening
+        })
+    }
+}
+
+impl<E: Engine> GrandProductSignature<E> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        writer.write_u32::<BigEndian>(self.c_commitments.len() as u32)?;
+        for (commitment, value) in self.c_commitments.iter() {
+            write_point(commitment, &mut writer)?;
+            write_field_element(value, &mut writer)?;
+        }
+
+        write_point(&self.t_commitment, &mut writer)?;
+
+        writer.write_u32::<BigEndian>(self.grand_product_openings.len() as u32)?;
+        for (value, opening) in self.grand_product_openings.iter() {
+            write_field_element(value, &mut writer)?;
+            write_point(opening, &mut writer)?;
+        }
+
+        self.proof.write(&mut writer)?;
+        self.wellformedness_signature.write(&mut writer)?;
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let num_commitments = reader.read_u32::<BigEndian>()? as usize;
+        let mut c_commitments = vec![];
+        for _ in 0..num_commitments {
+            let commitment = read_point(&mut reader)?;
+            let value = read_field_element(&mut reader)?;
+
+            c_commitments.push((commitment, value));
+        }
+
+        let t_commitment = read_point(&mut reader)?;
+
+        let num_openings = reader.read_u32::<BigEndian>()? as usize;
+        let mut grand_product_openings = vec![];
+        for _ in 0..num_openings {
+            let value = read_field_element(&mut reader)?;
+            let opening = read_point(&mut reader)?;
+
+            grand_product_openings.push((value, opening));
+        }
+
+        let proof = GrandProductProof::read(&mut reader)?;
+        let wellformedness_signature = WellformednessSignature::read(&mut reader)?;
+
+        Ok(GrandProductSignature {
+            c_commitments,
+            t_commitment,
+            grand_product_openings,
+            proof,
+            wellformedness_signature
+        })
+    }
+}
+
 impl<E: Engine> GrandProductArgument<E> {
     pub fn create_signature(
         transcript: &mut Transcript,
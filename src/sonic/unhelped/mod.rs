@@ -2,16 +2,43 @@
 /// s2(X, Y) = \sum_{i=1}^{N} (Y^{-i} + Y^{i})X^{i}
 /// s1(X, Y) = ...
 /// s1 part requires grand product and permutation arguments, that are also implemented
-
+///
+/// For bellman circuits the flow is: get parameters from `helped::generate_parameters_for_succinct_sonic`
+/// (or use a large enough SRS directly), `create_proof` and `create_advice` for every proof,
+/// `create_adapted_aggregate` for a batch of them and check everything with `verify_aggregate`.
+/// `create_aggregate` keeps taking a circuit of `sonic::cs` with its synthesis driver
 mod s2_proof;
 mod wellformed_argument;
 pub mod grand_product_argument;
 mod permutation_argument;
-mod verifier;
+pub mod verifier;
 pub mod permutation_structure;
-mod aggregate;
+pub mod aggregate;
+mod adapted_prover;
+mod adapted_aggregate;
+mod adapted_verifier;
 
 pub use self::wellformed_argument::{WellformednessArgument, WellformednessProof};
 pub use self::permutation_argument::{PermutationArgument, PermutationProof, PermutationArgumentProof};
 pub use self::verifier::SuccinctMultiVerifier;
-pub use self::aggregate::*;
\ No newline at end of file
+pub use self::aggregate::*;
+
+pub use self::adapted_prover::{
+    create_advice,
+    create_advice_on_srs,
+    create_advice_on_information_and_srs,
+    create_proof,
+    create_proof_on_srs,
+};
+
+pub use self::adapted_aggregate::{
+    create_adapted_aggregate,
+    create_adapted_aggregate_on_srs
+};
+
+pub use self::adapted_verifier::{
+    verify_proofs,
+    verify_proofs_on_srs,
+    verify_aggregate,
+    verify_aggregate_on_srs
+};
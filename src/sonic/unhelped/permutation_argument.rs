@@ -5,6 +5,8 @@
 use crate::pairing::ff::{Field, PrimeField, PrimeFieldRepr, ScalarEngine};
 use crate::pairing::{Engine, CurveProjective, CurveAffine};
 use std::marker::PhantomData;
+use std::io::{self, Read, Write};
+use byteorder::{BigEndian, WriteBytesExt, ReadBytesExt};
 
 use crate::sonic::srs::SRS;
 use crate::sonic::util::*;
@@ -55,6 +57,117 @@ pub struct SignatureOfCorrectComputation<E: Engine> {
     pub grand_product_signature: GrandProductSignature<E>
 }
 
+impl<E: Engine> PermutationProof<E> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        write_field_element(&self.v_zy, &mut writer)?;
+        write_point(&self.e_opening, &mut writer)?;
+        write_point(&self.f_opening, &mut writer)?;
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let v_zy = read_field_element(&mut reader)?;
+        let e_opening = read_point(&mut reader)?;
+        let f_opening = read_point(&mut reader)?;
+
+        Ok(PermutationProof {
+            v_zy,
+            e_opening,
+            f_opening
+        })
+    }
+}
+
+impl<E: Engine> PermutationArgumentProof<E> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        writer.write_u32::<BigEndian>(self.j as u32)?;
+        write_point(&self.s_opening, &mut writer)?;
+        write_field_element(&self.s_zy, &mut writer)?;
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let j = reader.read_u32::<BigEndian>()? as usize;
+        let s_opening = read_point(&mut reader)?;
+        let s_zy = read_field_element(&mut reader)?;
+
+        Ok(PermutationArgumentProof {
+            j,
+            s_opening,
+            s_zy
+        })
+    }
+}
+
+impl<E: Engine> SignatureOfCorrectComputation<E> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        writer.write_u32::<BigEndian>(self.s_commitments.len() as u32)?;
+        for commitment in self.s_commitments.iter() {
+            write_point(commitment, &mut writer)?;
+        }
+
+        writer.write_u32::<BigEndian>(self.s_prime_commitments.len() as u32)?;
+        for commitment in self.s_prime_commitments.iter() {
+            write_point(commitment, &mut writer)?;
+        }
+
+        self.perm_argument_proof.write(&mut writer)?;
+        self.perm_proof.write(&mut writer)?;
+        self.grand_product_signature.write(&mut writer)?;
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let num_commitments = reader.read_u32::<BigEndian>()? as usize;
+        let mut s_commitments = vec![];
+        for _ in 0..num_commitments {
+            s_commitments.push(read_point(&mut reader)?);
+        }
+
+        let num_commitments = reader.read_u32::<BigEndian>()? as usize;
+        let mut s_prime_commitments = vec![];
+        for _ in 0..num_commitments {
+            s_prime_commitments.push(read_point(&mut reader)?);
+        }
+
+        let perm_argument_proof = PermutationArgumentProof::read(&mut reader)?;
+        let perm_proof = PermutationProof::read(&mut reader)?;
+        let grand_product_signature = GrandProductSignature::read(&mut reader)?;
+
+        Ok(SignatureOfCorrectComputation {
+            s_commitments,
+            s_prime_commitments,
+            perm_argument_proof,
+            perm_proof,
+            grand_product_signature
+        })
+    }
+}
+
 // fn permute<F: Field>(coeffs: &[F], permutation: & [usize]) -> Vec<F>{
 //     assert_eq!(coeffs.len(), permutation.len());
 //     let mut result: Vec<F> = vec![F::zero(); coeffs.len()];
@@ -1,6 +1,7 @@
 use crate::pairing::ff::{Field, PrimeField, PrimeFieldRepr};
 use crate::pairing::{Engine, CurveProjective, CurveAffine};
 use std::marker::PhantomData;
+use std::io::{self, Read, Write};
 
 use crate::sonic::srs::SRS;
 use crate::sonic::util::*;
@@ -20,6 +21,41 @@ pub struct S2Proof<E: Engine> {
     pub d_opening: E::G1Affine
 }
 
+impl<E: Engine> S2Proof<E> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        write_point(&self.o, &mut writer)?;
+        write_field_element(&self.c_value, &mut writer)?;
+        write_field_element(&self.d_value, &mut writer)?;
+        write_point(&self.c_opening, &mut writer)?;
+        write_point(&self.d_opening, &mut writer)?;
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let o = read_point(&mut reader)?;
+        let c_value = read_field_element(&mut reader)?;
+        let d_value = read_field_element(&mut reader)?;
+        let c_opening = read_point(&mut reader)?;
+        let d_opening = read_point(&mut reader)?;
+
+        Ok(S2Proof {
+            o,
+            c_value,
+            d_value,
+            c_opening,
+            d_opening
+        })
+    }
+}
+
 impl<E: Engine> S2Eval<E> {
     pub fn calculate_commitment_element(n: usize, srs: &SRS<E>) -> E::G1Affine {
         // TODO: parallelize
@@ -92,7 +128,7 @@ impl<E: Engine> S2Eval<E> {
         minus_xy.negate();
 
         let mut h_alpha_term = proof.c_opening.mul(minus_xy.into_repr());
-        let g_in_c = E::G1Affine::one().mul(proof.c_value);
+        let g_in_c = srs.g_positive_x[0].mul(proof.c_value);
         h_alpha_term.add_assign(&g_in_c);
 
         let h_alpha_term = h_alpha_term.into_affine();
@@ -114,7 +150,7 @@ impl<E: Engine> S2Eval<E> {
         minus_x_y_inv.negate();
 
         let mut h_alpha_term = proof.d_opening.mul(minus_x_y_inv.into_repr());
-        let g_in_d = E::G1Affine::one().mul(proof.d_value);
+        let g_in_d = srs.g_positive_x[0].mul(proof.d_value);
         h_alpha_term.add_assign(&g_in_d);
 
         let h_alpha_term = h_alpha_term.into_affine();
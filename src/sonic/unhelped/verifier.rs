@@ -37,6 +37,9 @@ pub struct SuccinctMultiVerifier<E: Engine, C: Circuit<E>, S: SynthesisDriver, R
     n: usize,
     q: usize,
     randomness_source: R,
+    // pairing checks of the aggregate are not batched,
+    // so their result is kept until `check_all`
+    valid: bool,
     _marker: PhantomData<(E, S)>
 }
 
@@ -65,6 +68,7 @@ impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> SuccinctMultiVerifier
             n: n,
             q: q,
             randomness_source: rng,
+            valid: true,
             _marker: PhantomData
         })
     }
@@ -298,7 +302,10 @@ impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> SuccinctMultiVerifier
                     let h_alpha_x_precomp = srs.h_positive_x_alpha[1].prepare();
                     let h_alpha_precomp = srs.h_positive_x_alpha[0].prepare();
 
-                    let mut h_x_n_plus_one_precomp = srs.h_positive_x[self.n+1];
+                    // grand products are over permutation vectors of length 3n + 1
+                    let n = 3*self.n + 1;
+
+                    let mut h_x_n_plus_one_precomp = srs.h_positive_x[n+1];
                     h_x_n_plus_one_precomp.negate();
                     let h_x_n_plus_one_precomp = h_x_n_plus_one_precomp.prepare();
 
@@ -355,8 +362,8 @@ impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> SuccinctMultiVerifier
                             (&value, &h_alpha_precomp)
                         ])).unwrap() == E::Fqk::one();
 
-                    // TODO
-                    assert!(valid, "grand product arguments must be valid for individual commitments");
+                    // grand product arguments must be valid for individual commitments
+                    self.valid &= valid;
 
                 }
 
@@ -517,7 +524,8 @@ impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> SuccinctMultiVerifier
                             (&r_r2.prepare(), &alpha_x_n_minus_d_precomp)
                         ])).unwrap() == E::Fqk::one();
 
-                    assert!(valid, "wellformedness argument must be valid");
+                    // wellformedness argument must be valid
+                    self.valid &= valid;
                 }
 
             }
@@ -686,76 +694,83 @@ impl<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng> SuccinctMultiVerifier
     }
 
     pub fn check_all(self) -> bool {
-        self.batch.check_all()
+        self.valid && self.batch.check_all()
     }
 }
 
-// /// Check multiple proofs without aggregation. Verifier's work is 
-// /// not succint due to `S(X, Y)` evaluation
-// pub fn verify_proofs<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng>(
-//     proofs: &[Proof<E>],
-//     inputs: &[Vec<E::Fr>],
-//     circuit: C,
-//     rng: R,
-//     params: &Parameters<E>,
-// ) -> Result<bool, SynthesisError> {
-//     verify_proofs_on_srs::<E, C, S, R>(proofs, inputs, circuit, rng, &params.srs)
-// }
-
-// /// Check multiple proofs without aggregation. Verifier's work is 
-// /// not succint due to `S(X, Y)` evaluation
-// pub fn verify_proofs_on_srs<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng>(
-//     proofs: &[Proof<E>],
-//     inputs: &[Vec<E::Fr>],
-//     circuit: C,
-//     rng: R,
-//     srs: &SRS<E>,
-// ) -> Result<bool, SynthesisError> {
-//     let mut verifier = MultiVerifier::<E, C, S, R>::new(circuit, srs, rng)?;
-//     let expected_inputs_size = verifier.get_k_map().len() - 1;
-//     for (proof, inputs) in proofs.iter().zip(inputs.iter()) {
-//         if inputs.len() != expected_inputs_size {
-//             return Err(SynthesisError::Unsatisfiable);
-//         }
-//         verifier.add_proof(proof, &inputs, |_, _| None);
-//     }
-
-//     Ok(verifier.check_all())
-// }
-
-// /// Check multiple proofs with aggregation. Verifier's work is 
-// /// not succint due to `S(X, Y)` evaluation
-// pub fn verify_aggregate<E: Engine, C: Circuit<E>, S: SynthesisDriver,R: Rng>(
-//     proofs: &[(Proof<E>, SxyAdvice<E>)],
-//     aggregate: &Aggregate<E>,
-//     inputs: &[Vec<E::Fr>],
-//     circuit: C,
-//     rng: R,
-//     params: &Parameters<E>,
-// ) -> Result<bool, SynthesisError> {
-//     verify_aggregate_on_srs::<E, C, S, R>(proofs, aggregate, inputs, circuit, rng, &params.srs)
-// }
-
-// /// Check multiple proofs with aggregation. Verifier's work is 
-// /// not succint due to `S(X, Y)` evaluation
-// pub fn verify_aggregate_on_srs<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng>(
-//     proofs: &[(Proof<E>, SxyAdvice<E>)],
-//     aggregate: &Aggregate<E>,
-//     inputs: &[Vec<E::Fr>],
-//     circuit: C,
-//     rng: R,
-//     srs: &SRS<E>,
-// ) -> Result<bool, SynthesisError> {
-//     let mut verifier = MultiVerifier::<E, C, S, R>::new(circuit, srs, rng)?;
-//     let expected_inputs_size = verifier.get_k_map().len() - 1;
-//     for ((proof, advice), inputs) in proofs.iter().zip(inputs.iter()) {
-//         if inputs.len() != expected_inputs_size {
-//             return Err(SynthesisError::Unsatisfiable);
-//         }
-//         verifier.add_proof_with_advice(proof, &inputs, &advice);
-//     }
-//     verifier.add_aggregate(proofs, aggregate);
-
-//     Ok(verifier.check_all())
-// }
+/// Check multiple proofs without aggregation. Verifier's work is
+/// not succint due to `S(X, Y)` evaluation
+pub fn verify_proofs<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng>(
+    proofs: &[Proof<E>],
+    inputs: &[Vec<E::Fr>],
+    circuit: C,
+    rng: R,
+    params: &Parameters<E>,
+) -> Result<bool, SynthesisError> {
+    verify_proofs_on_srs::<E, C, S, R>(proofs, inputs, circuit, rng, &params.srs)
+}
+
+/// Check multiple proofs without aggregation. Verifier's work is
+/// not succint due to `S(X, Y)` evaluation
+pub fn verify_proofs_on_srs<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng>(
+    proofs: &[Proof<E>],
+    inputs: &[Vec<E::Fr>],
+    circuit: C,
+    rng: R,
+    srs: &SRS<E>,
+) -> Result<bool, SynthesisError> {
+    if proofs.len() != inputs.len() {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+    let mut verifier = SuccinctMultiVerifier::<E, C, S, R>::new(circuit, srs, rng)?;
+    let expected_inputs_size = verifier.get_k_map().len() - 1;
+    for (proof, inputs) in proofs.iter().zip(inputs.iter()) {
+        if inputs.len() != expected_inputs_size {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        verifier.add_proof(proof, inputs, |_, _| None);
+    }
+
+    Ok(verifier.check_all())
+}
 
+/// Check multiple proofs with the succinct aggregate. `s(z, w)` is
+/// checked with permutation and grand product arguments, so verifier
+/// doesn't need to evaluate `S(X, Y)` itself
+pub fn verify_aggregate<E: Engine, C: Circuit<E>, S: SynthesisDriver,R: Rng>(
+    proofs: &[(Proof<E>, SxyAdvice<E>)],
+    aggregate: &SuccinctAggregate<E>,
+    inputs: &[Vec<E::Fr>],
+    circuit: C,
+    rng: R,
+    params: &Parameters<E>,
+) -> Result<bool, SynthesisError> {
+    verify_aggregate_on_srs::<E, C, S, R>(proofs, aggregate, inputs, circuit, rng, &params.srs)
+}
+
+/// Check multiple proofs with the succinct aggregate. `s(z, w)` is
+/// checked with permutation and grand product arguments, so verifier
+/// doesn't need to evaluate `S(X, Y)` itself
+pub fn verify_aggregate_on_srs<E: Engine, C: Circuit<E>, S: SynthesisDriver, R: Rng>(
+    proofs: &[(Proof<E>, SxyAdvice<E>)],
+    aggregate: &SuccinctAggregate<E>,
+    inputs: &[Vec<E::Fr>],
+    circuit: C,
+    rng: R,
+    srs: &SRS<E>,
+) -> Result<bool, SynthesisError> {
+    if proofs.len() != inputs.len() {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+    let mut verifier = SuccinctMultiVerifier::<E, C, S, R>::new(circuit, srs, rng)?;
+    let expected_inputs_size = verifier.get_k_map().len() - 1;
+    for ((proof, advice), inputs) in proofs.iter().zip(inputs.iter()) {
+        if inputs.len() != expected_inputs_size {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        verifier.add_proof_with_advice(proof, inputs, advice);
+    }
+    verifier.add_aggregate(proofs, aggregate, srs);
+
+    Ok(verifier.check_all())
+}
@@ -4,6 +4,7 @@
 use crate::pairing::ff::{Field, PrimeField, PrimeFieldRepr};
 use crate::pairing::{Engine, CurveProjective, CurveAffine};
 use std::marker::PhantomData;
+use std::io::{self, Read, Write};
 
 use crate::sonic::srs::SRS;
 use crate::sonic::util::*;
@@ -25,6 +26,51 @@ pub struct WellformednessSignature<E: Engine> {
     pub proof: WellformednessProof<E>
 }
 
+impl<E: Engine> WellformednessProof<E> {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        write_point(&self.l, &mut writer)?;
+        write_point(&self.r, &mut writer)?;
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let l = read_point(&mut reader)?;
+        let r = read_point(&mut reader)?;
+
+        Ok(WellformednessProof {
+            l,
+            r
+        })
+    }
+}
+
+impl<E: Engine> WellformednessSignature<E> {
+    pub fn write<W: Write>(
+        &self,
+        writer: W
+    ) -> io::Result<()>
+    {
+        self.proof.write(writer)
+    }
+
+    pub fn read<R: Read>(
+        reader: R
+    ) -> io::Result<Self>
+    {
+        Ok(WellformednessSignature {
+            proof: WellformednessProof::read(reader)?
+        })
+    }
+}
+
 impl<E: Engine> WellformednessArgument<E> {
 
     pub fn create_signature(
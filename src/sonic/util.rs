@@ -2,6 +2,9 @@ use crate::SynthesisError;
 use crate::pairing::ff::{Field, PrimeField, PrimeFieldRepr, ScalarEngine};
 use crate::pairing::{CurveAffine, CurveProjective, Engine};
 use super::srs::SRS;
+use crate::multicore::Worker;
+
+pub(crate) use crate::serialization::{write_field_element, read_field_element, write_point, read_point};
 
 pub trait ChainExt: Iterator {
     fn chain_ext<U>(self, other: U) -> Chain<Self, U::IntoIter>
@@ -145,6 +148,62 @@ pub fn polynomial_commitment_opening<
         ).into_affine()
     }
 
+/// Same as `polynomial_commitment`, but coefficients are given as a slice
+/// and the multiexps run on the provided worker
+pub fn parallel_polynomial_commitment<E: Engine>(
+        max: usize,
+        largest_negative_power: usize,
+        largest_positive_power: usize,
+        srs: &SRS<E>,
+        s: &[E::Fr],
+        worker: &Worker
+    ) -> E::G1Affine
+    {
+        let d = srs.d;
+        assert!(max >= largest_positive_power);
+        if d < max + largest_negative_power + 1 {
+            let min_power = largest_negative_power + max - d;
+            let max_power = d + largest_positive_power - max;
+            assert_eq!(s.len(), min_power + max_power, "scalars and exponents must have the same length");
+
+            // bases for negative powers go in the reversed order
+            let negative: Vec<E::Fr> = s[0..min_power].iter().rev().cloned().collect();
+
+            let mut result = parallel_multiexp(&srs.g_negative_x_alpha[0..min_power], &negative, worker);
+            result.add_assign(&parallel_multiexp(&srs.g_positive_x_alpha[..max_power], &s[min_power..], worker));
+
+            result.into_affine()
+        } else {
+            parallel_multiexp(
+                &srs.g_positive_x_alpha[(srs.d - max - largest_negative_power - 1)..],
+                s,
+                worker
+            ).into_affine()
+        }
+    }
+
+/// Same as `polynomial_commitment_opening`, but the division
+/// and the multiexps run on the provided worker
+pub fn parallel_polynomial_commitment_opening<E: Engine>(
+        largest_negative_power: usize,
+        _largest_positive_power: usize,
+        polynomial_coefficients: &[E::Fr],
+        point: E::Fr,
+        srs: &SRS<E>,
+        worker: &Worker
+    ) -> E::G1Affine
+    {
+        let poly = parallel_kate_divison::<E, _>(polynomial_coefficients, point, worker);
+
+        let negative_poly: Vec<E::Fr> = poly[0..largest_negative_power].iter().rev().cloned().collect();
+        let positive_poly = &poly[largest_negative_power..];
+
+        let mut result = parallel_multiexp(&srs.g_negative_x[1..(negative_poly.len() + 1)], &negative_poly, worker);
+        result.add_assign(&parallel_multiexp(&srs.g_positive_x[0..positive_poly.len()], positive_poly, worker));
+
+        result.into_affine()
+    }
+
 extern crate crossbeam;
 use self::crossbeam::channel::{unbounded};
 
@@ -439,6 +498,36 @@ where
     acc
 }
 
+/// Multiexp over the slices of equal length, split into
+/// chunks between the threads of the worker
+pub fn parallel_multiexp<G: CurveAffine>(
+    bases: &[G],
+    scalars: &[G::Scalar],
+    worker: &Worker
+) -> G::Projective
+{
+    use crate::multiexp::dense_multiexp;
+
+    assert_eq!(bases.len(), scalars.len(), "scalars and exponents must have the same length");
+
+    if scalars.is_empty() {
+        return G::Projective::zero();
+    }
+
+    let mut reprs = vec![<G::Scalar as PrimeField>::Repr::default(); scalars.len()];
+    worker.scope(scalars.len(), |scope, chunk| {
+        for (scalar, repr) in scalars.chunks(chunk).zip(reprs.chunks_mut(chunk)) {
+            scope.spawn(move |_| {
+                for (scalar, repr) in scalar.iter().zip(repr.iter_mut()) {
+                    *repr = scalar.into_repr();
+                }
+            });
+        }
+    });
+
+    dense_multiexp(worker, bases, &reprs).expect("lengths are checked")
+}
+
 /// Divides polynomial `a` in `x` by `x - b` with
 /// no remainder.
 pub fn kate_divison<'a, F: Field, I: IntoIterator<Item = &'a F>>(a: I, mut b: F) -> Vec<F>
@@ -463,74 +552,67 @@ where
 }
 
 /// Divides polynomial `a` in `x` by `x - b` with
-/// no remainder using fft.
-pub fn parallel_kate_divison<'a, E: Engine, I: IntoIterator<Item = &'a E::Fr>>(a: I, b: E::Fr) -> Vec<E::Fr>
+/// no remainder, in parallel.
+pub fn parallel_kate_divison<'a, E: Engine, I: IntoIterator<Item = &'a E::Fr>>(a: I, b: E::Fr, worker: &Worker) -> Vec<E::Fr>
 where
     I::IntoIter: DoubleEndedIterator + ExactSizeIterator,
 {
-    // this implementation is only for division by `x - b` form polynomail,
-    // so we can manuall calculate the reciproical poly of the form `x^2/(x-b)`
-    // and the reminder
+    // q_{i-1} = a_i + b * q_i, so q_i is a sum of a_j b^{j - i - 1} for j > i.
+    // Every chunk is first divided on its own, then the missing tail of the
+    // sum is carried between chunks and distributed with powers of b
+    let mut q: Vec<E::Fr> = a.into_iter().skip(1).cloned().collect();
 
-    // x^2 /(x - b) = x + b*x/(x - b) = (x + b) + b^2/(x - b)
-
-    let reciproical = vec![b, E::Fr::one()]; // x + b
+    if q.is_empty() {
+        return q;
+    }
 
-    // and remainder b^2
-    let mut b_squared = b;
-    b_squared.square();
+    let chunk = worker.get_chunk_size(q.len());
 
-    let mut b_neg = b;
-    b_neg.negate();
+    worker.scope(q.len(), |scope, chunk| {
+        for q in q.chunks_mut(chunk) {
+            scope.spawn(move |_| {
+                let mut tmp = E::Fr::zero();
+                for q in q.iter_mut().rev() {
+                    q.add_assign(&tmp);
+                    tmp = *q;
+                    tmp.mul_assign(&b);
+                }
+            });
+        }
+    });
 
-    let divisor = vec![b_neg, E::Fr::one()];
+    // carry into every chunk is the value of the first coefficient of the next one
+    let num_chunks = q.chunks(chunk).len();
+    let mut carries = vec![E::Fr::zero(); num_chunks];
+    {
+        let mut carry = E::Fr::zero();
+        for (i, q) in q.chunks(chunk).enumerate().rev() {
+            carries[i] = carry;
 
-    let poly: Vec<E::Fr> = a.into_iter().map(|el| el.clone()).collect();
+            carry.mul_assign(&b.pow([q.len() as u64]));
+            carry.add_assign(&q[0]);
+        }
+    }
 
-    let (q, _) = kate_divison_inner::<E>(poly, divisor, reciproical, b_squared);
+    worker.scope(q.len(), |scope, chunk| {
+        for (q, carry) in q.chunks_mut(chunk).zip(carries.iter()) {
+            scope.spawn(move |_| {
+                if carry.is_zero() {
+                    return;
+                }
 
-    // assert_eq!(r.len(), 0);
+                let mut tmp = *carry;
+                for q in q.iter_mut().rev() {
+                    tmp.mul_assign(&b);
+                    q.add_assign(&tmp);
+                }
+            });
+        }
+    });
 
     q
 }
 
-fn kate_divison_inner<E: Engine>(
-        poly: Vec<E::Fr>, 
-        divisor: Vec<E::Fr>, 
-        reciproical: Vec<E::Fr>, 
-        remainder: E::Fr
-    ) -> (Vec<E::Fr>, Vec<E::Fr>) {
-    if poly.len() == 1 {
-        return (vec![], poly);
-    }
-    // TODO: Change generic multiplications by multiplications by degree 1 polynomial
-    let poly_degree = poly.len() - 1;
-    let mut q = multiply_polynomials::<E>(poly.clone(), reciproical.clone());
-    q.drain(0..2);
-    // recursion step
-    if poly_degree > 2 {
-        let mut rec_step = poly.clone();
-        mul_polynomial_by_scalar(&mut rec_step[..], remainder);
-        // truncate low order terms 
-        rec_step.drain(0..2);
-        let (q2, _) = kate_divison_inner::<E>(rec_step, divisor.clone(), reciproical, remainder);
-        // length of q2 is smaller
-        add_polynomials(&mut q[..q2.len()], &q2[..]);
-    }        
-
-    // although r must be zero, calculate it for now
-    if q.len() == 0 {
-        return (q, poly);
-    }
-
-    // r = u - v*q
-    let mut poly = poly;
-    let tmp = multiply_polynomials::<E>(divisor, q.clone());
-    sub_polynomials(&mut poly[..], &tmp[..]);
-
-    return (q, poly);
-}
-
 /// Convenience function to check polynomail commitment
 pub fn check_polynomial_commitment<E: Engine>(
     commitment: &E::G1Affine,
@@ -628,25 +710,30 @@ fn laurent_division() {
 }
 
 pub fn multiply_polynomials<E: Engine>(a: Vec<E::Fr>, b: Vec<E::Fr>) -> Vec<E::Fr> {
+    let worker = Worker::new();
+
+    parallel_multiply_polynomials::<E>(a, b, &worker)
+}
+
+/// Multiplies polynomials with FFTs on the provided worker
+pub fn parallel_multiply_polynomials<E: Engine>(a: Vec<E::Fr>, b: Vec<E::Fr>, worker: &Worker) -> Vec<E::Fr> {
     let result_len = a.len() + b.len() - 1;
 
-    use crate::multicore::Worker;
     use crate::domain::{EvaluationDomain, Scalar};
 
-    let worker = Worker::new();
     let scalars_a: Vec<Scalar<E>> = a.into_iter().map(|e| Scalar::<E>(e)).collect();
     let mut domain_a = EvaluationDomain::from_coeffs_into_sized(scalars_a, result_len).unwrap();
 
     let scalars_b: Vec<Scalar<E>> = b.into_iter().map(|e| Scalar::<E>(e)).collect();
     let mut domain_b = EvaluationDomain::from_coeffs_into_sized(scalars_b, result_len).unwrap();
 
-    domain_a.fft(&worker);
-    domain_b.fft(&worker);
+    domain_a.fft(worker);
+    domain_b.fft(worker);
 
-    domain_a.mul_assign(&worker, &domain_b);
+    domain_a.mul_assign(worker, &domain_b);
     drop(domain_b);
 
-    domain_a.ifft(&worker);
+    domain_a.ifft(worker);
 
     let mut mul_result: Vec<E::Fr> = domain_a.into_coeffs().iter().map(|e| e.0).collect();
 
@@ -1065,7 +1152,7 @@ fn test_trivial_parallel_kate_division() {
 
     let quotient_poly = kate_divison(&poly, z);
 
-    let parallel_q_poly = parallel_kate_divison::<Bls12, _>(&poly, z);
+    let parallel_q_poly = parallel_kate_divison::<Bls12, _>(&poly, z, &Worker::new());
 
     assert_eq!(quotient_poly, parallel_q_poly);
 }
@@ -1105,7 +1192,7 @@ fn test_less_trivial_parallel_kate_division() {
 
     let quotient_poly = kate_divison(&poly, z);
 
-    let parallel_q_poly = parallel_kate_divison::<Bls12, _>(&poly, z);
+    let parallel_q_poly = parallel_kate_divison::<Bls12, _>(&poly, z, &Worker::new());
 
     assert_eq!(quotient_poly, parallel_q_poly);
 }
@@ -1155,7 +1242,44 @@ fn test_parallel_kate_division() {
 
     let quotient_poly = kate_divison(&poly, z);
 
-    let parallel_q_poly = parallel_kate_divison::<Bls12, _>(&poly, z);
+    let parallel_q_poly = parallel_kate_divison::<Bls12, _>(&poly, z, &Worker::new());
 
     assert_eq!(quotient_poly, parallel_q_poly);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_parallel_kate_division_in_chunks() {
+    use rand::{self, Rand};
+    use crate::pairing::bls12_381::{Bls12, Fr};
+
+    let rng = &mut rand::thread_rng();
+
+    for &cpus in [1, 3, 8].iter() {
+        let worker = Worker::new_with_cpus(cpus);
+
+        for &len in [2, 7, 1000, 1001].iter() {
+            let poly = (0..len).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+            let z = Fr::rand(rng);
+
+            assert_eq!(kate_divison(&poly, z), parallel_kate_divison::<Bls12, _>(&poly, z, &worker));
+        }
+    }
+}
+
+#[test]
+fn test_parallel_multiexp() {
+    use rand::{self, Rand};
+    use crate::pairing::bls12_381::{Fr, G1};
+
+    const SAMPLES: usize = 1000;
+
+    let rng = &mut rand::thread_rng();
+    let g = (0..SAMPLES).map(|_| G1::rand(rng).into_affine()).collect::<Vec<_>>();
+    let s = (0..SAMPLES).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+
+    let worker = Worker::new_with_cpus(4);
+
+    assert_eq!(multiexp_serial(g.iter(), s.iter()), parallel_multiexp(&g, &s, &worker));
+    assert_eq!(multiexp_serial(g[..1].iter(), s[..1].iter()), parallel_multiexp(&g[..1], &s[..1], &worker));
+}
+
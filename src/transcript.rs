@@ -0,0 +1,147 @@
+//! Fiat-Shamir transcript that protocols and verifiers can be written against
+//! regardless of the proof system. PLONK transcripts are used through
+//! `PlonkTranscriptAdapter`, Sonic transcripts implement the trait directly.
+//!
+//! Every implementation keeps the byte encodings of its underlying transcript.
+//! PLONK provers commit points with `commit_point` like the adapter does, so
+//! the KZG PLONK verifier draws its challenges through `PlonkTranscriptAdapter`,
+//! see `plonk::plonk::verifier::verify_with_transcript`.
+
+use crate::pairing::ff::PrimeField;
+use crate::pairing::CurveAffine;
+
+pub trait ProtocolTranscript<F: PrimeField>: Clone {
+    fn commit_point<G: CurveAffine>(&mut self, point: &G);
+    fn commit_scalar(&mut self, scalar: &F);
+    fn commit_labelled_bytes(&mut self, label: &[u8], bytes: &[u8]);
+    fn squeeze_scalar(&mut self) -> F;
+    fn squeeze_bytes(&mut self) -> Vec<u8>;
+}
+
+#[cfg(feature = "plonk")]
+pub use self::plonk_adapter::PlonkTranscriptAdapter;
+
+#[cfg(feature = "plonk")]
+mod plonk_adapter {
+    use crate::pairing::ff::PrimeField;
+    use crate::pairing::CurveAffine;
+    use crate::plonk::commitments::transcript::Transcript;
+    use byteorder::{BigEndian, ByteOrder};
+
+    use super::ProtocolTranscript;
+
+    /// Uses any PLONK transcript as a `ProtocolTranscript`. Labelled bytes are committed as
+    /// the label length as 4 big endian bytes, the label and the bytes
+    #[derive(Clone)]
+    pub struct PlonkTranscriptAdapter<F: PrimeField, T: Transcript<F>> {
+        transcript: T,
+        _marker: std::marker::PhantomData<F>
+    }
+
+    impl<F: PrimeField, T: Transcript<F>> PlonkTranscriptAdapter<F, T> {
+        pub fn new(transcript: T) -> Self {
+            Self {
+                transcript,
+                _marker: std::marker::PhantomData
+            }
+        }
+
+        pub fn into_inner(self) -> T {
+            self.transcript
+        }
+    }
+
+    impl<F: PrimeField, T: Transcript<F>> ProtocolTranscript<F> for PlonkTranscriptAdapter<F, T> {
+        fn commit_point<G: CurveAffine>(&mut self, point: &G) {
+            self.transcript.commit_point(point);
+        }
+
+        fn commit_scalar(&mut self, scalar: &F) {
+            self.transcript.commit_field_element(scalar);
+        }
+
+        fn commit_labelled_bytes(&mut self, label: &[u8], bytes: &[u8]) {
+            let mut label_len = [0u8; 4];
+            BigEndian::write_u32(&mut label_len, label.len() as u32);
+            self.transcript.commit_bytes(&label_len);
+            self.transcript.commit_bytes(label);
+            self.transcript.commit_bytes(bytes);
+        }
+
+        fn squeeze_scalar(&mut self) -> F {
+            self.transcript.get_challenge()
+        }
+
+        fn squeeze_bytes(&mut self) -> Vec<u8> {
+            self.transcript.get_challenge_bytes()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pairing::ff::Field;
+    use crate::pairing::bn256::{Fr, G1Affine};
+
+    // a toy protocol step, written once for every transcript
+    fn run_protocol<T: ProtocolTranscript<Fr>>(transcript: &mut T) -> (Fr, Vec<u8>) {
+        transcript.commit_labelled_bytes(b"instance", &[1, 2, 3]);
+        transcript.commit_point(&G1Affine::one());
+        transcript.commit_scalar(&Fr::one());
+        let challenge = transcript.squeeze_scalar();
+        transcript.commit_scalar(&challenge);
+
+        (challenge, transcript.squeeze_bytes())
+    }
+
+    fn check_transcript<T: ProtocolTranscript<Fr>>(transcript: T) {
+        let (challenge, bytes) = run_protocol(&mut transcript.clone());
+        let (replayed_challenge, replayed_bytes) = run_protocol(&mut transcript.clone());
+        assert_eq!(challenge, replayed_challenge);
+        assert_eq!(bytes, replayed_bytes);
+
+        let mut other = transcript.clone();
+        other.commit_labelled_bytes(b"instanc", b"e");
+        let (other_challenge, _) = run_protocol(&mut other);
+        assert!(challenge != other_challenge);
+
+        let mut other = transcript;
+        other.commit_point(&G1Affine::zero());
+        let (other_challenge, _) = run_protocol(&mut other);
+        assert!(challenge != other_challenge);
+    }
+
+    #[cfg(feature = "plonk")]
+    #[test]
+    fn test_plonk_transcript_adapter() {
+        use crate::plonk::commitments::transcript::{Prng, Transcript, Blake2sTranscript};
+        use crate::plonk::commitments::transcript::keccak::Keccak256Transcript;
+
+        check_transcript(PlonkTranscriptAdapter::new(Blake2sTranscript::<Fr>::new()));
+        check_transcript(PlonkTranscriptAdapter::new(Keccak256Transcript::<Fr>::new()));
+
+        // adapter draws the same challenges as the transcript it wraps
+        let mut transcript = Blake2sTranscript::<Fr>::new();
+        let mut adapter = PlonkTranscriptAdapter::new(transcript.clone());
+        transcript.commit_field_element(&Fr::one());
+        adapter.commit_scalar(&Fr::one());
+        assert_eq!(transcript.get_challenge(), adapter.squeeze_scalar());
+    }
+
+    #[cfg(feature = "sonic")]
+    #[test]
+    fn test_sonic_transcripts() {
+        use crate::sonic::transcript::{Transcript, TranscriptProtocol};
+
+        check_transcript(Transcript::new(&[]));
+
+        // same challenges as drawn through `TranscriptProtocol`
+        let mut transcript = Transcript::new(&[]);
+        let mut unified = transcript.clone();
+        TranscriptProtocol::commit_point(&mut transcript, &G1Affine::one());
+        ProtocolTranscript::<Fr>::commit_point(&mut unified, &G1Affine::one());
+        let challenge: Fr = transcript.get_challenge_scalar();
+        assert_eq!(challenge, ProtocolTranscript::<Fr>::squeeze_scalar(&mut unified));
+    }
+}